@@ -1,5 +1,6 @@
 use serde_json::Value;
 use std::fs;
+use std::path::PathBuf;
 use tauri::Manager;
 
 pub fn get_api_url(app_handle: &tauri::AppHandle) -> String {
@@ -28,4 +29,14 @@ pub fn get_ws_url(app_handle: &tauri::AppHandle) -> String {
         }
     }
     "ws://localhost:8080/ws/notificacoes".to_string()
-}
\ No newline at end of file
+}
+
+/// Caminho de um arquivo local da estação (configurações e caches que não ficam na API)
+pub fn get_local_data_path(app_handle: &tauri::AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Não foi possível localizar o diretório de dados do app: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Erro ao criar diretório de dados: {}", e))?;
+    Ok(dir.join(file_name))
+}
@@ -9,7 +9,8 @@ use crate::{
     model::historico::{
         PaginatedHistoricoResponseFromApi, 
         PaginatedHistoricoResponseToFrontend, 
-        HistoricoFilterPayload
+        HistoricoFilterPayload,
+        NovoHistoricoPayload,
     },
 };
 
@@ -78,4 +79,22 @@ pub async fn listar_historico_command(
         },
         Err(e) => Err(ApiResponse::error(format!("Erro de conexão com a API: {}", e))),
     }
-}
\ No newline at end of file
+}
+
+/// Grava uma entrada no histórico (auditoria) a partir de rotinas internas
+pub async fn registrar_historico(client: &Client, api_url: &str, payload: &NovoHistoricoPayload) -> Result<(), String> {
+    let url = format!("{}{}", api_url, API_RESOURCE);
+
+    match client.post(&url).json(payload).send().await {
+        Ok(response) => {
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                let status = response.status();
+                let err_body = response.text().await.unwrap_or_default();
+                Err(format!("API retornou erro ({}): {}", status, err_body))
+            }
+        },
+        Err(e) => Err(format!("Erro de conexão com a API: {}", e)),
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
         AtualizarUsuarioAdminPayload,
         AtualizarStatusPayload,
     },
+    validacao::{resposta_validacao, ErroCadastro, Validador},
 };
 
 const API_RESOURCE: &str = "/admin/usuarios";
@@ -65,7 +66,13 @@ pub async fn buscar_usuario_admin_command(app_handle: AppHandle, id: u32) -> Res
 }
 
 #[tauri::command]
-pub async fn criar_usuario_admin_command(app_handle: AppHandle, payload: CriarUsuarioAdminPayload) -> Result<ApiResponse<UsuarioAdmin>, ApiResponse<()>> {
+pub async fn criar_usuario_admin_command(app_handle: AppHandle, mut payload: CriarUsuarioAdminPayload) -> Result<ApiResponse<UsuarioAdmin>, ErroCadastro> {
+    let mut v = Validador::new();
+    v.obrigatorio("nome", Some(&payload.nome), "Login");
+    v.obrigatorio("nome_completo", Some(&payload.nome_completo), "Nome completo");
+    payload.numero_doc = v.documento("numero_doc", payload.numero_doc.as_deref(), Some("CPF"));
+    v.concluir().map_err(resposta_validacao)?;
+
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}{}", api_url, API_RESOURCE);
@@ -89,7 +96,11 @@ pub async fn criar_usuario_admin_command(app_handle: AppHandle, payload: CriarUs
 }
 
 #[tauri::command]
-pub async fn atualizar_usuario_admin_command(app_handle: AppHandle, id: u32, payload: AtualizarUsuarioAdminPayload) -> Result<ApiResponse<UsuarioAdmin>, ApiResponse<()>> {
+pub async fn atualizar_usuario_admin_command(app_handle: AppHandle, id: u32, mut payload: AtualizarUsuarioAdminPayload) -> Result<ApiResponse<UsuarioAdmin>, ErroCadastro> {
+    let mut v = Validador::new();
+    payload.numero_doc = v.documento("numero_doc", payload.numero_doc.as_deref(), Some("CPF"));
+    v.concluir().map_err(resposta_validacao)?;
+
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}{}/{}", api_url, API_RESOURCE, id);
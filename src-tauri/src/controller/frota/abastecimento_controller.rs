@@ -3,6 +3,12 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::frota::consistencia_odometro_controller::{
+    inconsistencias_abastecimento_editado, inconsistencias_novo_abastecimento, rejeitar_inconsistencias,
+};
+use crate::controller::frota::comprovante_abastecimento_controller::{
+    enviar_comprovante, normalizar_imagem_comprovante, vincular_comprovante_abastecimento, ImagemNormalizada,
+};
 use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 
@@ -19,7 +25,11 @@ pub struct FrotaAbastecimentoInput {
     pub data: Option<String>, // String porque vem do frontend
     pub notafiscal: Option<String>,
     pub quilometragem: Option<i32>,
+    // Legado: se vier, é enviada como comprovante e nunca segue no corpo do abastecimento
+    #[serde(default, skip_serializing)]
     pub foto: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comprovante_id: Option<u32>,
 }
 
 // Struct para a resposta da API do backend Axum
@@ -36,7 +46,12 @@ pub struct FrotaAbastecimento {
     pub data: Option<String>,
     pub notafiscal: Option<String>,
     pub quilometragem: Option<i32>,
-    pub foto: Option<Vec<u8>>,
+    // A imagem fica no comprovante e é buscada sob demanda, nunca na listagem
+    #[serde(default)]
+    pub comprovante_id: Option<u32>,
+    /// Preenchido quando o abastecimento foi gravado mas a foto legada não virou comprovante
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aviso: Option<String>,
 }
 
 // Struct para atualização de abastecimento
@@ -53,7 +68,11 @@ pub struct UpdateFrotaAbastecimentoInput {
     pub data: Option<String>,
     pub notafiscal: Option<String>,
     pub quilometragem: Option<i32>,
+    // Legado: se vier, é enviada como comprovante e nunca segue no corpo do abastecimento
+    #[serde(default, skip_serializing)]
     pub foto: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comprovante_id: Option<u32>,
 }
 
 // Struct para filtros de busca
@@ -84,7 +103,7 @@ pub struct GenericResponse {
 }
 
 #[command]
-pub async fn criar_frota_abastecimento(app_handle: AppHandle, payload: FrotaAbastecimentoInput) -> Result<FrotaAbastecimento, String> {
+pub async fn criar_frota_abastecimento(app_handle: AppHandle, mut payload: FrotaAbastecimentoInput) -> Result<FrotaAbastecimento, String> {
     println!("Recebido comando criar_frota_abastecimento com payload: {:?}", payload);
     
     // Validações básicas no lado do Rust
@@ -106,6 +125,16 @@ pub async fn criar_frota_abastecimento(app_handle: AppHandle, payload: FrotaAbas
         }
     }
 
+    // Confronta com viagens, abastecimentos e manutenções já registrados do veículo
+    let inconsistencias = inconsistencias_novo_abastecimento(&app_handle, &payload)
+        .await
+        .map_err(|e| format!("Não foi possível verificar a consistência do odômetro: {}", e))?;
+    rejeitar_inconsistencias(&inconsistencias)?;
+
+    // A foto legada só sobe depois que o abastecimento existe, para não sobrar comprovante órfão;
+    // uma imagem inválida é recusada antes de criar qualquer coisa
+    let foto = payload.foto.take().map(|f| normalizar_imagem_comprovante(&f)).transpose()?;
+
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
@@ -152,12 +181,43 @@ pub async fn criar_frota_abastecimento(app_handle: AppHandle, payload: FrotaAbas
     let api_response: ApiResponse<FrotaAbastecimento> = serde_json::from_str(&response_text)
         .map_err(|e| format!("Erro ao processar JSON da resposta: {}. Resposta: {}", e, response_text))?;
 
-    if api_response.success {
-        api_response.data
-            .ok_or_else(|| "A API retornou sucesso, mas sem dados de abastecimento.".to_string())
-    } else {
-        Err(api_response.message
-            .unwrap_or_else(|| "A API indicou uma falha sem fornecer uma mensagem.".to_string()))
+    if !api_response.success {
+        return Err(api_response.message
+            .unwrap_or_else(|| "A API indicou uma falha sem fornecer uma mensagem.".to_string()));
+    }
+    let abastecimento = api_response.data
+        .ok_or_else(|| "A API retornou sucesso, mas sem dados de abastecimento.".to_string())?;
+
+    anexar_foto_legada(&app_handle, abastecimento, foto, payload.notafiscal.as_deref()).await
+}
+
+// Envia a foto legada como comprovante do abastecimento já gravado e faz o vínculo.
+// O abastecimento já existe na API, então uma falha aqui volta como aviso e não como erro
+async fn anexar_foto_legada(
+    app_handle: &AppHandle,
+    mut abastecimento: FrotaAbastecimento,
+    foto: Option<ImagemNormalizada>,
+    notafiscal: Option<&str>,
+) -> Result<FrotaAbastecimento, String> {
+    let Some(foto) = foto else {
+        return Ok(abastecimento);
+    };
+    let comprovante = match enviar_comprovante(app_handle, &foto, notafiscal, Some(abastecimento.id)).await {
+        Ok(comprovante) => comprovante,
+        Err(e) => {
+            abastecimento.aviso = Some(format!("Abastecimento gravado, mas o comprovante não foi enviado: {}", e));
+            return Ok(abastecimento);
+        }
+    };
+    match vincular_comprovante_abastecimento(app_handle.clone(), abastecimento.id, comprovante.id).await {
+        Ok(vinculado) => Ok(vinculado),
+        Err(e) => {
+            abastecimento.aviso = Some(format!(
+                "Abastecimento gravado, mas o comprovante {} não foi vinculado: {}",
+                comprovante.id, e
+            ));
+            Ok(abastecimento)
+        }
     }
 }
 
@@ -318,7 +378,7 @@ pub async fn buscar_abastecimento_filtrado(
 }
 
 #[command]
-pub async fn atualizar_frota_abastecimento(app_handle: AppHandle, id: u32, payload: UpdateFrotaAbastecimentoInput) -> Result<FrotaAbastecimento, String> {
+pub async fn atualizar_frota_abastecimento(app_handle: AppHandle, id: u32, mut payload: UpdateFrotaAbastecimentoInput) -> Result<FrotaAbastecimento, String> {
     println!("Recebido comando atualizar_frota_abastecimento para o ID: {}", id);
 
     // Validações básicas no lado do Rust
@@ -340,6 +400,15 @@ pub async fn atualizar_frota_abastecimento(app_handle: AppHandle, id: u32, paylo
         }
     }
 
+    // Confronta a versão editada com o restante do histórico
+    let inconsistencias = inconsistencias_abastecimento_editado(&app_handle, id, &payload)
+        .await
+        .map_err(|e| format!("Não foi possível verificar a consistência do odômetro: {}", e))?;
+    rejeitar_inconsistencias(&inconsistencias)?;
+
+    // Como no cadastro, a foto legada só sobe depois que a atualização for aceita
+    let foto = payload.foto.take().map(|f| normalizar_imagem_comprovante(&f)).transpose()?;
+
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/atualizar_frota_abastecimento/{}", api_url, id);
@@ -361,11 +430,13 @@ pub async fn atualizar_frota_abastecimento(app_handle: AppHandle, id: u32, paylo
     let api_response: ApiResponse<FrotaAbastecimento> = response.json().await
         .map_err(|e| format!("Erro ao processar JSON da resposta da atualização: {}", e))?;
 
-    if api_response.success {
-        api_response.data.ok_or_else(|| "API retornou sucesso sem dados do abastecimento atualizado.".to_string())
-    } else {
-        Err(api_response.message.unwrap_or_else(|| "API retornou falha na atualização sem mensagem.".to_string()))
+    if !api_response.success {
+        return Err(api_response.message.unwrap_or_else(|| "API retornou falha na atualização sem mensagem.".to_string()));
     }
+    let abastecimento = api_response.data
+        .ok_or_else(|| "API retornou sucesso sem dados do abastecimento atualizado.".to_string())?;
+
+    anexar_foto_legada(&app_handle, abastecimento, foto, payload.notafiscal.as_deref()).await
 }
 
 #[command]
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::dados_locais::{gravar_json, ler_json};
+
+const ARQUIVO_ALERTAS: &str = "alertas_frota.json";
+
+// Antecedências dos alertas da frota e regras de habilitação, guardadas na estação
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ParametrosAlertasFrota {
+    /// Km restantes para a manutenção preventiva entrar em "a vencer"
+    pub antecedencia_manutencao_km: i64,
+    /// Dias restantes para a manutenção preventiva entrar em "a vencer"
+    pub antecedencia_manutencao_dias: i64,
+    /// Dias antes do vencimento da CNH para alertar
+    pub antecedencia_vencimento_cnh_dias: i64,
+    /// Dias antes do vencimento de IPVA, licenciamento, seguro e multas para alertar
+    pub antecedencia_vencimento_veiculo_dias: i64,
+    /// Gestor da frota que recebe os alertas por e-mail
+    pub email_gestor_frota: Option<String>,
+    /// Exige a observação EAR na CNH para registrar viagens
+    pub exigir_ear: bool,
+}
+
+impl Default for ParametrosAlertasFrota {
+    fn default() -> Self {
+        ParametrosAlertasFrota {
+            antecedencia_manutencao_km: 500,
+            antecedencia_manutencao_dias: 15,
+            antecedencia_vencimento_cnh_dias: 30,
+            antecedencia_vencimento_veiculo_dias: 15,
+            email_gestor_frota: None,
+            exigir_ear: false,
+        }
+    }
+}
+
+#[command]
+pub async fn carregar_parametros_alertas_frota(app_handle: AppHandle) -> Result<ParametrosAlertasFrota, String> {
+    Ok(ler_parametros_alertas(&app_handle))
+}
+
+#[command]
+pub async fn salvar_parametros_alertas_frota(
+    app_handle: AppHandle,
+    parametros: ParametrosAlertasFrota,
+) -> Result<String, String> {
+    if parametros.antecedencia_manutencao_km < 0
+        || parametros.antecedencia_manutencao_dias < 0
+        || parametros.antecedencia_vencimento_cnh_dias < 0
+        || parametros.antecedencia_vencimento_veiculo_dias < 0
+    {
+        return Err("As antecedências de alerta não podem ser negativas".to_string());
+    }
+    if let Some(email) = parametros.email_gestor_frota.as_deref().filter(|e| !e.trim().is_empty()) {
+        if email.trim().parse::<lettre::Address>().is_err() {
+            return Err(format!("E-mail do gestor da frota inválido: {}", email));
+        }
+    }
+
+    gravar_json(&app_handle, ARQUIVO_ALERTAS, &parametros)?;
+    Ok("Parâmetros dos alertas da frota salvos".to_string())
+}
+
+pub fn ler_parametros_alertas(app_handle: &AppHandle) -> ParametrosAlertasFrota {
+    ler_json(app_handle, ARQUIVO_ALERTAS)
+}
@@ -0,0 +1,691 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Local, NaiveDateTime};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::controller::frota::abastecimento_controller::{buscar_abastecimento_filtrado, FiltrosAbastecimento, FrotaAbastecimento};
+use crate::controller::frota::posto_controller::buscar_postos;
+use crate::controller::frota::veiculo_controller::buscar_veiculos_e_marcas;
+use crate::exportacao::{destino_exportacao, escapar_html, pagina_relatorio, salvar_pdf, tabela_html};
+use crate::utils::parse_data_hora;
+
+const ARQUIVO_PARAMETROS: &str = "parametros_frota.json";
+
+// Parâmetros da análise de abastecimento, guardados na estação
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ParametrosAnaliseFrota {
+    /// Capacidade do tanque (litros) por id de veículo
+    pub capacidade_tanque: HashMap<u32, f64>,
+    /// Desvio do km/l em relação à mediana do veículo para marcar anomalia
+    pub desvio_consumo_percentual: f64,
+    /// Desvio do preço/litro em relação à mediana do combustível no período
+    pub desvio_preco_percentual: f64,
+    /// Trechos necessários para o veículo ter uma linha de base de consumo
+    pub trechos_minimos_base: usize,
+}
+
+impl Default for ParametrosAnaliseFrota {
+    fn default() -> Self {
+        ParametrosAnaliseFrota {
+            capacidade_tanque: HashMap::new(),
+            desvio_consumo_percentual: 25.0,
+            desvio_preco_percentual: 15.0,
+            trechos_minimos_base: 3,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoAnomaliaAbastecimento {
+    OdometroRegrediu,
+    LitrosAcimaDoTanque,
+    PrecoForaDoPadrao,
+    ConsumoForaDoPadrao,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnomaliaAbastecimento {
+    pub tipo: TipoAnomaliaAbastecimento,
+    pub abastecimento_id: u32,
+    pub veiculo_id: Option<u32>,
+    pub veiculo: String,
+    pub data: Option<String>,
+    pub descricao: String,
+}
+
+// Distância entre dois abastecimentos consecutivos do mesmo veículo (tanque cheio a tanque cheio)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrechoConsumo {
+    pub abastecimento_id: u32,
+    pub data: Option<String>,
+    pub km_inicial: i32,
+    pub km_final: i32,
+    pub km_rodados: i32,
+    pub litros: f64,
+    pub km_por_litro: f64,
+    pub custo: f64,
+    pub custo_por_km: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsumoVeiculo {
+    pub veiculo_id: Option<u32>,
+    pub veiculo: String,
+    pub abastecimentos: usize,
+    pub litros_total: f64,
+    pub valor_total: f64,
+    pub km_rodados: i64,
+    /// Total de km dos trechos / litros dos trechos
+    pub km_por_litro_medio: Option<f64>,
+    /// Mediana dos trechos, usada para marcar consumo fora do padrão
+    pub km_por_litro_base: Option<f64>,
+    pub custo_por_km: Option<f64>,
+    pub trechos: Vec<TrechoConsumo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrecoMensalPosto {
+    pub mes: String, // "YYYY-MM"
+    pub preco_medio: f64,
+    pub abastecimentos: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TendenciaPrecoPosto {
+    pub posto_id: Option<u16>,
+    pub posto: String,
+    pub combustivel: Option<u8>,
+    pub combustivel_nome: String,
+    pub abastecimentos: usize,
+    pub preco_medio: f64,
+    pub preco_minimo: f64,
+    pub preco_maximo: f64,
+    pub primeiro_preco: f64,
+    pub ultimo_preco: f64,
+    /// Último preço em relação ao primeiro do período
+    pub variacao_percentual: f64,
+    pub mensal: Vec<PrecoMensalPosto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatorioAbastecimento {
+    pub gerado_em: String,
+    pub periodo_inicio: Option<String>,
+    pub periodo_fim: Option<String>,
+    pub total_abastecimentos: usize,
+    pub litros_total: f64,
+    pub valor_total: f64,
+    pub veiculos: Vec<ConsumoVeiculo>,
+    pub postos: Vec<TendenciaPrecoPosto>,
+    pub anomalias: Vec<AnomaliaAbastecimento>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoAnaliseAbastecimento {
+    pub caminho: String,
+    pub total_veiculos: usize,
+    pub total_anomalias: usize,
+}
+
+// Abastecimento com os campos numéricos já convertidos
+struct Registro<'a> {
+    origem: &'a FrotaAbastecimento,
+    data: Option<NaiveDateTime>,
+    litros: f64,
+    preco: Option<f64>,
+    valor: f64,
+}
+
+#[command]
+pub async fn carregar_parametros_frota(app_handle: AppHandle) -> Result<ParametrosAnaliseFrota, String> {
+    Ok(ler_parametros(&app_handle))
+}
+
+#[command]
+pub async fn salvar_parametros_frota(app_handle: AppHandle, parametros: ParametrosAnaliseFrota) -> Result<String, String> {
+    if parametros.desvio_consumo_percentual <= 0.0 || parametros.desvio_preco_percentual <= 0.0 {
+        return Err("Os desvios devem ser maiores que zero".to_string());
+    }
+    if parametros.capacidade_tanque.values().any(|c| *c <= 0.0) {
+        return Err("Capacidade do tanque deve ser maior que zero".to_string());
+    }
+
+    gravar_json(&app_handle, ARQUIVO_PARAMETROS, &parametros)?;
+    Ok("Parâmetros da frota salvos".to_string())
+}
+
+/// Consumo por veículo, tendência de preço por posto e anomalias dos abastecimentos filtrados
+#[command]
+pub async fn analisar_abastecimentos(
+    app_handle: AppHandle,
+    filtros: FiltrosAbastecimento,
+) -> Result<RelatorioAbastecimento, String> {
+    let periodo_inicio = filtros.data_inicio.clone();
+    let periodo_fim = filtros.data_fim.clone();
+    let abastecimentos = buscar_abastecimento_filtrado(app_handle.clone(), filtros).await?;
+
+    // Nomes são só para exibição; sem eles o relatório usa os ids
+    let veiculos: HashMap<u32, String> = buscar_veiculos_e_marcas(app_handle.clone())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| (v.id, format!("{} ({})", v.nome, v.placa)))
+        .collect();
+    let postos: HashMap<u16, String> = buscar_postos(app_handle.clone())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| u16::try_from(p.id).ok().map(|id| (id, p.nome)))
+        .collect();
+
+    let mut relatorio = montar_relatorio(&abastecimentos, &veiculos, &postos, &ler_parametros(&app_handle));
+    relatorio.periodo_inicio = periodo_inicio;
+    relatorio.periodo_fim = periodo_fim;
+    Ok(relatorio)
+}
+
+#[command]
+pub async fn exportar_analise_abastecimento_xlsx(
+    relatorio: RelatorioAbastecimento,
+    caminho: Option<String>,
+) -> Result<ExportacaoAnaliseAbastecimento, String> {
+    let destino = resolver_destino(caminho, "xlsx")?;
+    gerar_xlsx(&relatorio, &destino)?;
+    Ok(ExportacaoAnaliseAbastecimento {
+        caminho: destino.to_string_lossy().to_string(),
+        total_veiculos: relatorio.veiculos.len(),
+        total_anomalias: relatorio.anomalias.len(),
+    })
+}
+
+#[command]
+pub async fn exportar_analise_abastecimento_pdf(
+    relatorio: RelatorioAbastecimento,
+    caminho: Option<String>,
+) -> Result<ExportacaoAnaliseAbastecimento, String> {
+    let destino = resolver_destino(caminho, "pdf")?;
+
+    salvar_pdf(gerar_html(&relatorio), &destino).await?;
+
+    Ok(ExportacaoAnaliseAbastecimento {
+        caminho: destino.to_string_lossy().to_string(),
+        total_veiculos: relatorio.veiculos.len(),
+        total_anomalias: relatorio.anomalias.len(),
+    })
+}
+
+// --- Cálculo ---
+
+pub fn ler_parametros(app_handle: &AppHandle) -> ParametrosAnaliseFrota {
+    ler_json(app_handle, ARQUIVO_PARAMETROS)
+}
+
+pub fn nome_combustivel(id: Option<u8>) -> String {
+    match id {
+        Some(1) => "Gasolina".to_string(),
+        Some(2) => "Etanol".to_string(),
+        Some(3) => "Diesel".to_string(),
+        Some(4) => "GNV".to_string(),
+        Some(outro) => format!("Combustível {}", outro),
+        None => "Não informado".to_string(),
+    }
+}
+
+fn decimal(valor: &Option<BigDecimal>) -> Option<f64> {
+    valor.as_ref().and_then(|v| v.to_f64())
+}
+
+fn mediana(valores: &[f64]) -> Option<f64> {
+    if valores.is_empty() {
+        return None;
+    }
+    let mut ordenados = valores.to_vec();
+    ordenados.sort_by(|a, b| a.total_cmp(b));
+    let meio = ordenados.len() / 2;
+    Some(if ordenados.len().is_multiple_of(2) { (ordenados[meio - 1] + ordenados[meio]) / 2.0 } else { ordenados[meio] })
+}
+
+fn desvio_percentual(valor: f64, referencia: f64) -> f64 {
+    (valor - referencia) / referencia * 100.0
+}
+
+fn montar_relatorio(
+    abastecimentos: &[FrotaAbastecimento],
+    veiculos: &HashMap<u32, String>,
+    postos: &HashMap<u16, String>,
+    parametros: &ParametrosAnaliseFrota,
+) -> RelatorioAbastecimento {
+    let registros: Vec<Registro> = abastecimentos
+        .iter()
+        .map(|a| {
+            let litros = decimal(&a.litro).unwrap_or(0.0);
+            let preco = decimal(&a.valor_litro);
+            Registro {
+                origem: a,
+                data: a.data.as_deref().and_then(|d| parse_data_hora(d, None)),
+                litros,
+                preco,
+                valor: decimal(&a.valor).unwrap_or_else(|| litros * preco.unwrap_or(0.0)),
+            }
+        })
+        .collect();
+    let nome_veiculo = |id: Option<u32>| match id {
+        Some(id) => veiculos.get(&id).cloned().unwrap_or_else(|| format!("Veículo {}", id)),
+        None => "Sem veículo".to_string(),
+    };
+
+    let mut anomalias = Vec::new();
+    let mut anomalia = |tipo, registro: &Registro, descricao: String| {
+        anomalias.push(AnomaliaAbastecimento {
+            tipo,
+            abastecimento_id: registro.origem.id,
+            veiculo_id: registro.origem.veiculo,
+            veiculo: nome_veiculo(registro.origem.veiculo),
+            data: registro.origem.data.clone(),
+            descricao,
+        });
+    };
+
+    // Consumo por veículo
+    let mut por_veiculo: BTreeMap<Option<u32>, Vec<&Registro>> = BTreeMap::new();
+    for registro in &registros {
+        por_veiculo.entry(registro.origem.veiculo).or_default().push(registro);
+    }
+    let mut consumos = Vec::new();
+    for (veiculo_id, mut lista) in por_veiculo {
+        lista.sort_by_key(|r| (r.data, r.origem.quilometragem, r.origem.id));
+
+        let mut trechos = Vec::new();
+        let mut anterior: Option<i32> = None;
+        for registro in &lista {
+            if let (Some(capacidade), Some(id)) = (veiculo_id.and_then(|id| parametros.capacidade_tanque.get(&id)), veiculo_id) {
+                if registro.litros > *capacidade {
+                    anomalia(
+                        TipoAnomaliaAbastecimento::LitrosAcimaDoTanque,
+                        registro,
+                        format!("{:.2} L abastecidos; tanque do veículo {} comporta {:.0} L", registro.litros, id, capacidade),
+                    );
+                }
+            }
+            let Some(km) = registro.origem.quilometragem else {
+                continue;
+            };
+            match anterior {
+                Some(km_anterior) if km < km_anterior => {
+                    // Mantém o odômetro anterior como referência para o próximo trecho
+                    anomalia(
+                        TipoAnomaliaAbastecimento::OdometroRegrediu,
+                        registro,
+                        format!("Odômetro {} km menor que o do abastecimento anterior ({} km)", km, km_anterior),
+                    );
+                    continue;
+                }
+                Some(km_anterior) if km > km_anterior && registro.litros > 0.0 => {
+                    let km_rodados = km - km_anterior;
+                    trechos.push(TrechoConsumo {
+                        abastecimento_id: registro.origem.id,
+                        data: registro.origem.data.clone(),
+                        km_inicial: km_anterior,
+                        km_final: km,
+                        km_rodados,
+                        litros: registro.litros,
+                        km_por_litro: km_rodados as f64 / registro.litros,
+                        custo: registro.valor,
+                        custo_por_km: registro.valor / km_rodados as f64,
+                    });
+                }
+                _ => {}
+            }
+            anterior = Some(km);
+        }
+
+        let base = mediana(&trechos.iter().map(|t| t.km_por_litro).collect::<Vec<_>>());
+        if let Some(base) = base.filter(|b| *b > 0.0 && trechos.len() >= parametros.trechos_minimos_base) {
+            for trecho in &trechos {
+                let desvio = desvio_percentual(trecho.km_por_litro, base);
+                if desvio.abs() > parametros.desvio_consumo_percentual {
+                    if let Some(registro) = lista.iter().find(|r| r.origem.id == trecho.abastecimento_id) {
+                        anomalia(
+                            TipoAnomaliaAbastecimento::ConsumoForaDoPadrao,
+                            registro,
+                            format!("{:.2} km/l ({:+.0}% em relação à média de {:.2} km/l do veículo)", trecho.km_por_litro, desvio, base),
+                        );
+                    }
+                }
+            }
+        }
+
+        let km_trechos: i64 = trechos.iter().map(|t| i64::from(t.km_rodados)).sum();
+        let litros_trechos: f64 = trechos.iter().map(|t| t.litros).sum();
+        let custo_trechos: f64 = trechos.iter().map(|t| t.custo).sum();
+        consumos.push(ConsumoVeiculo {
+            veiculo_id,
+            veiculo: nome_veiculo(veiculo_id),
+            abastecimentos: lista.len(),
+            litros_total: lista.iter().map(|r| r.litros).sum(),
+            valor_total: lista.iter().map(|r| r.valor).sum(),
+            km_rodados: km_trechos,
+            km_por_litro_medio: (litros_trechos > 0.0).then(|| km_trechos as f64 / litros_trechos),
+            km_por_litro_base: base,
+            custo_por_km: (km_trechos > 0).then(|| custo_trechos / km_trechos as f64),
+            trechos,
+        });
+    }
+
+    // Preço fora do padrão do combustível no período
+    let mut precos_combustivel: HashMap<Option<u8>, Vec<f64>> = HashMap::new();
+    for registro in &registros {
+        if let Some(preco) = registro.preco {
+            precos_combustivel.entry(registro.origem.combustivel).or_default().push(preco);
+        }
+    }
+    for registro in &registros {
+        let (Some(preco), Some(referencia)) = (
+            registro.preco,
+            precos_combustivel.get(&registro.origem.combustivel).and_then(|p| mediana(p)),
+        ) else {
+            continue;
+        };
+        let desvio = desvio_percentual(preco, referencia);
+        if referencia > 0.0 && desvio.abs() > parametros.desvio_preco_percentual {
+            anomalia(
+                TipoAnomaliaAbastecimento::PrecoForaDoPadrao,
+                registro,
+                format!(
+                    "R$ {:.3}/L de {} ({:+.0}% em relação à mediana de R$ {:.3})",
+                    preco,
+                    nome_combustivel(registro.origem.combustivel),
+                    desvio,
+                    referencia
+                ),
+            );
+        }
+    }
+
+    // Tendência de preço por posto e combustível
+    let mut por_posto: BTreeMap<(Option<u16>, Option<u8>), Vec<&Registro>> = BTreeMap::new();
+    for registro in registros.iter().filter(|r| r.preco.is_some()) {
+        por_posto.entry((registro.origem.posto, registro.origem.combustivel)).or_default().push(registro);
+    }
+    let mut tendencias = Vec::new();
+    for ((posto_id, combustivel), mut lista) in por_posto {
+        lista.sort_by_key(|r| (r.data, r.origem.id));
+        let precos: Vec<f64> = lista.iter().filter_map(|r| r.preco).collect();
+        let primeiro = precos[0];
+        let ultimo = precos[precos.len() - 1];
+
+        let mut meses: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for registro in &lista {
+            let mes = registro.data.map(|d| d.format("%Y-%m").to_string()).unwrap_or_else(|| "sem data".to_string());
+            meses.entry(mes).or_default().extend(registro.preco);
+        }
+
+        tendencias.push(TendenciaPrecoPosto {
+            posto_id,
+            posto: match posto_id {
+                Some(id) => postos.get(&id).cloned().unwrap_or_else(|| format!("Posto {}", id)),
+                None => "Sem posto".to_string(),
+            },
+            combustivel,
+            combustivel_nome: nome_combustivel(combustivel),
+            abastecimentos: lista.len(),
+            preco_medio: precos.iter().sum::<f64>() / precos.len() as f64,
+            preco_minimo: precos.iter().copied().fold(f64::MAX, f64::min),
+            preco_maximo: precos.iter().copied().fold(f64::MIN, f64::max),
+            primeiro_preco: primeiro,
+            ultimo_preco: ultimo,
+            variacao_percentual: if primeiro > 0.0 { desvio_percentual(ultimo, primeiro) } else { 0.0 },
+            mensal: meses
+                .into_iter()
+                .map(|(mes, valores)| PrecoMensalPosto {
+                    mes,
+                    preco_medio: valores.iter().sum::<f64>() / valores.len() as f64,
+                    abastecimentos: valores.len(),
+                })
+                .collect(),
+        });
+    }
+
+    anomalias.sort_by(|a, b| a.veiculo.cmp(&b.veiculo).then_with(|| a.abastecimento_id.cmp(&b.abastecimento_id)));
+    RelatorioAbastecimento {
+        gerado_em: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        periodo_inicio: None,
+        periodo_fim: None,
+        total_abastecimentos: registros.len(),
+        litros_total: registros.iter().map(|r| r.litros).sum(),
+        valor_total: registros.iter().map(|r| r.valor).sum(),
+        veiculos: consumos,
+        postos: tendencias,
+        anomalias,
+    }
+}
+
+// --- Exportação ---
+
+fn resolver_destino(caminho: Option<String>, extensao: &str) -> Result<PathBuf, String> {
+    destino_exportacao(
+        caminho,
+        &format!("analise_abastecimento_{}.{}", Local::now().format("%Y%m%d_%H%M"), extensao),
+    )
+}
+
+fn formatar_data(data: Option<&str>) -> String {
+    data.and_then(|d| parse_data_hora(d, None))
+        .map(|d| d.format("%d/%m/%Y").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn descrever_anomalia(tipo: TipoAnomaliaAbastecimento) -> &'static str {
+    match tipo {
+        TipoAnomaliaAbastecimento::OdometroRegrediu => "Odômetro regrediu",
+        TipoAnomaliaAbastecimento::LitrosAcimaDoTanque => "Litros acima do tanque",
+        TipoAnomaliaAbastecimento::PrecoForaDoPadrao => "Preço fora do padrão",
+        TipoAnomaliaAbastecimento::ConsumoForaDoPadrao => "Consumo fora do padrão",
+    }
+}
+
+fn gerar_xlsx(relatorio: &RelatorioAbastecimento, destino: &Path) -> Result<(), String> {
+    let erro = |e: rust_xlsxwriter::XlsxError| format!("Erro ao gerar XLSX: {}", e);
+
+    let mut workbook = Workbook::new();
+    let negrito = Format::new().set_bold();
+    let duas_casas = Format::new().set_num_format("0.00");
+    let tres_casas = Format::new().set_num_format("0.000");
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Consumo").map_err(erro)?;
+    let cabecalho = ["Veículo", "Data", "Km inicial", "Km final", "Km rodados", "Litros", "Km/l", "Custo (R$)", "R$/km"];
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+    let mut linha: u32 = 1;
+    for veiculo in &relatorio.veiculos {
+        for trecho in &veiculo.trechos {
+            sheet.write_string(linha, 0, &veiculo.veiculo).map_err(erro)?;
+            sheet.write_string(linha, 1, formatar_data(trecho.data.as_deref())).map_err(erro)?;
+            sheet.write_number(linha, 2, trecho.km_inicial).map_err(erro)?;
+            sheet.write_number(linha, 3, trecho.km_final).map_err(erro)?;
+            sheet.write_number(linha, 4, trecho.km_rodados).map_err(erro)?;
+            sheet.write_number_with_format(linha, 5, trecho.litros, &duas_casas).map_err(erro)?;
+            sheet.write_number_with_format(linha, 6, trecho.km_por_litro, &duas_casas).map_err(erro)?;
+            sheet.write_number_with_format(linha, 7, trecho.custo, &duas_casas).map_err(erro)?;
+            sheet.write_number_with_format(linha, 8, trecho.custo_por_km, &tres_casas).map_err(erro)?;
+            linha += 1;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    for (coluna, largura) in [30.0, 12.0, 12.0, 12.0, 12.0, 10.0, 10.0, 12.0, 10.0].iter().enumerate() {
+        sheet.set_column_width(coluna as u16, *largura).map_err(erro)?;
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Preços por posto").map_err(erro)?;
+    let cabecalho = ["Posto", "Combustível", "Mês", "Abastecimentos", "Preço médio (R$/L)"];
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+    let mut linha: u32 = 1;
+    for posto in &relatorio.postos {
+        for mes in &posto.mensal {
+            sheet.write_string(linha, 0, &posto.posto).map_err(erro)?;
+            sheet.write_string(linha, 1, &posto.combustivel_nome).map_err(erro)?;
+            sheet.write_string(linha, 2, &mes.mes).map_err(erro)?;
+            sheet.write_number(linha, 3, mes.abastecimentos as f64).map_err(erro)?;
+            sheet.write_number_with_format(linha, 4, mes.preco_medio, &tres_casas).map_err(erro)?;
+            linha += 1;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    for (coluna, largura) in [30.0, 14.0, 10.0, 14.0, 18.0].iter().enumerate() {
+        sheet.set_column_width(coluna as u16, *largura).map_err(erro)?;
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Anomalias").map_err(erro)?;
+    let cabecalho = ["Veículo", "Data", "Abastecimento", "Tipo", "Descrição"];
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+    for (i, anomalia) in relatorio.anomalias.iter().enumerate() {
+        let linha = i as u32 + 1;
+        sheet.write_string(linha, 0, &anomalia.veiculo).map_err(erro)?;
+        sheet.write_string(linha, 1, formatar_data(anomalia.data.as_deref())).map_err(erro)?;
+        sheet.write_number(linha, 2, anomalia.abastecimento_id).map_err(erro)?;
+        sheet.write_string(linha, 3, descrever_anomalia(anomalia.tipo)).map_err(erro)?;
+        sheet.write_string(linha, 4, &anomalia.descricao).map_err(erro)?;
+    }
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    for (coluna, largura) in [30.0, 12.0, 14.0, 24.0, 70.0].iter().enumerate() {
+        sheet.set_column_width(coluna as u16, *largura).map_err(erro)?;
+    }
+
+    workbook.save(destino).map_err(erro)
+}
+
+fn gerar_html(relatorio: &RelatorioAbastecimento) -> String {
+    let opcional = |valor: Option<f64>, casas: usize| valor.map(|v| format!("{:.*}", casas, v)).unwrap_or_else(|| "-".to_string());
+
+    let mut veiculos = String::new();
+    for v in &relatorio.veiculos {
+        veiculos.push_str(&format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:.2}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escapar_html(&v.veiculo),
+            v.abastecimentos,
+            v.km_rodados,
+            v.litros_total,
+            v.valor_total,
+            opcional(v.km_por_litro_medio, 2),
+            opcional(v.km_por_litro_base, 2),
+            opcional(v.custo_por_km, 3),
+        ));
+    }
+
+    let mut postos = String::new();
+    for p in &relatorio.postos {
+        postos.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:.3}</td><td class=\"num\">{:+.1}%</td></tr>",
+            escapar_html(&p.posto),
+            escapar_html(&p.combustivel_nome),
+            p.abastecimentos,
+            p.preco_medio,
+            p.preco_minimo,
+            p.preco_maximo,
+            p.variacao_percentual,
+        ));
+    }
+
+    let mut anomalias = String::new();
+    for a in &relatorio.anomalias {
+        anomalias.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escapar_html(&a.veiculo),
+            formatar_data(a.data.as_deref()),
+            descrever_anomalia(a.tipo),
+            escapar_html(&a.descricao),
+        ));
+    }
+    if anomalias.is_empty() {
+        anomalias.push_str("<tr><td colspan=\"4\">Nenhuma anomalia encontrada.</td></tr>");
+    }
+
+    let introducao = format!(
+        "<p>Período: {} a {} &middot; {} abastecimento(s), {:.2} L, R$ {:.2} &middot; Gerado em {}</p>",
+        formatar_data(relatorio.periodo_inicio.as_deref()),
+        formatar_data(relatorio.periodo_fim.as_deref()),
+        relatorio.total_abastecimentos,
+        relatorio.litros_total,
+        relatorio.valor_total,
+        formatar_data(Some(&relatorio.gerado_em)),
+    );
+    let corpo = format!(
+        "<h3>Consumo por veículo</h3>{}<h3>Preço por posto</h3>{}<h3>Anomalias</h3>{}",
+        tabela_html(&["Veículo", "Abast.", "Km rodados", "Litros", "Valor (R$)", "Km/l médio", "Km/l base", "R$/km"], &veiculos),
+        tabela_html(&["Posto", "Combustível", "Abast.", "Médio", "Mínimo", "Máximo", "Variação"], &postos),
+        tabela_html(&["Veículo", "Data", "Tipo", "Descrição"], &anomalias),
+    );
+    pagina_relatorio("Análise de abastecimentos da frota", &introducao, &corpo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn abastecimento(id: u32, data: &str, km: i32, litros: &str, preco: &str) -> FrotaAbastecimento {
+        let litro = BigDecimal::from_str(litros).unwrap();
+        let valor_litro = BigDecimal::from_str(preco).unwrap();
+        FrotaAbastecimento {
+            id,
+            veiculo: Some(1),
+            motorista: Some(1),
+            valor: Some(&litro * &valor_litro),
+            valor_litro: Some(valor_litro),
+            litro: Some(litro),
+            combustivel: Some(1),
+            posto: Some(1),
+            data: Some(data.to_string()),
+            notafiscal: None,
+            quilometragem: Some(km),
+            comprovante_id: None,
+            aviso: None,
+        }
+    }
+
+    #[test]
+    fn test_consumo_e_anomalias() {
+        let abastecimentos = vec![
+            abastecimento(1, "2026-01-01", 10_000, "40", "6.00"),
+            abastecimento(2, "2026-01-10", 10_400, "40", "6.00"),
+            abastecimento(3, "2026-01-20", 10_800, "40", "6.10"),
+            abastecimento(4, "2026-01-30", 11_200, "40", "6.00"),
+            abastecimento(5, "2026-02-05", 11_400, "40", "6.00"),
+            abastecimento(6, "2026-02-10", 11_300, "70", "8.00"),
+        ];
+        let mut parametros = ParametrosAnaliseFrota::default();
+        parametros.capacidade_tanque.insert(1, 55.0);
+
+        let relatorio = montar_relatorio(&abastecimentos, &HashMap::new(), &HashMap::new(), &parametros);
+        let veiculo = &relatorio.veiculos[0];
+        assert_eq!(veiculo.trechos.len(), 4);
+        assert_eq!(veiculo.km_por_litro_base, Some(10.0));
+        assert_eq!(veiculo.km_rodados, 1_400);
+        assert_eq!(veiculo.trechos[0].custo_por_km, 0.6);
+
+        let tipos: Vec<(u32, TipoAnomaliaAbastecimento)> = relatorio.anomalias.iter().map(|a| (a.abastecimento_id, a.tipo)).collect();
+        assert!(tipos.contains(&(5, TipoAnomaliaAbastecimento::ConsumoForaDoPadrao)));
+        assert!(tipos.contains(&(6, TipoAnomaliaAbastecimento::OdometroRegrediu)));
+        assert!(tipos.contains(&(6, TipoAnomaliaAbastecimento::LitrosAcimaDoTanque)));
+        assert!(tipos.contains(&(6, TipoAnomaliaAbastecimento::PrecoForaDoPadrao)));
+        assert!(!tipos.contains(&(3, TipoAnomaliaAbastecimento::PrecoForaDoPadrao)));
+
+        let posto = &relatorio.postos[0];
+        assert_eq!(posto.primeiro_preco, 6.0);
+        assert_eq!(posto.ultimo_preco, 8.0);
+        assert_eq!(posto.mensal.len(), 2);
+    }
+}
@@ -0,0 +1,385 @@
+use base64::{Engine as _, engine::general_purpose};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use lazy_static::lazy_static;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{command, AppHandle};
+
+use crate::config::get_api_url;
+use crate::controller::frota::abastecimento_controller::{ApiResponse, FrotaAbastecimento};
+
+/// Maior lado (px) da foto do comprovante guardada na API
+const LADO_MAXIMO_COMPROVANTE: u32 = 1600;
+/// Maior lado (px) da miniatura usada nas listagens
+const LADO_MAXIMO_MINIATURA: u32 = 320;
+const QUALIDADE_COMPROVANTE: u8 = 82;
+const QUALIDADE_MINIATURA: u8 = 70;
+/// Fotos acima disso nem são decodificadas (câmeras de celular ficam bem abaixo)
+const TAMANHO_MAXIMO_ORIGINAL: usize = 25 * 1024 * 1024;
+/// Miniaturas guardadas em memória; as mais antigas saem primeiro
+const MAXIMO_MINIATURAS: usize = 500;
+
+lazy_static! {
+    // Miniaturas já baixadas nesta sessão, por id do comprovante
+    static ref MINIATURAS: Mutex<CacheMiniaturas> = Mutex::new(CacheMiniaturas::default());
+}
+
+#[derive(Debug, Default)]
+struct CacheMiniaturas {
+    itens: HashMap<u32, String>,
+    ordem: VecDeque<u32>,
+}
+
+impl CacheMiniaturas {
+    fn obter(&self, comprovante_id: u32) -> Option<String> {
+        self.itens.get(&comprovante_id).cloned()
+    }
+
+    fn guardar(&mut self, comprovante_id: u32, miniatura: String) {
+        if self.itens.insert(comprovante_id, miniatura).is_none() {
+            self.ordem.push_back(comprovante_id);
+        }
+        while self.ordem.len() > MAXIMO_MINIATURAS {
+            if let Some(antigo) = self.ordem.pop_front() {
+                self.itens.remove(&antigo);
+            }
+        }
+    }
+}
+
+/// Payload do frontend: foto escolhida no disco ou já em base64
+#[derive(Debug, Deserialize)]
+pub struct EnviarComprovantePayload {
+    pub abastecimento_id: Option<u32>,
+    pub nome_arquivo: Option<String>,
+    pub caminho_origem: Option<String>,
+    pub conteudo_base64: Option<String>,
+}
+
+/// Corpo enviado ao backend: foto normalizada em JPEG e miniatura
+#[derive(Debug, Serialize)]
+struct ComprovanteApiPayload {
+    abastecimento_id: Option<u32>,
+    nome_arquivo: String,
+    mime: String,
+    largura: u32,
+    altura: u32,
+    conteudo_base64: String,
+    miniatura_base64: String,
+}
+
+/// Metadados do comprovante (sem o conteúdo da imagem)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComprovanteAbastecimento {
+    pub id: u32,
+    pub abastecimento_id: Option<u32>,
+    pub nome_arquivo: String,
+    pub mime: String,
+    pub largura: u32,
+    pub altura: u32,
+    pub tamanho: u64,
+    pub criado_em: Option<String>,
+}
+
+/// Imagem (ou miniatura) para exibição no app
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConteudoComprovante {
+    pub id: u32,
+    pub mime: String,
+    pub conteudo_base64: String,
+}
+
+#[derive(Debug, Serialize)]
+struct VincularComprovantePayload {
+    comprovante_id: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImagemNormalizada {
+    pub conteudo: Vec<u8>,
+    pub miniatura: Vec<u8>,
+    pub largura: u32,
+    pub altura: u32,
+}
+
+/// Decodifica a foto (JPEG, PNG ou WebP), corrige a orientação EXIF, reduz para
+/// no máximo `LADO_MAXIMO_COMPROVANTE` e regrava tudo em JPEG junto com a miniatura.
+pub fn normalizar_imagem_comprovante(bytes: &[u8]) -> Result<ImagemNormalizada, String> {
+    if bytes.is_empty() {
+        return Err("A foto do comprovante está vazia.".to_string());
+    }
+    if bytes.len() > TAMANHO_MAXIMO_ORIGINAL {
+        return Err(format!(
+            "A foto do comprovante tem {:.1} MB; o limite é {} MB.",
+            bytes.len() as f64 / (1024.0 * 1024.0),
+            TAMANHO_MAXIMO_ORIGINAL / (1024 * 1024)
+        ));
+    }
+
+    let leitor = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Erro ao ler a foto do comprovante: {}", e))?;
+
+    match leitor.format() {
+        Some(ImageFormat::Jpeg) | Some(ImageFormat::Png) | Some(ImageFormat::WebP) => {}
+        _ => return Err("Formato de imagem não suportado. Use JPEG, PNG ou WebP.".to_string()),
+    }
+
+    let mut decoder = leitor
+        .into_decoder()
+        .map_err(|e| format!("Erro ao decodificar a foto do comprovante: {}", e))?;
+    let orientacao = decoder
+        .orientation()
+        .map_err(|e| format!("Erro ao ler a orientação da foto: {}", e))?;
+    let mut imagem = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Erro ao decodificar a foto do comprovante: {}", e))?;
+    imagem.apply_orientation(orientacao);
+
+    let imagem = reduzir(imagem, LADO_MAXIMO_COMPROVANTE);
+    let miniatura = reduzir(imagem.clone(), LADO_MAXIMO_MINIATURA);
+
+    Ok(ImagemNormalizada {
+        largura: imagem.width(),
+        altura: imagem.height(),
+        conteudo: codificar_jpeg(&imagem, QUALIDADE_COMPROVANTE)?,
+        miniatura: codificar_jpeg(&miniatura, QUALIDADE_MINIATURA)?,
+    })
+}
+
+fn reduzir(imagem: DynamicImage, lado_maximo: u32) -> DynamicImage {
+    if imagem.width() <= lado_maximo && imagem.height() <= lado_maximo {
+        imagem
+    } else {
+        imagem.resize(lado_maximo, lado_maximo, FilterType::Triangle)
+    }
+}
+
+fn codificar_jpeg(imagem: &DynamicImage, qualidade: u8) -> Result<Vec<u8>, String> {
+    // JPEG não tem canal alfa: PNG/WebP transparentes viram RGB
+    let rgb = DynamicImage::ImageRgb8(imagem.to_rgb8());
+    let mut saida = Vec::new();
+    rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut saida, qualidade))
+        .map_err(|e| format!("Erro ao gerar JPEG do comprovante: {}", e))?;
+    Ok(saida)
+}
+
+/// Nome final do anexo, sempre com extensão .jpg
+fn nome_comprovante(nome_arquivo: Option<&str>) -> String {
+    let base = nome_arquivo
+        .map(|n| Path::new(n.trim()))
+        .and_then(|p| p.file_stem())
+        .and_then(|s| s.to_str())
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or("comprovante");
+    format!("{}.jpg", base)
+}
+
+/// Envia a foto já normalizada à API como anexo separado, devolvendo o comprovante criado.
+/// Usado também pelo cadastro e pela edição do abastecimento quando o frontend ainda manda `foto`.
+pub async fn enviar_comprovante(
+    app_handle: &AppHandle,
+    imagem: &ImagemNormalizada,
+    nome_arquivo: Option<&str>,
+    abastecimento_id: Option<u32>,
+) -> Result<ComprovanteAbastecimento, String> {
+    let payload = ComprovanteApiPayload {
+        abastecimento_id,
+        nome_arquivo: nome_comprovante(nome_arquivo),
+        mime: "image/jpeg".to_string(),
+        largura: imagem.largura,
+        altura: imagem.altura,
+        conteudo_base64: general_purpose::STANDARD.encode(&imagem.conteudo),
+        miniatura_base64: general_purpose::STANDARD.encode(&imagem.miniatura),
+    };
+
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Erro ao criar cliente HTTP: {}", e))?;
+
+    let api_url = get_api_url(app_handle);
+    let url = format!("{}/frota/abastecimentos/comprovantes", api_url);
+
+    let response = client
+        .post(&url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao enviar comprovante: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Falha ao ler corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<ComprovanteAbastecimento> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if !api_response.success {
+        return Err(api_response.message.unwrap_or_else(|| "A API recusou o comprovante sem mensagem.".to_string()));
+    }
+    let comprovante = api_response.data
+        .ok_or_else(|| "A API retornou sucesso, mas sem dados do comprovante.".to_string())?;
+
+    // A miniatura acabou de ser gerada aqui; evita buscá-la de volta na listagem
+    if let Ok(mut cache) = MINIATURAS.lock() {
+        cache.guardar(comprovante.id, payload.miniatura_base64);
+    }
+
+    Ok(comprovante)
+}
+
+async fn buscar_conteudo(app_handle: &AppHandle, caminho: &str) -> Result<ConteudoComprovante, String> {
+    let api_url = get_api_url(app_handle);
+    let url = format!("{}/frota/abastecimentos/comprovantes/{}", api_url, caminho);
+
+    let response = Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao buscar comprovante: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Falha ao ler corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<ConteudoComprovante> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if api_response.success {
+        api_response.data.ok_or_else(|| "Comprovante não encontrado.".to_string())
+    } else {
+        Err(api_response.message.unwrap_or_else(|| "Falha ao buscar comprovante.".to_string()))
+    }
+}
+
+#[command]
+pub async fn enviar_comprovante_abastecimento(
+    app_handle: AppHandle,
+    payload: EnviarComprovantePayload,
+) -> Result<ComprovanteAbastecimento, String> {
+    let bytes = match (&payload.caminho_origem, &payload.conteudo_base64) {
+        (Some(caminho), _) if !caminho.trim().is_empty() => std::fs::read(caminho)
+            .map_err(|e| format!("Erro ao ler o arquivo '{}': {}", caminho, e))?,
+        (_, Some(conteudo)) => general_purpose::STANDARD
+            .decode(conteudo.trim())
+            .map_err(|e| format!("Conteúdo base64 inválido: {}", e))?,
+        _ => return Err("Informe o arquivo ou o conteúdo da foto do comprovante.".to_string()),
+    };
+
+    let imagem = normalizar_imagem_comprovante(&bytes)?;
+    let nome = payload.nome_arquivo.as_deref().or(payload.caminho_origem.as_deref());
+    enviar_comprovante(&app_handle, &imagem, nome, payload.abastecimento_id).await
+}
+
+/// Miniatura em base64 para as listagens; o frontend chama sob demanda, linha a linha.
+#[command]
+pub async fn obter_miniatura_comprovante(app_handle: AppHandle, comprovante_id: u32) -> Result<String, String> {
+    if let Some(miniatura) = MINIATURAS.lock().ok().and_then(|c| c.obter(comprovante_id)) {
+        return Ok(miniatura);
+    }
+
+    let conteudo = buscar_conteudo(&app_handle, &format!("{}/miniatura", comprovante_id)).await?;
+    if let Ok(mut cache) = MINIATURAS.lock() {
+        cache.guardar(comprovante_id, conteudo.conteudo_base64.clone());
+    }
+    Ok(conteudo.conteudo_base64)
+}
+
+/// Foto completa do comprovante, só quando o usuário abre o abastecimento
+#[command]
+pub async fn obter_comprovante_abastecimento(app_handle: AppHandle, comprovante_id: u32) -> Result<ConteudoComprovante, String> {
+    buscar_conteudo(&app_handle, &comprovante_id.to_string()).await
+}
+
+/// Associa um comprovante já enviado a um abastecimento existente
+#[command]
+pub async fn vincular_comprovante_abastecimento(
+    app_handle: AppHandle,
+    abastecimento_id: u32,
+    comprovante_id: u32,
+) -> Result<FrotaAbastecimento, String> {
+    let api_url = get_api_url(&app_handle);
+    let url = format!("{}/frota/abastecimentos/{}/comprovante", api_url, abastecimento_id);
+
+    let response = Client::new()
+        .put(&url)
+        .json(&VincularComprovantePayload { comprovante_id })
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao vincular comprovante: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Falha ao ler corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<FrotaAbastecimento> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if api_response.success {
+        api_response.data.ok_or_else(|| "A API retornou sucesso, mas sem dados do abastecimento.".to_string())
+    } else {
+        Err(api_response.message.unwrap_or_else(|| "A API recusou o vínculo sem mensagem.".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn png(largura: u32, altura: u32) -> Vec<u8> {
+        let img = ImageBuffer::from_pixel(largura, altura, Rgba([200u8, 30, 30, 128]));
+        let mut saida = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut Cursor::new(&mut saida), ImageFormat::Png)
+            .unwrap();
+        saida
+    }
+
+    #[test]
+    fn test_reduz_e_converte_para_jpeg() {
+        let imagem = normalizar_imagem_comprovante(&png(3200, 1000)).unwrap();
+
+        assert_eq!((imagem.largura, imagem.altura), (1600, 500));
+        assert_eq!(&imagem.conteudo[..2], &[0xFF, 0xD8]);
+
+        let miniatura = image::load_from_memory(&imagem.miniatura).unwrap();
+        assert_eq!((miniatura.width(), miniatura.height()), (320, 100));
+    }
+
+    #[test]
+    fn test_mantem_tamanho_de_fotos_pequenas_e_recusa_outros_formatos() {
+        let imagem = normalizar_imagem_comprovante(&png(400, 300)).unwrap();
+        assert_eq!((imagem.largura, imagem.altura), (400, 300));
+
+        assert!(normalizar_imagem_comprovante(b"%PDF-1.4 nota").is_err());
+        assert_eq!(nome_comprovante(Some("C:/fotos/IMG_001.PNG")), "IMG_001.jpg");
+        assert_eq!(nome_comprovante(None), "comprovante.jpg");
+    }
+
+    #[test]
+    fn test_cache_de_miniaturas_descarta_as_mais_antigas() {
+        let mut cache = CacheMiniaturas::default();
+        for id in 0..(MAXIMO_MINIATURAS as u32 + 10) {
+            cache.guardar(id, id.to_string());
+        }
+        cache.guardar(20, "de novo".to_string());
+
+        assert_eq!(cache.itens.len(), MAXIMO_MINIATURAS);
+        assert_eq!(cache.obter(9), None);
+        assert_eq!(cache.obter(10).as_deref(), Some("10"));
+        assert_eq!(cache.obter(20).as_deref(), Some("de novo"));
+    }
+}
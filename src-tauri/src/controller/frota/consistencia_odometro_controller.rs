@@ -0,0 +1,669 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::controller::frota::abastecimento_controller::{
+    buscar_abastecimento, buscar_abastecimento_filtrado, FiltrosAbastecimento, FrotaAbastecimento, FrotaAbastecimentoInput,
+    UpdateFrotaAbastecimentoInput,
+};
+use crate::controller::frota::manutencao_controller::{buscar_manutencoes, buscar_manutencoes_do_veiculo, FrotaManutencao};
+use crate::controller::frota::viagem_controller::{buscar_viagens, buscar_viagens_filtradas, FrotaViagem, FrotaViagemInput};
+use crate::utils::parse_data_hora;
+
+// Id usado para o registro ainda não salvo durante a validação
+const NOVO_REGISTRO: u32 = 0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrigemLeitura {
+    Viagem,
+    Abastecimento,
+    Manutencao,
+}
+
+/// Leitura do odômetro de um veículo em um momento, vinda de qualquer um dos cadastros
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeituraOdometro {
+    pub origem: OrigemLeitura,
+    pub registro_id: u32,
+    pub veiculo_id: u32,
+    pub data: NaiveDateTime,
+    pub km: i64,
+    /// "inicio"/"fim" para viagens
+    pub marco: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct IntervaloViagem {
+    id: u32,
+    veiculo_id: u32,
+    motorista_id: u32,
+    inicio: Option<NaiveDateTime>,
+    // Viagem sem término é considerada em andamento
+    fim: Option<NaiveDateTime>,
+    km_inicial: Option<u32>,
+    km_final: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoInconsistencia {
+    TerminoAntesDoInicio,
+    KmFinalMenorQueInicial,
+    ViagemSobrepostaVeiculo,
+    ViagemSobrepostaMotorista,
+    KmRegrediu,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GravidadeInconsistencia {
+    /// Impede o cadastro
+    Erro,
+    /// Só avisa; usado quando a leitura envolvida é de manutenção, cujo km costuma ser aproximado
+    Aviso,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ReferenciaRegistro {
+    pub origem: OrigemLeitura,
+    pub registro_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InconsistenciaFrota {
+    pub tipo: TipoInconsistencia,
+    pub gravidade: GravidadeInconsistencia,
+    pub veiculo_id: Option<u32>,
+    pub motorista_id: Option<u32>,
+    pub registro: ReferenciaRegistro,
+    /// Registro com o qual o conflito acontece
+    pub relacionado: Option<ReferenciaRegistro>,
+    pub data: Option<String>,
+    pub descricao: String,
+}
+
+impl InconsistenciaFrota {
+    fn envolve(&self, origem: OrigemLeitura, registro_id: u32) -> bool {
+        let alvo = ReferenciaRegistro { origem, registro_id };
+        self.registro == alvo || self.relacionado == Some(alvo)
+    }
+}
+
+// --- Comandos ---
+
+/// Leituras de odômetro do veículo (viagens, abastecimentos e manutenções) em ordem cronológica
+#[command]
+pub async fn linha_do_tempo_odometro(app_handle: AppHandle, veiculo_id: u32) -> Result<Vec<LeituraOdometro>, String> {
+    let (viagens, leituras) = carregar_historico(&app_handle, Some(veiculo_id)).await?;
+    let mut linha = leituras_viagens(&viagens);
+    linha.extend(leituras);
+    linha.retain(|l| l.veiculo_id == veiculo_id);
+    ordenar_leituras(&mut linha);
+    Ok(linha)
+}
+
+/// Inconsistências já gravadas, para limpeza do histórico
+#[command]
+pub async fn listar_inconsistencias_frota(
+    app_handle: AppHandle,
+    veiculo_id: Option<u32>,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    let (mut viagens, leituras) = carregar_historico(&app_handle, veiculo_id).await?;
+    let Some(id) = veiculo_id else {
+        return Ok(analisar_consistencia(&viagens, &leituras));
+    };
+
+    let do_veiculo: Vec<u32> = viagens.iter().map(|v| v.id).collect();
+    let motoristas: Vec<u32> = viagens.iter().map(|v| v.motorista_id).collect();
+    incluir_viagens_dos_motoristas(&app_handle, &mut viagens, motoristas).await?;
+    let mut inconsistencias = analisar_consistencia(&viagens, &leituras);
+    inconsistencias.retain(|i| {
+        i.veiculo_id == Some(id) || do_veiculo.iter().any(|v| i.envolve(OrigemLeitura::Viagem, *v))
+    });
+    Ok(inconsistencias)
+}
+
+/// Pré-validação usada pelo formulário para mostrar erros e avisos antes de salvar
+#[command]
+pub async fn verificar_consistencia_viagem(
+    app_handle: AppHandle,
+    payload: FrotaViagemInput,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    inconsistencias_nova_viagem(&app_handle, &payload).await
+}
+
+#[command]
+pub async fn verificar_consistencia_abastecimento(
+    app_handle: AppHandle,
+    payload: FrotaAbastecimentoInput,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    inconsistencias_novo_abastecimento(&app_handle, &payload).await
+}
+
+// --- Validação dos cadastros ---
+
+pub async fn inconsistencias_nova_viagem(
+    app_handle: &AppHandle,
+    payload: &FrotaViagemInput,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    let (mut viagens, leituras) = carregar_historico(app_handle, Some(payload.veiculo)).await?;
+    incluir_viagens_dos_motoristas(app_handle, &mut viagens, [payload.motorista]).await?;
+    let viagem = IntervaloViagem {
+        id: NOVO_REGISTRO,
+        veiculo_id: payload.veiculo,
+        motorista_id: payload.motorista,
+        inicio: parse_data_hora(&payload.data_inicio, None),
+        fim: payload.data_termino.as_deref().and_then(|d| parse_data_hora(d, None)),
+        km_inicial: Some(payload.quilometragem_inicial),
+        km_final: payload.quilometragem_final,
+    };
+    Ok(inconsistencias_da_viagem(viagens, &leituras, viagem))
+}
+
+/// Mesma validação da nova viagem, com a versão editada no lugar da gravada
+pub async fn inconsistencias_viagem_editada(
+    app_handle: &AppHandle,
+    viagem: &FrotaViagem,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    let (mut viagens, leituras) = carregar_historico(app_handle, Some(viagem.veiculo)).await?;
+    incluir_viagens_dos_motoristas(app_handle, &mut viagens, [viagem.motorista]).await?;
+    Ok(inconsistencias_da_viagem(viagens, &leituras, intervalo_viagem(viagem)))
+}
+
+pub async fn inconsistencias_novo_abastecimento(
+    app_handle: &AppHandle,
+    payload: &FrotaAbastecimentoInput,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    let (Some(veiculo_id), Some(km), Some(data)) = (
+        payload.veiculo,
+        payload.quilometragem,
+        payload.data.as_deref().and_then(|d| parse_data_hora(d, None)),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let (viagens, leituras) = carregar_historico(app_handle, Some(veiculo_id)).await?;
+    let leitura = LeituraOdometro {
+        origem: OrigemLeitura::Abastecimento,
+        registro_id: NOVO_REGISTRO,
+        veiculo_id,
+        data,
+        km: i64::from(km),
+        marco: None,
+    };
+    Ok(inconsistencias_do_abastecimento(&viagens, leituras, leitura))
+}
+
+/// Mesma validação do novo abastecimento, com a versão editada no lugar da gravada
+pub async fn inconsistencias_abastecimento_editado(
+    app_handle: &AppHandle,
+    id: u32,
+    payload: &UpdateFrotaAbastecimentoInput,
+) -> Result<Vec<InconsistenciaFrota>, String> {
+    let gravado = buscar_abastecimento(app_handle.clone())
+        .await?
+        .into_iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("Abastecimento {} não encontrado", id))?;
+
+    let (Some(veiculo_id), Some(km), Some(data)) = (
+        payload.veiculo.or(gravado.veiculo),
+        payload.quilometragem.or(gravado.quilometragem),
+        payload.data.as_deref().or(gravado.data.as_deref()).and_then(|d| parse_data_hora(d, None)),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let (viagens, leituras) = carregar_historico(app_handle, Some(veiculo_id)).await?;
+    let leitura = LeituraOdometro {
+        origem: OrigemLeitura::Abastecimento,
+        registro_id: id,
+        veiculo_id,
+        data,
+        km: i64::from(km),
+        marco: None,
+    };
+    Ok(inconsistencias_do_abastecimento(&viagens, leituras, leitura))
+}
+
+/// Leitura mais recente do odômetro de cada veículo
+pub async fn ultimas_leituras(app_handle: &AppHandle) -> Result<HashMap<u32, LeituraOdometro>, String> {
+    let (viagens, leituras) = carregar_historico(app_handle, None).await?;
+    let mut linha = leituras_viagens(&viagens);
+    linha.extend(leituras);
+    ordenar_leituras(&mut linha);
+    Ok(linha.into_iter().map(|l| (l.veiculo_id, l)).collect())
+}
+
+/// Falha com os erros encontrados; avisos são apenas registrados no log
+pub fn rejeitar_inconsistencias(inconsistencias: &[InconsistenciaFrota]) -> Result<(), String> {
+    for aviso in inconsistencias.iter().filter(|i| i.gravidade == GravidadeInconsistencia::Aviso) {
+        println!("Aviso de consistência do odômetro: {}", aviso.descricao);
+    }
+
+    let erros: Vec<&str> = inconsistencias
+        .iter()
+        .filter(|i| i.gravidade == GravidadeInconsistencia::Erro)
+        .map(|i| i.descricao.as_str())
+        .collect();
+    if erros.is_empty() {
+        Ok(())
+    } else {
+        Err(erros.join("; "))
+    }
+}
+
+// --- Histórico ---
+
+/// Viagens e leituras do veículo (de todos com `None`). Viagens do mesmo motorista em
+/// outros veículos entram por `incluir_viagens_dos_motoristas`, só onde a sobreposição importa
+async fn carregar_historico(
+    app_handle: &AppHandle,
+    veiculo_id: Option<u32>,
+) -> Result<(Vec<IntervaloViagem>, Vec<LeituraOdometro>), String> {
+    let viagens = match veiculo_id {
+        Some(id) => buscar_viagens_filtradas(app_handle, Some(id), None).await?,
+        None => buscar_viagens(app_handle.clone()).await?,
+    };
+    let abastecimentos = buscar_abastecimento_filtrado(
+        app_handle.clone(),
+        FiltrosAbastecimento {
+            veiculo_id,
+            motorista_id: None,
+            posto_id: None,
+            combustivel_id: None,
+            data_inicio: None,
+            data_fim: None,
+            valor_minimo: None,
+            valor_maximo: None,
+        },
+    )
+    .await?;
+    let manutencoes = match veiculo_id {
+        Some(id) => buscar_manutencoes_do_veiculo(app_handle, id).await?,
+        None => buscar_manutencoes(app_handle.clone()).await?,
+    };
+
+    let mut leituras: Vec<LeituraOdometro> = abastecimentos.iter().filter_map(leitura_abastecimento).collect();
+    leituras.extend(manutencoes.iter().filter_map(leitura_manutencao));
+    Ok((viagens.iter().map(intervalo_viagem).collect(), leituras))
+}
+
+async fn incluir_viagens_dos_motoristas(
+    app_handle: &AppHandle,
+    viagens: &mut Vec<IntervaloViagem>,
+    motoristas: impl IntoIterator<Item = u32>,
+) -> Result<(), String> {
+    for motorista_id in motoristas.into_iter().collect::<BTreeSet<u32>>() {
+        for viagem in buscar_viagens_filtradas(app_handle, None, Some(motorista_id)).await? {
+            if !viagens.iter().any(|v| v.id == viagem.id) {
+                viagens.push(intervalo_viagem(&viagem));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Inconsistências que envolvem a viagem, descartando a versão gravada de mesmo id
+fn inconsistencias_da_viagem(
+    mut viagens: Vec<IntervaloViagem>,
+    leituras: &[LeituraOdometro],
+    viagem: IntervaloViagem,
+) -> Vec<InconsistenciaFrota> {
+    let id = viagem.id;
+    viagens.retain(|v| v.id != id);
+    viagens.push(viagem);
+
+    let mut inconsistencias = analisar_consistencia(&viagens, leituras);
+    inconsistencias.retain(|i| i.envolve(OrigemLeitura::Viagem, id));
+    inconsistencias
+}
+
+fn inconsistencias_do_abastecimento(
+    viagens: &[IntervaloViagem],
+    mut leituras: Vec<LeituraOdometro>,
+    leitura: LeituraOdometro,
+) -> Vec<InconsistenciaFrota> {
+    let id = leitura.registro_id;
+    leituras.retain(|l| !(l.origem == OrigemLeitura::Abastecimento && l.registro_id == id));
+    leituras.push(leitura);
+
+    let mut inconsistencias = analisar_consistencia(viagens, &leituras);
+    inconsistencias.retain(|i| i.envolve(OrigemLeitura::Abastecimento, id));
+    inconsistencias
+}
+
+fn intervalo_viagem(viagem: &FrotaViagem) -> IntervaloViagem {
+    IntervaloViagem {
+        id: viagem.id,
+        veiculo_id: viagem.veiculo,
+        motorista_id: viagem.motorista,
+        inicio: parse_data_hora(&viagem.data_inicio, None),
+        fim: viagem.data_termino.as_deref().and_then(|d| parse_data_hora(d, None)),
+        km_inicial: viagem.quilometragem_inicial,
+        km_final: viagem.quilometragem_final,
+    }
+}
+
+fn leitura_abastecimento(abastecimento: &FrotaAbastecimento) -> Option<LeituraOdometro> {
+    Some(LeituraOdometro {
+        origem: OrigemLeitura::Abastecimento,
+        registro_id: abastecimento.id,
+        veiculo_id: abastecimento.veiculo?,
+        data: parse_data_hora(abastecimento.data.as_deref()?, None)?,
+        km: i64::from(abastecimento.quilometragem?),
+        marco: None,
+    })
+}
+
+fn leitura_manutencao(manutencao: &FrotaManutencao) -> Option<LeituraOdometro> {
+    Some(LeituraOdometro {
+        origem: OrigemLeitura::Manutencao,
+        registro_id: manutencao.id,
+        veiculo_id: manutencao.veiculo?,
+        data: manutencao.data_servico()?,
+        km: i64::from(manutencao.km?),
+        marco: None,
+    })
+}
+
+fn leituras_viagens(viagens: &[IntervaloViagem]) -> Vec<LeituraOdometro> {
+    let mut leituras = Vec::new();
+    for viagem in viagens {
+        let Some(inicio) = viagem.inicio else {
+            continue;
+        };
+        if let Some(km) = viagem.km_inicial {
+            leituras.push(LeituraOdometro {
+                origem: OrigemLeitura::Viagem,
+                registro_id: viagem.id,
+                veiculo_id: viagem.veiculo_id,
+                data: inicio,
+                km: i64::from(km),
+                marco: Some("inicio".to_string()),
+            });
+        }
+        // Sem término a leitura final fica no início; a ordenação por km a coloca depois da inicial
+        if let Some(km) = viagem.km_final {
+            leituras.push(LeituraOdometro {
+                origem: OrigemLeitura::Viagem,
+                registro_id: viagem.id,
+                veiculo_id: viagem.veiculo_id,
+                data: viagem.fim.filter(|f| *f >= inicio).unwrap_or(inicio),
+                km: i64::from(km),
+                marco: Some("fim".to_string()),
+            });
+        }
+    }
+    leituras
+}
+
+// Datas costumam vir sem hora; no mesmo instante a ordem do km é a única plausível
+fn ordenar_leituras(leituras: &mut [LeituraOdometro]) {
+    leituras.sort_by_key(|l| (l.data, l.km, l.registro_id));
+}
+
+// --- Análise ---
+
+fn referencia(origem: OrigemLeitura, registro_id: u32) -> ReferenciaRegistro {
+    ReferenciaRegistro { origem, registro_id }
+}
+
+fn formatar(data: NaiveDateTime) -> String {
+    data.format("%d/%m/%Y %H:%M").to_string()
+}
+
+fn nome_origem(origem: OrigemLeitura) -> &'static str {
+    match origem {
+        OrigemLeitura::Viagem => "viagem",
+        OrigemLeitura::Abastecimento => "abastecimento",
+        OrigemLeitura::Manutencao => "manutenção",
+    }
+}
+
+fn descrever_registro(registro: ReferenciaRegistro) -> String {
+    if registro.registro_id == NOVO_REGISTRO {
+        format!("{} informada", nome_origem(registro.origem))
+    } else {
+        format!("{} #{}", nome_origem(registro.origem), registro.registro_id)
+    }
+}
+
+fn sobrepoe(a: &IntervaloViagem, b: &IntervaloViagem) -> bool {
+    let (Some(inicio_a), Some(inicio_b)) = (a.inicio, b.inicio) else {
+        return false;
+    };
+    inicio_a < b.fim.unwrap_or(NaiveDateTime::MAX) && inicio_b < a.fim.unwrap_or(NaiveDateTime::MAX)
+}
+
+fn analisar_consistencia(viagens: &[IntervaloViagem], leituras: &[LeituraOdometro]) -> Vec<InconsistenciaFrota> {
+    let mut inconsistencias = Vec::new();
+
+    for viagem in viagens {
+        let registro = referencia(OrigemLeitura::Viagem, viagem.id);
+        if let (Some(inicio), Some(fim)) = (viagem.inicio, viagem.fim) {
+            if fim < inicio {
+                inconsistencias.push(InconsistenciaFrota {
+                    tipo: TipoInconsistencia::TerminoAntesDoInicio,
+                    gravidade: GravidadeInconsistencia::Erro,
+                    veiculo_id: Some(viagem.veiculo_id),
+                    motorista_id: Some(viagem.motorista_id),
+                    registro,
+                    relacionado: None,
+                    data: Some(formatar(inicio)),
+                    descricao: format!(
+                        "Término da {} ({}) é anterior ao início ({})",
+                        descrever_registro(registro),
+                        formatar(fim),
+                        formatar(inicio)
+                    ),
+                });
+            }
+        }
+        if let (Some(km_inicial), Some(km_final)) = (viagem.km_inicial, viagem.km_final) {
+            if km_final < km_inicial {
+                inconsistencias.push(InconsistenciaFrota {
+                    tipo: TipoInconsistencia::KmFinalMenorQueInicial,
+                    gravidade: GravidadeInconsistencia::Erro,
+                    veiculo_id: Some(viagem.veiculo_id),
+                    motorista_id: Some(viagem.motorista_id),
+                    registro,
+                    relacionado: None,
+                    data: viagem.inicio.map(formatar),
+                    descricao: format!(
+                        "Km final da {} ({}) é menor que o inicial ({})",
+                        descrever_registro(registro),
+                        km_final,
+                        km_inicial
+                    ),
+                });
+            }
+        }
+    }
+
+    // Sobreposição de viagens do mesmo veículo ou do mesmo motorista
+    for (i, a) in viagens.iter().enumerate() {
+        for b in &viagens[i + 1..] {
+            if !sobrepoe(a, b) {
+                continue;
+            }
+            let (registro, relacionado) = (referencia(OrigemLeitura::Viagem, a.id), referencia(OrigemLeitura::Viagem, b.id));
+            if a.veiculo_id == b.veiculo_id {
+                inconsistencias.push(InconsistenciaFrota {
+                    tipo: TipoInconsistencia::ViagemSobrepostaVeiculo,
+                    gravidade: GravidadeInconsistencia::Erro,
+                    veiculo_id: Some(a.veiculo_id),
+                    motorista_id: None,
+                    registro,
+                    relacionado: Some(relacionado),
+                    data: a.inicio.map(formatar),
+                    descricao: format!(
+                        "{} e {} do mesmo veículo acontecem ao mesmo tempo",
+                        descrever_registro(registro),
+                        descrever_registro(relacionado)
+                    ),
+                });
+            }
+            if a.motorista_id == b.motorista_id {
+                inconsistencias.push(InconsistenciaFrota {
+                    tipo: TipoInconsistencia::ViagemSobrepostaMotorista,
+                    gravidade: GravidadeInconsistencia::Erro,
+                    veiculo_id: Some(a.veiculo_id),
+                    motorista_id: Some(a.motorista_id),
+                    registro,
+                    relacionado: Some(relacionado),
+                    data: a.inicio.map(formatar),
+                    descricao: format!(
+                        "{} e {} do mesmo motorista acontecem ao mesmo tempo",
+                        descrever_registro(registro),
+                        descrever_registro(relacionado)
+                    ),
+                });
+            }
+        }
+    }
+
+    // Km menor que o da leitura imediatamente anterior do veículo. Comparar só com a
+    // vizinha faz uma leitura digitada errado aparecer uma vez, e não contra todo o
+    // histórico seguinte
+    let mut por_veiculo: BTreeMap<u32, Vec<LeituraOdometro>> = BTreeMap::new();
+    for leitura in leituras_viagens(viagens).into_iter().chain(leituras.iter().cloned()) {
+        por_veiculo.entry(leitura.veiculo_id).or_default().push(leitura);
+    }
+    for (veiculo_id, mut linha) in por_veiculo {
+        ordenar_leituras(&mut linha);
+        for par in linha.windows(2) {
+            let (anterior, leitura) = (&par[0], &par[1]);
+            if leitura.km >= anterior.km {
+                continue;
+            }
+            let registro = referencia(leitura.origem, leitura.registro_id);
+            let relacionado = referencia(anterior.origem, anterior.registro_id);
+            let envolve_manutencao =
+                leitura.origem == OrigemLeitura::Manutencao || anterior.origem == OrigemLeitura::Manutencao;
+            inconsistencias.push(InconsistenciaFrota {
+                tipo: TipoInconsistencia::KmRegrediu,
+                gravidade: if envolve_manutencao { GravidadeInconsistencia::Aviso } else { GravidadeInconsistencia::Erro },
+                veiculo_id: Some(veiculo_id),
+                motorista_id: None,
+                registro,
+                relacionado: Some(relacionado),
+                data: Some(formatar(leitura.data)),
+                descricao: format!(
+                    "Km {} da {} em {} é menor que o km {} da {} em {}",
+                    leitura.km,
+                    descrever_registro(registro),
+                    formatar(leitura.data),
+                    anterior.km,
+                    descrever_registro(relacionado),
+                    formatar(anterior.data)
+                ),
+            });
+        }
+    }
+
+    inconsistencias
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(valor: &str) -> Option<NaiveDateTime> {
+        parse_data_hora(valor, None)
+    }
+
+    fn viagem(id: u32, veiculo_id: u32, motorista_id: u32, inicio: &str, fim: Option<&str>, km: (u32, Option<u32>)) -> IntervaloViagem {
+        IntervaloViagem {
+            id,
+            veiculo_id,
+            motorista_id,
+            inicio: data(inicio),
+            fim: fim.and_then(data),
+            km_inicial: Some(km.0),
+            km_final: km.1,
+        }
+    }
+
+    fn leitura(origem: OrigemLeitura, registro_id: u32, quando: &str, km: i64) -> LeituraOdometro {
+        LeituraOdometro { origem, registro_id, veiculo_id: 1, data: data(quando).unwrap(), km, marco: None }
+    }
+
+    #[test]
+    fn test_analisar_consistencia() {
+        let viagens = vec![
+            viagem(1, 1, 10, "2026-03-01 08:00", Some("2026-03-01 18:00"), (1_000, Some(1_200))),
+            // Mesmo motorista, outro veículo, no meio da viagem 1
+            viagem(2, 2, 10, "2026-03-01 12:00", Some("2026-03-01 14:00"), (500, Some(560))),
+            // Começa quando a 1 termina: não sobrepõe
+            viagem(3, 1, 11, "2026-03-01 18:00", None, (1_200, None)),
+            viagem(4, 3, 12, "2026-03-05 10:00", Some("2026-03-04 10:00"), (300, Some(250))),
+        ];
+        let leituras = vec![
+            leitura(OrigemLeitura::Abastecimento, 7, "2026-03-02 09:00", 1_150),
+            leitura(OrigemLeitura::Manutencao, 8, "2026-03-03 09:00", 1_100),
+        ];
+
+        let inconsistencias = analisar_consistencia(&viagens, &leituras);
+        let tipos: Vec<(TipoInconsistencia, u32, GravidadeInconsistencia)> =
+            inconsistencias.iter().map(|i| (i.tipo, i.registro.registro_id, i.gravidade)).collect();
+
+        assert!(tipos.contains(&(TipoInconsistencia::ViagemSobrepostaMotorista, 1, GravidadeInconsistencia::Erro)));
+        assert!(!tipos.iter().any(|t| t.0 == TipoInconsistencia::ViagemSobrepostaVeiculo));
+        assert!(tipos.contains(&(TipoInconsistencia::TerminoAntesDoInicio, 4, GravidadeInconsistencia::Erro)));
+        assert!(tipos.contains(&(TipoInconsistencia::KmFinalMenorQueInicial, 4, GravidadeInconsistencia::Erro)));
+        assert!(tipos.contains(&(TipoInconsistencia::KmRegrediu, 7, GravidadeInconsistencia::Erro)));
+        assert!(tipos.contains(&(TipoInconsistencia::KmRegrediu, 8, GravidadeInconsistencia::Aviso)));
+
+        // Um abastecimento com km digitado a mais aparece uma vez, não contra as leituras seguintes
+        let digitado_errado = vec![
+            leitura(OrigemLeitura::Abastecimento, 20, "2026-04-01 09:00", 15_000),
+            leitura(OrigemLeitura::Abastecimento, 21, "2026-04-02 09:00", 1_500),
+            leitura(OrigemLeitura::Abastecimento, 22, "2026-04-03 09:00", 1_600),
+        ];
+        let regressoes: Vec<InconsistenciaFrota> = analisar_consistencia(&[], &digitado_errado)
+            .into_iter()
+            .filter(|i| i.tipo == TipoInconsistencia::KmRegrediu)
+            .collect();
+        assert_eq!(regressoes.len(), 1);
+        assert_eq!(regressoes[0].registro.registro_id, 21);
+
+        // Nova viagem em andamento sobreposta à viagem 3, que também está em aberto
+        let mut com_nova = viagens.clone();
+        com_nova.push(viagem(NOVO_REGISTRO, 1, 13, "2026-03-10 08:00", None, (1_300, None)));
+        let novas: Vec<InconsistenciaFrota> = analisar_consistencia(&com_nova, &[])
+            .into_iter()
+            .filter(|i| i.envolve(OrigemLeitura::Viagem, NOVO_REGISTRO))
+            .collect();
+        assert_eq!(novas.len(), 1);
+        assert_eq!(novas[0].tipo, TipoInconsistencia::ViagemSobrepostaVeiculo);
+        assert!(rejeitar_inconsistencias(&novas).is_err());
+    }
+
+    #[test]
+    fn test_registro_editado_substitui_o_gravado() {
+        let viagens = vec![
+            viagem(1, 1, 10, "2026-03-01 08:00", Some("2026-03-01 18:00"), (1_000, Some(1_200))),
+            viagem(2, 1, 10, "2026-03-02 08:00", Some("2026-03-02 18:00"), (1_220, Some(1_400))),
+        ];
+        let leituras = vec![leitura(OrigemLeitura::Abastecimento, 7, "2026-03-01 20:00", 1_210)];
+
+        // Corrigir o km final não conflita com a própria versão gravada
+        let editada = viagem(2, 1, 10, "2026-03-02 08:00", Some("2026-03-02 18:00"), (1_220, Some(1_450)));
+        assert!(inconsistencias_da_viagem(viagens.clone(), &leituras, editada).is_empty());
+
+        // Mover a viagem para cima da viagem 1 é rejeitado
+        let sobreposta = viagem(2, 1, 10, "2026-03-01 10:00", Some("2026-03-01 12:00"), (1_220, Some(1_400)));
+        let inconsistencias = inconsistencias_da_viagem(viagens.clone(), &leituras, sobreposta);
+        assert!(inconsistencias.iter().any(|i| i.tipo == TipoInconsistencia::ViagemSobrepostaVeiculo));
+
+        // O km gravado do abastecimento 7 é descartado em favor do editado
+        let corrigido = leitura(OrigemLeitura::Abastecimento, 7, "2026-03-01 20:00", 1_190);
+        let inconsistencias = inconsistencias_do_abastecimento(&viagens, leituras.clone(), corrigido);
+        assert_eq!(inconsistencias.len(), 1);
+        assert_eq!(inconsistencias[0].tipo, TipoInconsistencia::KmRegrediu);
+        let mantido = leitura(OrigemLeitura::Abastecimento, 7, "2026-03-01 20:00", 1_205);
+        assert!(inconsistencias_do_abastecimento(&viagens, leituras, mantido).is_empty());
+    }
+}
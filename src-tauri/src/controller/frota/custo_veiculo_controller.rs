@@ -0,0 +1,818 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::Duration;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Local, Months, NaiveDate, NaiveDateTime};
+use reqwest::Client;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::config::get_api_url;
+use crate::controller::frota::abastecimento_controller::{buscar_abastecimento, FrotaAbastecimento};
+use crate::controller::frota::alertas_frota_controller::ler_parametros_alertas;
+use crate::controller::frota::manutencao_controller::{buscar_manutencoes, ApiResponse, FrotaManutencao};
+use crate::controller::frota::veiculo_controller::buscar_veiculos_e_marcas;
+use crate::controller::frota::viagem_controller::{buscar_viagens, FrotaViagem};
+use crate::controller::frota::email_frota_controller::avisar_gestor_frota;
+use crate::exportacao::destino_exportacao;
+use crate::monitor::Monitor;
+use crate::utils::parse_data_hora;
+
+const API_RESOURCE: &str = "/frota/veiculos/lancamentos";
+const EVENTO_VENCIMENTO_VEICULO: &str = "vencimento_veiculo";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoLancamentoVeiculo {
+    Ipva,
+    Licenciamento,
+    Seguro,
+    Multa,
+    Outro,
+}
+
+/// Obrigação ou ocorrência de custo do veículo fora de abastecimento e manutenção
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LancamentoVeiculo {
+    pub id: Option<u32>,
+    pub veiculo_id: u32,
+    pub tipo: TipoLancamentoVeiculo,
+    pub descricao: Option<String>,
+    pub valor: BigDecimal,
+    /// Competência do custo; nas multas, a data/hora da infração
+    pub data: String,
+    pub vencimento: Option<String>, // "YYYY-MM-DD"
+    pub pago_em: Option<String>,
+    /// Ao registrar o pagamento, gera a próxima ocorrência (ex.: 12 para IPVA e licenciamento)
+    pub recorrencia_meses: Option<u32>,
+    /// Condutor responsável pela multa
+    pub motorista_id: Option<u32>,
+    pub auto_infracao: Option<String>,
+    pub pontos: Option<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoVencimento {
+    AVencer,
+    Vencido,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VencimentoVeiculo {
+    pub lancamento_id: u32,
+    pub veiculo_id: u32,
+    pub veiculo: String,
+    pub tipo: TipoLancamentoVeiculo,
+    pub descricao: Option<String>,
+    pub valor: BigDecimal,
+    pub vencimento: String,
+    pub dias_restantes: i64,
+    pub situacao: SituacaoVencimento,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TotaisCusto {
+    pub combustivel: BigDecimal,
+    pub manutencao: BigDecimal,
+    pub ipva: BigDecimal,
+    pub licenciamento: BigDecimal,
+    pub seguro: BigDecimal,
+    pub multas: BigDecimal,
+    pub outros: BigDecimal,
+    pub total: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustoMensal {
+    pub mes: String, // "YYYY-MM"
+    pub custos: TotaisCusto,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustoVeiculo {
+    pub veiculo_id: u32,
+    pub veiculo: String,
+    pub custos: TotaisCusto,
+    /// Diferença entre a maior e a menor leitura de odômetro do período
+    pub km_rodados: Option<i64>,
+    pub custo_por_km: Option<BigDecimal>,
+    pub mensal: Vec<CustoMensal>,
+}
+
+/// Custo total de propriedade: lançamentos + abastecimentos + manutenções
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatorioCustoVeiculos {
+    pub periodo_inicio: Option<String>,
+    pub periodo_fim: Option<String>,
+    pub gerado_em: String,
+    pub custos: TotaisCusto,
+    pub veiculos: Vec<CustoVeiculo>,
+    pub mensal: Vec<CustoMensal>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoCustoVeiculos {
+    pub caminho: String,
+    pub total_veiculos: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CategoriaCusto {
+    Combustivel,
+    Manutencao,
+    Lancamento(TipoLancamentoVeiculo),
+}
+
+#[derive(Default)]
+struct AcumuladoVeiculo {
+    custos: TotaisCusto,
+    mensal: BTreeMap<String, TotaisCusto>,
+    leituras_km: Vec<i64>,
+}
+
+impl TotaisCusto {
+    fn somar(&mut self, categoria: CategoriaCusto, valor: &BigDecimal) {
+        let campo = match categoria {
+            CategoriaCusto::Combustivel => &mut self.combustivel,
+            CategoriaCusto::Manutencao => &mut self.manutencao,
+            CategoriaCusto::Lancamento(TipoLancamentoVeiculo::Ipva) => &mut self.ipva,
+            CategoriaCusto::Lancamento(TipoLancamentoVeiculo::Licenciamento) => &mut self.licenciamento,
+            CategoriaCusto::Lancamento(TipoLancamentoVeiculo::Seguro) => &mut self.seguro,
+            CategoriaCusto::Lancamento(TipoLancamentoVeiculo::Multa) => &mut self.multas,
+            CategoriaCusto::Lancamento(TipoLancamentoVeiculo::Outro) => &mut self.outros,
+        };
+        *campo += valor;
+        self.total += valor;
+    }
+}
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn listar_lancamentos_veiculo(
+    app_handle: AppHandle,
+    veiculo_id: Option<u32>,
+) -> Result<Vec<LancamentoVeiculo>, String> {
+    let mut lancamentos = buscar_lancamentos(&Client::new(), &get_api_url(&app_handle)).await?;
+    if let Some(veiculo_id) = veiculo_id {
+        lancamentos.retain(|l| l.veiculo_id == veiculo_id);
+    }
+    lancamentos.sort_by(|a, b| b.data.cmp(&a.data));
+    Ok(lancamentos)
+}
+
+/// Salva o lançamento; multa sem condutor é atribuída ao motorista da viagem em curso na data
+#[command]
+pub async fn salvar_lancamento_veiculo(
+    app_handle: AppHandle,
+    mut lancamento: LancamentoVeiculo,
+) -> Result<LancamentoVeiculo, String> {
+    validar_lancamento(&lancamento)?;
+
+    if lancamento.tipo == TipoLancamentoVeiculo::Multa && lancamento.motorista_id.is_none() {
+        match buscar_viagens(app_handle.clone()).await {
+            Ok(viagens) => lancamento.motorista_id = motorista_na_data(&viagens, lancamento.veiculo_id, &lancamento.data),
+            Err(e) => eprintln!("Não foi possível atribuir o condutor da multa: {}", e),
+        }
+    }
+
+    enviar_lancamento(&Client::new(), &get_api_url(&app_handle), &lancamento).await
+}
+
+#[command]
+pub async fn excluir_lancamento_veiculo(app_handle: AppHandle, id: u32) -> Result<String, String> {
+    let url = format!("{}{}/{}", get_api_url(&app_handle), API_RESOURCE, id);
+
+    let response = Client::new()
+        .delete(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao excluir lançamento: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+    Ok("Lançamento excluído".to_string())
+}
+
+/// Marca o lançamento como pago e, se recorrente, cria a próxima ocorrência em aberto
+#[command]
+pub async fn registrar_pagamento_lancamento(
+    app_handle: AppHandle,
+    id: u32,
+    pago_em: Option<String>,
+) -> Result<Option<LancamentoVeiculo>, String> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let mut lancamento = buscar_lancamentos(&client, &api_url)
+        .await?
+        .into_iter()
+        .find(|l| l.id == Some(id))
+        .ok_or_else(|| format!("Lançamento {} não encontrado", id))?;
+
+    if lancamento.pago_em.is_some() {
+        return Err("Lançamento já está pago".to_string());
+    }
+    let pago_em = pago_em
+        .filter(|d| !d.trim().is_empty())
+        .unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+    if parse_data_hora(&pago_em, None).is_none() {
+        return Err(format!("Data de pagamento inválida: {}", pago_em));
+    }
+    lancamento.pago_em = Some(pago_em);
+    enviar_lancamento(&client, &api_url, &lancamento).await?;
+
+    match proxima_ocorrencia(&lancamento) {
+        Some(proxima) => enviar_lancamento(&client, &api_url, &proxima).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Motorista que estava com o veículo na data da infração, se houver um só
+#[command]
+pub async fn sugerir_motorista_multa(app_handle: AppHandle, veiculo_id: u32, data: String) -> Result<Option<u32>, String> {
+    let viagens = buscar_viagens(app_handle).await?;
+    Ok(motorista_na_data(&viagens, veiculo_id, &data))
+}
+
+/// Lançamentos em aberto vencidos ou dentro da antecedência configurada
+#[command]
+pub async fn verificar_vencimentos_veiculos(app_handle: AppHandle) -> Result<Vec<VencimentoVeiculo>, String> {
+    let lancamentos = buscar_lancamentos(&Client::new(), &get_api_url(&app_handle)).await?;
+    let veiculos: HashMap<u32, String> = buscar_veiculos_e_marcas(app_handle.clone())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| (v.id, format!("{} ({})", v.nome, v.placa)))
+        .collect();
+    let antecedencia = ler_parametros_alertas(&app_handle).antecedencia_vencimento_veiculo_dias;
+    Ok(vencimentos(&lancamentos, &veiculos, Local::now().date_naive(), antecedencia))
+}
+
+/// Custo total por veículo e por mês no período (datas "YYYY-MM-DD", ambas opcionais)
+#[command]
+pub async fn relatorio_custo_veiculos(
+    app_handle: AppHandle,
+    data_inicio: Option<String>,
+    data_fim: Option<String>,
+    veiculo_id: Option<u32>,
+) -> Result<RelatorioCustoVeiculos, String> {
+    let inicio = data_inicio.as_deref().map(|d| parse_data_hora(d, None).map(|d| d.date()).ok_or(format!("Data inicial inválida: {}", d))).transpose()?;
+    let fim = data_fim.as_deref().map(|d| parse_data_hora(d, None).map(|d| d.date()).ok_or(format!("Data final inválida: {}", d))).transpose()?;
+
+    let lancamentos = buscar_lancamentos(&Client::new(), &get_api_url(&app_handle)).await?;
+    let abastecimentos = buscar_abastecimento(app_handle.clone()).await?;
+    let manutencoes = buscar_manutencoes(app_handle.clone()).await?;
+    let veiculos: HashMap<u32, String> = buscar_veiculos_e_marcas(app_handle.clone())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| (v.id, format!("{} ({})", v.nome, v.placa)))
+        .collect();
+
+    let mut relatorio = montar_relatorio(&lancamentos, &abastecimentos, &manutencoes, &veiculos, inicio, fim);
+    if let Some(veiculo_id) = veiculo_id {
+        relatorio.veiculos.retain(|v| v.veiculo_id == veiculo_id);
+        relatorio.custos = relatorio.veiculos.first().map(|v| v.custos.clone()).unwrap_or_default();
+        relatorio.mensal = relatorio.veiculos.first().map(|v| v.mensal.clone()).unwrap_or_default();
+    }
+    relatorio.periodo_inicio = data_inicio;
+    relatorio.periodo_fim = data_fim;
+    Ok(relatorio)
+}
+
+#[command]
+pub async fn exportar_custo_veiculos_xlsx(
+    relatorio: RelatorioCustoVeiculos,
+    caminho: Option<String>,
+) -> Result<ExportacaoCustoVeiculos, String> {
+    let destino = destino_exportacao(caminho, &format!("custo_veiculos_{}.xlsx", Local::now().format("%Y%m%d_%H%M")))?;
+    gerar_xlsx(&relatorio, &destino)?;
+    Ok(ExportacaoCustoVeiculos {
+        caminho: destino.to_string_lossy().to_string(),
+        total_veiculos: relatorio.veiculos.len(),
+    })
+}
+
+// ==================== MONITOR ====================
+
+/// Emite `vencimento_veiculo` e avisa o gestor da frota por e-mail quando
+/// um IPVA, licenciamento, seguro ou multa entra em "a vencer" ou vence
+pub async fn monitorar_vencimentos_veiculos(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("vencimentos dos veículos", EVENTO_VENCIMENTO_VEICULO);
+
+    loop {
+        monitor.aguardar(INTERVALO_MONITOR).await;
+        match verificar_vencimentos_veiculos(app_handle.clone()).await {
+            Ok(alertas) => {
+                let novos = monitor.notificar(&app_handle, &alertas, |a| (a.lancamento_id, a.situacao));
+                if !novos.is_empty() {
+                    if let Err(e) = avisar_gestor(&app_handle, &novos).await {
+                        println!("Falha ao avisar gestor da frota: {}", e);
+                    }
+                }
+            }
+            Err(e) => monitor.falha(&e),
+        }
+    }
+}
+
+async fn avisar_gestor(app_handle: &AppHandle, pendentes: &[&VencimentoVeiculo]) -> Result<(), String> {
+    let mut texto = String::from("Obrigações dos veículos que precisam de atenção:\n\n");
+    for p in pendentes {
+        let situacao = if p.situacao == SituacaoVencimento::Vencido { "VENCIDO" } else { "a vencer" };
+        texto.push_str(&format!(
+            "- {} | {}: R$ {:.2}, {} em {}\n",
+            p.veiculo,
+            nome_tipo(p.tipo),
+            p.valor,
+            situacao,
+            formatar_data(&p.vencimento)
+        ));
+    }
+
+    avisar_gestor_frota(app_handle, &format!("Vencimentos da frota: {} pendência(s)", pendentes.len()), texto).await
+}
+
+// ==================== API ====================
+
+async fn buscar_lancamentos(client: &Client, api_url: &str) -> Result<Vec<LancamentoVeiculo>, String> {
+    let url = format!("{}{}", api_url, API_RESOURCE);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao buscar lançamentos dos veículos: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<Vec<LancamentoVeiculo>> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if api_response.success {
+        Ok(api_response.data.unwrap_or_default())
+    } else {
+        Err(api_response.message.unwrap_or_else(|| "API retornou falha sem mensagem.".to_string()))
+    }
+}
+
+async fn enviar_lancamento(client: &Client, api_url: &str, lancamento: &LancamentoVeiculo) -> Result<LancamentoVeiculo, String> {
+    let request = match lancamento.id {
+        Some(id) => client.put(format!("{}{}/{}", api_url, API_RESOURCE, id)),
+        None => client.post(format!("{}{}", api_url, API_RESOURCE)),
+    };
+
+    let response = request
+        .json(lancamento)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao salvar lançamento: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<LancamentoVeiculo> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if api_response.success {
+        Ok(api_response.data.unwrap_or_else(|| lancamento.clone()))
+    } else {
+        Err(api_response.message.unwrap_or_else(|| "API retornou falha sem mensagem.".to_string()))
+    }
+}
+
+// ==================== CÁLCULO ====================
+
+fn validar_lancamento(lancamento: &LancamentoVeiculo) -> Result<(), String> {
+    if lancamento.valor <= BigDecimal::from(0) {
+        return Err("O valor do lançamento deve ser maior que zero".to_string());
+    }
+    if parse_data_hora(&lancamento.data, None).is_none() {
+        return Err(format!("Data inválida: {}", lancamento.data));
+    }
+    for (rotulo, data) in [("vencimento", &lancamento.vencimento), ("pagamento", &lancamento.pago_em)] {
+        if let Some(data) = data.as_deref().filter(|d| parse_data_hora(d, None).is_none()) {
+            return Err(format!("Data de {} inválida: {}", rotulo, data));
+        }
+    }
+    if lancamento.recorrencia_meses == Some(0) {
+        return Err("A recorrência deve ser de pelo menos 1 mês".to_string());
+    }
+    if lancamento.tipo != TipoLancamentoVeiculo::Multa
+        && (lancamento.motorista_id.is_some() || lancamento.auto_infracao.is_some() || lancamento.pontos.is_some())
+    {
+        return Err("Condutor, auto de infração e pontos só se aplicam a multas".to_string());
+    }
+    Ok(())
+}
+
+fn proxima_ocorrencia(lancamento: &LancamentoVeiculo) -> Option<LancamentoVeiculo> {
+    let meses = Months::new(lancamento.recorrencia_meses.filter(|m| *m > 0)?);
+    let avancar = |data: &str| {
+        parse_data_hora(data, None)
+            .and_then(|d| d.date().checked_add_months(meses))
+            .map(|d| d.format("%Y-%m-%d").to_string())
+    };
+    Some(LancamentoVeiculo {
+        id: None,
+        data: avancar(&lancamento.data)?,
+        vencimento: lancamento.vencimento.as_deref().and_then(avancar),
+        pago_em: None,
+        ..lancamento.clone()
+    })
+}
+
+/// Janela da infração: o instante informado ou, sem hora, o dia inteiro
+fn janela_infracao(data: &str) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let momento = parse_data_hora(data, None)?;
+    if data.trim().len() <= 10 {
+        let dia = momento.date();
+        Some((dia.and_hms_opt(0, 0, 0)?, dia.and_hms_opt(23, 59, 59)?))
+    } else {
+        Some((momento, momento))
+    }
+}
+
+fn motorista_na_data(viagens: &[FrotaViagem], veiculo_id: u32, data: &str) -> Option<u32> {
+    let (inicio, fim) = janela_infracao(data)?;
+    let mut motoristas: Vec<u32> = viagens
+        .iter()
+        .filter(|v| v.veiculo == veiculo_id)
+        .filter(|v| {
+            let Some(saida) = parse_data_hora(&v.data_inicio, None) else {
+                return false;
+            };
+            // Viagem sem término é considerada em andamento
+            let chegada = v.data_termino.as_deref().and_then(|d| parse_data_hora(d, None));
+            saida <= fim && chegada.is_none_or(|c| c >= inicio)
+        })
+        .map(|v| v.motorista)
+        .collect();
+    motoristas.sort();
+    motoristas.dedup();
+
+    // Mais de um condutor na janela: a atribuição fica para o gestor
+    match motoristas.as_slice() {
+        [unico] => Some(*unico),
+        _ => None,
+    }
+}
+
+fn vencimentos(
+    lancamentos: &[LancamentoVeiculo],
+    veiculos: &HashMap<u32, String>,
+    hoje: NaiveDate,
+    antecedencia_dias: i64,
+) -> Vec<VencimentoVeiculo> {
+    let mut alertas: Vec<VencimentoVeiculo> = lancamentos
+        .iter()
+        .filter(|l| l.pago_em.is_none())
+        .filter_map(|l| {
+            let id = l.id?;
+            let vencimento = parse_data_hora(l.vencimento.as_deref()?, None)?.date();
+            let dias_restantes = (vencimento - hoje).num_days();
+            let situacao = if dias_restantes < 0 {
+                SituacaoVencimento::Vencido
+            } else if dias_restantes <= antecedencia_dias {
+                SituacaoVencimento::AVencer
+            } else {
+                return None;
+            };
+            Some(VencimentoVeiculo {
+                lancamento_id: id,
+                veiculo_id: l.veiculo_id,
+                veiculo: nome_veiculo(veiculos, l.veiculo_id),
+                tipo: l.tipo,
+                descricao: l.descricao.clone(),
+                valor: l.valor.clone(),
+                vencimento: vencimento.format("%Y-%m-%d").to_string(),
+                dias_restantes,
+                situacao,
+            })
+        })
+        .collect();
+    alertas.sort_by_key(|a| a.dias_restantes);
+    alertas
+}
+
+fn nome_veiculo(veiculos: &HashMap<u32, String>, id: u32) -> String {
+    veiculos.get(&id).cloned().unwrap_or_else(|| format!("Veículo {}", id))
+}
+
+fn montar_relatorio(
+    lancamentos: &[LancamentoVeiculo],
+    abastecimentos: &[FrotaAbastecimento],
+    manutencoes: &[FrotaManutencao],
+    veiculos: &HashMap<u32, String>,
+    inicio: Option<NaiveDate>,
+    fim: Option<NaiveDate>,
+) -> RelatorioCustoVeiculos {
+    // (veiculo, data, categoria, valor, km)
+    let mut itens: Vec<(u32, NaiveDate, CategoriaCusto, BigDecimal, Option<i64>)> = Vec::new();
+
+    for l in lancamentos {
+        if let Some(data) = parse_data_hora(&l.data, None) {
+            itens.push((l.veiculo_id, data.date(), CategoriaCusto::Lancamento(l.tipo), l.valor.clone(), None));
+        }
+    }
+    for a in abastecimentos {
+        let (Some(veiculo), Some(data)) = (a.veiculo, a.data.as_deref().and_then(|d| parse_data_hora(d, None))) else {
+            continue;
+        };
+        let valor = a.valor.clone().unwrap_or_default();
+        itens.push((veiculo, data.date(), CategoriaCusto::Combustivel, valor, a.quilometragem.map(i64::from)));
+    }
+    for m in manutencoes {
+        // Só entra o que já foi realizado; agendamento ainda não é custo
+        let (Some(veiculo), Some(data)) = (m.veiculo, m.data_servico()) else {
+            continue;
+        };
+        itens.push((veiculo, data.date(), CategoriaCusto::Manutencao, m.valor.clone().unwrap_or_default(), m.km.map(i64::from)));
+    }
+
+    let mut por_veiculo: BTreeMap<u32, AcumuladoVeiculo> = BTreeMap::new();
+    let mut geral = TotaisCusto::default();
+    let mut geral_mensal: BTreeMap<String, TotaisCusto> = BTreeMap::new();
+
+    for (veiculo, data, categoria, valor, km) in itens {
+        if inicio.is_some_and(|i| data < i) || fim.is_some_and(|f| data > f) {
+            continue;
+        }
+        let mes = data.format("%Y-%m").to_string();
+        let acumulado = por_veiculo.entry(veiculo).or_default();
+        acumulado.custos.somar(categoria, &valor);
+        acumulado.mensal.entry(mes.clone()).or_default().somar(categoria, &valor);
+        acumulado.leituras_km.extend(km.filter(|k| *k > 0));
+        geral.somar(categoria, &valor);
+        geral_mensal.entry(mes).or_default().somar(categoria, &valor);
+    }
+
+    let para_lista = |mensal: BTreeMap<String, TotaisCusto>| {
+        mensal.into_iter().map(|(mes, custos)| CustoMensal { mes, custos }).collect::<Vec<_>>()
+    };
+
+    let mut custos_veiculos: Vec<CustoVeiculo> = por_veiculo
+        .into_iter()
+        .map(|(veiculo_id, AcumuladoVeiculo { custos, mensal, leituras_km })| {
+            let km_rodados = leituras_km
+                .iter()
+                .max()
+                .zip(leituras_km.iter().min())
+                .map(|(max, min)| max - min)
+                .filter(|km| *km > 0);
+            CustoVeiculo {
+                veiculo_id,
+                veiculo: nome_veiculo(veiculos, veiculo_id),
+                custo_por_km: km_rodados.map(|km| (&custos.total / BigDecimal::from(km)).round(3)),
+                km_rodados,
+                custos,
+                mensal: para_lista(mensal),
+            }
+        })
+        .collect();
+    custos_veiculos.sort_by(|a, b| b.custos.total.cmp(&a.custos.total));
+
+    RelatorioCustoVeiculos {
+        periodo_inicio: inicio.map(|d| d.format("%Y-%m-%d").to_string()),
+        periodo_fim: fim.map(|d| d.format("%Y-%m-%d").to_string()),
+        gerado_em: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        custos: geral,
+        veiculos: custos_veiculos,
+        mensal: para_lista(geral_mensal),
+    }
+}
+
+// ==================== EXPORTAÇÃO ====================
+
+fn nome_tipo(tipo: TipoLancamentoVeiculo) -> &'static str {
+    match tipo {
+        TipoLancamentoVeiculo::Ipva => "IPVA",
+        TipoLancamentoVeiculo::Licenciamento => "Licenciamento",
+        TipoLancamentoVeiculo::Seguro => "Seguro",
+        TipoLancamentoVeiculo::Multa => "Multa",
+        TipoLancamentoVeiculo::Outro => "Outro",
+    }
+}
+
+fn formatar_data(data: &str) -> String {
+    parse_data_hora(data, None)
+        .map(|d| d.format("%d/%m/%Y").to_string())
+        .unwrap_or_else(|| data.to_string())
+}
+
+fn gerar_xlsx(relatorio: &RelatorioCustoVeiculos, destino: &Path) -> Result<(), String> {
+    let erro = |e: rust_xlsxwriter::XlsxError| format!("Erro ao gerar XLSX: {}", e);
+
+    let mut workbook = Workbook::new();
+    let negrito = Format::new().set_bold();
+    let moeda = Format::new().set_num_format("#,##0.00");
+    let tres_casas = Format::new().set_num_format("0.000");
+    let colunas_custo = ["Combustível", "Manutenção", "IPVA", "Licenciamento", "Seguro", "Multas", "Outros", "Total"];
+    // A planilha só recebe f64; os totais já foram somados em BigDecimal
+    let numero = |v: &BigDecimal| v.to_f64().unwrap_or(0.0);
+    let valores = |c: &TotaisCusto| {
+        [&c.combustivel, &c.manutencao, &c.ipva, &c.licenciamento, &c.seguro, &c.multas, &c.outros, &c.total].map(numero)
+    };
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Por veículo").map_err(erro)?;
+    let cabecalho: Vec<&str> = ["Veículo"].into_iter().chain(colunas_custo).chain(["Km rodados", "R$/km"]).collect();
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+    for (i, veiculo) in relatorio.veiculos.iter().enumerate() {
+        let linha = i as u32 + 1;
+        sheet.write_string(linha, 0, &veiculo.veiculo).map_err(erro)?;
+        for (j, valor) in valores(&veiculo.custos).iter().enumerate() {
+            sheet.write_number_with_format(linha, j as u16 + 1, *valor, &moeda).map_err(erro)?;
+        }
+        if let Some(km) = veiculo.km_rodados {
+            sheet.write_number(linha, 9, km as f64).map_err(erro)?;
+        }
+        if let Some(custo_por_km) = &veiculo.custo_por_km {
+            sheet.write_number_with_format(linha, 10, numero(custo_por_km), &tres_casas).map_err(erro)?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    sheet.set_column_width(0, 30.0).map_err(erro)?;
+    for coluna in 1..cabecalho.len() as u16 {
+        sheet.set_column_width(coluna, 14.0).map_err(erro)?;
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Por mês").map_err(erro)?;
+    let cabecalho: Vec<&str> = ["Veículo", "Mês"].into_iter().chain(colunas_custo).collect();
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+    let linhas = relatorio
+        .veiculos
+        .iter()
+        .flat_map(|v| v.mensal.iter().map(move |m| (v.veiculo.as_str(), m)))
+        .chain(relatorio.mensal.iter().map(|m| ("Frota", m)));
+    for (linha, (veiculo, mes)) in (1u32..).zip(linhas) {
+        sheet.write_string(linha, 0, veiculo).map_err(erro)?;
+        sheet.write_string(linha, 1, &mes.mes).map_err(erro)?;
+        for (j, valor) in valores(&mes.custos).iter().enumerate() {
+            sheet.write_number_with_format(linha, j as u16 + 2, *valor, &moeda).map_err(erro)?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    sheet.set_column_width(0, 30.0).map_err(erro)?;
+    for coluna in 1..cabecalho.len() as u16 {
+        sheet.set_column_width(coluna, 14.0).map_err(erro)?;
+    }
+
+    workbook.save(destino).map_err(erro)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dinheiro(valor: &str) -> BigDecimal {
+        BigDecimal::from_str(valor).unwrap()
+    }
+
+    fn lancamento(id: u32, tipo: TipoLancamentoVeiculo, valor: &str, data: &str, vencimento: Option<&str>) -> LancamentoVeiculo {
+        LancamentoVeiculo {
+            id: Some(id),
+            veiculo_id: 7,
+            tipo,
+            descricao: None,
+            valor: dinheiro(valor),
+            data: data.to_string(),
+            vencimento: vencimento.map(str::to_string),
+            pago_em: None,
+            recorrencia_meses: None,
+            motorista_id: None,
+            auto_infracao: None,
+            pontos: None,
+        }
+    }
+
+    fn viagem(motorista: u32, inicio: &str, termino: Option<&str>) -> FrotaViagem {
+        FrotaViagem {
+            id: motorista,
+            descricao: None,
+            origem: None,
+            destino: None,
+            data_inicio: inicio.to_string(),
+            quilometragem_inicial: None,
+            quilometragem_final: None,
+            veiculo: 7,
+            motorista,
+            data_termino: termino.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_atribuicao_de_multa() {
+        let viagens = vec![
+            viagem(1, "2026-03-10 07:00:00", Some("2026-03-10 12:00:00")),
+            viagem(2, "2026-03-10 13:00:00", Some("2026-03-10 18:00:00")),
+        ];
+        assert_eq!(motorista_na_data(&viagens, 7, "2026-03-10 09:15:00"), Some(1));
+        assert_eq!(motorista_na_data(&viagens, 7, "2026-03-10 14:00"), Some(2));
+        // Só a data: dois condutores no dia, atribuição manual
+        assert_eq!(motorista_na_data(&viagens, 7, "2026-03-10"), None);
+        assert_eq!(motorista_na_data(&viagens, 8, "2026-03-10 09:15:00"), None);
+    }
+
+    #[test]
+    fn test_vencimentos_e_recorrencia() {
+        let hoje = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let mut pago = lancamento(3, TipoLancamentoVeiculo::Seguro, "900.0", "2026-02-01", Some("2026-02-10"));
+        pago.pago_em = Some("2026-02-09".to_string());
+        let lancamentos = vec![
+            lancamento(1, TipoLancamentoVeiculo::Ipva, "1200.0", "2026-01-01", Some("2026-02-20")),
+            lancamento(2, TipoLancamentoVeiculo::Licenciamento, "150.0", "2026-01-01", Some("2026-03-10")),
+            lancamento(4, TipoLancamentoVeiculo::Multa, "195.23", "2026-02-15", Some("2026-06-01")),
+            pago,
+        ];
+        let alertas = vencimentos(&lancamentos, &HashMap::new(), hoje, 15);
+        let resumo: Vec<_> = alertas.iter().map(|a| (a.lancamento_id, a.situacao, a.dias_restantes)).collect();
+        assert_eq!(resumo, vec![(1, SituacaoVencimento::Vencido, -9), (2, SituacaoVencimento::AVencer, 9)]);
+
+        let mut ipva = lancamentos[0].clone();
+        ipva.recorrencia_meses = Some(12);
+        let proximo = proxima_ocorrencia(&ipva).unwrap();
+        assert_eq!((proximo.id, proximo.data.as_str(), proximo.vencimento.as_deref()), (None, "2027-01-01", Some("2027-02-20")));
+    }
+
+    #[test]
+    fn test_custo_total_por_veiculo_e_mes() {
+        let abastecimento = FrotaAbastecimento {
+            id: 1,
+            veiculo: Some(7),
+            motorista: None,
+            valor_litro: None,
+            litro: None,
+            valor: Some(dinheiro("300.50")),
+            combustivel: Some(1),
+            posto: None,
+            data: Some("2026-01-20 10:00:00".to_string()),
+            notafiscal: None,
+            quilometragem: Some(10_000),
+            comprovante_id: None,
+            aviso: None,
+        };
+        let manutencao = FrotaManutencao {
+            id: 1,
+            tipo: Some(1),
+            veiculo: Some(7),
+            data: "2026-02-05 08:00:00".to_string(),
+            data_manutencao: None,
+            observacao: None,
+            data_realizada: Some("2026-02-05 08:00:00".to_string()),
+            km: Some(10_800),
+            proxima: None,
+            valor: Some(dinheiro("450.00")),
+        };
+        let agendada = FrotaManutencao {
+            id: 2,
+            data: "2026-02-20 08:00:00".to_string(),
+            data_realizada: None,
+            valor: Some(dinheiro("900.00")),
+            ..manutencao.clone()
+        };
+        let lancamentos = vec![
+            lancamento(1, TipoLancamentoVeiculo::Ipva, "1200.0", "2026-01-10", None),
+            lancamento(2, TipoLancamentoVeiculo::Multa, "130.16", "2026-02-11 09:00:00", None),
+            // Fora do período
+            lancamento(3, TipoLancamentoVeiculo::Seguro, "2000.0", "2025-12-01", None),
+        ];
+
+        let relatorio = montar_relatorio(
+            &lancamentos,
+            &[abastecimento],
+            &[manutencao, agendada],
+            &HashMap::new(),
+            NaiveDate::from_ymd_opt(2026, 1, 1),
+            NaiveDate::from_ymd_opt(2026, 2, 28),
+        );
+
+        let veiculo = &relatorio.veiculos[0];
+        assert_eq!(veiculo.custos.combustivel, dinheiro("300.5"));
+        assert_eq!(veiculo.custos.manutencao, dinheiro("450"));
+        assert_eq!(veiculo.custos.seguro, BigDecimal::from(0));
+        assert_eq!(veiculo.custos.total, dinheiro("2080.66"));
+        assert_eq!(veiculo.km_rodados, Some(800));
+        assert_eq!(veiculo.custo_por_km, Some(dinheiro("2.601")));
+        let meses: Vec<_> = veiculo.mensal.iter().map(|m| (m.mes.as_str(), m.custos.total.clone())).collect();
+        assert_eq!(meses, vec![("2026-01", dinheiro("1500.50")), ("2026-02", dinheiro("580.16"))]);
+        assert_eq!(relatorio.custos, veiculo.custos);
+    }
+}
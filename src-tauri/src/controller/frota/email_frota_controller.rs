@@ -0,0 +1,68 @@
+use tauri::{command, AppHandle};
+use crate::controller::frota::alertas_frota_controller::ler_parametros_alertas;
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::email::{criar_transporte, enviar_email, guardar_senha, ler_senha, validar_config_smtp};
+use crate::model::email::ConfiguracaoSmtp;
+
+/// Separada da configuração da pesquisa de satisfação: os alertas da frota têm remetente próprio
+const ARQUIVO_SMTP: &str = "smtp_frota.json";
+const CREDENCIAL_SMTP: &str = "smtp_frota";
+
+/// Retorna a configuração sem a senha
+#[command]
+pub async fn carregar_config_smtp_frota(app_handle: AppHandle) -> Result<ConfiguracaoSmtp, String> {
+    let mut configuracao = ler_arquivo_smtp(&app_handle);
+    configuracao.senha = None;
+    Ok(configuracao)
+}
+
+#[command]
+pub async fn salvar_config_smtp_frota(app_handle: AppHandle, mut configuracao: ConfiguracaoSmtp) -> Result<(), String> {
+    validar_config_smtp(&configuracao)?;
+    if let Some(senha) = configuracao.senha.take() {
+        guardar_senha(CREDENCIAL_SMTP, &senha)?;
+    }
+    gravar_json(&app_handle, ARQUIVO_SMTP, &configuracao)
+}
+
+/// Envia uma mensagem de teste com a configuração salva
+#[command]
+pub async fn testar_config_smtp_frota(app_handle: AppHandle, email_destino: String) -> Result<(), String> {
+    let configuracao = ler_config_smtp(&app_handle)?;
+    validar_config_smtp(&configuracao)?;
+    let transporte = criar_transporte(&configuracao)?;
+    enviar_email(
+        &transporte,
+        &configuracao,
+        &email_destino,
+        &email_destino,
+        "Teste de envio - alertas da frota",
+        "Mensagem de teste. Se você a recebeu, os alertas da frota por e-mail estão configurados.".to_string(),
+    )
+    .await
+}
+
+/// Manda o alerta ao gestor da frota; sem e-mail do gestor cadastrado, não faz nada
+pub async fn avisar_gestor_frota(app_handle: &AppHandle, assunto: &str, corpo: String) -> Result<(), String> {
+    let Some(email) = ler_parametros_alertas(app_handle).email_gestor_frota.filter(|e| !e.trim().is_empty()) else {
+        return Ok(());
+    };
+    let configuracao = ler_config_smtp(app_handle)?;
+    validar_config_smtp(&configuracao)?;
+
+    let transporte = criar_transporte(&configuracao)?;
+    enviar_email(&transporte, &configuracao, "Gestor da frota", email.trim(), assunto, corpo).await
+}
+
+fn ler_arquivo_smtp(app_handle: &AppHandle) -> ConfiguracaoSmtp {
+    ler_json::<Option<ConfiguracaoSmtp>>(app_handle, ARQUIVO_SMTP).unwrap_or_else(|| ConfiguracaoSmtp {
+        remetente_nome: "Frota".to_string(),
+        ..Default::default()
+    })
+}
+
+fn ler_config_smtp(app_handle: &AppHandle) -> Result<ConfiguracaoSmtp, String> {
+    let mut configuracao = ler_arquivo_smtp(app_handle);
+    configuracao.senha = ler_senha(CREDENCIAL_SMTP)?;
+    Ok(configuracao)
+}
@@ -14,11 +14,14 @@ pub struct ApiResponse<T> {
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct AgendamentoDia {
-    id: u32,
-    descricao: String,
-    data: NaiveDate,
-    hora: Option<NaiveTime>,
-    nome: Option<String>,
+    pub id: u32,
+    pub descricao: String,
+    pub data: NaiveDate,
+    pub hora: Option<NaiveTime>,
+    pub nome: Option<String>,
+    /// Usado no planejamento de rotas para buscar o endereço do cliente
+    #[serde(default)]
+    pub cliente_id: Option<u32>,
 }
 
 #[command]
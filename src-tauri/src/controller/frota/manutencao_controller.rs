@@ -3,7 +3,10 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::frota::manutencao_preventiva_controller::sugerir_proxima_data;
+use crate::utils::parse_data_hora;
 use chrono::{NaiveDateTime, DateTime, Utc};
+use bigdecimal::BigDecimal;
 // Structs para o input da API
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CreateFrotaManutencaoInput {
@@ -15,6 +18,8 @@ pub struct CreateFrotaManutencaoInput {
     pub data_realizada: Option<NaiveDateTime>,
     pub km: Option<u32>,
     pub proxima: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valor: Option<BigDecimal>,
 }
 
 
@@ -24,7 +29,7 @@ pub struct UpdateFrotaManutencaoInput {
     pub veiculo_id: Option<u32>,
     pub data_servico: Option<String>,
     pub descricao_servico: Option<String>,
-    pub valor: Option<f64>,
+    pub valor: Option<BigDecimal>,
     pub km: Option<u32>,
     pub tipo_manutencao_id: Option<u32>,
 }
@@ -40,6 +45,15 @@ pub struct FrotaManutencao {
     pub data_realizada: Option<String>,
     pub km: Option<u32>,
     pub proxima: Option<String>,
+    #[serde(default)]
+    pub valor: Option<BigDecimal>,
+}
+
+impl FrotaManutencao {
+    /// Data em que o serviço foi feito; manutenção só agendada ainda não tem
+    pub fn data_servico(&self) -> Option<NaiveDateTime> {
+        parse_data_hora(self.data_realizada.as_deref()?, None)
+    }
 }
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CreateTipoManutencaoInput {
@@ -71,7 +85,15 @@ pub struct GenericResponse {
 
 /// Comando para criar um novo registro de manutenção
 #[command]
-pub async fn criar_frota_manutencao(app_handle: AppHandle, payload: CreateFrotaManutencaoInput) -> Result<FrotaManutencao, String> {
+pub async fn criar_frota_manutencao(app_handle: AppHandle, mut payload: CreateFrotaManutencaoInput) -> Result<FrotaManutencao, String> {
+    // Próxima data pelo plano preventivo do tipo, quando não foi digitada
+    if let (None, Some(veiculo_id), Some(tipo_id)) = (payload.proxima, payload.veiculo, payload.tipo) {
+        let data = payload.data_realizada.or(payload.data_manutencao).unwrap_or(payload.data);
+        payload.proxima = sugerir_proxima_data(&app_handle, veiculo_id, tipo_id, data.date())
+            .await
+            .map(|d| d.and_time(data.time()));
+    }
+
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/frota/manutencao", api_url);
@@ -102,12 +124,26 @@ pub async fn criar_frota_manutencao(app_handle: AppHandle, payload: CreateFrotaM
 /// Comando para buscar todos os registros de manutenção
 #[command]
 pub async fn buscar_manutencoes(app_handle: AppHandle) -> Result<Vec<FrotaManutencao>, String> {
+    consultar_manutencoes(&app_handle, None).await
+}
+
+/// Manutenções de um veículo, filtradas pela API
+pub async fn buscar_manutencoes_do_veiculo(app_handle: &AppHandle, veiculo_id: u32) -> Result<Vec<FrotaManutencao>, String> {
+    let mut manutencoes = consultar_manutencoes(app_handle, Some(veiculo_id)).await?;
+    manutencoes.retain(|m| m.veiculo == Some(veiculo_id));
+    Ok(manutencoes)
+}
+
+async fn consultar_manutencoes(app_handle: &AppHandle, veiculo_id: Option<u32>) -> Result<Vec<FrotaManutencao>, String> {
     let client = Client::new();
-    let api_url = get_api_url(&app_handle);
+    let api_url = get_api_url(app_handle);
     let url = format!("{}/frota/manutencao", api_url);
 
-    let response = client
-        .get(&url)
+    let mut requisicao = client.get(&url);
+    if let Some(id) = veiculo_id {
+        requisicao = requisicao.query(&[("veiculo_id", id)]);
+    }
+    let response = requisicao
         .send()
         .await
         .map_err(|e| format!("Erro de rede ao buscar registros de manutenção: {}", e))?;
@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{Local, Months, NaiveDate};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::config::get_api_url;
+use crate::controller::frota::alertas_frota_controller::{ler_parametros_alertas, ParametrosAlertasFrota};
+use crate::controller::frota::consistencia_odometro_controller::ultimas_leituras;
+use crate::controller::frota::manutencao_controller::{buscar_manutencoes, buscar_tipos_manutencao, ApiResponse, FrotaManutencao};
+use crate::controller::frota::veiculo_controller::buscar_veiculos_e_marcas;
+use crate::controller::frota::email_frota_controller::avisar_gestor_frota;
+use crate::monitor::Monitor;
+use crate::utils::parse_data_hora;
+
+const API_RESOURCE: &str = "/frota/manutencao/planos";
+const EVENTO_MANUTENCAO: &str = "manutencao_preventiva";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(3600);
+
+/// Plano de manutenção preventiva de um tipo: a cada `intervalo_km` e/ou `intervalo_meses`.
+/// Sem `veiculo_id` vale para toda a frota; um plano do próprio veículo substitui o geral.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanoManutencao {
+    pub id: Option<u32>,
+    pub tipo_manutencao_id: u32,
+    pub veiculo_id: Option<u32>,
+    pub intervalo_km: Option<u32>,
+    pub intervalo_meses: Option<u32>,
+    #[serde(default = "plano_ativo")]
+    pub ativo: bool,
+}
+
+fn plano_ativo() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoManutencao {
+    EmDia,
+    AVencer,
+    Vencida,
+    /// Nenhuma manutenção do tipo registrada para o veículo
+    SemHistorico,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProximaManutencao {
+    pub proxima_km: Option<i64>,
+    pub proxima_data: Option<String>, // "YYYY-MM-DD"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManutencaoPrevista {
+    pub veiculo_id: u32,
+    pub veiculo: String,
+    pub tipo_manutencao_id: u32,
+    pub tipo_manutencao: String,
+    pub plano_id: Option<u32>,
+    pub ultima_manutencao_id: Option<u32>,
+    pub ultima_data: Option<String>,
+    pub ultimo_km: Option<u32>,
+    /// Leitura mais recente entre viagens, abastecimentos e manutenções
+    pub km_atual: Option<i64>,
+    pub proxima_km: Option<i64>,
+    pub proxima_data: Option<String>,
+    pub km_restante: Option<i64>,
+    pub dias_restantes: Option<i64>,
+    pub situacao: SituacaoManutencao,
+}
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn listar_planos_manutencao(app_handle: AppHandle) -> Result<Vec<PlanoManutencao>, String> {
+    buscar_planos(&Client::new(), &get_api_url(&app_handle)).await
+}
+
+#[command]
+pub async fn salvar_plano_manutencao(app_handle: AppHandle, plano: PlanoManutencao) -> Result<String, String> {
+    if plano.intervalo_km.unwrap_or(0) == 0 && plano.intervalo_meses.unwrap_or(0) == 0 {
+        return Err("Informe o intervalo em km e/ou em meses".to_string());
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let request = match plano.id {
+        Some(id) => client.put(format!("{}{}/{}", api_url, API_RESOURCE, id)),
+        None => client.post(format!("{}{}", api_url, API_RESOURCE)),
+    };
+
+    let response = request
+        .json(&plano)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao salvar plano de manutenção: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+    Ok("Plano de manutenção salvo".to_string())
+}
+
+#[command]
+pub async fn excluir_plano_manutencao(app_handle: AppHandle, id: u32) -> Result<String, String> {
+    let client = Client::new();
+    let url = format!("{}{}/{}", get_api_url(&app_handle), API_RESOURCE, id);
+
+    let response = client
+        .delete(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao excluir plano de manutenção: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+    Ok("Plano de manutenção excluído".to_string())
+}
+
+/// Próximo km/data de um serviço feito em `data` com o odômetro em `km`, segundo o plano do tipo
+#[command]
+pub async fn calcular_proxima_manutencao(
+    app_handle: AppHandle,
+    veiculo_id: u32,
+    tipo_manutencao_id: u32,
+    data: String,
+    km: Option<u32>,
+) -> Result<ProximaManutencao, String> {
+    let planos = buscar_planos(&Client::new(), &get_api_url(&app_handle)).await?;
+    let Some(plano) = plano_aplicavel(&planos, veiculo_id, tipo_manutencao_id) else {
+        return Ok(ProximaManutencao::default());
+    };
+    let data = parse_data_hora(&data, None).map(|d| d.date());
+    Ok(proxima(plano, data, km))
+}
+
+/// Manutenções preventivas de cada veículo com a situação (em dia, a vencer, vencida)
+#[command]
+pub async fn painel_manutencao_preventiva(
+    app_handle: AppHandle,
+    somente_pendentes: Option<bool>,
+) -> Result<Vec<ManutencaoPrevista>, String> {
+    let mut previstas = coletar_previsoes(&app_handle).await?;
+    if somente_pendentes.unwrap_or(false) {
+        previstas.retain(|p| matches!(p.situacao, SituacaoManutencao::AVencer | SituacaoManutencao::Vencida));
+    }
+    Ok(previstas)
+}
+
+// ==================== MONITOR ====================
+
+/// Emite `manutencao_preventiva` e avisa o gestor da frota por e-mail quando
+/// uma manutenção entra em "a vencer" ou vence
+pub async fn monitorar_manutencoes(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("manutenção preventiva", EVENTO_MANUTENCAO);
+
+    loop {
+        monitor.aguardar(INTERVALO_MONITOR).await;
+        let pendentes: Vec<ManutencaoPrevista> = match coletar_previsoes(&app_handle).await {
+            Ok(previstas) => previstas
+                .into_iter()
+                .filter(|p| matches!(p.situacao, SituacaoManutencao::AVencer | SituacaoManutencao::Vencida))
+                .collect(),
+            Err(e) => {
+                monitor.falha(&e);
+                continue;
+            }
+        };
+
+        let novos = monitor.notificar(&app_handle, &pendentes, |p| (p.veiculo_id, p.tipo_manutencao_id, p.situacao));
+        if !novos.is_empty() {
+            if let Err(e) = avisar_gestor(&app_handle, &novos).await {
+                println!("Falha ao avisar gestor da frota: {}", e);
+            }
+        }
+    }
+}
+
+async fn avisar_gestor(app_handle: &AppHandle, pendentes: &[&ManutencaoPrevista]) -> Result<(), String> {
+    avisar_gestor_frota(
+        app_handle,
+        &format!("Manutenção preventiva: {} pendência(s)", pendentes.len()),
+        redigir_aviso(pendentes),
+    )
+    .await
+}
+
+fn redigir_aviso(pendentes: &[&ManutencaoPrevista]) -> String {
+    let mut texto = String::from("Manutenções preventivas que precisam de atenção:\n\n");
+    for p in pendentes {
+        let situacao = if p.situacao == SituacaoManutencao::Vencida { "VENCIDA" } else { "a vencer" };
+        let mut limites = Vec::new();
+        if let Some(km) = p.proxima_km {
+            limites.push(format!("{} km", km));
+        }
+        if let Some(data) = p.proxima_data.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+            limites.push(data.format("%d/%m/%Y").to_string());
+        }
+        texto.push_str(&format!("- {} | {}: {} (limite {})\n", p.veiculo, p.tipo_manutencao, situacao, limites.join(" ou ")));
+    }
+    texto
+}
+
+// ==================== CÁLCULO ====================
+
+async fn buscar_planos(client: &Client, api_url: &str) -> Result<Vec<PlanoManutencao>, String> {
+    let url = format!("{}{}", api_url, API_RESOURCE);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao buscar planos de manutenção: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_else(|_| "Não foi possível ler o corpo do erro".into());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<Vec<PlanoManutencao>> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+
+    if api_response.success {
+        Ok(api_response.data.unwrap_or_default())
+    } else {
+        Err(api_response.message.unwrap_or_else(|| "API retornou falha sem mensagem.".to_string()))
+    }
+}
+
+/// Plano ativo do veículo para o tipo; na falta dele, o plano geral do tipo
+fn plano_aplicavel(planos: &[PlanoManutencao], veiculo_id: u32, tipo_manutencao_id: u32) -> Option<&PlanoManutencao> {
+    let do_tipo = || planos.iter().filter(move |p| p.ativo && p.tipo_manutencao_id == tipo_manutencao_id);
+    do_tipo()
+        .find(|p| p.veiculo_id == Some(veiculo_id))
+        .or_else(|| do_tipo().find(|p| p.veiculo_id.is_none()))
+}
+
+fn proxima(plano: &PlanoManutencao, data: Option<NaiveDate>, km: Option<u32>) -> ProximaManutencao {
+    ProximaManutencao {
+        proxima_km: km
+            .zip(plano.intervalo_km.filter(|i| *i > 0))
+            .map(|(km, intervalo)| i64::from(km) + i64::from(intervalo)),
+        proxima_data: data
+            .zip(plano.intervalo_meses.filter(|i| *i > 0))
+            .and_then(|(data, meses)| data.checked_add_months(Months::new(meses)))
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    }
+}
+
+fn prever(
+    plano: &PlanoManutencao,
+    ultima: Option<&FrotaManutencao>,
+    km_atual: Option<i64>,
+    hoje: NaiveDate,
+    parametros: &ParametrosAlertasFrota,
+) -> (ProximaManutencao, Option<i64>, Option<i64>, SituacaoManutencao) {
+    let Some(ultima) = ultima else {
+        return (ProximaManutencao::default(), None, None, SituacaoManutencao::SemHistorico);
+    };
+
+    let prevista = proxima(plano, ultima.data_servico().map(|d| d.date()), ultima.km);
+    let km_restante = prevista.proxima_km.zip(km_atual).map(|(proxima, atual)| proxima - atual);
+    let dias_restantes = prevista
+        .proxima_data
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map(|d| (d - hoje).num_days());
+
+    // Vence pelo que acontecer primeiro: km ou data
+    let situacao = if km_restante.is_some_and(|k| k <= 0) || dias_restantes.is_some_and(|d| d <= 0) {
+        SituacaoManutencao::Vencida
+    } else if km_restante.is_some_and(|k| k <= parametros.antecedencia_manutencao_km)
+        || dias_restantes.is_some_and(|d| d <= parametros.antecedencia_manutencao_dias)
+    {
+        SituacaoManutencao::AVencer
+    } else {
+        SituacaoManutencao::EmDia
+    };
+    (prevista, km_restante, dias_restantes, situacao)
+}
+
+async fn coletar_previsoes(app_handle: &AppHandle) -> Result<Vec<ManutencaoPrevista>, String> {
+    let planos = buscar_planos(&Client::new(), &get_api_url(app_handle)).await?;
+    if planos.is_empty() {
+        return Ok(Vec::new());
+    }
+    let veiculos = buscar_veiculos_e_marcas(app_handle.clone()).await?;
+    let manutencoes = buscar_manutencoes(app_handle.clone()).await?;
+    let tipos: HashMap<u32, String> = buscar_tipos_manutencao(app_handle.clone())
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|t| t.id.map(|id| (id, t.nome)))
+        .collect();
+    let leituras = ultimas_leituras(app_handle).await?;
+    let parametros = ler_parametros_alertas(app_handle);
+    let hoje = Local::now().date_naive();
+
+    let mut tipos_planejados: Vec<u32> = planos.iter().filter(|p| p.ativo).map(|p| p.tipo_manutencao_id).collect();
+    tipos_planejados.sort();
+    tipos_planejados.dedup();
+
+    let mut previstas = Vec::new();
+    for veiculo in &veiculos {
+        let km_atual = leituras.get(&veiculo.id).map(|l| l.km);
+        for tipo_id in &tipos_planejados {
+            let Some(plano) = plano_aplicavel(&planos, veiculo.id, *tipo_id) else {
+                continue;
+            };
+            let ultima = manutencoes
+                .iter()
+                .filter(|m| m.veiculo == Some(veiculo.id) && m.tipo == Some(*tipo_id))
+                // Agendada ainda não conta como feita
+                .filter(|m| m.data_servico().is_some())
+                .max_by_key(|m| (m.data_servico(), m.km, m.id));
+
+            let (prevista, km_restante, dias_restantes, situacao) = prever(plano, ultima, km_atual, hoje, &parametros);
+            previstas.push(ManutencaoPrevista {
+                veiculo_id: veiculo.id,
+                veiculo: format!("{} ({})", veiculo.nome, veiculo.placa),
+                tipo_manutencao_id: *tipo_id,
+                tipo_manutencao: tipos.get(tipo_id).cloned().unwrap_or_else(|| format!("Tipo {}", tipo_id)),
+                plano_id: plano.id,
+                ultima_manutencao_id: ultima.map(|m| m.id),
+                ultima_data: ultima.and_then(|m| m.data_servico()).map(|d| d.format("%Y-%m-%d").to_string()),
+                ultimo_km: ultima.and_then(|m| m.km),
+                km_atual,
+                proxima_km: prevista.proxima_km,
+                proxima_data: prevista.proxima_data,
+                km_restante,
+                dias_restantes,
+                situacao,
+            });
+        }
+    }
+
+    // Mais urgentes primeiro
+    let ordem = |s: SituacaoManutencao| match s {
+        SituacaoManutencao::Vencida => 0,
+        SituacaoManutencao::AVencer => 1,
+        SituacaoManutencao::SemHistorico => 2,
+        SituacaoManutencao::EmDia => 3,
+    };
+    previstas.sort_by_key(|p| (ordem(p.situacao), p.dias_restantes.unwrap_or(i64::MAX), p.km_restante.unwrap_or(i64::MAX)));
+    Ok(previstas)
+}
+
+/// Preenche `proxima` de uma manutenção nova a partir do plano do tipo, quando não informada
+pub async fn sugerir_proxima_data(
+    app_handle: &AppHandle,
+    veiculo_id: u32,
+    tipo_manutencao_id: u32,
+    data: NaiveDate,
+) -> Option<NaiveDate> {
+    let planos = buscar_planos(&Client::new(), &get_api_url(app_handle)).await.ok()?;
+    let plano = plano_aplicavel(&planos, veiculo_id, tipo_manutencao_id)?;
+    proxima(plano, Some(data), None)
+        .proxima_data
+        .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plano(id: u32, veiculo_id: Option<u32>, intervalo_km: Option<u32>, intervalo_meses: Option<u32>) -> PlanoManutencao {
+        PlanoManutencao { id: Some(id), tipo_manutencao_id: 1, veiculo_id, intervalo_km, intervalo_meses, ativo: true }
+    }
+
+    fn manutencao(data: &str, km: u32) -> FrotaManutencao {
+        FrotaManutencao {
+            id: 10,
+            tipo: Some(1),
+            veiculo: Some(7),
+            data: data.to_string(),
+            data_manutencao: None,
+            observacao: None,
+            data_realizada: Some(data.to_string()),
+            km: Some(km),
+            proxima: None,
+            valor: None,
+        }
+    }
+
+    #[test]
+    fn test_prever_manutencao() {
+        let planos = vec![plano(1, None, Some(10_000), Some(6)), plano(2, Some(7), Some(5_000), None)];
+        assert_eq!(plano_aplicavel(&planos, 7, 1).and_then(|p| p.id), Some(2));
+        assert_eq!(plano_aplicavel(&planos, 8, 1).and_then(|p| p.id), Some(1));
+        assert!(plano_aplicavel(&planos, 8, 2).is_none());
+
+        let parametros = ParametrosAlertasFrota::default();
+        let hoje = NaiveDate::from_ymd_opt(2026, 6, 20).unwrap();
+        let ultima = manutencao("2026-01-10", 40_000);
+
+        let (prevista, km_restante, dias, situacao) = prever(&planos[0], Some(&ultima), Some(45_000), hoje, &parametros);
+        assert_eq!(prevista.proxima_km, Some(50_000));
+        assert_eq!(prevista.proxima_data.as_deref(), Some("2026-07-10"));
+        assert_eq!(km_restante, Some(5_000));
+        assert_eq!(dias, Some(20));
+        assert_eq!(situacao, SituacaoManutencao::EmDia);
+
+        let (_, _, _, situacao) = prever(&planos[0], Some(&ultima), Some(49_600), hoje, &parametros);
+        assert_eq!(situacao, SituacaoManutencao::AVencer);
+        let (_, _, _, situacao) = prever(&planos[1], Some(&ultima), Some(45_200), hoje, &parametros);
+        assert_eq!(situacao, SituacaoManutencao::Vencida);
+        let (_, _, _, situacao) = prever(&planos[0], None, Some(45_000), hoje, &parametros);
+        assert_eq!(situacao, SituacaoManutencao::SemHistorico);
+    }
+}
@@ -4,4 +4,12 @@ pub mod veiculo_controller;
 pub mod posto_controller;
 pub mod viagem_controller;
 pub mod abastecimento_controller;
-pub mod manutencao_controller;
\ No newline at end of file
+pub mod manutencao_controller;
+pub mod analise_abastecimento_controller;
+pub mod consistencia_odometro_controller;
+pub mod manutencao_preventiva_controller;
+pub mod rota_coleta_controller;
+pub mod comprovante_abastecimento_controller;
+pub mod custo_veiculo_controller;
+pub mod email_frota_controller;
+pub mod alertas_frota_controller;
\ No newline at end of file
@@ -1,9 +1,18 @@
-use chrono::{NaiveDate, NaiveTime};
+use std::collections::HashSet;
+use std::time::Duration;
+use chrono::{Local, NaiveDate, NaiveTime};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::frota::alertas_frota_controller::ler_parametros_alertas;
+use crate::controller::frota::veiculo_controller::buscar_veiculos_e_marcas;
+use crate::monitor::Monitor;
+use crate::utils::{is_valid_cnh, parse_data_hora};
+
+const EVENTO_VENCIMENTO_CNH: &str = "vencimento_cnh";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(3600);
 
 // Estrutura de resposta da API genérica para ser reutilizável
 #[derive(Deserialize, Serialize, Debug)]
@@ -19,6 +28,13 @@ pub struct Motorista {
     pub id: u32,
     pub nome: String,
     pub cnh: String,
+    #[serde(default)]
+    pub categoria_cnh: Option<String>,
+    #[serde(default)]
+    pub validade_cnh: Option<String>, // "YYYY-MM-DD"
+    /// Observação EAR (exerce atividade remunerada) na CNH
+    #[serde(default)]
+    pub ear: Option<bool>,
 }
 
 // Estrutura para criar/editar motorista
@@ -26,6 +42,9 @@ pub struct Motorista {
 pub struct MotoristaInput {
     pub nome: String,
     pub cnh: String,
+    pub categoria_cnh: Option<String>,
+    pub validade_cnh: Option<String>,
+    pub ear: Option<bool>,
 }
 
 #[command]
@@ -58,12 +77,19 @@ pub async fn buscar_motoristas(app_handle: AppHandle) -> Result<Vec<Motorista>,
 }
 
 #[command]
-pub async fn criar_motorista(app_handle: AppHandle, nome: String, cnh: String) -> Result<Motorista, String> {
+pub async fn criar_motorista(
+    app_handle: AppHandle,
+    nome: String,
+    cnh: String,
+    categoria_cnh: Option<String>,
+    validade_cnh: Option<String>,
+    ear: Option<bool>,
+) -> Result<Motorista, String> {
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/criar_motorista", api_url);
 
-    let motorista_input = MotoristaInput { nome, cnh };
+    let motorista_input = montar_input(nome, cnh, categoria_cnh, validade_cnh, ear)?;
 
     let response = match client
         .post(&url)
@@ -90,6 +116,9 @@ pub async fn criar_motorista(app_handle: AppHandle, nome: String, cnh: String) -
                         id: 0,
                         nome: "".to_string(),
                         cnh: "".to_string(),
+                        categoria_cnh: None,
+                        validade_cnh: None,
+                        ear: None,
                     }
                 }))
             } else {
@@ -101,12 +130,20 @@ pub async fn criar_motorista(app_handle: AppHandle, nome: String, cnh: String) -
 }
 
 #[command]
-pub async fn atualizar_motorista(app_handle: AppHandle, id: u32, nome: String, cnh: String) -> Result<Motorista, String> {
+pub async fn atualizar_motorista(
+    app_handle: AppHandle,
+    id: u32,
+    nome: String,
+    cnh: String,
+    categoria_cnh: Option<String>,
+    validade_cnh: Option<String>,
+    ear: Option<bool>,
+) -> Result<Motorista, String> {
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/atualizar_motorista/{}", api_url, id);
 
-    let motorista_input = MotoristaInput { nome, cnh };
+    let motorista_input = montar_input(nome, cnh, categoria_cnh, validade_cnh, ear)?;
 
     let response = match client
         .put(&url)
@@ -133,6 +170,9 @@ pub async fn atualizar_motorista(app_handle: AppHandle, id: u32, nome: String, c
                         id,
                         nome: "".to_string(),
                         cnh: "".to_string(),
+                        categoria_cnh: None,
+                        validade_cnh: None,
+                        ear: None,
                     }
                 }))
             } else {
@@ -170,4 +210,259 @@ pub async fn deletar_motorista(app_handle: AppHandle, id: u32) -> Result<bool, S
         }
         Err(e) => Err(format!("Erro ao processar o JSON da resposta: {}", e)),
     }
-}
\ No newline at end of file
+}
+
+// ==================== HABILITAÇÃO (CNH) ====================
+
+const CATEGORIAS_CNH: [&str; 9] = ["A", "B", "C", "D", "E", "AB", "AC", "AD", "AE"];
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoCnh {
+    AVencer,
+    Vencida,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AlertaCnh {
+    pub motorista_id: u32,
+    pub nome: String,
+    pub validade_cnh: String,
+    pub dias_restantes: i64,
+    pub situacao: SituacaoCnh,
+}
+
+/// Motoristas com CNH vencida ou vencendo dentro da antecedência configurada
+#[command]
+pub async fn verificar_vencimento_cnh(app_handle: AppHandle) -> Result<Vec<AlertaCnh>, String> {
+    let motoristas = buscar_motoristas(app_handle.clone()).await?;
+    let antecedencia = ler_parametros_alertas(&app_handle).antecedencia_vencimento_cnh_dias;
+    Ok(alertas_cnh(&motoristas, Local::now().date_naive(), antecedencia))
+}
+
+/// Emite `vencimento_cnh` quando a CNH de um motorista entra no prazo de aviso ou vence
+pub async fn monitorar_cnh(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("CNH", EVENTO_VENCIMENTO_CNH);
+
+    loop {
+        monitor.aguardar(INTERVALO_MONITOR).await;
+        match verificar_vencimento_cnh(app_handle.clone()).await {
+            Ok(alertas) => {
+                monitor.notificar(&app_handle, &alertas, |a| (a.motorista_id, a.situacao));
+            }
+            Err(e) => monitor.falha(&e),
+        }
+    }
+}
+
+/// Confere se o motorista pode conduzir o veículo durante toda a viagem.
+/// Sem validade da CNH (ou sem categoria, quando o veículo exige uma) a viagem é recusada.
+pub async fn verificar_habilitacao_viagem(
+    app_handle: &AppHandle,
+    motorista_id: u32,
+    veiculo_id: u32,
+    data_inicio: &str,
+    data_termino: Option<&str>,
+) -> Result<(), String> {
+    let motoristas = buscar_motoristas(app_handle.clone()).await?;
+    let motorista = motoristas
+        .iter()
+        .find(|m| m.id == motorista_id)
+        .ok_or_else(|| format!("Motorista {} não encontrado", motorista_id))?;
+    let veiculos = buscar_veiculos_e_marcas(app_handle.clone()).await?;
+    let categoria_exigida = veiculos
+        .iter()
+        .find(|v| v.id == veiculo_id)
+        .and_then(|v| v.categoria_cnh.clone());
+
+    let inicio = parse_data_hora(data_inicio, None)
+        .map(|d| d.date())
+        .ok_or_else(|| format!("Data de início inválida: {}", data_inicio))?;
+    let fim = data_termino
+        .and_then(|d| parse_data_hora(d, None))
+        .map(|d| d.date())
+        .unwrap_or(inicio);
+
+    validar_habilitacao(motorista, categoria_exigida.as_deref(), inicio, fim, ler_parametros_alertas(app_handle).exigir_ear)
+}
+
+fn montar_input(
+    nome: String,
+    cnh: String,
+    categoria_cnh: Option<String>,
+    validade_cnh: Option<String>,
+    ear: Option<bool>,
+) -> Result<MotoristaInput, String> {
+    if nome.trim().is_empty() {
+        return Err("Nome do motorista não pode estar vazio".to_string());
+    }
+    if !is_valid_cnh(&cnh) {
+        return Err(format!("Número de CNH inválido: {}", cnh));
+    }
+    let categoria_cnh = categoria_cnh
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| normalizar_categoria_cnh(&c))
+        .transpose()?;
+    let validade_cnh = validade_cnh
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            parse_data_hora(&v, None)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .ok_or_else(|| format!("Validade da CNH inválida: {}", v))
+        })
+        .transpose()?;
+
+    Ok(MotoristaInput {
+        nome: nome.trim().to_string(),
+        cnh: cnh.chars().filter(|c| c.is_ascii_digit()).collect(),
+        categoria_cnh,
+        validade_cnh,
+        ear,
+    })
+}
+
+/// Aceita "A", "B", "C", "D", "E" e as combinações com A ("AB", "AE"...)
+pub fn normalizar_categoria_cnh(categoria: &str) -> Result<String, String> {
+    let normalizada: String = categoria.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+    if CATEGORIAS_CNH.contains(&normalizada.as_str()) {
+        Ok(normalizada)
+    } else {
+        Err(format!("Categoria de CNH inválida: {}", categoria))
+    }
+}
+
+// C, D e E também habilitam as categorias inferiores de veículos de quatro rodas
+fn categorias_habilitadas(categoria: &str) -> HashSet<char> {
+    let mut habilitadas = HashSet::new();
+    for letra in categoria.chars() {
+        let incluidas: &[char] = match letra {
+            'A' => &['A'],
+            'B' => &['B'],
+            'C' => &['B', 'C'],
+            'D' => &['B', 'C', 'D'],
+            'E' => &['B', 'C', 'D', 'E'],
+            _ => &[],
+        };
+        habilitadas.extend(incluidas);
+    }
+    habilitadas
+}
+
+pub fn categoria_compativel(categoria_motorista: &str, categoria_exigida: &str) -> bool {
+    let habilitadas = categorias_habilitadas(categoria_motorista);
+    categoria_exigida.chars().all(|letra| habilitadas.contains(&letra))
+}
+
+fn validar_habilitacao(
+    motorista: &Motorista,
+    categoria_exigida: Option<&str>,
+    inicio: NaiveDate,
+    fim: NaiveDate,
+    exigir_ear: bool,
+) -> Result<(), String> {
+    match motorista.validade_cnh.as_deref().and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()) {
+        Some(validade) if validade < fim.max(inicio) => {
+            return Err(format!(
+                "CNH de {} vence em {} e não cobre a viagem",
+                motorista.nome,
+                validade.format("%d/%m/%Y")
+            ));
+        }
+        Some(_) => {}
+        None => {
+            return Err(format!(
+                "{} não tem a validade da CNH cadastrada; complete o cadastro do motorista",
+                motorista.nome
+            ));
+        }
+    }
+
+    match (motorista.categoria_cnh.as_deref(), categoria_exigida) {
+        (Some(categoria), Some(exigida)) if !categoria_compativel(categoria, exigida) => {
+            return Err(format!(
+                "CNH categoria {} de {} não habilita para veículo categoria {}",
+                categoria, motorista.nome, exigida
+            ));
+        }
+        (None, Some(exigida)) => {
+            return Err(format!(
+                "{} não tem a categoria da CNH cadastrada e o veículo exige categoria {}; complete o cadastro do motorista",
+                motorista.nome, exigida
+            ));
+        }
+        _ => {}
+    }
+
+    if exigir_ear && motorista.ear != Some(true) {
+        return Err(format!("CNH de {} não possui a observação EAR", motorista.nome));
+    }
+    Ok(())
+}
+
+fn alertas_cnh(motoristas: &[Motorista], hoje: NaiveDate, antecedencia_dias: i64) -> Vec<AlertaCnh> {
+    let mut alertas: Vec<AlertaCnh> = motoristas
+        .iter()
+        .filter_map(|m| {
+            let validade = m.validade_cnh.as_deref()?;
+            let dias_restantes = (NaiveDate::parse_from_str(validade, "%Y-%m-%d").ok()? - hoje).num_days();
+            let situacao = match dias_restantes {
+                d if d < 0 => SituacaoCnh::Vencida,
+                d if d <= antecedencia_dias => SituacaoCnh::AVencer,
+                _ => return None,
+            };
+            Some(AlertaCnh {
+                motorista_id: m.id,
+                nome: m.nome.clone(),
+                validade_cnh: validade.to_string(),
+                dias_restantes,
+                situacao,
+            })
+        })
+        .collect();
+    alertas.sort_by_key(|a| a.dias_restantes);
+    alertas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn motorista(categoria: &str, validade: &str) -> Motorista {
+        Motorista {
+            id: 1,
+            nome: "João".to_string(),
+            cnh: "12345678900".to_string(),
+            categoria_cnh: Some(categoria.to_string()),
+            validade_cnh: Some(validade.to_string()),
+            ear: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_validar_habilitacao() {
+        assert_eq!(normalizar_categoria_cnh(" ae "), Ok("AE".to_string()));
+        assert!(normalizar_categoria_cnh("F").is_err());
+        assert!(categoria_compativel("D", "C"));
+        assert!(categoria_compativel("AB", "A"));
+        assert!(!categoria_compativel("E", "A"));
+        assert!(!categoria_compativel("B", "C"));
+
+        let dia = |d: u32| NaiveDate::from_ymd_opt(2026, 5, d).unwrap();
+        let m = motorista("D", "2026-05-10");
+        assert!(validar_habilitacao(&m, Some("C"), dia(1), dia(10), false).is_ok());
+        assert!(validar_habilitacao(&m, Some("C"), dia(9), dia(11), false).is_err());
+        assert!(validar_habilitacao(&m, Some("E"), dia(1), dia(2), false).is_err());
+        assert!(validar_habilitacao(&m, None, dia(1), dia(2), true).is_err());
+
+        let sem_dados = Motorista { categoria_cnh: None, validade_cnh: None, ..motorista("D", "2026-05-10") };
+        assert!(validar_habilitacao(&sem_dados, None, dia(1), dia(2), false).is_err());
+        let sem_categoria = Motorista { categoria_cnh: None, ..motorista("D", "2026-05-10") };
+        assert!(validar_habilitacao(&sem_categoria, None, dia(1), dia(2), false).is_ok());
+        assert!(validar_habilitacao(&sem_categoria, Some("B"), dia(1), dia(2), false).is_err());
+
+        let alertas = alertas_cnh(&[m, motorista("B", "2026-04-30")], dia(1), 30);
+        assert_eq!(alertas.len(), 2);
+        assert_eq!(alertas[0].situacao, SituacaoCnh::Vencida);
+        assert_eq!(alertas[1].situacao, SituacaoCnh::AVencer);
+    }
+}
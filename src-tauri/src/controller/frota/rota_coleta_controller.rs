@@ -0,0 +1,785 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{Local, NaiveDate, NaiveTime, Timelike};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use crate::controller::frota::consistencia_odometro_controller::ultimas_leituras;
+use crate::controller::frota::frota_controller::buscar_agendamentos_hoje;
+use crate::controller::geral::cadastrarcliente_controller::{get_cliente_data, DadosCliente};
+use crate::dados_locais::{gravar_json, ler_json, mesclar_cache};
+use crate::model::consulta_endereco::ProvedorConsulta;
+use crate::utils::{clean_document, normalizar_texto};
+
+const ARQUIVO_CONFIG: &str = "rotas_coleta.json";
+const ARQUIVO_CACHE: &str = "geocodificacao_cache.json";
+// Política de uso do Nominatim: no máximo uma requisição por segundo
+const INTERVALO_NOMINATIM: Duration = Duration::from_secs(1);
+// 23:59, último horário aceito por `minutos_do_dia`
+const ULTIMO_MINUTO_DO_DIA: i64 = 23 * 60 + 59;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Coordenada {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EnderecoColeta {
+    pub endereco: Option<String>,
+    pub numero: Option<String>,
+    pub bairro: Option<String>,
+    pub cidade: Option<String>,
+    pub uf: Option<String>,
+    pub cep: Option<String>,
+}
+
+impl EnderecoColeta {
+    pub fn texto(&self) -> String {
+        let rua = match (self.endereco.as_deref(), self.numero.as_deref()) {
+            (Some(rua), Some(numero)) if !numero.trim().is_empty() => Some(format!("{}, {}", rua.trim(), numero.trim())),
+            (Some(rua), _) => Some(rua.trim().to_string()),
+            _ => None,
+        };
+        [rua, self.bairro.clone(), self.cidade.clone(), self.uf.clone()]
+            .into_iter()
+            .flatten()
+            .map(|parte| parte.trim().to_string())
+            .filter(|parte| !parte.is_empty())
+            .collect::<Vec<_>>()
+            .join(" - ")
+    }
+}
+
+/// Configuração local do planejamento (arquivo `rotas_coleta.json`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfiguracaoRotas {
+    pub provedor: ProvedorConsulta,
+    /// Servidor compatível com a API do Nominatim; vazio até ser configurado
+    pub url_nominatim: String,
+    /// Chave: endereço normalizado (`normalizar_texto`) ou CEP só com dígitos
+    pub coordenadas_estaticas: HashMap<String, Coordenada>,
+    /// Ponto de saída e retorno das rotas (laboratório)
+    pub base: EnderecoColeta,
+    pub base_coordenada: Option<Coordenada>,
+    pub velocidade_media_kmh: f64,
+    /// Multiplica a distância em linha reta para estimar a distância por ruas
+    pub fator_rota: f64,
+    pub duracao_parada_minutos: i64,
+    /// Janela em torno do horário agendado em que a chegada é aceita
+    pub tolerancia_janela_minutos: i64,
+    pub horario_saida: String, // "HH:MM"
+}
+
+impl Default for ConfiguracaoRotas {
+    fn default() -> Self {
+        ConfiguracaoRotas {
+            provedor: ProvedorConsulta::Estatico,
+            url_nominatim: String::new(),
+            coordenadas_estaticas: HashMap::new(),
+            base: EnderecoColeta::default(),
+            base_coordenada: None,
+            velocidade_media_kmh: 40.0,
+            fator_rota: 1.3,
+            duracao_parada_minutos: 20,
+            tolerancia_janela_minutos: 60,
+            horario_saida: "08:00".to_string(),
+        }
+    }
+}
+
+/// Coleta a visitar; sem `veiculo_id` o planejamento escolhe o veículo
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParadaColeta {
+    pub agendamento_id: Option<u32>,
+    pub descricao: String,
+    pub cliente_id: Option<u32>,
+    #[serde(default)]
+    pub endereco: EnderecoColeta,
+    pub coordenada: Option<Coordenada>,
+    pub janela_inicio: Option<String>, // "HH:MM"
+    pub janela_fim: Option<String>,
+    pub duracao_minutos: Option<i64>,
+    pub veiculo_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VeiculoRota {
+    pub veiculo_id: u32,
+    pub motorista_id: u32,
+    pub saida: Option<String>, // "HH:MM"
+    /// Horário máximo de retorno à base
+    pub retorno_limite: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlanejamentoRotasPayload {
+    pub data: Option<String>, // "YYYY-MM-DD"; padrão: hoje
+    pub veiculos: Vec<VeiculoRota>,
+    pub paradas: Vec<ParadaColeta>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParadaPlanejada {
+    pub ordem: usize,
+    pub agendamento_id: Option<u32>,
+    pub descricao: String,
+    pub endereco: String,
+    pub coordenada: Coordenada,
+    pub chegada: String,
+    pub inicio_atendimento: String,
+    pub saida: String,
+    pub espera_minutos: i64,
+    pub distancia_trecho_km: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RotaPlanejada {
+    pub veiculo_id: u32,
+    pub motorista_id: u32,
+    pub paradas: Vec<ParadaPlanejada>,
+    pub distancia_km: f64,
+    pub duracao_minutos: i64,
+    pub saida: String,
+    pub retorno_previsto: String,
+    /// Viagem pré-preenchida para `criar_frota_viagem`
+    pub viagem: ViagemSugerida,
+}
+
+/// Campos de `FrotaViagemInput`; o km inicial fica vazio quando o odômetro do veículo é desconhecido
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViagemSugerida {
+    pub descricao: String,
+    pub origem: String,
+    pub destino: String,
+    pub data_inicio: String,
+    pub quilometragem_inicial: Option<u32>,
+    pub quilometragem_final: Option<u32>,
+    pub veiculo: u32,
+    pub motorista: u32,
+    pub data_termino: Option<String>,
+    /// O usuário precisa informar o km inicial antes de registrar a viagem
+    pub km_inicial_pendente: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParadaNaoAtendida {
+    pub agendamento_id: Option<u32>,
+    pub descricao: String,
+    pub motivo: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanoRotas {
+    pub data: String,
+    pub rotas: Vec<RotaPlanejada>,
+    pub nao_atendidas: Vec<ParadaNaoAtendida>,
+    pub distancia_total_km: f64,
+}
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn carregar_config_rotas(app_handle: AppHandle) -> Result<ConfiguracaoRotas, String> {
+    Ok(ler_json(&app_handle, ARQUIVO_CONFIG))
+}
+
+#[command]
+pub async fn salvar_config_rotas(app_handle: AppHandle, configuracao: ConfiguracaoRotas) -> Result<String, String> {
+    if configuracao.velocidade_media_kmh <= 0.0 || configuracao.fator_rota < 1.0 {
+        return Err("Velocidade média deve ser positiva e o fator de rota no mínimo 1".to_string());
+    }
+    if minutos_do_dia(&configuracao.horario_saida).is_none() {
+        return Err(format!("Horário de saída inválido: {}", configuracao.horario_saida));
+    }
+    if configuracao.provedor == ProvedorConsulta::Servico && configuracao.url_nominatim.trim().is_empty() {
+        return Err("Informe a URL do serviço de geocodificação".to_string());
+    }
+
+    gravar_json(&app_handle, ARQUIVO_CONFIG, &configuracao)?;
+    Ok("Configuração de rotas salva".to_string())
+}
+
+#[command]
+pub async fn geocodificar_endereco(app_handle: AppHandle, endereco: EnderecoColeta) -> Result<Coordenada, String> {
+    let configuracao: ConfiguracaoRotas = ler_json(&app_handle, ARQUIVO_CONFIG);
+    let mut cache = ler_json(&app_handle, ARQUIVO_CACHE);
+    let coordenada = geocodificar(&Client::new(), &configuracao, &mut cache, &endereco).await?;
+    mesclar_cache(&app_handle, ARQUIVO_CACHE, cache)?;
+    Ok(coordenada)
+}
+
+/// Paradas a partir dos agendamentos de hoje, com o endereço do cliente quando disponível
+#[command]
+pub async fn montar_paradas_agendamentos(app_handle: AppHandle) -> Result<Vec<ParadaColeta>, String> {
+    let configuracao: ConfiguracaoRotas = ler_json(&app_handle, ARQUIVO_CONFIG);
+    let agendamentos = buscar_agendamentos_hoje(app_handle.clone()).await?;
+
+    let mut paradas = Vec::new();
+    for agendamento in agendamentos {
+        let endereco = match agendamento.cliente_id {
+            Some(cliente_id) => endereco_cliente(&app_handle, cliente_id).await.unwrap_or_default(),
+            None => EnderecoColeta::default(),
+        };
+        let janela = agendamento
+            .hora
+            .map(|hora| janela_agendamento(i64::from(hora.num_seconds_from_midnight() / 60), configuracao.tolerancia_janela_minutos));
+
+        paradas.push(ParadaColeta {
+            agendamento_id: Some(agendamento.id),
+            descricao: match agendamento.nome {
+                Some(nome) => format!("{} - {}", nome, agendamento.descricao),
+                None => agendamento.descricao,
+            },
+            cliente_id: agendamento.cliente_id,
+            endereco,
+            coordenada: None,
+            janela_inicio: janela.as_ref().map(|j| j.0.clone()),
+            janela_fim: janela.map(|j| j.1),
+            duracao_minutos: None,
+            veiculo_id: None,
+        });
+    }
+    Ok(paradas)
+}
+
+/// Distribui as paradas entre os veículos e ordena cada rota respeitando janelas e jornada
+#[command]
+pub async fn planejar_rotas_coleta(app_handle: AppHandle, payload: PlanejamentoRotasPayload) -> Result<PlanoRotas, String> {
+    if payload.veiculos.is_empty() {
+        return Err("Informe ao menos um veículo para o planejamento".to_string());
+    }
+    let data = match payload.data.as_deref() {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| format!("Data inválida: {}", d))?,
+        None => Local::now().date_naive(),
+    };
+
+    let configuracao: ConfiguracaoRotas = ler_json(&app_handle, ARQUIVO_CONFIG);
+    let client = Client::new();
+    let mut cache = ler_json(&app_handle, ARQUIVO_CACHE);
+
+    let base = match configuracao.base_coordenada {
+        Some(base) => base,
+        None => geocodificar(&client, &configuracao, &mut cache, &configuracao.base)
+            .await
+            .map_err(|e| format!("Base das rotas sem localização: {}", e))?,
+    };
+
+    let mut paradas = Vec::new();
+    let mut nao_atendidas = Vec::new();
+    for parada in payload.paradas {
+        let coordenada = match parada.coordenada {
+            Some(c) => c,
+            None => match geocodificar(&client, &configuracao, &mut cache, &parada.endereco).await {
+                Ok(c) => c,
+                Err(e) => {
+                    nao_atendidas.push(ParadaNaoAtendida {
+                        agendamento_id: parada.agendamento_id,
+                        descricao: parada.descricao,
+                        motivo: e,
+                    });
+                    continue;
+                }
+            },
+        };
+        paradas.push(ParadaInterna {
+            coordenada,
+            janela_inicio: parada.janela_inicio.as_deref().and_then(minutos_do_dia),
+            janela_fim: parada.janela_fim.as_deref().and_then(minutos_do_dia),
+            duracao: parada.duracao_minutos.unwrap_or(configuracao.duracao_parada_minutos),
+            veiculo_id: parada.veiculo_id,
+            origem: parada,
+        });
+    }
+    if let Err(e) = mesclar_cache(&app_handle, ARQUIVO_CACHE, cache) {
+        println!("Falha ao gravar cache de geocodificação: {}", e);
+    }
+
+    let saida_padrao = minutos_do_dia(&configuracao.horario_saida).unwrap_or(8 * 60);
+    let veiculos: Vec<JornadaVeiculo> = payload
+        .veiculos
+        .iter()
+        .map(|v| JornadaVeiculo {
+            veiculo_id: v.veiculo_id,
+            saida: v.saida.as_deref().and_then(minutos_do_dia).unwrap_or(saida_padrao),
+            retorno_limite: v.retorno_limite.as_deref().and_then(minutos_do_dia),
+        })
+        .collect();
+
+    let contexto = ContextoRota { base, paradas: &paradas, configuracao: &configuracao };
+    let (ordens, sem_rota) = distribuir_paradas(&contexto, &veiculos);
+    nao_atendidas.extend(sem_rota.into_iter().map(|(indice, motivo)| ParadaNaoAtendida {
+        agendamento_id: paradas[indice].origem.agendamento_id,
+        descricao: paradas[indice].origem.descricao.clone(),
+        motivo,
+    }));
+
+    // Km atual de cada veículo para a viagem pré-preenchida
+    let km_atual: HashMap<u32, i64> = match ultimas_leituras(&app_handle).await {
+        Ok(leituras) => leituras.into_iter().map(|(id, l)| (id, l.km)).collect(),
+        Err(e) => {
+            println!("Km atual dos veículos indisponível: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let mut rotas = Vec::new();
+    for ((veiculo, jornada), ordem) in payload.veiculos.iter().zip(&veiculos).zip(ordens) {
+        if ordem.is_empty() {
+            continue;
+        }
+        let Some(simulacao) = simular(&contexto, jornada, &ordem) else {
+            continue;
+        };
+        rotas.push(montar_rota(
+            &contexto,
+            veiculo,
+            jornada,
+            &ordem,
+            &simulacao,
+            data,
+            km_atual.get(&veiculo.veiculo_id).copied(),
+        ));
+    }
+
+    Ok(PlanoRotas {
+        data: data.format("%Y-%m-%d").to_string(),
+        distancia_total_km: rotas.iter().map(|r| r.distancia_km).sum(),
+        rotas,
+        nao_atendidas,
+    })
+}
+
+// ==================== GEOCODIFICAÇÃO ====================
+
+async fn endereco_cliente(app_handle: &AppHandle, cliente_id: u32) -> Option<EnderecoColeta> {
+    let resposta = get_cliente_data(app_handle.clone(), cliente_id).await;
+    let dados: DadosCliente = serde_json::from_value(resposta.data?).ok()?;
+    let gerais = dados.dados_gerais;
+    Some(EnderecoColeta {
+        endereco: gerais.endereco,
+        numero: gerais.numero,
+        bairro: gerais.bairro,
+        cidade: gerais.cidade,
+        uf: gerais.uf,
+        cep: gerais.cep,
+    })
+}
+
+fn consultar_estatico(configuracao: &ConfiguracaoRotas, endereco: &EnderecoColeta) -> Option<Coordenada> {
+    let por_endereco = configuracao.coordenadas_estaticas.get(&normalizar_texto(&endereco.texto()));
+    let por_cep = endereco
+        .cep
+        .as_deref()
+        .map(clean_document)
+        .filter(|cep| !cep.is_empty())
+        .and_then(|cep| configuracao.coordenadas_estaticas.get(&cep));
+    por_endereco.or(por_cep).copied()
+}
+
+#[derive(Deserialize)]
+struct ResultadoNominatim {
+    lat: String,
+    lon: String,
+}
+
+async fn consultar_nominatim(client: &Client, url_base: &str, consulta: &str) -> Result<Option<Coordenada>, String> {
+    let url = format!("{}/search", url_base.trim_end_matches('/'));
+    let response = client
+        .get(&url)
+        .query(&[("q", consulta), ("format", "json"), ("limit", "1"), ("countrycodes", "br")])
+        .header("User-Agent", "sistema-bioma/rotas-coleta")
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let resultados: Vec<ResultadoNominatim> = response.json().await.map_err(|e| format!("Erro JSON: {}", e))?;
+    Ok(resultados.first().and_then(|r| {
+        Some(Coordenada { latitude: r.lat.parse().ok()?, longitude: r.lon.parse().ok()? })
+    }))
+}
+
+async fn geocodificar(
+    client: &Client,
+    configuracao: &ConfiguracaoRotas,
+    cache: &mut HashMap<String, Coordenada>,
+    endereco: &EnderecoColeta,
+) -> Result<Coordenada, String> {
+    let texto = endereco.texto();
+    if texto.is_empty() && endereco.cep.as_deref().is_none_or(|c| c.trim().is_empty()) {
+        return Err("Endereço não informado".to_string());
+    }
+    if let Some(coordenada) = consultar_estatico(configuracao, endereco) {
+        return Ok(coordenada);
+    }
+    let chave = normalizar_texto(&texto);
+    if let Some(coordenada) = cache.get(&chave) {
+        return Ok(*coordenada);
+    }
+
+    let coordenada = match configuracao.provedor {
+        ProvedorConsulta::Estatico => None,
+        ProvedorConsulta::Servico => {
+            tokio::time::sleep(INTERVALO_NOMINATIM).await;
+            // Sem resultado para o endereço completo, tenta pelo CEP
+            match consultar_nominatim(client, &configuracao.url_nominatim, &texto).await? {
+                Some(c) => Some(c),
+                None => match endereco.cep.as_deref().filter(|c| !c.trim().is_empty()) {
+                    Some(cep) => {
+                        tokio::time::sleep(INTERVALO_NOMINATIM).await;
+                        consultar_nominatim(client, &configuracao.url_nominatim, cep).await?
+                    }
+                    None => None,
+                },
+            }
+        }
+    };
+
+    let coordenada = coordenada.ok_or_else(|| format!("Endereço não localizado: {}", texto))?;
+    cache.insert(chave, coordenada);
+    Ok(coordenada)
+}
+
+// ==================== ROTEIRIZAÇÃO ====================
+
+struct ParadaInterna {
+    origem: ParadaColeta,
+    coordenada: Coordenada,
+    janela_inicio: Option<i64>, // minutos desde 00:00
+    janela_fim: Option<i64>,
+    duracao: i64,
+    veiculo_id: Option<u32>,
+}
+
+struct JornadaVeiculo {
+    veiculo_id: u32,
+    saida: i64,
+    retorno_limite: Option<i64>,
+}
+
+struct ContextoRota<'a> {
+    base: Coordenada,
+    paradas: &'a [ParadaInterna],
+    configuracao: &'a ConfiguracaoRotas,
+}
+
+// (chegada, início do atendimento, saída, km do trecho) de cada parada
+struct Simulacao {
+    horarios: Vec<(i64, i64, i64, f64)>,
+    distancia_km: f64,
+    retorno: i64,
+}
+
+fn minutos_do_dia(hora: &str) -> Option<i64> {
+    NaiveTime::parse_from_str(hora.trim(), "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(hora.trim(), "%H:%M:%S"))
+        .ok()
+        .map(|t| i64::from(t.num_seconds_from_midnight() / 60))
+}
+
+// Tolerância em volta do horário agendado, sem passar da meia-noite para nenhum dos lados
+fn janela_agendamento(centro: i64, tolerancia: i64) -> (String, String) {
+    (
+        formatar_minutos((centro - tolerancia).max(0)),
+        formatar_minutos((centro + tolerancia).min(ULTIMO_MINUTO_DO_DIA)),
+    )
+}
+
+fn formatar_minutos(minutos: i64) -> String {
+    format!("{:02}:{:02}", minutos / 60, minutos % 60)
+}
+
+fn distancia_linha_reta_km(a: Coordenada, b: Coordenada) -> f64 {
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * 6371.0 * h.sqrt().asin()
+}
+
+impl ContextoRota<'_> {
+    fn km(&self, a: Coordenada, b: Coordenada) -> f64 {
+        distancia_linha_reta_km(a, b) * self.configuracao.fator_rota
+    }
+
+    fn minutos(&self, km: f64) -> i64 {
+        (km / self.configuracao.velocidade_media_kmh * 60.0).ceil() as i64
+    }
+}
+
+/// Percorre a rota a partir da base; `None` se alguma janela ou o retorno limite não for cumprido
+fn simular(contexto: &ContextoRota, jornada: &JornadaVeiculo, ordem: &[usize]) -> Option<Simulacao> {
+    let mut horarios = Vec::with_capacity(ordem.len());
+    let mut posicao = contexto.base;
+    let mut relogio = jornada.saida;
+    let mut distancia_km = 0.0;
+
+    for indice in ordem {
+        let parada = &contexto.paradas[*indice];
+        let km = contexto.km(posicao, parada.coordenada);
+        let chegada = relogio + contexto.minutos(km);
+        let inicio = chegada.max(parada.janela_inicio.unwrap_or(chegada));
+        if parada.janela_fim.is_some_and(|fim| inicio > fim) {
+            return None;
+        }
+        relogio = inicio + parada.duracao;
+        distancia_km += km;
+        posicao = parada.coordenada;
+        horarios.push((chegada, inicio, relogio, km));
+    }
+
+    let km_retorno = contexto.km(posicao, contexto.base);
+    let retorno = relogio + contexto.minutos(km_retorno);
+    if jornada.retorno_limite.is_some_and(|limite| retorno > limite) {
+        return None;
+    }
+    Some(Simulacao { horarios, distancia_km: distancia_km + km_retorno, retorno })
+}
+
+/// Posição de menor acréscimo de km em que a parada cabe na rota
+fn melhor_insercao(contexto: &ContextoRota, jornada: &JornadaVeiculo, ordem: &[usize], parada: usize) -> Option<(usize, f64)> {
+    let atual = simular(contexto, jornada, ordem)?.distancia_km;
+    (0..=ordem.len())
+        .filter_map(|posicao| {
+            let mut candidata = ordem.to_vec();
+            candidata.insert(posicao, parada);
+            simular(contexto, jornada, &candidata).map(|s| (posicao, s.distancia_km - atual))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// 2-opt: inverte trechos enquanto a rota encurta sem violar janelas
+fn melhorar_rota(contexto: &ContextoRota, jornada: &JornadaVeiculo, ordem: &mut Vec<usize>) {
+    let Some(mut melhor) = simular(contexto, jornada, ordem).map(|s| s.distancia_km) else {
+        return;
+    };
+    let mut melhorou = true;
+    while melhorou {
+        melhorou = false;
+        for i in 0..ordem.len().saturating_sub(1) {
+            for j in i + 1..ordem.len() {
+                let mut candidata = ordem.clone();
+                candidata[i..=j].reverse();
+                if let Some(simulacao) = simular(contexto, jornada, &candidata) {
+                    if simulacao.distancia_km + 1e-9 < melhor {
+                        melhor = simulacao.distancia_km;
+                        *ordem = candidata;
+                        melhorou = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Paradas com veículo definido vão para ele; as demais para o veículo com a inserção mais barata
+fn distribuir_paradas(contexto: &ContextoRota, veiculos: &[JornadaVeiculo]) -> (Vec<Vec<usize>>, Vec<(usize, String)>) {
+    let mut ordens: Vec<Vec<usize>> = vec![Vec::new(); veiculos.len()];
+    let mut sem_rota = Vec::new();
+
+    // Mais restritas primeiro: janela que fecha antes
+    let mut indices: Vec<usize> = (0..contexto.paradas.len()).collect();
+    indices.sort_by_key(|i| (contexto.paradas[*i].janela_fim.unwrap_or(i64::MAX), contexto.paradas[*i].janela_inicio));
+    let (fixas, livres): (Vec<usize>, Vec<usize>) = indices.into_iter().partition(|i| contexto.paradas[*i].veiculo_id.is_some());
+
+    for indice in fixas {
+        let veiculo_id = contexto.paradas[indice].veiculo_id.unwrap_or_default();
+        let Some(v) = veiculos.iter().position(|j| j.veiculo_id == veiculo_id) else {
+            sem_rota.push((indice, format!("Veículo {} não está no planejamento", veiculo_id)));
+            continue;
+        };
+        match melhor_insercao(contexto, &veiculos[v], &ordens[v], indice) {
+            Some((posicao, _)) => ordens[v].insert(posicao, indice),
+            None => sem_rota.push((indice, "Não cabe na janela de horário ou na jornada do veículo".to_string())),
+        }
+    }
+
+    for indice in livres {
+        let melhor = veiculos
+            .iter()
+            .enumerate()
+            .filter_map(|(v, jornada)| melhor_insercao(contexto, jornada, &ordens[v], indice).map(|(posicao, custo)| (v, posicao, custo)))
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        match melhor {
+            Some((v, posicao, _)) => ordens[v].insert(posicao, indice),
+            None => sem_rota.push((indice, "Nenhum veículo atende a janela de horário".to_string())),
+        }
+    }
+
+    for (ordem, jornada) in ordens.iter_mut().zip(veiculos) {
+        melhorar_rota(contexto, jornada, ordem);
+    }
+    (ordens, sem_rota)
+}
+
+fn montar_rota(
+    contexto: &ContextoRota,
+    veiculo: &VeiculoRota,
+    jornada: &JornadaVeiculo,
+    ordem: &[usize],
+    simulacao: &Simulacao,
+    data: NaiveDate,
+    km_atual: Option<i64>,
+) -> RotaPlanejada {
+    let paradas: Vec<ParadaPlanejada> = ordem
+        .iter()
+        .zip(&simulacao.horarios)
+        .enumerate()
+        .map(|(posicao, (indice, (chegada, inicio, saida, km)))| {
+            let parada = &contexto.paradas[*indice];
+            ParadaPlanejada {
+                ordem: posicao + 1,
+                agendamento_id: parada.origem.agendamento_id,
+                descricao: parada.origem.descricao.clone(),
+                endereco: parada.origem.endereco.texto(),
+                coordenada: parada.coordenada,
+                chegada: formatar_minutos(*chegada),
+                inicio_atendimento: formatar_minutos(*inicio),
+                saida: formatar_minutos(*saida),
+                espera_minutos: inicio - chegada,
+                distancia_trecho_km: (km * 10.0).round() / 10.0,
+            }
+        })
+        .collect();
+
+    let mut cidades: Vec<String> = Vec::new();
+    for indice in ordem {
+        if let Some(cidade) = contexto.paradas[*indice].origem.endereco.cidade.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            if !cidades.iter().any(|c| c == cidade) {
+                cidades.push(cidade.to_string());
+            }
+        }
+    }
+    let origem = match contexto.configuracao.base.texto() {
+        texto if texto.is_empty() => "Laboratório".to_string(),
+        texto => texto,
+    };
+    let data_hora = |minutos: i64| format!("{} {}:00", data.format("%Y-%m-%d"), formatar_minutos(minutos));
+    let distancia_km = (simulacao.distancia_km * 10.0).round() / 10.0;
+    let quilometragem_inicial = km_atual.and_then(|km| u32::try_from(km).ok());
+
+    RotaPlanejada {
+        veiculo_id: veiculo.veiculo_id,
+        motorista_id: veiculo.motorista_id,
+        distancia_km,
+        duracao_minutos: simulacao.retorno - jornada.saida,
+        saida: formatar_minutos(jornada.saida),
+        retorno_previsto: formatar_minutos(simulacao.retorno),
+        viagem: ViagemSugerida {
+            descricao: format!("Rota de coleta {} ({} parada(s), ~{:.0} km)", data.format("%d/%m/%Y"), paradas.len(), distancia_km),
+            origem,
+            destino: if cidades.is_empty() { "Coletas".to_string() } else { cidades.join(", ") },
+            data_inicio: data_hora(jornada.saida),
+            quilometragem_inicial,
+            quilometragem_final: None,
+            veiculo: veiculo.veiculo_id,
+            motorista: veiculo.motorista_id,
+            data_termino: Some(data_hora(simulacao.retorno)),
+            km_inicial_pendente: quilometragem_inicial.is_none(),
+        },
+        paradas,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parada(descricao: &str, latitude: f64, longitude: f64, janela: Option<(&str, &str)>, veiculo_id: Option<u32>) -> ParadaInterna {
+        ParadaInterna {
+            origem: ParadaColeta {
+                agendamento_id: None,
+                descricao: descricao.to_string(),
+                cliente_id: None,
+                endereco: EnderecoColeta::default(),
+                coordenada: None,
+                janela_inicio: None,
+                janela_fim: None,
+                duracao_minutos: None,
+                veiculo_id,
+            },
+            coordenada: Coordenada { latitude, longitude },
+            janela_inicio: janela.and_then(|j| minutos_do_dia(j.0)),
+            janela_fim: janela.and_then(|j| minutos_do_dia(j.1)),
+            duracao: 15,
+            veiculo_id,
+        }
+    }
+
+    #[test]
+    fn test_consultar_estatico() {
+        let mut configuracao = ConfiguracaoRotas { provedor: ProvedorConsulta::Estatico, ..Default::default() };
+        let laboratorio = Coordenada { latitude: -25.43, longitude: -49.27 };
+        configuracao.coordenadas_estaticas.insert("rua xv de novembro, 100 - centro - curitiba - pr".to_string(), laboratorio);
+        configuracao.coordenadas_estaticas.insert("80020310".to_string(), laboratorio);
+
+        let endereco = EnderecoColeta {
+            endereco: Some("Rua XV de Novembro".to_string()),
+            numero: Some("100".to_string()),
+            bairro: Some("Centro".to_string()),
+            cidade: Some("Curitiba".to_string()),
+            uf: Some("PR".to_string()),
+            cep: None,
+        };
+        assert_eq!(consultar_estatico(&configuracao, &endereco), Some(laboratorio));
+
+        let so_cep = EnderecoColeta { cep: Some("80020-310".to_string()), ..Default::default() };
+        assert_eq!(consultar_estatico(&configuracao, &so_cep), Some(laboratorio));
+        assert_eq!(consultar_estatico(&configuracao, &EnderecoColeta::default()), None);
+    }
+
+    #[test]
+    fn test_janela_agendamento() {
+        assert_eq!(janela_agendamento(10 * 60, 30), ("09:30".to_string(), "10:30".to_string()));
+        // Agendamento perto da meia-noite não gera "24:30", que não seria aceito de volta
+        let (inicio, fim) = janela_agendamento(23 * 60 + 45, 45);
+        assert_eq!((inicio.as_str(), fim.as_str()), ("23:00", "23:59"));
+        assert!(minutos_do_dia(&fim).is_some());
+        assert_eq!(janela_agendamento(15, 30).0, "00:00");
+    }
+
+    #[test]
+    fn test_distribuir_paradas() {
+        let configuracao = ConfiguracaoRotas::default();
+        // Pontos em linha a leste da base, ~11 km entre cada (0,1 grau de longitude no equador)
+        let paradas = vec![
+            parada("C", 0.0, 0.3, None, None),
+            parada("A", 0.0, 0.1, None, None),
+            parada("B", 0.0, 0.2, None, None),
+            // Só pode ser atendida cedo: vai primeiro mesmo sendo a mais distante
+            parada("D", 0.0, 0.4, Some(("08:00", "09:30")), None),
+            parada("Fixa", 0.0, -0.1, None, Some(2)),
+            parada("Impossível", 0.0, 0.5, Some(("06:00", "06:30")), None),
+        ];
+        let contexto = ContextoRota { base: Coordenada { latitude: 0.0, longitude: 0.0 }, paradas: &paradas, configuracao: &configuracao };
+        let veiculos = vec![
+            JornadaVeiculo { veiculo_id: 1, saida: 8 * 60, retorno_limite: Some(18 * 60) },
+            JornadaVeiculo { veiculo_id: 2, saida: 8 * 60, retorno_limite: Some(18 * 60) },
+        ];
+
+        let (ordens, sem_rota) = distribuir_paradas(&contexto, &veiculos);
+        assert_eq!(sem_rota.len(), 1);
+        assert_eq!(paradas[sem_rota[0].0].origem.descricao, "Impossível");
+        assert!(ordens[1].contains(&4));
+
+        let nomes: Vec<&str> = ordens
+            .iter()
+            .flatten()
+            .filter(|i| **i != 4)
+            .map(|i| paradas[*i].origem.descricao.as_str())
+            .collect();
+        assert_eq!(nomes.len(), 4);
+        let rota_d = ordens.iter().find(|o| o.contains(&3)).unwrap();
+        assert_eq!(rota_d[0], 3);
+        let simulacao = simular(&contexto, &veiculos[0], &ordens[0]).unwrap();
+        assert!(simulacao.retorno <= 18 * 60);
+    }
+}
@@ -1,9 +1,11 @@
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::frota::motoristas_controller::normalizar_categoria_cnh;
+use crate::utils::{clean_document, format_placa, is_valid_renavam, normalizar_placa};
 
 // Estrutura de resposta da API genérica para ser reutilizável
 #[derive(Deserialize, Serialize, Debug)]
@@ -21,6 +23,11 @@ pub struct Veiculo {
     pub marca: u32,
     pub ano: String,
     pub placa: String,
+    /// Categoria mínima de CNH para conduzir o veículo
+    #[serde(default)]
+    pub categoria_cnh: Option<String>,
+    #[serde(default)]
+    pub renavam: Option<String>,
 }
 
 // Estrutura para os dados de uma marca
@@ -37,6 +44,8 @@ pub struct VeiculoInput {
     pub marca: u32,
     pub ano: String,
     pub placa: String,
+    pub categoria_cnh: Option<String>,
+    pub renavam: Option<String>,
 }
 
 // Estrutura para resposta de busca de veículos e marcas
@@ -46,7 +55,81 @@ pub struct VeiculosMarcasResponse {
     pub marcas: Vec<Marca>,
 }
 
+/// Valida o ano do veículo: "AAAA" ou o par fabricação/modelo ("2020/2021").
+/// O modelo é o mesmo ano da fabricação ou o seguinte, e no máximo o ano que vem.
+pub fn normalizar_ano_veiculo(ano: &str, ano_atual: i32) -> Result<String, String> {
+    let partes: Vec<&str> = ano
+        .split(|c: char| c == '/' || c == '-' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let anos: Vec<i32> = partes
+        .iter()
+        .map(|p| p.parse::<i32>().ok().filter(|a| p.len() == 4 && *a >= 1900))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| format!("Ano inválido: '{}'. Use AAAA ou fabricação/modelo (ex.: 2020/2021)", ano.trim()))?;
+
+    match anos.as_slice() {
+        [fabricacao] if *fabricacao <= ano_atual + 1 => Ok(fabricacao.to_string()),
+        [fabricacao, _] if *fabricacao > ano_atual => {
+            Err(format!("Ano de fabricação {} é posterior ao ano atual", fabricacao))
+        }
+        [fabricacao, modelo] if *modelo == *fabricacao || *modelo == *fabricacao + 1 => {
+            Ok(format!("{}/{}", fabricacao, modelo))
+        }
+        [fabricacao, modelo] => Err(format!(
+            "Ano modelo {} incompatível com a fabricação {}: deve ser o mesmo ano ou o seguinte",
+            modelo, fabricacao
+        )),
+        [fabricacao] => Err(format!("Ano {} é posterior ao ano que vem", fabricacao)),
+        _ => Err(format!("Ano inválido: '{}'. Use AAAA ou fabricação/modelo (ex.: 2020/2021)", ano.trim())),
+    }
+}
 
+/// RENAVAM só com dígitos e 11 posições; vazio vira `None`
+pub fn normalizar_renavam(renavam: Option<String>) -> Result<Option<String>, String> {
+    let Some(renavam) = renavam.filter(|r| !r.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if !is_valid_renavam(&renavam) {
+        return Err(format!("RENAVAM inválido: {}", renavam.trim()));
+    }
+    Ok(Some(format!("{:0>11}", clean_document(&renavam))))
+}
+
+fn placa_duplicada<'a>(veiculos: &'a [Veiculo], placa: &str, id_atual: Option<u32>) -> Option<&'a Veiculo> {
+    veiculos
+        .iter()
+        .filter(|v| Some(v.id) != id_atual)
+        .find(|v| normalizar_placa(&v.placa).as_deref() == Some(placa))
+}
+
+/// Valida e formata a placa e confere se outro veículo já a usa.
+/// Se a lista de veículos não puder ser consultada, a API continua responsável pela unicidade.
+async fn preparar_placa(app_handle: &AppHandle, placa: &str, id_atual: Option<u32>) -> Result<String, String> {
+    let normalizada = normalizar_placa(placa)
+        .ok_or_else(|| format!("Placa inválida: '{}'. Use o padrão ABC-1234 ou Mercosul ABC1D23", placa.trim()))?;
+
+    match buscar_veiculos_e_marcas(app_handle.clone()).await {
+        Ok(veiculos) => {
+            if let Some(existente) = placa_duplicada(&veiculos, &normalizada, id_atual) {
+                return Err(format!(
+                    "A placa {} já está cadastrada no veículo {} (id {})",
+                    format_placa(&normalizada),
+                    existente.nome,
+                    existente.id
+                ));
+            }
+        }
+        Err(e) => eprintln!("Não foi possível verificar placa duplicada: {}", e),
+    }
+    Ok(format_placa(&normalizada))
+}
+
+// Valida a placa enquanto o usuário digita; devolve a placa formatada
+#[command]
+pub async fn validar_placa_veiculo(app_handle: AppHandle, placa: String, id: Option<u32>) -> Result<String, String> {
+    preparar_placa(&app_handle, &placa, id).await
+}
 
 
 // Buscar veículos e marcas
@@ -114,13 +197,22 @@ pub async fn criar_veiculo(
     nome: String, 
     marca: u32, 
     ano: String, 
-    placa: String
+    placa: String,
+    categoria_cnh: Option<String>,
+    renavam: Option<String>
 ) -> Result<Veiculo, String> {
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/criar_veiculo", api_url);
 
-    let veiculo_input = VeiculoInput { nome, marca, ano, placa };
+    let categoria_cnh = categoria_cnh
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| normalizar_categoria_cnh(&c))
+        .transpose()?;
+    let ano = normalizar_ano_veiculo(&ano, Local::now().year())?;
+    let renavam = normalizar_renavam(renavam)?;
+    let placa = preparar_placa(&app_handle, &placa, None).await?;
+    let veiculo_input = VeiculoInput { nome, marca, ano, placa, categoria_cnh, renavam };
 
     let response = match client
         .post(&url)
@@ -149,6 +241,8 @@ pub async fn criar_veiculo(
                         marca: 0,
                         ano: "".to_string(),
                         placa: "".to_string(),
+                        categoria_cnh: None,
+                        renavam: None,
                     }
                 }))
             } else {
@@ -161,13 +255,16 @@ pub async fn criar_veiculo(
 
 // Atualizar veículo
 #[command]
+#[allow(clippy::too_many_arguments)] // argumentos nomeados do invoke do frontend
 pub async fn atualizar_veiculo(
     app_handle: AppHandle, 
     id: u32, 
     nome: Option<String>, 
     marca: Option<u32>, 
     ano: Option<String>, 
-    placa: Option<String>
+    placa: Option<String>,
+    categoria_cnh: Option<String>,
+    renavam: Option<String>
 ) -> Result<Veiculo, String> {
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
@@ -180,9 +277,23 @@ pub async fn atualizar_veiculo(
         marca: Option<u32>,
         ano: Option<String>,
         placa: Option<String>,
+        categoria_cnh: Option<String>,
+        renavam: Option<String>,
     }
 
-    let update_data = UpdateVeiculo { nome, marca, ano, placa };
+    let categoria_cnh = categoria_cnh
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| normalizar_categoria_cnh(&c))
+        .transpose()?;
+    let ano = ano
+        .map(|a| normalizar_ano_veiculo(&a, Local::now().year()))
+        .transpose()?;
+    let renavam = normalizar_renavam(renavam)?;
+    let placa = match placa {
+        Some(placa) => Some(preparar_placa(&app_handle, &placa, Some(id)).await?),
+        None => None,
+    };
+    let update_data = UpdateVeiculo { nome, marca, ano, placa, categoria_cnh, renavam };
 
     let response = match client
         .put(&url)
@@ -211,6 +322,8 @@ pub async fn atualizar_veiculo(
                         marca: 0,
                         ano: "".to_string(),
                         placa: "".to_string(),
+                        categoria_cnh: None,
+                        renavam: None,
                     }
                 }))
             } else {
@@ -250,3 +363,39 @@ pub async fn deletar_veiculo(app_handle: AppHandle, id: u32) -> Result<bool, Str
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn veiculo(id: u32, placa: &str) -> Veiculo {
+        Veiculo {
+            id,
+            nome: format!("Veículo {}", id),
+            marca: 1,
+            ano: "2020".to_string(),
+            placa: placa.to_string(),
+            categoria_cnh: None,
+            renavam: None,
+        }
+    }
+
+    #[test]
+    fn test_ano_e_placa_duplicada() {
+        assert_eq!(normalizar_ano_veiculo("2020/2021", 2026), Ok("2020/2021".to_string()));
+        assert_eq!(normalizar_ano_veiculo(" 2019 - 2019 ", 2026), Ok("2019/2019".to_string()));
+        assert_eq!(normalizar_ano_veiculo("2027", 2026), Ok("2027".to_string()));
+        assert!(normalizar_ano_veiculo("2020/2022", 2026).is_err());
+        assert!(normalizar_ano_veiculo("2027/2028", 2026).is_err());
+        assert!(normalizar_ano_veiculo("20/21", 2026).is_err());
+        assert!(normalizar_ano_veiculo("", 2026).is_err());
+
+        assert_eq!(normalizar_renavam(Some("639884962".to_string())), Ok(Some("00639884962".to_string())));
+        assert!(normalizar_renavam(Some("00639884961".to_string())).is_err());
+        assert_eq!(normalizar_renavam(Some(" ".to_string())), Ok(None));
+
+        let veiculos = vec![veiculo(1, "abc-1234"), veiculo(2, "BRA2E19")];
+        assert_eq!(placa_duplicada(&veiculos, "ABC1234", None).map(|v| v.id), Some(1));
+        assert_eq!(placa_duplicada(&veiculos, "ABC1234", Some(1)).map(|v| v.id), None);
+        assert_eq!(placa_duplicada(&veiculos, "BRA2E19", Some(1)).map(|v| v.id), Some(2));
+    }
+}
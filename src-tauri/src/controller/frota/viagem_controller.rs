@@ -3,6 +3,10 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::frota::motoristas_controller::verificar_habilitacao_viagem;
+use crate::controller::frota::consistencia_odometro_controller::{
+    inconsistencias_nova_viagem, inconsistencias_viagem_editada, rejeitar_inconsistencias,
+};
 
 // Definir as structs que mapeiam os dados do frontend
 // Elas precisam ser idênticas às interfaces do TypeScript
@@ -104,6 +108,22 @@ pub async fn criar_frota_viagem(app_handle: AppHandle, payload: FrotaViagemInput
         }
     }
 
+    // Confronta com viagens, abastecimentos e manutenções já registrados do veículo
+    let inconsistencias = inconsistencias_nova_viagem(&app_handle, &payload)
+        .await
+        .map_err(|e| format!("Não foi possível verificar a consistência do odômetro: {}", e))?;
+    rejeitar_inconsistencias(&inconsistencias)?;
+
+    // CNH válida durante a viagem e de categoria compatível com o veículo
+    verificar_habilitacao_viagem(
+        &app_handle,
+        payload.motorista,
+        payload.veiculo,
+        &payload.data_inicio,
+        payload.data_termino.as_deref(),
+    )
+    .await?;
+
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()
@@ -215,10 +235,78 @@ pub async fn buscar_viagens(app_handle: tauri::AppHandle) -> Result<Vec<FrotaVia
     }
 }
 
+/// Viagens de um veículo e/ou de um motorista, filtradas pela API
+pub async fn buscar_viagens_filtradas(
+    app_handle: &AppHandle,
+    veiculo_id: Option<u32>,
+    motorista_id: Option<u32>,
+) -> Result<Vec<FrotaViagem>, String> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Erro ao criar cliente HTTP: {}", e))?;
+
+    let url = format!("{}/buscar_viagem", get_api_url(app_handle));
+    let mut query = Vec::new();
+    if let Some(id) = veiculo_id {
+        query.push(("veiculo_id", id));
+    }
+    if let Some(id) = motorista_id {
+        query.push(("motorista_id", id));
+    }
+
+    let response = client
+        .get(&url)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de rede ao buscar viagens: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await
+            .unwrap_or_else(|_| "Não foi possível ler o corpo do erro".to_string());
+        return Err(format!("A API retornou um erro ({}): {}", status, err_body));
+    }
+
+    let api_response: ApiResponse<Vec<FrotaViagem>> = response.json().await
+        .map_err(|e| format!("Erro ao processar JSON da resposta: {}", e))?;
+    if !api_response.success {
+        return Err(api_response.message
+            .unwrap_or_else(|| "A API indicou uma falha sem fornecer uma mensagem.".to_string()));
+    }
+
+    let mut viagens = api_response.data.unwrap_or_default();
+    viagens.retain(|v| veiculo_id.is_none_or(|id| v.veiculo == id) && motorista_id.is_none_or(|id| v.motorista == id));
+    Ok(viagens)
+}
+
 #[command]
 pub async fn atualizar_frota_viagem(app_handle: AppHandle, id: u32, payload: UpdateFrotaViagemInput) -> Result<FrotaViagem, String> {
     println!("Recebido comando atualizar_frota_viagem para o ID: {}", id);
 
+    let atual = buscar_viagens(app_handle.clone())
+        .await?
+        .into_iter()
+        .find(|v| v.id == id)
+        .ok_or_else(|| format!("Viagem {} não encontrada", id))?;
+    let editada = aplicar_edicao(atual, &payload);
+
+    // Confronta a versão editada com o restante do histórico
+    let inconsistencias = inconsistencias_viagem_editada(&app_handle, &editada)
+        .await
+        .map_err(|e| format!("Não foi possível verificar a consistência do odômetro: {}", e))?;
+    rejeitar_inconsistencias(&inconsistencias)?;
+
+    verificar_habilitacao_viagem(
+        &app_handle,
+        editada.motorista,
+        editada.veiculo,
+        &editada.data_inicio,
+        editada.data_termino.as_deref(),
+    )
+    .await?;
+
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/atualizar_frota_viagem/{}", api_url, id); // Rota específica para o ID
@@ -247,6 +335,22 @@ pub async fn atualizar_frota_viagem(app_handle: AppHandle, id: u32, payload: Upd
     }
 }
 
+// Campos não enviados na edição mantêm o valor gravado
+fn aplicar_edicao(atual: FrotaViagem, payload: &UpdateFrotaViagemInput) -> FrotaViagem {
+    FrotaViagem {
+        id: atual.id,
+        descricao: payload.descricao.clone().or(atual.descricao),
+        origem: payload.origem.clone().or(atual.origem),
+        destino: payload.destino.clone().or(atual.destino),
+        data_inicio: payload.data_inicio.clone().unwrap_or(atual.data_inicio),
+        quilometragem_inicial: payload.quilometragem_inicial.or(atual.quilometragem_inicial),
+        quilometragem_final: payload.quilometragem_final.or(atual.quilometragem_final),
+        veiculo: payload.veiculo.unwrap_or(atual.veiculo),
+        motorista: payload.motorista.unwrap_or(atual.motorista),
+        data_termino: payload.data_termino.clone().or(atual.data_termino),
+    }
+}
+
 #[command]
 pub async fn deletar_frota_viagem(app_handle: AppHandle, id: u32) -> Result<String, String> {
     println!("Recebido comando deletar_frota_viagem para o ID: {}", id);
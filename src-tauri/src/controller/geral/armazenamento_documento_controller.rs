@@ -0,0 +1,496 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use tauri::{command, AppHandle};
+use tauri_plugin_opener::OpenerExt;
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::model::api_response::ApiResponse;
+use crate::model::documento::{
+    CategoriaDocumento, ConfiguracaoArmazenamento, ConteudoDocumento, DocumentoApiPayload,
+    DocumentoArmazenado, EnviarDocumentoPayload, EscopoDocumento, TipoArmazenamento, VersaoDocumento,
+};
+
+const ARQUIVO_CONFIGURACAO: &str = "armazenamento_documentos.json";
+/// Subpasta (dentro da pasta da categoria) que guarda as versões substituídas
+const PASTA_VERSOES: &str = ".versoes";
+const CATEGORIAS: [CategoriaDocumento; 4] = [
+    CategoriaDocumento::Certificado,
+    CategoriaDocumento::NotaFiscal,
+    CategoriaDocumento::Boleto,
+    CategoriaDocumento::Outro,
+];
+
+// ==================== CONFIGURAÇÃO ====================
+
+#[command]
+pub async fn carregar_config_armazenamento(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<ConfiguracaoArmazenamento>, ApiResponse<()>> {
+    Ok(ApiResponse::success("Configuração carregada.".to_string(), Some(ler_configuracao(&app_handle))))
+}
+
+#[command]
+pub async fn salvar_config_armazenamento(
+    app_handle: AppHandle,
+    configuracao: ConfiguracaoArmazenamento,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    if configuracao.tipo == TipoArmazenamento::NaoConfigurado {
+        return Err(ApiResponse::error("Escolha onde os documentos devem ficar.".to_string()));
+    }
+    if configuracao.tipo != TipoArmazenamento::Api {
+        let raiz = configuracao
+            .caminho_raiz
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .ok_or_else(|| ApiResponse::error("Informe a pasta raiz dos documentos.".to_string()))?;
+        if !Path::new(raiz).is_dir() {
+            return Err(ApiResponse::error(format!("Pasta não encontrada ou sem acesso: {}", raiz)));
+        }
+    }
+
+    gravar_json(&app_handle, ARQUIVO_CONFIGURACAO, &configuracao).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Configuração salva.".to_string(), None))
+}
+
+// ==================== DOCUMENTOS ====================
+
+#[command]
+pub async fn listar_documentos(
+    app_handle: AppHandle,
+    escopo: EscopoDocumento,
+) -> Result<ApiResponse<Vec<DocumentoArmazenado>>, ApiResponse<()>> {
+    let documentos = match raiz_pasta(&app_handle).map_err(ApiResponse::error)? {
+        Some(raiz) => listar_pasta(&raiz, &escopo).map_err(ApiResponse::error)?,
+        None => {
+            let client = Client::new();
+            let url = format!("{}/documentos", get_api_url(&app_handle));
+            let requisicao = client.get(&url).query(&[("modulo", &escopo.modulo), ("referencia", &escopo.referencia)]);
+            resposta_json(requisicao.send().await).await.map_err(ApiResponse::error)?
+        }
+    };
+
+    Ok(ApiResponse::success(format!("{} documento(s).", documentos.len()), Some(documentos)))
+}
+
+/// Envia um documento; se já existir um com o mesmo nome na categoria, o atual vira versão anterior
+#[command]
+pub async fn enviar_documento(
+    app_handle: AppHandle,
+    payload: EnviarDocumentoPayload,
+) -> Result<ApiResponse<DocumentoArmazenado>, ApiResponse<()>> {
+    let (nome, bytes) = conteudo_do_payload(&payload).map_err(ApiResponse::error)?;
+
+    let documento = match raiz_pasta(&app_handle).map_err(ApiResponse::error)? {
+        Some(raiz) => salvar_em_pasta(&raiz, &payload.escopo, payload.categoria, &nome, &bytes).map_err(ApiResponse::error)?,
+        None => {
+            let client = Client::new();
+            let url = format!("{}/documentos", get_api_url(&app_handle));
+            let corpo = DocumentoApiPayload {
+                modulo: payload.escopo.modulo.clone(),
+                referencia: payload.escopo.referencia.clone(),
+                categoria: payload.categoria,
+                nome: nome.clone(),
+                conteudo_base64: general_purpose::STANDARD.encode(&bytes),
+            };
+            resposta_json(client.post(&url).json(&corpo).send().await).await.map_err(ApiResponse::error)?
+        }
+    };
+
+    Ok(ApiResponse::success(
+        format!("{} enviado (versão {}).", documento.nome, documento.versao),
+        Some(documento),
+    ))
+}
+
+/// Conteúdo em base64 para pré-visualização (PDF/imagem) dentro do app
+#[command]
+pub async fn visualizar_documento(
+    app_handle: AppHandle,
+    escopo: EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: String,
+    versao: Option<u32>,
+) -> Result<ApiResponse<ConteudoDocumento>, ApiResponse<()>> {
+    let conteudo = obter_conteudo(&app_handle, &escopo, categoria, &nome, versao)
+        .await
+        .map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success("Documento carregado.".to_string(), Some(conteudo)))
+}
+
+/// Salva uma cópia do documento (padrão: pasta Downloads) e devolve o caminho
+#[command]
+pub async fn baixar_documento(
+    app_handle: AppHandle,
+    escopo: EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: String,
+    versao: Option<u32>,
+    caminho: Option<String>,
+) -> Result<ApiResponse<String>, ApiResponse<()>> {
+    let conteudo = obter_conteudo(&app_handle, &escopo, categoria, &nome, versao)
+        .await
+        .map_err(ApiResponse::error)?;
+    let bytes = general_purpose::STANDARD
+        .decode(&conteudo.conteudo_base64)
+        .map_err(|e| ApiResponse::error(format!("Conteúdo inválido: {}", e)))?;
+
+    let destino = match caminho.filter(|c| !c.trim().is_empty()) {
+        Some(caminho) => PathBuf::from(caminho),
+        None => {
+            let downloads_dir = dirs::download_dir()
+                .ok_or_else(|| ApiResponse::error("Não foi possível encontrar o diretório Downloads".to_string()))?;
+            downloads_dir.join(nome_versionado(&nome_seguro(&conteudo.nome).map_err(ApiResponse::error)?, versao))
+        }
+    };
+    fs::write(&destino, bytes).map_err(|e| ApiResponse::error(format!("Erro ao salvar arquivo: {}", e)))?;
+
+    let caminho = destino.to_string_lossy().to_string();
+    Ok(ApiResponse::success(format!("Documento salvo em {}", caminho), Some(caminho)))
+}
+
+/// Abre a pasta do escopo no explorador de arquivos (apenas armazenamento em pasta)
+pub fn abrir_pasta_escopo(app_handle: &AppHandle, escopo: &EscopoDocumento) -> Result<PathBuf, String> {
+    let raiz = raiz_pasta(app_handle)?
+        .ok_or("Os documentos ficam no servidor; consulte-os pela lista de documentos do app.")?;
+    let pasta = pasta_escopo(&raiz, escopo)?;
+    fs::create_dir_all(&pasta).map_err(|e| format!("Erro ao criar pasta {}: {}", pasta.display(), e))?;
+
+    app_handle
+        .opener()
+        .open_path(pasta.to_string_lossy(), None::<String>)
+        .map_err(|e| format!("Não foi possível abrir a pasta: {}", e))?;
+    Ok(pasta)
+}
+
+/// Abre o documento no aplicativo padrão do sistema. Em pasta abre o próprio arquivo; na
+/// API baixa uma cópia para a pasta temporária
+pub async fn abrir_documento(
+    app_handle: &AppHandle,
+    escopo: &EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: &str,
+) -> Result<PathBuf, String> {
+    let caminho = match raiz_pasta(app_handle)? {
+        Some(raiz) => {
+            let caminho = pasta_categoria(&raiz, escopo, categoria)?.join(nome_seguro(nome)?);
+            if !caminho.is_file() {
+                return Err(format!("Documento não encontrado: {}", caminho.display()));
+            }
+            caminho
+        }
+        None => {
+            let conteudo = obter_conteudo(app_handle, escopo, categoria, nome, None).await?;
+            let bytes = general_purpose::STANDARD
+                .decode(&conteudo.conteudo_base64)
+                .map_err(|e| format!("Conteúdo inválido: {}", e))?;
+            let caminho = std::env::temp_dir().join(nome_seguro(&conteudo.nome)?);
+            fs::write(&caminho, bytes).map_err(|e| format!("Erro ao salvar cópia temporária: {}", e))?;
+            caminho
+        }
+    };
+
+    app_handle
+        .opener()
+        .open_path(caminho.to_string_lossy(), None::<String>)
+        .map_err(|e| format!("Não foi possível abrir o arquivo: {}", e))?;
+    Ok(caminho)
+}
+
+// ==================== AUXILIARES ====================
+
+fn ler_configuracao(app_handle: &AppHandle) -> ConfiguracaoArmazenamento {
+    ler_json(app_handle, ARQUIVO_CONFIGURACAO)
+}
+
+/// Raiz para os armazenamentos em pasta; `None` quando o backend é a API
+fn raiz_pasta(app_handle: &AppHandle) -> Result<Option<PathBuf>, String> {
+    let configuracao = ler_configuracao(app_handle);
+    match configuracao.tipo {
+        TipoArmazenamento::NaoConfigurado => {
+            return Err("Armazenamento de documentos não configurado nesta estação.".to_string())
+        }
+        TipoArmazenamento::Api => return Ok(None),
+        TipoArmazenamento::PastaLocal | TipoArmazenamento::Smb => {}
+    }
+    configuracao
+        .caminho_raiz
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| Some(PathBuf::from(c)))
+        .ok_or_else(|| "Pasta raiz dos documentos não configurada.".to_string())
+}
+
+async fn resposta_json<T: DeserializeOwned>(resultado: reqwest::Result<reqwest::Response>) -> Result<T, String> {
+    let response = resultado.map_err(|e| format!("Falha conexão: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+    response.json::<T>().await.map_err(|e| format!("Erro JSON: {}", e))
+}
+
+async fn obter_conteudo(
+    app_handle: &AppHandle,
+    escopo: &EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: &str,
+    versao: Option<u32>,
+) -> Result<ConteudoDocumento, String> {
+    match raiz_pasta(app_handle)? {
+        Some(raiz) => ler_da_pasta(&raiz, escopo, categoria, nome, versao),
+        None => {
+            let client = Client::new();
+            let url = format!("{}/documentos/conteudo", get_api_url(app_handle));
+            let mut query = vec![
+                ("modulo", escopo.modulo.clone()),
+                ("referencia", escopo.referencia.clone()),
+                ("categoria", serde_json::to_value(categoria).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()),
+                ("nome", nome.to_string()),
+            ];
+            if let Some(versao) = versao {
+                query.push(("versao", versao.to_string()));
+            }
+            resposta_json(client.get(&url).query(&query).send().await).await
+        }
+    }
+}
+
+/// Troca separadores e caracteres proibidos em pastas de rede por `-` (ex.: fantasia "A/B Química")
+/// e remove pontos iniciais, para que nomes vindos do frontend não escapem da pasta raiz
+fn nome_seguro(nome: &str) -> Result<String, String> {
+    let limpo = nome.trim().replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "-");
+    let limpo = limpo.trim_start_matches('.').trim();
+    if limpo.is_empty() {
+        return Err(format!("Nome inválido: {}", nome));
+    }
+    Ok(limpo.to_string())
+}
+
+fn pasta_escopo(raiz: &Path, escopo: &EscopoDocumento) -> Result<PathBuf, String> {
+    Ok(raiz.join(nome_seguro(&escopo.modulo)?).join(nome_seguro(&escopo.referencia)?))
+}
+
+fn pasta_categoria(raiz: &Path, escopo: &EscopoDocumento, categoria: CategoriaDocumento) -> Result<PathBuf, String> {
+    let pasta = pasta_escopo(raiz, escopo)?;
+    Ok(match categoria.pasta() {
+        Some(sub) => pasta.join(sub),
+        None => pasta,
+    })
+}
+
+/// `laudo.pdf` na versão 2 -> `laudo.v2.pdf`
+fn nome_versionado(nome: &str, versao: Option<u32>) -> String {
+    let Some(versao) = versao else {
+        return nome.to_string();
+    };
+    match nome.rsplit_once('.') {
+        Some((base, extensao)) if !base.is_empty() => format!("{}.v{}.{}", base, versao, extensao),
+        _ => format!("{}.v{}", nome, versao),
+    }
+}
+
+/// Inverso de `nome_versionado`: `laudo.v2.pdf` é a versão 2 de `laudo.pdf`
+fn versao_do_arquivo(nome: &str, arquivo: &str) -> Option<u32> {
+    let (base, extensao) = match nome.rsplit_once('.') {
+        Some((base, extensao)) if !base.is_empty() => (base, format!(".{}", extensao)),
+        _ => (nome, String::new()),
+    };
+    let numero = arquivo.strip_prefix(base)?.strip_prefix(".v")?.strip_suffix(extensao.as_str())?;
+    if numero.is_empty() || !numero.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    numero.parse().ok().filter(|v| *v > 0)
+}
+
+fn mime_por_extensao(nome: &str) -> &'static str {
+    let extensao = nome.rsplit_once('.').map(|(_, e)| e.to_lowercase()).unwrap_or_default();
+    match extensao.as_str() {
+        "pdf" => "application/pdf",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "xml" => "application/xml",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+}
+
+fn conteudo_do_payload(payload: &EnviarDocumentoPayload) -> Result<(String, Vec<u8>), String> {
+    if let Some(origem) = payload.caminho_origem.as_deref().filter(|c| !c.trim().is_empty()) {
+        let bytes = fs::read(origem).map_err(|e| format!("Erro ao ler arquivo {}: {}", origem, e))?;
+        let nome = payload
+            .nome_arquivo
+            .clone()
+            .or_else(|| Path::new(origem).file_name().map(|n| n.to_string_lossy().to_string()))
+            .ok_or("Nome do arquivo não informado.")?;
+        return Ok((nome_seguro(&nome)?, bytes));
+    }
+
+    let conteudo = payload.conteudo_base64.as_deref().ok_or("Informe o arquivo ou o conteúdo do documento.")?;
+    let nome = payload.nome_arquivo.as_deref().ok_or("Nome do arquivo não informado.")?;
+    let bytes = general_purpose::STANDARD
+        .decode(conteudo.trim())
+        .map_err(|e| format!("Conteúdo base64 inválido: {}", e))?;
+    Ok((nome_seguro(nome)?, bytes))
+}
+
+fn modificado_em(metadata: &fs::Metadata) -> String {
+    metadata
+        .modified()
+        .map(|m| DateTime::<Local>::from(m).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Versões anteriores de `nome` guardadas em `.versoes`, da mais recente para a mais antiga
+fn versoes_anteriores(pasta: &Path, nome: &str) -> Vec<VersaoDocumento> {
+    let Ok(entradas) = fs::read_dir(pasta.join(PASTA_VERSOES)) else {
+        return Vec::new();
+    };
+    let mut versoes: Vec<VersaoDocumento> = entradas
+        .flatten()
+        .filter_map(|entrada| {
+            let arquivo = entrada.file_name().to_string_lossy().to_string();
+            let versao = versao_do_arquivo(nome, &arquivo)?;
+            let metadata = entrada.metadata().ok()?;
+            Some(VersaoDocumento { versao, tamanho: metadata.len(), modificado_em: modificado_em(&metadata) })
+        })
+        .collect();
+    versoes.sort_by_key(|v| std::cmp::Reverse(v.versao));
+    versoes
+}
+
+fn listar_pasta(raiz: &Path, escopo: &EscopoDocumento) -> Result<Vec<DocumentoArmazenado>, String> {
+    let mut documentos = Vec::new();
+    for categoria in CATEGORIAS {
+        let pasta = pasta_categoria(raiz, escopo, categoria)?;
+        let Ok(entradas) = fs::read_dir(&pasta) else {
+            continue;
+        };
+        for entrada in entradas.flatten() {
+            let nome = entrada.file_name().to_string_lossy().to_string();
+            let Ok(metadata) = entrada.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || nome.starts_with('.') {
+                continue;
+            }
+            let anteriores = versoes_anteriores(&pasta, &nome);
+            documentos.push(DocumentoArmazenado {
+                versao: anteriores.first().map(|v| v.versao + 1).unwrap_or(1),
+                categoria,
+                tamanho: metadata.len(),
+                modificado_em: modificado_em(&metadata),
+                versoes_anteriores: anteriores,
+                nome,
+            });
+        }
+    }
+    documentos.sort_by(|a, b| a.categoria.cmp(&b.categoria).then_with(|| a.nome.to_lowercase().cmp(&b.nome.to_lowercase())));
+    Ok(documentos)
+}
+
+fn salvar_em_pasta(
+    raiz: &Path,
+    escopo: &EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: &str,
+    bytes: &[u8],
+) -> Result<DocumentoArmazenado, String> {
+    let nome = nome_seguro(nome)?;
+    let nome = nome.as_str();
+    let pasta = pasta_categoria(raiz, escopo, categoria)?;
+    fs::create_dir_all(&pasta).map_err(|e| format!("Erro ao criar pasta {}: {}", pasta.display(), e))?;
+
+    let destino = pasta.join(nome);
+    if destino.exists() {
+        let versao_atual = versoes_anteriores(&pasta, nome).first().map(|v| v.versao + 1).unwrap_or(1);
+        let pasta_versoes = pasta.join(PASTA_VERSOES);
+        fs::create_dir_all(&pasta_versoes).map_err(|e| format!("Erro ao criar pasta de versões: {}", e))?;
+        fs::rename(&destino, pasta_versoes.join(nome_versionado(nome, Some(versao_atual))))
+            .map_err(|e| format!("Erro ao arquivar versão anterior: {}", e))?;
+    }
+    fs::write(&destino, bytes).map_err(|e| format!("Erro ao gravar {}: {}", destino.display(), e))?;
+
+    listar_pasta(raiz, escopo)?
+        .into_iter()
+        .find(|d| d.categoria == categoria && d.nome == nome)
+        .ok_or_else(|| "Documento gravado, mas não encontrado na listagem.".to_string())
+}
+
+fn ler_da_pasta(
+    raiz: &Path,
+    escopo: &EscopoDocumento,
+    categoria: CategoriaDocumento,
+    nome: &str,
+    versao: Option<u32>,
+) -> Result<ConteudoDocumento, String> {
+    let nome = nome_seguro(nome)?;
+    let nome = nome.as_str();
+    let pasta = pasta_categoria(raiz, escopo, categoria)?;
+    let atual = versoes_anteriores(&pasta, nome).first().map(|v| v.versao + 1).unwrap_or(1);
+
+    let (caminho, versao) = match versao.filter(|v| *v != atual) {
+        Some(v) => (pasta.join(PASTA_VERSOES).join(nome_versionado(nome, Some(v))), v),
+        None => (pasta.join(nome), atual),
+    };
+    let bytes = fs::read(&caminho).map_err(|e| format!("Documento não encontrado ({}): {}", caminho.display(), e))?;
+
+    Ok(ConteudoDocumento {
+        nome: nome.to_string(),
+        mime: mime_por_extensao(nome).to_string(),
+        versao,
+        tamanho: bytes.len() as u64,
+        conteudo_base64: general_purpose::STANDARD.encode(&bytes),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versionamento_em_pasta() {
+        let raiz = std::env::temp_dir().join(format!("documentos_{}", uuid::Uuid::new_v4()));
+        let escopo = EscopoDocumento { modulo: "Fornecedores".to_string(), referencia: "Química Sul".to_string() };
+
+        let v1 = salvar_em_pasta(&raiz, &escopo, CategoriaDocumento::Certificado, "iso.pdf", b"primeira").unwrap();
+        assert_eq!(v1.versao, 1);
+        let v2 = salvar_em_pasta(&raiz, &escopo, CategoriaDocumento::Certificado, "iso.pdf", b"segunda").unwrap();
+        assert_eq!(v2.versao, 2);
+        assert_eq!(v2.versoes_anteriores.len(), 1);
+        salvar_em_pasta(&raiz, &escopo, CategoriaDocumento::Outro, "contrato.docx", b"x").unwrap();
+
+        let lista = listar_pasta(&raiz, &escopo).unwrap();
+        assert_eq!(lista.len(), 2);
+        assert_eq!(lista[0].nome, "iso.pdf");
+        assert_eq!(lista[1].categoria, CategoriaDocumento::Outro);
+
+        let atual = ler_da_pasta(&raiz, &escopo, CategoriaDocumento::Certificado, "iso.pdf", None).unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(atual.conteudo_base64).unwrap(), b"segunda");
+        assert_eq!(atual.mime, "application/pdf");
+        let antiga = ler_da_pasta(&raiz, &escopo, CategoriaDocumento::Certificado, "iso.pdf", Some(1)).unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(antiga.conteudo_base64).unwrap(), b"primeira");
+
+        let fora = salvar_em_pasta(&raiz, &escopo, CategoriaDocumento::Boleto, "../fora.pdf", b"x").unwrap();
+        assert_eq!(fora.nome, "-fora.pdf");
+        assert!(salvar_em_pasta(&raiz, &escopo, CategoriaDocumento::Boleto, "..", b"x").is_err());
+
+        let com_barra = EscopoDocumento { modulo: "Fornecedores".to_string(), referencia: "A/B Química".to_string() };
+        assert_eq!(pasta_escopo(&raiz, &com_barra).unwrap(), raiz.join("Fornecedores").join("A-B Química"));
+        assert_eq!(versao_do_arquivo("iso.pdf", "iso.v12.pdf"), Some(12));
+        assert_eq!(versao_do_arquivo("iso.pdf", "iso.v+2.pdf"), None);
+        assert_eq!(versao_do_arquivo("iso.pdf", "iso.v2.doc"), None);
+        assert_eq!(versao_do_arquivo("LEIAME", "LEIAME.v3"), Some(3));
+        let _ = fs::remove_dir_all(&raiz);
+    }
+}
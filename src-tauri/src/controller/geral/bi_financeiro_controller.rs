@@ -1,8 +1,9 @@
 use tauri::{command, AppHandle};
 use reqwest::Client;
-use std::process::Command;
 use crate::model::api_response::ApiResponse;
+use crate::model::documento::{CategoriaDocumento, EscopoDocumento};
 use crate::config::get_api_url;
+use crate::controller::geral::armazenamento_documento_controller::abrir_documento;
 
 // Importa os models completos
 use crate::model::financeiro_bi::{
@@ -84,6 +85,7 @@ pub async fn abrir_arquivo_rede_bioma_tauri(
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/financeiro/arquivo-rede", api_url); 
 
+    let referencia = if payload.tipo.eq_ignore_ascii_case("BOLETO") { "Boletos" } else { "Orcamentos" };
     let api_payload = ArquivoRedeApiPayload {
         tipo: payload.tipo,
         numero: payload.numero,
@@ -108,19 +110,20 @@ pub async fn abrir_arquivo_rede_bioma_tauri(
 
     if caminho.is_empty() { return Err(ApiResponse::error("Caminho retornado vazio".to_string())); }
 
-    println!("Tentando abrir no SO: {}", caminho);
+    // O servidor só identifica o arquivo; ele é aberto pelo armazenamento de documentos
+    // configurado na estação (`Financeiro/Orcamentos` ou `Financeiro/Boletos`)
+    let nome = caminho
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| ApiResponse::error(format!("Caminho sem nome de arquivo: {}", caminho)))?;
+    let escopo = EscopoDocumento { modulo: "Financeiro".to_string(), referencia: referencia.to_string() };
 
-    #[cfg(target_os = "windows")]
-    {
-        // Sanitização para Windows
-        let caminho_win = caminho.replace("/", "\\"); 
-        Command::new("explorer").arg(&caminho_win).spawn().map_err(|e| ApiResponse::error(e.to_string()))?; 
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    { 
-        Command::new("xdg-open").arg(&caminho).spawn().map_err(|e| ApiResponse::error(e.to_string()))?; 
-    }
+    println!("Abrindo documento {} ({}/{})", nome, escopo.modulo, escopo.referencia);
+    let aberto = abrir_documento(&app_handle, &escopo, CategoriaDocumento::Outro, nome)
+        .await
+        .map_err(ApiResponse::error)?;
 
-    Ok(ApiResponse::success(format!("Abrindo: {}", caminho), Some(caminho)))
+    let aberto = aberto.to_string_lossy().to_string();
+    Ok(ApiResponse::success(format!("Abrindo: {}", aberto), Some(aberto)))
 }
\ No newline at end of file
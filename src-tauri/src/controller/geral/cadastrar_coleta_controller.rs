@@ -2,6 +2,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::config::get_api_url;
+use crate::controller::laboratorio::faixa_numeracao_controller::checar_reuso;
 use tauri::AppHandle;
 
 // Estrutura para o request payload
@@ -198,7 +199,30 @@ pub async fn buscar_coleta_referente(app_handle: AppHandle, id_coleta: u32) -> C
 }
 
 #[command]
-pub async fn atualizar_numero_amostra(app_handle: AppHandle, id_amostra: u32, novo_numero: u32) -> SingleAmostraResponse {
+pub async fn atualizar_numero_amostra(app_handle: AppHandle, id_amostra: u32, novo_numero: u32, confirmar_reuso: Option<bool>) -> SingleAmostraResponse {
+    // Avisa antes de reaproveitar um número já cadastrado ou inutilizado
+    if !confirmar_reuso.unwrap_or(false) {
+        match checar_reuso(&app_handle, &[novo_numero]).await {
+            Ok(avisos) if !avisos.is_empty() => {
+                let detalhes: Vec<String> = avisos.into_iter().map(|a| a.mensagem).collect();
+                return SingleAmostraResponse {
+                    success: false,
+                    data: None,
+                    message: Some(format!("REUSO_NUMERO: {}", detalhes.join(" "))),
+                };
+            }
+            Ok(_) => {}
+            // Sem a verificação, só segue com confirmação explícita
+            Err(e) => {
+                return SingleAmostraResponse {
+                    success: false,
+                    data: None,
+                    message: Some(format!("REUSO_NUMERO: Não foi possível verificar o reuso de números ({}). Confirme para prosseguir.", e)),
+                };
+            }
+        }
+    }
+
     let client = Client::new();
     let url_base = get_api_url(&app_handle);
     let full_url = format!("{}/atualizar_numero_amostra/{}", url_base, id_amostra);
@@ -2,6 +2,9 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::validacao::{resposta_validacao, ErroCadastro, ErroCampo, Validador};
+use serde_json::{Map, Value};
 use tauri::AppHandle;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -154,7 +157,9 @@ pub async fn setor_portal() -> GeralResponse {
 }
 
 #[command]
-pub async fn salvar_cliente(app_handle: AppHandle, dados: serde_json::Value) -> Result<SalvarClienteResponse, String> {
+pub async fn salvar_cliente(app_handle: AppHandle, mut dados: serde_json::Value) -> Result<SalvarClienteResponse, ErroCadastro> {
+    validar_dados_cliente(&mut dados).map_err(resposta_validacao)?;
+
     let client = Client::new();
       let url = get_api_url(&app_handle);
     let full_url = format!("{}/salvar_cliente", url);
@@ -170,14 +175,14 @@ pub async fn salvar_cliente(app_handle: AppHandle, dados: serde_json::Value) ->
         Err(e) => {
             let msg = format!("Erro de conexão: {:?}", e);
             eprintln!("{}", msg);
-            return Err(msg);
+            return Err(ApiResponse::error(msg));
         }
     };
 
     if !res.status().is_success() {
         let msg = format!("Falha ao salvar cliente: status HTTP {}", res.status());
         eprintln!("{}", msg);
-        return Err(msg);
+        return Err(ApiResponse::error(msg));
     }
 
     match res.json::<SalvarClienteResponse>().await {
@@ -185,13 +190,15 @@ pub async fn salvar_cliente(app_handle: AppHandle, dados: serde_json::Value) ->
         Err(e) => {
             let msg = format!("Erro ao parsear resposta: {:?}", e);
             eprintln!("{}", msg);
-            Err(msg)
+            Err(ApiResponse::error(msg))
         }
     }
 }
 
 #[command]
-pub async fn editar_cliente(app_handle: AppHandle, dados: serde_json::Value) -> Result<SalvarClienteResponse, String> {
+pub async fn editar_cliente(app_handle: AppHandle, mut dados: serde_json::Value) -> Result<SalvarClienteResponse, ErroCadastro> {
+    validar_dados_cliente(&mut dados).map_err(resposta_validacao)?;
+
     let client = Client::new();
       let url = get_api_url(&app_handle);
     let full_url = format!("{}/editar_cliente", url);
@@ -208,14 +215,14 @@ pub async fn editar_cliente(app_handle: AppHandle, dados: serde_json::Value) ->
         Err(e) => {
             let msg = format!("Erro de conexão: {:?}", e);
             eprintln!("{}", msg);
-            return Err(msg);
+            return Err(ApiResponse::error(msg));
         }
     };
 
     if !res.status().is_success() {
         let msg = format!("Falha ao editar cliente: status HTTP {}", res.status());
         eprintln!("{}", msg);
-        return Err(msg);
+        return Err(ApiResponse::error(msg));
     }
 
     match res.json::<SalvarClienteResponse>().await {
@@ -223,9 +230,70 @@ pub async fn editar_cliente(app_handle: AppHandle, dados: serde_json::Value) ->
         Err(e) => {
             let msg = format!("Erro ao parsear resposta: {:?}", e);
             eprintln!("{}", msg);
-            Err(msg)
+            Err(ApiResponse::error(msg))
+        }
+    }
+}
+
+fn texto(obj: &Map<String, Value>, chave: &str) -> Option<String> {
+    obj.get(chave).and_then(Value::as_str).map(str::to_string)
+}
+
+// Campos `String` obrigatórios no modelo (ex.: documento) continuam string, vazios
+fn gravar(obj: &mut Map<String, Value>, chave: &str, valor: Option<String>) {
+    let novo = match valor {
+        Some(valor) => Value::String(valor),
+        None if obj.get(chave).is_some_and(Value::is_string) => Value::String(String::new()),
+        None => return,
+    };
+    obj.insert(chave.to_string(), novo);
+}
+
+/// Dados gerais e de cobrança têm os mesmos campos cadastrais
+fn validar_bloco(v: &mut Validador, bloco: &str, obj: &mut Map<String, Value>) {
+    let campo = |nome: &str| format!("{}.{}", bloco, nome);
+
+    let documento = v.documento(&campo("documento"), texto(obj, "documento").as_deref(), texto(obj, "tipo_documento").as_deref());
+    gravar(obj, "documento", documento);
+
+    let uf = v.uf(&campo("uf"), texto(obj, "uf").as_deref());
+    gravar(obj, "uf", uf.clone());
+    if texto(obj, "tipo_registro").is_some_and(|t| t.trim().eq_ignore_ascii_case("IE")) {
+        let registro = v.inscricao_estadual(&campo("registro"), texto(obj, "registro").as_deref(), uf.as_deref());
+        gravar(obj, "registro", registro);
+    }
+
+    let cep = v.cep(&campo("cep"), texto(obj, "cep").as_deref());
+    gravar(obj, "cep", cep);
+    let telefone = v.telefone(&campo("telefone"), texto(obj, "telefone").as_deref());
+    gravar(obj, "telefone", telefone);
+    let celular = v.telefone(&campo("celular"), texto(obj, "celular").as_deref());
+    gravar(obj, "celular", celular);
+    let email = v.email(&campo("email"), texto(obj, "email").as_deref());
+    gravar(obj, "email", email);
+}
+
+/// Normaliza e valida o `DadosCliente` enviado pela tela, no próprio JSON
+fn validar_dados_cliente(dados: &mut Value) -> Result<(), Vec<ErroCampo>> {
+    let mut v = Validador::new();
+
+    for bloco in ["dados_gerais", "dados_cobranca"] {
+        if let Some(obj) = dados.get_mut(bloco).and_then(Value::as_object_mut) {
+            validar_bloco(&mut v, bloco, obj);
         }
     }
+
+    if let Some(contatos) = dados.get_mut("contatos").and_then(Value::as_array_mut) {
+        for (i, contato) in contatos.iter_mut().enumerate() {
+            let Some(obj) = contato.as_object_mut() else { continue };
+            let email = v.email(&format!("contatos[{}].email", i), texto(obj, "email").as_deref());
+            gravar(obj, "email", email);
+            let telefone = v.telefone(&format!("contatos[{}].telefone", i), texto(obj, "telefone").as_deref());
+            gravar(obj, "telefone", telefone);
+        }
+    }
+
+    v.concluir()
 }
 
 #[command]
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{command, AppHandle};
+use crate::dados_locais::{gravar_cache, gravar_json, ler_json, mesclar_cache};
+use crate::model::consulta_endereco::ProvedorConsulta;
+use crate::utils::{clean_document, normalizar_texto};
+use crate::validacao::{resumir_erros, Validador};
+
+const ARQUIVO_CONFIG: &str = "consulta_cep.json";
+const ARQUIVO_CACHE: &str = "cep_cache.json";
+const ARQUIVO_MUNICIPIOS: &str = "municipios_ibge.json";
+
+/// Campos com os mesmos nomes dos formulários de cliente, fornecedor e posto
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct EnderecoCep {
+    pub cep: String, // "00000-000"
+    pub endereco: String,
+    pub bairro: String,
+    pub cidade: String,
+    pub uf: String,
+    pub codigo_ibge: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MunicipioIbge {
+    pub codigo: String,
+    pub nome: String,
+    pub uf: String,
+}
+
+/// Configuração local da consulta de CEP (arquivo `consulta_cep.json`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfiguracaoCep {
+    /// `Servico`: qualquer serviço compatível com a API do ViaCEP (`{url}/{cep}/json/`)
+    pub provedor: ProvedorConsulta,
+    pub url_viacep: String,
+    pub url_municipios_ibge: String,
+    /// Chave: CEP só com dígitos
+    pub enderecos_estaticos: HashMap<String, EnderecoCep>,
+    /// Usados junto com a lista baixada do IBGE (ou no lugar dela, sem internet)
+    pub municipios_estaticos: Vec<MunicipioIbge>,
+    /// Depois disso o CEP é consultado de novo no provedor
+    pub validade_cache_dias: i64,
+}
+
+impl Default for ConfiguracaoCep {
+    fn default() -> Self {
+        ConfiguracaoCep {
+            provedor: ProvedorConsulta::Servico,
+            url_viacep: "https://viacep.com.br/ws".to_string(),
+            url_municipios_ibge: "https://servicodados.ibge.gov.br/api/v1/localidades/municipios".to_string(),
+            enderecos_estaticos: HashMap::new(),
+            municipios_estaticos: Vec::new(),
+            validade_cache_dias: 180,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct EntradaCacheCep {
+    endereco: EnderecoCep,
+    consultado_em: NaiveDate,
+}
+
+
+#[command]
+pub async fn carregar_config_cep(app_handle: AppHandle) -> Result<ConfiguracaoCep, String> {
+    Ok(ler_json(&app_handle, ARQUIVO_CONFIG))
+}
+
+#[command]
+pub async fn salvar_config_cep(app_handle: AppHandle, configuracao: ConfiguracaoCep) -> Result<String, String> {
+    let mut configuracao = configuracao;
+    if configuracao.provedor == ProvedorConsulta::Servico && configuracao.url_viacep.trim().is_empty() {
+        return Err("Informe a URL do serviço de CEP".to_string());
+    }
+    if configuracao.validade_cache_dias < 0 {
+        return Err("Validade do cache não pode ser negativa".to_string());
+    }
+
+    // Chaves e CEPs da tabela estática ficam sempre no mesmo formato da consulta
+    let mut enderecos = HashMap::new();
+    for (cep, mut endereco) in configuracao.enderecos_estaticos {
+        let digitos = digitos_cep(&cep)?;
+        endereco.cep = formatar_cep(&digitos);
+        endereco.uf = endereco.uf.trim().to_uppercase();
+        enderecos.insert(digitos, endereco);
+    }
+    configuracao.enderecos_estaticos = enderecos;
+
+    gravar_json(&app_handle, ARQUIVO_CONFIG, &configuracao)?;
+    Ok("Configuração de consulta de CEP salva".to_string())
+}
+
+#[command]
+pub async fn buscar_endereco_cep(app_handle: AppHandle, cep: String) -> Result<EnderecoCep, String> {
+    buscar_endereco(&app_handle, &Client::new(), &cep).await
+}
+
+/// Preenche cep, endereco, bairro, cidade e uf de um formulário (dados gerais ou de
+/// cobrança do cliente, fornecedor, posto) a partir do campo `cep` dele.
+/// Sem `sobrescrever`, só os campos vazios são preenchidos; número fica como está.
+#[command]
+pub async fn preencher_endereco_cep(
+    app_handle: AppHandle,
+    dados: Value,
+    sobrescrever: Option<bool>,
+) -> Result<Value, String> {
+    let mut dados = dados;
+    let campos = dados.as_object_mut().ok_or("Formulário inválido: esperado um objeto")?;
+    let cep = campos
+        .get("cep")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or("Informe o CEP")?;
+
+    let endereco = buscar_endereco(&app_handle, &Client::new(), &cep).await?;
+    preencher_campos(campos, &endereco, sobrescrever.unwrap_or(false));
+    Ok(dados)
+}
+
+/// Nome oficial e código IBGE para uma cidade digitada à mão
+#[command]
+pub async fn normalizar_cidade_ibge(app_handle: AppHandle, cidade: String, uf: String) -> Result<MunicipioIbge, String> {
+    let configuracao: ConfiguracaoCep = ler_json(&app_handle, ARQUIVO_CONFIG);
+    let municipios = obter_municipios(&app_handle, &Client::new(), &configuracao).await?;
+    localizar_municipio(&municipios, &cidade, &uf, None)
+        .cloned()
+        .ok_or_else(|| format!("Município não encontrado na lista do IBGE: {} - {}", cidade.trim(), uf.trim().to_uppercase()))
+}
+
+/// Baixa de novo a lista de municípios do IBGE; retorna quantos foram gravados
+#[command]
+pub async fn atualizar_municipios_ibge(app_handle: AppHandle) -> Result<usize, String> {
+    let configuracao: ConfiguracaoCep = ler_json(&app_handle, ARQUIVO_CONFIG);
+    let municipios = baixar_municipios(&Client::new(), &configuracao.url_municipios_ibge).await?;
+    gravar_cache(&app_handle, ARQUIVO_MUNICIPIOS, &municipios)?;
+    Ok(municipios.len())
+}
+
+// ==================== CONSULTA ====================
+
+fn digitos_cep(cep: &str) -> Result<String, String> {
+    let mut validador = Validador::new();
+    validador.cep("cep", Some(cep)).ok_or("Informe o CEP")?;
+    validador.concluir().map_err(|erros| resumir_erros(&erros))?;
+    Ok(clean_document(cep))
+}
+
+fn formatar_cep(digitos: &str) -> String {
+    format!("{}-{}", &digitos[..5], &digitos[5..])
+}
+
+/// Tabela estática e cache em disco, nessa ordem; `None` quando é preciso ir ao provedor
+fn consultar_local(
+    configuracao: &ConfiguracaoCep,
+    cache: &HashMap<String, EntradaCacheCep>,
+    digitos: &str,
+    hoje: NaiveDate,
+) -> Option<EnderecoCep> {
+    if let Some(endereco) = configuracao.enderecos_estaticos.get(digitos) {
+        return Some(endereco.clone());
+    }
+    cache
+        .get(digitos)
+        .filter(|entrada| (hoje - entrada.consultado_em).num_days() <= configuracao.validade_cache_dias)
+        .map(|entrada| entrada.endereco.clone())
+}
+
+async fn buscar_endereco(app_handle: &AppHandle, client: &Client, cep: &str) -> Result<EnderecoCep, String> {
+    let digitos = digitos_cep(cep)?;
+    let configuracao: ConfiguracaoCep = ler_json(app_handle, ARQUIVO_CONFIG);
+    let hoje = Local::now().date_naive();
+
+    let (mut endereco, consultado) = match consultar_local(&configuracao, &ler_json(app_handle, ARQUIVO_CACHE), &digitos, hoje) {
+        Some(endereco) => (endereco, false),
+        None => {
+            let encontrado = match configuracao.provedor {
+                ProvedorConsulta::Estatico => None,
+                ProvedorConsulta::Servico => consultar_viacep(client, &configuracao.url_viacep, &digitos).await?,
+            };
+            (encontrado.ok_or_else(|| format!("CEP {} não encontrado", formatar_cep(&digitos)))?, true)
+        }
+    };
+
+    // Mesmo com o código IBGE, provedores compatíveis e tabelas digitadas à mão trazem a
+    // cidade sem acento ou abreviada; a lista devolve o nome oficial
+    let municipios = obter_municipios(app_handle, client, &configuracao)
+        .await
+        .unwrap_or_else(|_| configuracao.municipios_estaticos.clone());
+    aplicar_municipio(&mut endereco, &municipios);
+
+    if consultado {
+        // O cache só poupa a próxima consulta; sem ele o endereço encontrado continua valendo
+        let entrada = EntradaCacheCep { endereco: endereco.clone(), consultado_em: hoje };
+        if let Err(e) = mesclar_cache(app_handle, ARQUIVO_CACHE, [(digitos, entrada)]) {
+            println!("Cache de CEP não gravado: {}", e);
+        }
+    }
+    Ok(endereco)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RespostaViaCep {
+    cep: String,
+    logradouro: String,
+    bairro: String,
+    localidade: String,
+    uf: String,
+    ibge: String,
+    /// ViaCEP responde 200 com `"erro": true` (ou `"true"`) para CEP inexistente
+    erro: Option<Value>,
+}
+
+fn converter_viacep(resposta: RespostaViaCep, digitos: &str) -> Option<EnderecoCep> {
+    let inexistente = match &resposta.erro {
+        Some(Value::Bool(erro)) => *erro,
+        Some(Value::String(erro)) => erro == "true",
+        _ => false,
+    };
+    if inexistente || resposta.localidade.trim().is_empty() {
+        return None;
+    }
+    let cep = clean_document(&resposta.cep);
+    Some(EnderecoCep {
+        cep: formatar_cep(if cep.len() == 8 { &cep } else { digitos }),
+        endereco: resposta.logradouro.trim().to_string(),
+        bairro: resposta.bairro.trim().to_string(),
+        cidade: resposta.localidade.trim().to_string(),
+        uf: resposta.uf.trim().to_uppercase(),
+        codigo_ibge: Some(resposta.ibge.trim().to_string()).filter(|codigo| !codigo.is_empty()),
+    })
+}
+
+async fn consultar_viacep(client: &Client, url_base: &str, digitos: &str) -> Result<Option<EnderecoCep>, String> {
+    let url = format!("{}/{}/json/", url_base.trim_end_matches('/'), digitos);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let resposta: RespostaViaCep = response.json().await.map_err(|e| format!("Erro JSON: {}", e))?;
+    Ok(converter_viacep(resposta, digitos))
+}
+
+fn preencher_campos(campos: &mut Map<String, Value>, endereco: &EnderecoCep, sobrescrever: bool) {
+    let valores = [
+        ("cep", &endereco.cep),
+        ("endereco", &endereco.endereco),
+        ("bairro", &endereco.bairro),
+        ("cidade", &endereco.cidade),
+        ("uf", &endereco.uf),
+    ];
+    for (campo, valor) in valores {
+        if valor.is_empty() {
+            continue;
+        }
+        let vazio = campos
+            .get(campo)
+            .is_none_or(|atual| atual.as_str().is_none_or(|texto| texto.trim().is_empty()));
+        // O CEP digitado é sempre trocado pelo formatado
+        if sobrescrever || vazio || campo == "cep" {
+            campos.insert(campo.to_string(), Value::String(valor.clone()));
+        }
+    }
+}
+
+// ==================== MUNICÍPIOS IBGE ====================
+
+/// Ignora acentos, caixa, hífens, apóstrofos e espaços ("Sao Joao d Alianca" = "São João d'Aliança")
+fn chave_municipio(nome: &str) -> String {
+    normalizar_texto(nome).chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+fn localizar_municipio<'a>(
+    municipios: &'a [MunicipioIbge],
+    cidade: &str,
+    uf: &str,
+    codigo: Option<&str>,
+) -> Option<&'a MunicipioIbge> {
+    if let Some(codigo) = codigo.map(str::trim).filter(|c| !c.is_empty()) {
+        if let Some(municipio) = municipios.iter().find(|m| m.codigo == codigo) {
+            return Some(municipio);
+        }
+    }
+    let chave = chave_municipio(cidade);
+    let uf = uf.trim().to_uppercase();
+    if chave.is_empty() {
+        return None;
+    }
+    municipios
+        .iter()
+        .find(|m| m.uf == uf && chave_municipio(&m.nome) == chave)
+}
+
+fn aplicar_municipio(endereco: &mut EnderecoCep, municipios: &[MunicipioIbge]) {
+    if let Some(municipio) = localizar_municipio(municipios, &endereco.cidade, &endereco.uf, endereco.codigo_ibge.as_deref()) {
+        endereco.cidade = municipio.nome.clone();
+        endereco.uf = municipio.uf.clone();
+        endereco.codigo_ibge = Some(municipio.codigo.clone());
+    }
+}
+
+/// Lista estática da configuração mais a baixada do IBGE; baixa na primeira vez que faltar
+async fn obter_municipios(app_handle: &AppHandle, client: &Client, configuracao: &ConfiguracaoCep) -> Result<Vec<MunicipioIbge>, String> {
+    let mut municipios = configuracao.municipios_estaticos.clone();
+    let mut baixados: Vec<MunicipioIbge> = ler_json(app_handle, ARQUIVO_MUNICIPIOS);
+    if baixados.is_empty() && configuracao.provedor == ProvedorConsulta::Servico {
+        baixados = baixar_municipios(client, &configuracao.url_municipios_ibge).await?;
+        if let Err(e) = gravar_cache(app_handle, ARQUIVO_MUNICIPIOS, &baixados) {
+            println!("Lista de municípios do IBGE não gravada: {}", e);
+        }
+    }
+    municipios.extend(baixados);
+    if municipios.is_empty() {
+        return Err("Lista de municípios do IBGE indisponível".to_string());
+    }
+    Ok(municipios)
+}
+
+#[derive(Deserialize)]
+struct MunicipioNivelado {
+    #[serde(rename = "municipio-id")]
+    id: u32,
+    #[serde(rename = "municipio-nome")]
+    nome: String,
+    #[serde(rename = "UF-sigla")]
+    uf: String,
+}
+
+async fn baixar_municipios(client: &Client, url: &str) -> Result<Vec<MunicipioIbge>, String> {
+    let response = client
+        .get(url)
+        .query(&[("view", "nivelado")])
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let lista: Vec<MunicipioNivelado> = response.json().await.map_err(|e| format!("Erro JSON: {}", e))?;
+    Ok(lista
+        .into_iter()
+        .map(|m| MunicipioIbge { codigo: m.id.to_string(), nome: m.nome, uf: m.uf })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn municipio(codigo: &str, nome: &str, uf: &str) -> MunicipioIbge {
+        MunicipioIbge { codigo: codigo.to_string(), nome: nome.to_string(), uf: uf.to_string() }
+    }
+
+    #[test]
+    fn test_consultar_local() {
+        let mut configuracao = ConfiguracaoCep { provedor: ProvedorConsulta::Estatico, validade_cache_dias: 30, ..Default::default() };
+        let laboratorio = EnderecoCep {
+            cep: "80020-310".to_string(),
+            endereco: "Rua XV de Novembro".to_string(),
+            bairro: "Centro".to_string(),
+            cidade: "Curitiba".to_string(),
+            uf: "PR".to_string(),
+            codigo_ibge: Some("4106902".to_string()),
+        };
+        configuracao.enderecos_estaticos.insert("80020310".to_string(), laboratorio.clone());
+
+        let hoje = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let mut cache = HashMap::new();
+        let antigo = EnderecoCep { cep: "01310-100".to_string(), cidade: "São Paulo".to_string(), ..Default::default() };
+        cache.insert("01310100".to_string(), EntradaCacheCep { endereco: antigo.clone(), consultado_em: hoje - chrono::Duration::days(10) });
+        cache.insert("20040002".to_string(), EntradaCacheCep { endereco: antigo.clone(), consultado_em: hoje - chrono::Duration::days(31) });
+
+        assert_eq!(consultar_local(&configuracao, &cache, "80020310", hoje), Some(laboratorio));
+        assert_eq!(consultar_local(&configuracao, &cache, "01310100", hoje), Some(antigo));
+        assert_eq!(consultar_local(&configuracao, &cache, "20040002", hoje), None);
+        assert!(digitos_cep("1234").is_err());
+        assert_eq!(digitos_cep("80.020-310"), Ok("80020310".to_string()));
+    }
+
+    #[test]
+    fn test_converter_viacep_e_preencher_campos() {
+        let resposta: RespostaViaCep = serde_json::from_str(
+            r#"{"cep":"01310-100","logradouro":"Avenida Paulista","complemento":"de 612 a 1510 - lado par",
+                "bairro":"Bela Vista","localidade":"São Paulo","uf":"SP","ibge":"3550308"}"#,
+        )
+        .unwrap();
+        let endereco = converter_viacep(resposta, "01310100").unwrap();
+        assert_eq!(endereco.codigo_ibge.as_deref(), Some("3550308"));
+
+        let inexistente: RespostaViaCep = serde_json::from_str(r#"{"erro":"true"}"#).unwrap();
+        assert_eq!(converter_viacep(inexistente, "99999999"), None);
+
+        let mut dados = serde_json::json!({"cep": "01310100", "endereco": "Av. Paulista", "numero": "1000", "bairro": null});
+        preencher_campos(dados.as_object_mut().unwrap(), &endereco, false);
+        assert_eq!(
+            dados,
+            serde_json::json!({"cep": "01310-100", "endereco": "Av. Paulista", "numero": "1000",
+                "bairro": "Bela Vista", "cidade": "São Paulo", "uf": "SP"})
+        );
+    }
+
+    #[test]
+    fn test_localizar_municipio() {
+        let municipios = vec![
+            municipio("5220009", "São João d'Aliança", "GO"),
+            municipio("3550308", "São Paulo", "SP"),
+            municipio("2512101", "Pombal", "PB"),
+        ];
+        assert_eq!(localizar_municipio(&municipios, "SAO JOAO D ALIANCA", "go", None).map(|m| m.codigo.as_str()), Some("5220009"));
+        assert_eq!(localizar_municipio(&municipios, "sao paulo", "RJ", None), None);
+        assert_eq!(localizar_municipio(&municipios, "", "SP", Some("2512101")).map(|m| m.nome.as_str()), Some("Pombal"));
+
+        let mut endereco = EnderecoCep { cidade: "sao paulo".to_string(), uf: "sp".to_string(), ..Default::default() };
+        aplicar_municipio(&mut endereco, &municipios);
+        assert_eq!((endereco.cidade.as_str(), endereco.uf.as_str(), endereco.codigo_ibge.as_deref()), ("São Paulo", "SP", Some("3550308")));
+
+        // Com o código já preenchido, o nome ainda vem da lista
+        let mut endereco = EnderecoCep { cidade: "Sao Joao D Alianca".to_string(), uf: "GO".to_string(), codigo_ibge: Some("5220009".to_string()), ..Default::default() };
+        aplicar_municipio(&mut endereco, &municipios);
+        assert_eq!(endereco.cidade, "São João d'Aliança");
+    }
+}
@@ -2,6 +2,7 @@ use tauri::command;
 use reqwest::Client;
 use crate::model::api_response::{ApiResponse, ApiError};
 use crate::model::consultor::{Consultor, NovoConsultor, CriarConsultorPayload};
+use crate::validacao::{resposta_validacao, ErroCadastro, Validador};
 
 const API_BASE_URL: &str = "http://127.0.0.1:8082";
 
@@ -30,14 +31,17 @@ pub async fn show_cadastrados() -> Result<ApiResponse<Vec<Consultor>>, ApiRespon
 
 /// [POST] Cadastra um novo consultor via API.
 #[command]
-pub async fn cadastrar_consultor(consultor_data: CriarConsultorPayload) -> Result<ApiResponse<Consultor>, ApiResponse<()>> {
+pub async fn cadastrar_consultor(consultor_data: CriarConsultorPayload) -> Result<ApiResponse<Consultor>, ErroCadastro> {
+    let mut v = Validador::new();
+    v.obrigatorio("nome", Some(&consultor_data.nome), "Nome");
     let novo_consultor = NovoConsultor {
         nome: Some(consultor_data.nome),
-        documento: consultor_data.documento,
-        telefone: consultor_data.telefone,
-        email: consultor_data.email,
+        documento: v.documento("documento", consultor_data.documento.as_deref(), None),
+        telefone: v.telefone("telefone", consultor_data.telefone.as_deref()),
+        email: v.email("email", consultor_data.email.as_deref()),
         ativo: Some(if consultor_data.ativo { 1 } else { 0 }),
     };
+    v.concluir().map_err(resposta_validacao)?;
 
     let client = Client::new();
     let url = format!("{}/consultores", API_BASE_URL);
@@ -61,12 +65,18 @@ pub async fn cadastrar_consultor(consultor_data: CriarConsultorPayload) -> Resul
 
 /// [PUT] Edita um consultor existente.
 #[command]
-pub async fn editar_consultor(consultor: Consultor) -> Result<ApiResponse<Consultor>, ApiResponse<()>> {
+pub async fn editar_consultor(mut consultor: Consultor) -> Result<ApiResponse<Consultor>, ErroCadastro> {
     let consultor_id = match consultor.id {
         Some(id) => id,
         None => return Err(ApiResponse::error("ID do consultor é necessário para edição.".to_string())),
     };
 
+    let mut v = Validador::new();
+    consultor.documento = v.documento("documento", consultor.documento.as_deref(), None);
+    consultor.telefone = v.telefone("telefone", consultor.telefone.as_deref());
+    consultor.email = v.email("email", consultor.email.as_deref());
+    v.concluir().map_err(resposta_validacao)?;
+
     let client = Client::new();
     let url = format!("{}/consultores/{}", API_BASE_URL, consultor_id);
 
@@ -27,4 +27,6 @@ pub mod etapa_controller;
 pub mod tecnica_etapa_controller;
 pub mod calculo_controller;
 pub mod analise_controller;
-pub mod bi_financeiro_controller;
\ No newline at end of file
+pub mod bi_financeiro_controller;
+pub mod armazenamento_documento_controller;
+pub mod cep_controller;
\ No newline at end of file
@@ -4,6 +4,7 @@ use tauri::command;
 use chrono::{DateTime, Utc, NaiveDateTime};
 
 use crate::config::get_api_url;
+use crate::controller::laboratorio::faixa_numeracao_controller::checar_reuso;
 use tauri::AppHandle;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -648,6 +649,7 @@ pub async fn buscar_orcamentos(
 pub async fn cadastrar_amostra_completa(
     app_handle: AppHandle,
     dados_cadastro: CadastroAmostraCompletaRequest,
+    confirmar_reuso: Option<bool>,
 ) -> Result<CadastroResponse, String> {
     println!("Iniciando cadastro de amostra completa...");
     
@@ -656,6 +658,26 @@ pub async fn cadastrar_amostra_completa(
         println!("Erro de validação: {}", erro_validacao);
         return Err(erro_validacao);
     }
+
+    // Avisa antes de reaproveitar um número já cadastrado ou inutilizado
+    if !confirmar_reuso.unwrap_or(false) {
+        let numeros: Vec<u32> = dados_cadastro
+            .amostras
+            .iter()
+            .filter_map(|a| a.numero.trim().parse::<u32>().ok())
+            .collect();
+        match checar_reuso(&app_handle, &numeros).await {
+            Ok(avisos) if !avisos.is_empty() => {
+                let detalhes: Vec<String> = avisos.into_iter().map(|a| a.mensagem).collect();
+                return Err(format!("REUSO_NUMERO: {}", detalhes.join(" ")));
+            }
+            Ok(_) => {}
+            // Sem a verificação, só segue com confirmação explícita
+            Err(e) => {
+                return Err(format!("REUSO_NUMERO: Não foi possível verificar o reuso de números ({}). Confirme para prosseguir.", e));
+            }
+        }
+    }
     
     // --- 2. Preparar e enviar a requisição ---
     let payload = preparar_dados_para_api(&dados_cadastro);
@@ -0,0 +1,493 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+use bigdecimal::BigDecimal;
+use chrono::{Local, NaiveDate};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::monitor::Monitor;
+use crate::model::historico::NovoHistoricoPayload;
+use crate::model::insumo_registro::{AtualizacaoForaDeUsoPayload, InsumoRegistroDetalhado};
+use crate::model::calibracao::{
+    CertificadoCalibracao, NovoCertificadoPayload, PlanoCalibracao, PontoCalibracao,
+    SituacaoCalibracao, StatusCalibracaoEquipamento, TipoEventoCalibracao,
+};
+use crate::controller::admin::historico_controller::registrar_historico;
+use crate::utils::{buscar_json, parse_data_hora, parse_decimal};
+
+const EVENTO_CALIBRACAO_VENCIDA: &str = "calibracao_vencida";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(60 * 60);
+
+// ==================== PLANOS ====================
+
+#[command]
+pub async fn listar_planos_calibracao(
+    app_handle: AppHandle,
+    insumo_registro_id: Option<u32>,
+) -> Result<ApiResponse<Vec<PlanoCalibracao>>, ApiResponse<()>> {
+    let client = Client::new();
+    let planos = buscar_planos(&client, &get_api_url(&app_handle), insumo_registro_id)
+        .await
+        .map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success(format!("{} plano(s) encontrado(s).", planos.len()), Some(planos)))
+}
+
+#[command]
+pub async fn salvar_plano_calibracao(
+    app_handle: AppHandle,
+    plano: PlanoCalibracao,
+) -> Result<ApiResponse<PlanoCalibracao>, ApiResponse<()>> {
+    if plano.periodicidade_dias == 0 {
+        return Err(ApiResponse::error("A periodicidade deve ser de pelo menos 1 dia.".to_string()));
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let requisicao = match plano.id {
+        Some(id) => client.put(format!("{}/laboratorio/calibracao/planos/{}", api_url, id)),
+        None => client.post(format!("{}/laboratorio/calibracao/planos", api_url)),
+    };
+
+    let salvo = enviar_json::<_, PlanoCalibracao>(requisicao, &plano).await.map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success("Plano de calibração salvo.".to_string(), Some(salvo)))
+}
+
+// ==================== CERTIFICADOS ====================
+
+#[command]
+pub async fn listar_certificados_calibracao(
+    app_handle: AppHandle,
+    insumo_registro_id: u32,
+) -> Result<ApiResponse<Vec<CertificadoCalibracao>>, ApiResponse<()>> {
+    let client = Client::new();
+    let certificados = buscar_certificados(&client, &get_api_url(&app_handle), insumo_registro_id)
+        .await
+        .map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success(format!("{} certificado(s) encontrado(s).", certificados.len()), Some(certificados)))
+}
+
+/// Registra o certificado avaliando o erro de cada ponto contra o desvio máximo
+/// do equipamento; se reprovado, o equipamento é colocado fora de uso
+#[command]
+pub async fn registrar_certificado_calibracao(
+    app_handle: AppHandle,
+    payload: NovoCertificadoPayload,
+) -> Result<ApiResponse<CertificadoCalibracao>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+
+    let data_execucao = parse_data_hora(&payload.data_execucao, None)
+        .map(|d| d.date())
+        .ok_or_else(|| ApiResponse::error(format!("Data de execução inválida: {}", payload.data_execucao)))?;
+    if data_execucao > Local::now().date_naive() {
+        return Err(ApiResponse::error("A data de execução não pode estar no futuro.".to_string()));
+    }
+
+    let equipamento = buscar_equipamento(&client, &api_url, payload.insumo_registro_id)
+        .await
+        .map_err(ApiResponse::error)?;
+    let tolerancia = equipamento.desvios.clone();
+
+    let (erro_maximo, aprovado) = if payload.tipo == TipoEventoCalibracao::Manutencao {
+        (None, true)
+    } else {
+        avaliar_pontos(&payload.pontos, tolerancia.as_ref()).map_err(ApiResponse::error)?
+    };
+
+    let certificado = CertificadoCalibracao {
+        id: None,
+        insumo_registro_id: payload.insumo_registro_id,
+        plano_id: payload.plano_id,
+        tipo: payload.tipo,
+        data_execucao: data_execucao.format("%Y-%m-%d").to_string(),
+        numero_certificado: payload.numero_certificado,
+        executor: payload.executor,
+        pontos: payload.pontos,
+        erro_maximo,
+        tolerancia,
+        aprovado,
+        observacao: payload.observacao,
+        usuario_id: payload.usuario_id,
+    };
+
+    let url = format!("{}/laboratorio/calibracao/certificados", api_url);
+    let salvo = enviar_json::<_, CertificadoCalibracao>(client.post(url), &certificado)
+        .await
+        .map_err(ApiResponse::error)?;
+
+    if aprovado {
+        let mensagem = if equipamento.fora_de_uso {
+            "Certificado aprovado. O equipamento continua fora de uso até ser reativado no cadastro."
+        } else {
+            "Certificado aprovado."
+        };
+        return Ok(ApiResponse::success(mensagem.to_string(), Some(salvo)));
+    }
+
+    let motivo = format!(
+        "Certificado {} reprovado: erro máximo {} acima da tolerância {}.",
+        salvo.numero_certificado.as_deref().unwrap_or("s/n"),
+        salvo.erro_maximo.as_ref().map(|e| e.to_string()).unwrap_or_default(),
+        salvo.tolerancia.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+    );
+    if !equipamento.fora_de_uso {
+        colocar_fora_de_uso(&client, &api_url, &equipamento, payload.usuario_id, &motivo)
+            .await
+            .map_err(ApiResponse::error)?;
+    }
+
+    Ok(ApiResponse::success(format!("{} Equipamento colocado fora de uso.", motivo), Some(salvo)))
+}
+
+// ==================== AGENDA ====================
+
+#[command]
+pub async fn listar_agenda_calibracao(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<StatusCalibracaoEquipamento>>, ApiResponse<()>> {
+    let client = Client::new();
+    let agenda = montar_agenda(&client, &get_api_url(&app_handle), None)
+        .await
+        .map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success(format!("{} equipamento(s) com plano de calibração.", agenda.len()), Some(agenda)))
+}
+
+/// Usado antes de iniciar análises: todos os equipamentos informados precisam estar
+/// em uso e com calibração em dia
+pub async fn verificar_equipamentos_liberados(client: &Client, api_url: &str, ids: &[u32]) -> Result<(), String> {
+    let mut bloqueados = Vec::new();
+
+    for &id in ids {
+        let agenda = montar_agenda(client, api_url, Some(id)).await?;
+        if let Some(status) = agenda.iter().find(|s| s.fora_de_uso || !s.situacao.liberado()) {
+            let motivo = if status.fora_de_uso {
+                "fora de uso".to_string()
+            } else {
+                format!("calibração {}", descricao_situacao(status.situacao))
+            };
+            bloqueados.push(format!("{} ({})", status.equipamento, motivo));
+        } else if agenda.is_empty() && buscar_equipamento(client, api_url, id).await?.fora_de_uso {
+            bloqueados.push(format!("Equipamento {} (fora de uso)", id));
+        }
+    }
+
+    if bloqueados.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("EQUIPAMENTO_NAO_CALIBRADO: {}", bloqueados.join("; ")))
+    }
+}
+
+// ==================== MONITOR ====================
+
+/// Coloca fora de uso os equipamentos com calibração vencida e emite `calibracao_vencida`
+pub async fn monitorar_calibracoes(app_handle: AppHandle) {
+    // Cada equipamento sai da agenda ao ficar fora de uso, então não há o que deduplicar
+    let monitor: Monitor<u32> = Monitor::new("calibrações", EVENTO_CALIBRACAO_VENCIDA);
+
+    loop {
+        let user_id = monitor.aguardar(INTERVALO_MONITOR).await;
+        let client = Client::new();
+        let api_url = get_api_url(&app_handle);
+        let agenda = match montar_agenda(&client, &api_url, None).await {
+            Ok(agenda) => agenda,
+            Err(e) => {
+                monitor.falha(&e);
+                continue;
+            }
+        };
+
+        let mut afetados = Vec::new();
+        for mut status in agenda.into_iter().filter(|s| s.situacao == SituacaoCalibracao::Vencida && !s.fora_de_uso) {
+            let motivo = format!(
+                "Calibração vencida desde {}.",
+                status.proxima_execucao.as_deref().unwrap_or("-")
+            );
+            let resultado = match buscar_equipamento(&client, &api_url, status.insumo_registro_id).await {
+                Ok(equipamento) => colocar_fora_de_uso(&client, &api_url, &equipamento, user_id, &motivo).await,
+                Err(e) => Err(e),
+            };
+            match resultado {
+                Ok(()) => {
+                    status.fora_de_uso = true;
+                    afetados.push(status);
+                }
+                Err(e) => println!("Falha ao colocar equipamento {} fora de uso: {}", status.insumo_registro_id, e),
+            }
+        }
+
+        if !afetados.is_empty() {
+            monitor.emitir(&app_handle, &afetados);
+        }
+    }
+}
+
+// ==================== AUXILIARES ====================
+
+fn descricao_situacao(situacao: SituacaoCalibracao) -> &'static str {
+    match situacao {
+        SituacaoCalibracao::Vencida => "vencida",
+        SituacaoCalibracao::Reprovada => "reprovada",
+        SituacaoCalibracao::SemRegistro => "sem registro",
+        SituacaoCalibracao::AVencer => "a vencer",
+        SituacaoCalibracao::EmDia => "em dia",
+    }
+}
+
+/// Erro de cada ponto = |medido - nominal| + incerteza. Aprovado quando o maior erro
+/// não passa da tolerância (sem tolerância cadastrada, apenas registra)
+fn avaliar_pontos(
+    pontos: &[PontoCalibracao],
+    tolerancia: Option<&BigDecimal>,
+) -> Result<(Option<BigDecimal>, bool), String> {
+    if pontos.is_empty() {
+        return Err("Informe ao menos um ponto medido.".to_string());
+    }
+
+    let mut erro_maximo: Option<BigDecimal> = None;
+    for ponto in pontos {
+        let mut erro = (parse_decimal::<BigDecimal>(&ponto.valor_medido)? - parse_decimal::<BigDecimal>(&ponto.valor_nominal)?).abs();
+        if let Some(incerteza) = ponto.incerteza.as_deref().filter(|i| !i.trim().is_empty()) {
+            erro += parse_decimal::<BigDecimal>(incerteza)?.abs();
+        }
+        if erro_maximo.as_ref().is_none_or(|maximo| erro > *maximo) {
+            erro_maximo = Some(erro);
+        }
+    }
+
+    let aprovado = match (tolerancia, erro_maximo.as_ref()) {
+        (Some(tolerancia), Some(erro)) => erro <= tolerancia,
+        _ => true,
+    };
+    Ok((erro_maximo, aprovado))
+}
+
+/// Situação de um plano a partir do último certificado correspondente
+fn situacao_plano(
+    plano: &PlanoCalibracao,
+    certificados: &[CertificadoCalibracao],
+    hoje: NaiveDate,
+) -> (SituacaoCalibracao, Option<NaiveDate>, Option<NaiveDate>) {
+    let ultimo = certificados
+        .iter()
+        .filter(|c| match (c.plano_id, plano.id) {
+            (Some(plano_certificado), Some(id)) => plano_certificado == id,
+            _ => c.tipo == plano.tipo,
+        })
+        .filter_map(|c| parse_data_hora(&c.data_execucao, None).map(|d| (d.date(), c)))
+        .max_by_key(|(data, c)| (*data, c.id));
+
+    let Some((execucao, certificado)) = ultimo else {
+        return (SituacaoCalibracao::SemRegistro, None, None);
+    };
+
+    let proxima = execucao + chrono::Duration::days(plano.periodicidade_dias as i64);
+    let dias = (proxima - hoje).num_days();
+    let situacao = if !certificado.aprovado {
+        SituacaoCalibracao::Reprovada
+    } else if dias < 0 {
+        SituacaoCalibracao::Vencida
+    } else if dias <= plano.antecedencia_aviso_dias as i64 {
+        SituacaoCalibracao::AVencer
+    } else {
+        SituacaoCalibracao::EmDia
+    };
+    (situacao, Some(execucao), Some(proxima))
+}
+
+/// Na agenda geral, equipamento que não pôde ser consultado fica de fora (com log)
+/// em vez de derrubar a agenda inteira
+async fn montar_agenda(
+    client: &Client,
+    api_url: &str,
+    insumo_registro_id: Option<u32>,
+) -> Result<Vec<StatusCalibracaoEquipamento>, String> {
+    let mut por_equipamento: BTreeMap<u32, Vec<PlanoCalibracao>> = BTreeMap::new();
+    for plano in buscar_planos(client, api_url, insumo_registro_id).await?.into_iter().filter(|p| p.ativo) {
+        por_equipamento.entry(plano.insumo_registro_id).or_default().push(plano);
+    }
+
+    let hoje = Local::now().date_naive();
+    let mut agenda = Vec::new();
+    for (id, planos) in por_equipamento {
+        let consulta = async {
+            let equipamento = buscar_equipamento(client, api_url, id).await?;
+            if equipamento.obsoleto {
+                return Ok(None);
+            }
+            let certificados = buscar_certificados(client, api_url, id).await?;
+            Ok::<_, String>(Some((equipamento, certificados)))
+        };
+        let (equipamento, certificados) = match consulta.await {
+            Ok(Some(dados)) => dados,
+            Ok(None) => continue,
+            // Consultando um equipamento só, a falha precisa chegar a quem verifica a liberação
+            Err(e) if insumo_registro_id.is_some() => return Err(e),
+            Err(e) => {
+                println!("Equipamento {} fora da agenda de calibração: {}", id, e);
+                continue;
+            }
+        };
+
+        for plano in planos {
+            let (situacao, ultima, proxima) = situacao_plano(&plano, &certificados, hoje);
+            agenda.push(StatusCalibracaoEquipamento {
+                insumo_registro_id: id,
+                equipamento: equipamento.insumo_nome.clone(),
+                modelo: equipamento.modelo.clone(),
+                numero_serie: equipamento.numero_serie.clone(),
+                plano_id: plano.id,
+                tipo: Some(plano.tipo),
+                ultima_execucao: ultima.map(|d| d.format("%Y-%m-%d").to_string()),
+                proxima_execucao: proxima.map(|d| d.format("%Y-%m-%d").to_string()),
+                dias_restantes: proxima.map(|d| (d - hoje).num_days()),
+                situacao,
+                fora_de_uso: equipamento.fora_de_uso,
+            });
+        }
+    }
+
+    agenda.sort_by_key(|s| (s.situacao, s.dias_restantes));
+    Ok(agenda)
+}
+
+async fn colocar_fora_de_uso(
+    client: &Client,
+    api_url: &str,
+    equipamento: &InsumoRegistroDetalhado,
+    usuario_id: u32,
+    motivo: &str,
+) -> Result<(), String> {
+    let url = format!("{}/laboratorio/insumos-registros/{}/fora-de-uso", api_url, equipamento.id);
+    let response = client
+        .patch(&url)
+        .json(&AtualizacaoForaDeUsoPayload { fora_de_uso: true })
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let historico = NovoHistoricoPayload {
+        usuario_id,
+        acao: "Equipamento fora de uso".to_string(),
+        descricao: format!(
+            "{} (registro {}, série {}): {}",
+            equipamento.insumo_nome,
+            equipamento.id,
+            equipamento.numero_serie.as_deref().unwrap_or("-"),
+            motivo
+        ),
+    };
+    if let Err(e) = registrar_historico(client, api_url, &historico).await {
+        println!("Falha ao registrar histórico do equipamento {}: {}", equipamento.id, e);
+    }
+    Ok(())
+}
+
+async fn buscar_planos(client: &Client, api_url: &str, insumo_registro_id: Option<u32>) -> Result<Vec<PlanoCalibracao>, String> {
+    let mut url = format!("{}/laboratorio/calibracao/planos", api_url);
+    if let Some(id) = insumo_registro_id {
+        url.push_str(&format!("?insumo_registro_id={}", id));
+    }
+    buscar_json(client, &url).await
+}
+
+async fn buscar_certificados(client: &Client, api_url: &str, insumo_registro_id: u32) -> Result<Vec<CertificadoCalibracao>, String> {
+    let url = format!("{}/laboratorio/calibracao/certificados?insumo_registro_id={}", api_url, insumo_registro_id);
+    buscar_json(client, &url).await
+}
+
+async fn buscar_equipamento(client: &Client, api_url: &str, id: u32) -> Result<InsumoRegistroDetalhado, String> {
+    buscar_json(client, &format!("{}/laboratorio/insumos-registros/{}", api_url, id)).await
+}
+
+async fn enviar_json<P: Serialize, T: DeserializeOwned>(requisicao: reqwest::RequestBuilder, payload: &P) -> Result<T, String> {
+    let response = requisicao.json(payload).send().await.map_err(|e| format!("Falha conexão: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+    response.json::<T>().await.map_err(|e| format!("Erro JSON: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn ponto(nominal: &str, medido: &str, incerteza: Option<&str>) -> PontoCalibracao {
+        PontoCalibracao {
+            valor_nominal: nominal.to_string(),
+            valor_medido: medido.to_string(),
+            incerteza: incerteza.map(str::to_string),
+        }
+    }
+
+    fn certificado(id: u32, data: &str, aprovado: bool) -> CertificadoCalibracao {
+        CertificadoCalibracao {
+            id: Some(id),
+            insumo_registro_id: 7,
+            plano_id: Some(1),
+            tipo: TipoEventoCalibracao::Calibracao,
+            data_execucao: data.to_string(),
+            numero_certificado: None,
+            executor: None,
+            pontos: Vec::new(),
+            erro_maximo: None,
+            tolerancia: None,
+            aprovado,
+            observacao: None,
+            usuario_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_avaliar_pontos() {
+        let tolerancia = BigDecimal::from_str("0.05").unwrap();
+        let pontos = vec![ponto("4,00", "4,02", None), ponto("7.00", "6.97", Some("0.02"))];
+
+        let (erro, aprovado) = avaliar_pontos(&pontos, Some(&tolerancia)).unwrap();
+        assert_eq!(erro, Some(BigDecimal::from_str("0.05").unwrap()));
+        assert!(aprovado);
+
+        let (_, aprovado) = avaliar_pontos(&[ponto("10", "10.06", None)], Some(&tolerancia)).unwrap();
+        assert!(!aprovado);
+        assert!(avaliar_pontos(&[], Some(&tolerancia)).is_err());
+        assert!(avaliar_pontos(&[ponto("10", "abc", None)], None).is_err());
+    }
+
+    #[test]
+    fn test_situacao_plano() {
+        let plano = PlanoCalibracao {
+            id: Some(1),
+            insumo_registro_id: 7,
+            tipo: TipoEventoCalibracao::Calibracao,
+            periodicidade_dias: 365,
+            antecedencia_aviso_dias: 30,
+            ativo: true,
+        };
+        let hoje = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        assert_eq!(situacao_plano(&plano, &[], hoje).0, SituacaoCalibracao::SemRegistro);
+
+        let certificados = vec![certificado(1, "2024-01-10", true), certificado(2, "2024-06-20", true)];
+        let (situacao, ultima, proxima) = situacao_plano(&plano, &certificados, hoje);
+        assert_eq!(situacao, SituacaoCalibracao::AVencer);
+        assert_eq!(ultima, NaiveDate::from_ymd_opt(2024, 6, 20));
+        assert_eq!(proxima, NaiveDate::from_ymd_opt(2025, 6, 20));
+
+        assert_eq!(situacao_plano(&plano, &certificados[..1], hoje).0, SituacaoCalibracao::Vencida);
+        assert_eq!(
+            situacao_plano(&plano, &[certificado(3, "2025-05-01", false)], hoje).0,
+            SituacaoCalibracao::Reprovada
+        );
+    }
+}
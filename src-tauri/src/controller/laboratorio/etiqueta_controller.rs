@@ -0,0 +1,644 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use qrcode::QrCode;
+use crate::config::get_api_url;
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::model::api_response::ApiResponse;
+use crate::model::etiqueta::{
+    CampoEtiqueta, CodigoLido, ConexaoImpressora, DadosEtiquetaAmostra, DadosEtiquetaReagente, EtiquetaGerada,
+    FormatoEtiqueta, ImpressoraEtiqueta, ItemEtiqueta, LinguagemImpressora, OrigemRegistro,
+    TemplateEtiqueta, TipoCodigo, TipoEtiqueta,
+};
+use crate::controller::qualidade::tauri_print_commands_controller::{generate_pdf_from_html, get_default_print_settings};
+use crate::exportacao::escapar_html;
+
+const API_RESOURCE: &str = "/laboratorio/etiquetas/templates";
+const ARQUIVO_IMPRESSORAS: &str = "impressoras_etiqueta.json";
+
+/// Prefixos gravados no código de barras para identificar o que foi lido pelo scanner
+pub const PREFIXO_AMOSTRA: &str = "AM";
+pub const PREFIXO_REAGENTE: &str = "RL";
+pub const PREFIXO_INSUMO: &str = "IN";
+
+// ==================== COMANDOS TAURI ====================
+
+/// Lista os templates salvos na API; sem templates cadastrados, retorna os padrões
+#[command]
+pub async fn listar_templates_etiqueta(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<TemplateEtiqueta>>, ApiResponse<()>> {
+    let client = Client::new();
+    let url = format!("{}{}", get_api_url(&app_handle), API_RESOURCE);
+
+    match client.get(&url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                match response.json::<Vec<TemplateEtiqueta>>().await {
+                    Ok(data) if data.is_empty() => Ok(ApiResponse::success("Templates padrão.".to_string(), Some(templates_padrao()))),
+                    Ok(data) => Ok(ApiResponse::success("Templates carregados.".to_string(), Some(data))),
+                    Err(e) => Err(ApiResponse::error(format!("Erro JSON: {}", e))),
+                }
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                Err(ApiResponse::error(format!("Erro API ({}): {}", status, msg)))
+            }
+        }
+        Err(e) => Err(ApiResponse::error(format!("Falha conexão: {}", e))),
+    }
+}
+
+#[command]
+pub async fn salvar_template_etiqueta(
+    app_handle: AppHandle,
+    template: TemplateEtiqueta,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    validar_template(&template).map_err(ApiResponse::error)?;
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let request = match template.id {
+        Some(id) => client.put(format!("{}{}/{}", api_url, API_RESOURCE, id)),
+        None => client.post(format!("{}{}", api_url, API_RESOURCE)),
+    };
+
+    match request.json(&template).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                Ok(ApiResponse::success("Template salvo com sucesso!".to_string(), None))
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                Err(ApiResponse::error(format!("Erro ao salvar ({}): {}", status, msg)))
+            }
+        }
+        Err(e) => Err(ApiResponse::error(format!("Erro de conexão: {}", e))),
+    }
+}
+
+/// Impressoras de etiqueta ficam na configuração local da estação
+#[command]
+pub async fn listar_impressoras_etiqueta(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<ImpressoraEtiqueta>>, ApiResponse<()>> {
+    let impressoras: Vec<ImpressoraEtiqueta> = ler_json(&app_handle, ARQUIVO_IMPRESSORAS);
+    Ok(ApiResponse::success("Impressoras carregadas.".to_string(), Some(impressoras)))
+}
+
+#[command]
+pub async fn salvar_impressoras_etiqueta(
+    app_handle: AppHandle,
+    impressoras: Vec<ImpressoraEtiqueta>,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    gravar_json(&app_handle, ARQUIVO_IMPRESSORAS, &impressoras).map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success("Impressoras salvas.".to_string(), None))
+}
+
+/// Gera etiquetas em ZPL, EPL ou PDF para amostras e/ou lotes de reagentes/insumos
+#[command]
+pub async fn gerar_etiquetas(
+    template: TemplateEtiqueta,
+    amostras: Option<Vec<DadosEtiquetaAmostra>>,
+    reagentes: Option<Vec<DadosEtiquetaReagente>>,
+    formato: FormatoEtiqueta,
+) -> Result<ApiResponse<EtiquetaGerada>, ApiResponse<()>> {
+    validar_template(&template).map_err(ApiResponse::error)?;
+    let itens = montar_itens(amostras, reagentes);
+    if itens.is_empty() {
+        return Err(ApiResponse::error("Nenhuma etiqueta para gerar.".to_string()));
+    }
+
+    let gerada = match formato {
+        FormatoEtiqueta::Zpl => EtiquetaGerada {
+            formato,
+            conteudo: itens.iter().map(|i| renderizar_zpl(&template, i)).collect::<Vec<_>>().join("\n"),
+            caminho: None,
+            total_etiquetas: itens.len(),
+        },
+        FormatoEtiqueta::Epl => EtiquetaGerada {
+            formato,
+            conteudo: itens.iter().map(|i| renderizar_epl(&template, i)).collect::<Vec<_>>().join("\n"),
+            caminho: None,
+            total_etiquetas: itens.len(),
+        },
+        FormatoEtiqueta::Pdf => {
+            let html = renderizar_html(&template, &itens).map_err(ApiResponse::error)?;
+            let opcoes = get_default_print_settings().map_err(ApiResponse::error)?;
+            let resultado = generate_pdf_from_html(html.clone(), opcoes)
+                .await
+                .map_err(ApiResponse::error)?;
+            if !resultado.success {
+                return Err(ApiResponse::error(
+                    resultado.error.unwrap_or_else(|| "Falha ao gerar PDF.".to_string()),
+                ));
+            }
+            EtiquetaGerada {
+                formato,
+                conteudo: html,
+                caminho: resultado.path,
+                total_etiquetas: itens.len(),
+            }
+        }
+    };
+
+    Ok(ApiResponse::success("Etiquetas geradas.".to_string(), Some(gerada)))
+}
+
+/// Envia as etiquetas em modo RAW para a impressora térmica (rede 9100 ou USB)
+#[command]
+pub async fn imprimir_etiquetas(
+    template: TemplateEtiqueta,
+    amostras: Option<Vec<DadosEtiquetaAmostra>>,
+    reagentes: Option<Vec<DadosEtiquetaReagente>>,
+    impressora: ImpressoraEtiqueta,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    validar_template(&template).map_err(ApiResponse::error)?;
+    let itens = montar_itens(amostras, reagentes);
+    if itens.is_empty() {
+        return Err(ApiResponse::error("Nenhuma etiqueta para imprimir.".to_string()));
+    }
+
+    let comandos: String = itens
+        .iter()
+        .map(|i| match impressora.linguagem {
+            LinguagemImpressora::Zpl => renderizar_zpl(&template, i),
+            LinguagemImpressora::Epl => renderizar_epl(&template, i),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let conexao = impressora.conexao.clone();
+    tokio::task::spawn_blocking(move || enviar_raw(&conexao, comandos.as_bytes()))
+        .await
+        .map_err(|e| ApiResponse::error(format!("Falha na tarefa de impressão: {}", e)))?
+        .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        format!("{} etiqueta(s) enviada(s) para {}.", itens.len(), impressora.nome),
+        None,
+    ))
+}
+
+// ==================== CONTEÚDO DO CÓDIGO ====================
+
+pub fn codigo_amostra(numero: &str) -> String {
+    format!("{}{}", PREFIXO_AMOSTRA, numero.trim())
+}
+
+pub fn codigo_registro(origem: OrigemRegistro, id_registro: u32) -> String {
+    let prefixo = match origem {
+        OrigemRegistro::ReagenteLimpeza => PREFIXO_REAGENTE,
+        OrigemRegistro::Insumo => PREFIXO_INSUMO,
+    };
+    format!("{}{}", prefixo, id_registro)
+}
+
+/// Interpreta o código lido pelo scanner: formato da etiqueta (`AM123`, `RL45`, `IN7`) ou número puro
+pub fn decodificar_codigo(codigo: &str) -> Option<CodigoLido> {
+    let codigo = codigo.trim();
+    let apenas_digitos = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if apenas_digitos(codigo) {
+        return Some(CodigoLido::Amostra { numero: codigo.to_string() });
+    }
+    if codigo.len() < 3 || !codigo.is_char_boundary(2) {
+        return None;
+    }
+
+    let (prefixo, resto) = codigo.split_at(2);
+    let prefixo = prefixo.to_ascii_uppercase();
+    if prefixo == PREFIXO_AMOSTRA && !resto.trim().is_empty() {
+        return Some(CodigoLido::Amostra { numero: resto.trim().to_string() });
+    }
+
+    let origem = match prefixo.as_str() {
+        PREFIXO_REAGENTE => OrigemRegistro::ReagenteLimpeza,
+        PREFIXO_INSUMO => OrigemRegistro::Insumo,
+        _ => return None,
+    };
+    if !apenas_digitos(resto) {
+        return None;
+    }
+    resto.parse().ok().map(|id_registro| CodigoLido::Registro { origem, id_registro })
+}
+
+fn montar_itens(
+    amostras: Option<Vec<DadosEtiquetaAmostra>>,
+    reagentes: Option<Vec<DadosEtiquetaReagente>>,
+) -> Vec<ItemEtiqueta> {
+    let mut itens = Vec::new();
+
+    for a in amostras.unwrap_or_default() {
+        let mut campos = HashMap::new();
+        campos.insert("numero".to_string(), a.numero.trim().to_string());
+        campos.insert("cliente".to_string(), a.cliente.unwrap_or_default());
+        campos.insert("matriz".to_string(), a.matriz.unwrap_or_default());
+        campos.insert("data_coleta".to_string(), a.data_coleta.unwrap_or_default());
+        itens.push(ItemEtiqueta {
+            codigo: codigo_amostra(&a.numero),
+            campos,
+        });
+    }
+
+    for r in reagentes.unwrap_or_default() {
+        let mut campos = HashMap::new();
+        campos.insert("nome".to_string(), r.nome.unwrap_or_default());
+        campos.insert("lote".to_string(), r.lote.unwrap_or_default());
+        campos.insert("validade".to_string(), r.validade.unwrap_or_default());
+        campos.insert("preparo".to_string(), r.preparo.unwrap_or_default());
+        itens.push(ItemEtiqueta {
+            codigo: codigo_registro(r.origem, r.id_registro),
+            campos,
+        });
+    }
+
+    itens
+}
+
+// ==================== RENDERIZAÇÃO ====================
+
+fn validar_template(template: &TemplateEtiqueta) -> Result<(), String> {
+    if template.nome.trim().is_empty() {
+        return Err("Nome do template é obrigatório.".to_string());
+    }
+    if template.largura_mm <= 0.0 || template.altura_mm <= 0.0 {
+        return Err("Dimensões da etiqueta devem ser maiores que zero.".to_string());
+    }
+    if !matches!(template.dpi, 152 | 203 | 300 | 600) {
+        return Err(format!("Resolução {} dpi não suportada (use 152, 203, 300 ou 600).", template.dpi));
+    }
+    Ok(())
+}
+
+fn mm_para_dots(mm: f64, dpi: u32) -> u32 {
+    (mm * dpi as f64 / 25.4).round().max(0.0) as u32
+}
+
+fn texto_campo(campo: &CampoEtiqueta, item: &ItemEtiqueta) -> String {
+    let valor = item.campos.get(&campo.chave).map(String::as_str).unwrap_or("");
+    match &campo.rotulo {
+        Some(rotulo) if !rotulo.is_empty() => format!("{} {}", rotulo, valor),
+        _ => valor.to_string(),
+    }
+}
+
+/// `^` e `~` são comandos em ZPL; removidos do texto impresso
+fn escapar_zpl(texto: &str) -> String {
+    texto.replace(['^', '~'], " ")
+}
+
+/// No `^BC`, `>` inicia os códigos de troca de subconjunto; `><` imprime o próprio `>`
+fn escapar_zpl_code128(texto: &str) -> String {
+    escapar_zpl(texto).replace('>', "><")
+}
+
+fn renderizar_zpl(template: &TemplateEtiqueta, item: &ItemEtiqueta) -> String {
+    let dpi = template.dpi;
+    let mut zpl = String::from("^XA\n^CI28\n");
+    zpl.push_str(&format!("^PW{}\n^LL{}\n", mm_para_dots(template.largura_mm, dpi), mm_para_dots(template.altura_mm, dpi)));
+
+    let x = mm_para_dots(template.codigo_x_mm, dpi);
+    let y = mm_para_dots(template.codigo_y_mm, dpi);
+    let altura = mm_para_dots(template.codigo_altura_mm, dpi).max(1);
+    match template.codigo {
+        TipoCodigo::Code128 => zpl.push_str(&format!("^FO{},{}^BCN,{},N,N,N^FD{}^FS\n", x, y, altura, escapar_zpl_code128(&item.codigo))),
+        TipoCodigo::Qr => {
+            // Magnificação aproximada para o QR ocupar a altura configurada
+            let magnificacao = (altura / 25).clamp(1, 10);
+            zpl.push_str(&format!("^FO{},{}^BQN,2,{}^FDQA,{}^FS\n", x, y, magnificacao, escapar_zpl(&item.codigo)));
+        }
+    }
+
+    for campo in &template.campos {
+        let h = mm_para_dots(campo.altura_mm, dpi).max(10);
+        zpl.push_str(&format!(
+            "^FO{},{}^A0N,{},{}^FD{}^FS\n",
+            mm_para_dots(campo.x_mm, dpi),
+            mm_para_dots(campo.y_mm, dpi),
+            h,
+            h,
+            escapar_zpl(&texto_campo(campo, item)),
+        ));
+    }
+
+    zpl.push_str("^XZ");
+    zpl
+}
+
+fn escapar_epl(texto: &str) -> String {
+    texto.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn renderizar_epl(template: &TemplateEtiqueta, item: &ItemEtiqueta) -> String {
+    let dpi = template.dpi;
+    let mut epl = String::from("\nN\n");
+    epl.push_str(&format!("q{}\nQ{},24\n", mm_para_dots(template.largura_mm, dpi), mm_para_dots(template.altura_mm, dpi)));
+
+    let x = mm_para_dots(template.codigo_x_mm, dpi);
+    let y = mm_para_dots(template.codigo_y_mm, dpi);
+    let altura = mm_para_dots(template.codigo_altura_mm, dpi).max(1);
+    match template.codigo {
+        TipoCodigo::Code128 => epl.push_str(&format!("B{},{},0,1,2,4,{},N,\"{}\"\n", x, y, altura, escapar_epl(&item.codigo))),
+        TipoCodigo::Qr => {
+            let escala = (altura / 25).clamp(1, 10);
+            epl.push_str(&format!("b{},{},Q,s{},\"{}\"\n", x, y, escala, escapar_epl(&item.codigo)));
+        }
+    }
+
+    for campo in &template.campos {
+        // Fontes residentes EPL 1 a 4 têm 12, 16, 20 e 24 dots de altura (203 dpi)
+        let h = mm_para_dots(campo.altura_mm, dpi);
+        let fonte = match h {
+            0..=13 => 1,
+            14..=17 => 2,
+            18..=21 => 3,
+            _ => 4,
+        };
+        epl.push_str(&format!(
+            "A{},{},0,{},1,1,N,\"{}\"\n",
+            mm_para_dots(campo.x_mm, dpi),
+            mm_para_dots(campo.y_mm, dpi),
+            fonte,
+            escapar_epl(&texto_campo(campo, item)),
+        ));
+    }
+
+    epl.push_str("P1\n");
+    epl
+}
+
+/// Tabela de larguras (barra/espaço alternados) dos símbolos Code 128, valores 0 a 106
+const CODE128_PADROES: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+const CODE128_START_B: usize = 104;
+const CODE128_STOP: usize = 106;
+
+/// Codifica o texto em Code 128 (conjunto B) e retorna as larguras dos módulos
+fn code128_larguras(texto: &str) -> Result<Vec<u8>, String> {
+    let mut valores = vec![CODE128_START_B];
+    for c in texto.chars() {
+        let codigo = c as u32;
+        if !(32..=126).contains(&codigo) {
+            return Err(format!("Caractere '{}' não suportado no Code 128.", c));
+        }
+        valores.push((codigo - 32) as usize);
+    }
+
+    let soma: usize = valores
+        .iter()
+        .enumerate()
+        .map(|(i, v)| if i == 0 { *v } else { v * i })
+        .sum();
+    valores.push(soma % 103);
+    valores.push(CODE128_STOP);
+
+    Ok(valores
+        .iter()
+        .flat_map(|v| CODE128_PADROES[*v].bytes().map(|b| b - b'0'))
+        .collect())
+}
+
+fn code128_svg(texto: &str, altura: u32) -> Result<String, String> {
+    let larguras = code128_larguras(texto)?;
+    let quiet = 10u32;
+    let total: u32 = larguras.iter().map(|l| *l as u32).sum::<u32>() + quiet * 2;
+
+    let mut x = quiet;
+    let mut barras = String::new();
+    for (i, largura) in larguras.iter().enumerate() {
+        if i % 2 == 0 {
+            barras.push_str(&format!("<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\"/>", x, largura, altura));
+        }
+        x += *largura as u32;
+    }
+
+    Ok(format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" preserveAspectRatio=\"none\" width=\"100%\" height=\"100%\">{}</svg>",
+        total, altura, barras
+    ))
+}
+
+fn qr_svg(texto: &str) -> Result<String, String> {
+    let qr = QrCode::new(texto.as_bytes()).map_err(|e| format!("Erro ao gerar QR Code: {}", e))?;
+    Ok(qr.render::<qrcode::render::svg::Color>().quiet_zone(false).build())
+}
+
+fn renderizar_html(template: &TemplateEtiqueta, itens: &[ItemEtiqueta]) -> Result<String, String> {
+    let mut etiquetas = String::new();
+
+    for item in itens {
+        let codigo = match template.codigo {
+            TipoCodigo::Code128 => code128_svg(&item.codigo, 50)?,
+            TipoCodigo::Qr => qr_svg(&item.codigo)?,
+        };
+        let largura_codigo = match template.codigo {
+            TipoCodigo::Code128 => template.largura_mm - template.codigo_x_mm * 2.0,
+            TipoCodigo::Qr => template.codigo_altura_mm,
+        };
+
+        let mut textos = String::new();
+        for campo in &template.campos {
+            textos.push_str(&format!(
+                "<div class=\"campo\" style=\"left:{}mm;top:{}mm;font-size:{}mm\">{}</div>",
+                campo.x_mm,
+                campo.y_mm,
+                campo.altura_mm,
+                escapar_html(&texto_campo(campo, item)),
+            ));
+        }
+
+        etiquetas.push_str(&format!(
+            "<div class=\"etiqueta\"><div class=\"codigo\" style=\"left:{}mm;top:{}mm;width:{}mm;height:{}mm\">{}</div>{}</div>",
+            template.codigo_x_mm, template.codigo_y_mm, largura_codigo, template.codigo_altura_mm, codigo, textos,
+        ));
+    }
+
+    Ok(format!(
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
+body {{ margin: 0; font-family: Arial, sans-serif; }}
+.etiqueta {{ position: relative; width: {}mm; height: {}mm; border: 1px dashed #ccc; display: inline-block; margin: 1mm; overflow: hidden; }}
+.codigo, .campo {{ position: absolute; white-space: nowrap; }}
+</style></head><body>{}</body></html>"#,
+        template.largura_mm, template.altura_mm, etiquetas
+    ))
+}
+
+fn templates_padrao() -> Vec<TemplateEtiqueta> {
+    let campo = |chave: &str, rotulo: Option<&str>, y_mm: f64| CampoEtiqueta {
+        chave: chave.to_string(),
+        rotulo: rotulo.map(str::to_string),
+        x_mm: 3.0,
+        y_mm,
+        altura_mm: 2.5,
+    };
+
+    vec![
+        TemplateEtiqueta {
+            id: None,
+            nome: "Amostra 50x30".to_string(),
+            tipo: TipoEtiqueta::Amostra,
+            largura_mm: 50.0,
+            altura_mm: 30.0,
+            dpi: 203,
+            codigo: TipoCodigo::Code128,
+            codigo_x_mm: 3.0,
+            codigo_y_mm: 2.0,
+            codigo_altura_mm: 10.0,
+            campos: vec![
+                campo("numero", Some("Nº"), 13.0),
+                campo("cliente", None, 17.0),
+                campo("matriz", None, 21.0),
+                campo("data_coleta", Some("Coleta:"), 25.0),
+            ],
+        },
+        TemplateEtiqueta {
+            id: None,
+            nome: "Reagente 50x30 QR".to_string(),
+            tipo: TipoEtiqueta::Reagente,
+            largura_mm: 50.0,
+            altura_mm: 30.0,
+            dpi: 203,
+            codigo: TipoCodigo::Qr,
+            codigo_x_mm: 2.0,
+            codigo_y_mm: 2.0,
+            codigo_altura_mm: 20.0,
+            campos: vec![
+                CampoEtiqueta { x_mm: 24.0, ..campo("nome", None, 3.0) },
+                CampoEtiqueta { x_mm: 24.0, ..campo("lote", Some("Lote:"), 8.0) },
+                CampoEtiqueta { x_mm: 24.0, ..campo("preparo", Some("Prep.:"), 13.0) },
+                CampoEtiqueta { x_mm: 24.0, ..campo("validade", Some("Val.:"), 18.0) },
+            ],
+        },
+    ]
+}
+
+// ==================== ENVIO RAW ====================
+
+fn enviar_raw(conexao: &ConexaoImpressora, dados: &[u8]) -> Result<(), String> {
+    match conexao {
+        ConexaoImpressora::Rede { host, porta } => {
+            let endereco = (host.as_str(), *porta)
+                .to_socket_addrs()
+                .map_err(|e| format!("Endereço inválido {}:{}: {}", host, porta, e))?
+                .next()
+                .ok_or_else(|| format!("Endereço não resolvido: {}:{}", host, porta))?;
+
+            let mut stream = TcpStream::connect_timeout(&endereco, Duration::from_secs(5))
+                .map_err(|e| format!("Impressora {}:{} inacessível: {}", host, porta, e))?;
+            stream
+                .set_write_timeout(Some(Duration::from_secs(10)))
+                .map_err(|e| format!("Erro ao configurar conexão: {}", e))?;
+            stream
+                .write_all(dados)
+                .and_then(|_| stream.flush())
+                .map_err(|e| format!("Erro ao enviar dados para a impressora: {}", e))
+        }
+        ConexaoImpressora::Usb { caminho } => {
+            let mut dispositivo = OpenOptions::new()
+                .write(true)
+                .open(caminho)
+                .map_err(|e| format!("Erro ao abrir impressora {}: {}", caminho, e))?;
+            dispositivo
+                .write_all(dados)
+                .and_then(|_| dispositivo.flush())
+                .map_err(|e| format!("Erro ao enviar dados para a impressora: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    fn item_amostra() -> ItemEtiqueta {
+        montar_itens(
+            Some(vec![DadosEtiquetaAmostra {
+                numero: "12345".to_string(),
+                cliente: Some("Cliente ^Teste".to_string()),
+                matriz: Some("Água".to_string()),
+                data_coleta: Some("05/03/2024".to_string()),
+            }]),
+            None,
+        )
+        .remove(0)
+    }
+
+    #[test]
+    fn test_code128_checksum_e_larguras() {
+        // "AM1": start B (104) + A(33) + M(45) + 1(17) -> checksum (104 + 33 + 90 + 51) % 103 = 72
+        let larguras = code128_larguras("AM1").unwrap();
+        let esperado: Vec<u8> = [104, 33, 45, 17, 72, 106]
+            .iter()
+            .flat_map(|v| CODE128_PADROES[*v].bytes().map(|b| b - b'0'))
+            .collect();
+        assert_eq!(larguras, esperado);
+        assert!(CODE128_PADROES[..106].iter().all(|p| p.bytes().map(|b| (b - b'0') as u32).sum::<u32>() == 11));
+        assert!(code128_larguras("Água").is_err());
+    }
+
+    #[test]
+    fn test_decodificar_codigo() {
+        assert_eq!(decodificar_codigo(" 12345\r\n"), Some(CodigoLido::Amostra { numero: "12345".to_string() }));
+        assert_eq!(decodificar_codigo(&codigo_amostra("12345")), Some(CodigoLido::Amostra { numero: "12345".to_string() }));
+        assert_eq!(
+            decodificar_codigo(&codigo_registro(OrigemRegistro::Insumo, 7)),
+            Some(CodigoLido::Registro { origem: OrigemRegistro::Insumo, id_registro: 7 })
+        );
+        assert_eq!(decodificar_codigo("rl45"), Some(CodigoLido::Registro { origem: OrigemRegistro::ReagenteLimpeza, id_registro: 45 }));
+        assert_eq!(decodificar_codigo("XX12"), None);
+        assert_eq!(decodificar_codigo("RLabc"), None);
+    }
+
+    #[test]
+    fn test_renderizar_zpl_amostra() {
+        let template = templates_padrao().remove(0);
+        let zpl = renderizar_zpl(&template, &item_amostra());
+
+        assert!(zpl.starts_with("^XA"));
+        assert!(zpl.ends_with("^XZ"));
+        assert!(zpl.contains("^PW400"));
+        assert!(zpl.contains("^BCN,80,N,N,N^FDAM12345^FS"));
+        assert!(zpl.contains("^FDCliente  Teste^FS"));
+
+        let mut item = item_amostra();
+        item.codigo = "A>B^C".to_string();
+        assert!(renderizar_zpl(&template, &item).contains("^FDA><B C^FS"));
+    }
+
+    #[test]
+    fn test_enviar_raw_para_socket_local() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let porta = listener.local_addr().unwrap().port();
+        let receptor = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut recebido = String::new();
+            socket.read_to_string(&mut recebido).unwrap();
+            recebido
+        });
+
+        let template = templates_padrao().remove(0);
+        let zpl = renderizar_zpl(&template, &item_amostra());
+        let conexao = ConexaoImpressora::Rede { host: "127.0.0.1".to_string(), porta };
+        enviar_raw(&conexao, zpl.as_bytes()).unwrap();
+
+        assert_eq!(receptor.join().unwrap(), zpl);
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::usuario::get_user_id;
+use crate::model::faixa_numeracao::{
+    AvisoReusoNumero, IntervaloNumeros, InutilizarNumeroPayload, MotivoAvisoNumero,
+    NumeroInutilizado, RelatorioFaixaPlanilha,
+};
+use crate::controller::laboratorio::planilha_controller::{
+    buscar_amostras_planilha, buscar_intervalos_planilhas, NumberRange,
+};
+
+/// Concilia todas as planilhas emitidas com os números efetivamente cadastrados
+#[command]
+pub async fn reconciliar_faixas_planilhas(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<RelatorioFaixaPlanilha>>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+
+    let faixas = buscar_intervalos_planilhas(&client, &api_url)
+        .await
+        .map_err(ApiResponse::error)?;
+    let inutilizados: HashSet<u32> = buscar_inutilizados(&client, &api_url)
+        .await
+        .map_err(ApiResponse::error)?
+        .into_iter()
+        .map(|n| n.numero)
+        .collect();
+
+    let mut relatorios = Vec::with_capacity(faixas.len());
+    for faixa in &faixas {
+        let numeros = buscar_amostras_planilha(&client, &api_url, faixa.id)
+            .await
+            .map_err(|e| ApiResponse::error(format!("Planilha {}: {}", faixa.id, e)))?;
+        relatorios.push(reconciliar_faixa(faixa, &numeros, &inutilizados, &faixas));
+    }
+
+    Ok(ApiResponse::success("Faixas conciliadas.".to_string(), Some(relatorios)))
+}
+
+/// Inutiliza um número de amostra (não poderá ser reaproveitado) registrando o motivo
+#[command]
+pub async fn inutilizar_numero_amostra(
+    app_handle: AppHandle,
+    numero: u32,
+    motivo: String,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    if motivo.trim().is_empty() {
+        return Err(ApiResponse::error("Informe o motivo da inutilização.".to_string()));
+    }
+    let user_id = get_user_id().ok_or_else(|| ApiResponse::error("Usuário não autenticado".to_string()))?;
+
+    let client = Client::new();
+    let url = format!("{}/planilha/inutilizar", get_api_url(&app_handle));
+    let payload = InutilizarNumeroPayload {
+        numero,
+        motivo: motivo.trim().to_string(),
+        user_id,
+    };
+
+    match client.post(&url).json(&payload).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                Ok(ApiResponse::success(format!("Número {} inutilizado.", numero), None))
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                Err(ApiResponse::error(format!("Erro API ({}): {}", status, msg)))
+            }
+        }
+        Err(e) => Err(ApiResponse::error(format!("Falha conexão: {}", e))),
+    }
+}
+
+#[command]
+pub async fn listar_numeros_inutilizados(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<NumeroInutilizado>>, ApiResponse<()>> {
+    let client = Client::new();
+    let lista = buscar_inutilizados(&client, &get_api_url(&app_handle))
+        .await
+        .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Números inutilizados carregados.".to_string(), Some(lista)))
+}
+
+/// Verifica, antes de cadastrar ou renumerar, se algum número já foi usado ou inutilizado
+#[command]
+pub async fn verificar_reuso_numeros(
+    app_handle: AppHandle,
+    numeros: Vec<u32>,
+) -> Result<ApiResponse<Vec<AvisoReusoNumero>>, ApiResponse<()>> {
+    let avisos = checar_reuso(&app_handle, &numeros)
+        .await
+        .map_err(ApiResponse::error)?;
+
+    let mensagem = if avisos.is_empty() {
+        "Nenhum conflito encontrado.".to_string()
+    } else {
+        format!("{} número(s) com conflito.", avisos.len())
+    };
+    Ok(ApiResponse::success(mensagem, Some(avisos)))
+}
+
+/// Usado por `cadastrar_amostra_completa` e `atualizar_numero_amostra`
+pub async fn checar_reuso(app_handle: &AppHandle, numeros: &[u32]) -> Result<Vec<AvisoReusoNumero>, String> {
+    if numeros.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+
+    let faixas = buscar_intervalos_planilhas(&client, &api_url).await?;
+    let inutilizados: HashSet<u32> = buscar_inutilizados(&client, &api_url)
+        .await?
+        .into_iter()
+        .map(|n| n.numero)
+        .collect();
+
+    // Só consulta as planilhas que contêm algum dos números informados
+    let mut registrados: HashMap<u32, HashSet<u32>> = HashMap::new();
+    for faixa in faixas.iter().filter(|f| numeros.iter().any(|n| *n >= f.inicial && *n <= f.final_)) {
+        let usados = buscar_amostras_planilha(&client, &api_url, faixa.id).await?;
+        registrados.insert(faixa.id, usados.into_iter().filter_map(|n| u32::try_from(n).ok()).collect());
+    }
+
+    Ok(avaliar_reuso(numeros, &faixas, &registrados, &inutilizados))
+}
+
+async fn buscar_inutilizados(client: &Client, api_url: &str) -> Result<Vec<NumeroInutilizado>, String> {
+    let url = format!("{}/planilha/inutilizados", api_url);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro na requisição: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Erro HTTP: {}", response.status()));
+    }
+
+    response
+        .json::<Vec<NumeroInutilizado>>()
+        .await
+        .map_err(|e| format!("Erro ao deserializar JSON: {}", e))
+}
+
+/// Agrupa números ordenados em intervalos contínuos
+fn agrupar_intervalos(numeros: impl IntoIterator<Item = u32>) -> Vec<IntervaloNumeros> {
+    let mut intervalos: Vec<IntervaloNumeros> = Vec::new();
+    for n in numeros {
+        match intervalos.last_mut() {
+            Some(ultimo) if ultimo.fim + 1 == n => ultimo.fim = n,
+            _ => intervalos.push(IntervaloNumeros { inicio: n, fim: n }),
+        }
+    }
+    intervalos
+}
+
+fn reconciliar_faixa(
+    faixa: &NumberRange,
+    numeros: &[i32],
+    inutilizados: &HashSet<u32>,
+    todas: &[NumberRange],
+) -> RelatorioFaixaPlanilha {
+    let mut contagem: BTreeMap<u32, u32> = BTreeMap::new();
+    for n in numeros.iter().filter_map(|n| u32::try_from(*n).ok()) {
+        *contagem.entry(n).or_insert(0) += 1;
+    }
+
+    let dentro = |n: u32| n >= faixa.inicial && n <= faixa.final_;
+    let duplicados: Vec<u32> = contagem.iter().filter(|(_, c)| **c > 1).map(|(n, _)| *n).collect();
+    let fora_da_faixa: Vec<u32> = contagem.keys().copied().filter(|n| !dentro(*n)).collect();
+    let maior_registrado = contagem.keys().copied().filter(|n| dentro(*n)).max();
+
+    let livres = (faixa.inicial..=faixa.final_).filter(|n| !contagem.contains_key(n) && !inutilizados.contains(n));
+    let (lacunas, nao_utilizados): (Vec<u32>, Vec<u32>) = match maior_registrado {
+        Some(maior) => livres.partition(|n| *n < maior),
+        None => (Vec::new(), livres.collect()),
+    };
+
+    let sobreposicoes = todas
+        .iter()
+        .filter(|o| o.id != faixa.id && o.inicial <= faixa.final_ && faixa.inicial <= o.final_)
+        .map(|o| o.id)
+        .collect();
+
+    RelatorioFaixaPlanilha {
+        id_planilha: faixa.id,
+        inicial: faixa.inicial,
+        final_: faixa.final_,
+        total_numeros: faixa.final_.saturating_sub(faixa.inicial) + 1,
+        total_registrados: contagem.keys().filter(|n| dentro(**n)).count() as u32,
+        total_inutilizados: (faixa.inicial..=faixa.final_).filter(|n| inutilizados.contains(n)).count() as u32,
+        lacunas: agrupar_intervalos(lacunas),
+        nao_utilizados: agrupar_intervalos(nao_utilizados),
+        fora_da_faixa,
+        duplicados,
+        sobreposicoes,
+    }
+}
+
+fn avaliar_reuso(
+    numeros: &[u32],
+    faixas: &[NumberRange],
+    registrados: &HashMap<u32, HashSet<u32>>,
+    inutilizados: &HashSet<u32>,
+) -> Vec<AvisoReusoNumero> {
+    let mut avisos = Vec::new();
+    let mut vistos = HashSet::new();
+
+    for &numero in numeros {
+        if !vistos.insert(numero) {
+            continue;
+        }
+
+        if inutilizados.contains(&numero) {
+            avisos.push(AvisoReusoNumero {
+                numero,
+                motivo: MotivoAvisoNumero::Inutilizado,
+                id_planilha: None,
+                mensagem: format!("O número {} foi inutilizado e não pode ser reaproveitado.", numero),
+            });
+            continue;
+        }
+
+        // Faixas podem se sobrepor: o número precisa estar livre em todas as que o contêm
+        let contendo: Vec<&NumberRange> = faixas.iter().filter(|f| numero >= f.inicial && numero <= f.final_).collect();
+        if contendo.is_empty() {
+            avisos.push(AvisoReusoNumero {
+                numero,
+                motivo: MotivoAvisoNumero::ForaDeFaixa,
+                id_planilha: None,
+                mensagem: format!("O número {} não pertence a nenhuma planilha emitida.", numero),
+            });
+            continue;
+        }
+        if let Some(f) = contendo.iter().find(|f| registrados.get(&f.id).is_some_and(|r| r.contains(&numero))) {
+            avisos.push(AvisoReusoNumero {
+                numero,
+                motivo: MotivoAvisoNumero::JaRegistrado,
+                id_planilha: Some(f.id),
+                mensagem: format!("O número {} já está cadastrado na planilha {}.", numero, f.id),
+            });
+        }
+    }
+
+    avisos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn faixa(id: u32, inicial: u32, final_: u32) -> NumberRange {
+        NumberRange { id, inicial, final_ }
+    }
+
+    #[test]
+    fn test_reconciliar_faixa_lacunas_e_sobreposicao() {
+        let todas = vec![faixa(1, 100, 109), faixa(2, 108, 120)];
+        let inutilizados: HashSet<u32> = [102].into_iter().collect();
+        let numeros = vec![100, 101, 104, 104, 105, 130];
+
+        let r = reconciliar_faixa(&todas[0], &numeros, &inutilizados, &todas);
+
+        assert_eq!(r.total_numeros, 10);
+        assert_eq!(r.total_registrados, 4);
+        assert_eq!(r.total_inutilizados, 1);
+        assert_eq!(r.lacunas, vec![IntervaloNumeros { inicio: 103, fim: 103 }]);
+        assert_eq!(r.nao_utilizados, vec![IntervaloNumeros { inicio: 106, fim: 109 }]);
+        assert_eq!(r.duplicados, vec![104]);
+        assert_eq!(r.fora_da_faixa, vec![130]);
+        assert_eq!(r.sobreposicoes, vec![2]);
+    }
+
+    #[test]
+    fn test_avaliar_reuso() {
+        // A planilha 2 se sobrepõe à 1 e já usou o 108
+        let faixas = vec![faixa(1, 100, 109), faixa(2, 108, 120)];
+        let registrados: HashMap<u32, HashSet<u32>> =
+            [(1, [100, 101].into_iter().collect()), (2, [108].into_iter().collect())].into_iter().collect();
+        let inutilizados: HashSet<u32> = [105].into_iter().collect();
+
+        let avisos = avaliar_reuso(&[101, 102, 105, 500, 101, 108], &faixas, &registrados, &inutilizados);
+        let motivos: Vec<(u32, MotivoAvisoNumero)> = avisos.iter().map(|a| (a.numero, a.motivo)).collect();
+
+        assert_eq!(
+            motivos,
+            vec![
+                (101, MotivoAvisoNumero::JaRegistrado),
+                (105, MotivoAvisoNumero::Inutilizado),
+                (500, MotivoAvisoNumero::ForaDeFaixa),
+                (108, MotivoAvisoNumero::JaRegistrado),
+            ]
+        );
+    }
+}
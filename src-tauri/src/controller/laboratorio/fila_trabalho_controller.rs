@@ -2,7 +2,11 @@ use tauri::{command, AppHandle};
 use reqwest::Client;
 use crate::model::api_response::ApiResponse;
 use crate::config::get_api_url;
-use crate::model::fila_trabalho::{PaginatedFilaResponse, IniciarAnalisePayload}; // Import atualizado
+use crate::model::fila_trabalho::{ItemFilaTrabalho, PaginatedFilaResponse, IniciarAnalisePayload}; // Import atualizado
+use crate::model::prazo_validade::StatusPrazo;
+use crate::controller::laboratorio::prazo_validade_controller::{aplicar_prazos, ordenar_por_urgencia, regras_ou_padrao};
+use crate::controller::laboratorio::calibracao_controller::verificar_equipamentos_liberados;
+use crate::controller::laboratorio::validade_lote_controller::verificar_lote_insumo_2;
 
 #[command]
 pub async fn listar_fila_trabalho_tauri(
@@ -10,38 +14,111 @@ pub async fn listar_fila_trabalho_tauri(
     status: String,
     id_laboratorio: Option<u32>, // NOVO
     page: Option<u32>,           // NOVO
-    per_page: Option<u32>        // NOVO
+    per_page: Option<u32>,       // NOVO
+    ordenar_urgencia: Option<bool>,
+    filtro_prazo: Option<Vec<StatusPrazo>>,
 ) -> Result<ApiResponse<PaginatedFilaResponse>, ApiResponse<()>> {
     
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
-    
+
+    // Paginação
+    let p = page.unwrap_or(1).max(1);
+    let pp = per_page.unwrap_or(50).max(1);
+
+    let regras = regras_ou_padrao(&client, &api_url).await;
+    let agora = chrono::Local::now().naive_local();
+    let filtro = filtro_prazo.filter(|f| !f.is_empty());
+    let ordenar = ordenar_urgencia.unwrap_or(false);
+
+    // Sem filtro nem ordenação por prazo, a paginação fica com a API
+    if filtro.is_none() && !ordenar {
+        let mut data = buscar_fila(&client, &api_url, &status, id_laboratorio, p, pp)
+            .await
+            .map_err(ApiResponse::error)?;
+        aplicar_prazos(&mut data.items, &regras, agora);
+        return Ok(ApiResponse::success("Dados carregados.".to_string(), Some(data)));
+    }
+
+    // O prazo é calculado localmente: filtra e ordena a fila inteira e pagina aqui
+    let mut itens = buscar_fila_completa(&client, &api_url, &status, id_laboratorio)
+        .await
+        .map_err(ApiResponse::error)?;
+    aplicar_prazos(&mut itens, &regras, agora);
+
+    if let Some(filtro) = filtro {
+        itens.retain(|i| i.status_prazo.is_some_and(|s| filtro.contains(&s)));
+    }
+    if ordenar {
+        ordenar_por_urgencia(&mut itens);
+    }
+    let data = paginar(itens, p, pp);
+
+    Ok(ApiResponse::success("Dados carregados.".to_string(), Some(data)))
+}
+
+/// Todas as páginas da fila; também usada pelo monitor de prazos
+pub async fn buscar_fila_completa(
+    client: &Client,
+    api_url: &str,
+    status: &str,
+    id_laboratorio: Option<u32>,
+) -> Result<Vec<ItemFilaTrabalho>, String> {
+    let mut itens = Vec::new();
+    let mut pagina = 1;
+    loop {
+        let resposta = buscar_fila(client, api_url, status, id_laboratorio, pagina, 200).await?;
+        itens.extend(resposta.items);
+        if pagina >= resposta.total_pages {
+            break;
+        }
+        pagina += 1;
+    }
+    Ok(itens)
+}
+
+fn paginar(itens: Vec<ItemFilaTrabalho>, page: u32, per_page: u32) -> PaginatedFilaResponse {
+    let total = itens.len();
+    let inicio = (page as usize - 1).saturating_mul(per_page as usize);
+    PaginatedFilaResponse {
+        items: itens.into_iter().skip(inicio).take(per_page as usize).collect(),
+        total: total as i64,
+        page,
+        per_page,
+        total_pages: total.div_ceil(per_page as usize) as u32,
+    }
+}
+
+async fn buscar_fila(
+    client: &Client,
+    api_url: &str,
+    status: &str,
+    id_laboratorio: Option<u32>,
+    page: u32,
+    per_page: u32,
+) -> Result<PaginatedFilaResponse, String> {
     // Monta Query String
     let mut url = format!("{}/laboratorio/fila-trabalho?status={}", api_url, status);
     
     if let Some(id_lab) = id_laboratorio {
         url.push_str(&format!("&id_laboratorio={}", id_lab));
     }
-    
-    // Paginação
-    let p = page.unwrap_or(1);
-    let pp = per_page.unwrap_or(50);
-    url.push_str(&format!("&page={}&per_page={}", p, pp));
+    url.push_str(&format!("&page={}&per_page={}", page, per_page));
 
     match client.get(&url).send().await {
         Ok(response) => {
             let status = response.status();
             if status.is_success() {
-                match response.json::<PaginatedFilaResponse>().await {
-                    Ok(data) => Ok(ApiResponse::success("Dados carregados.".to_string(), Some(data))),
-                    Err(e) => Err(ApiResponse::error(format!("Erro JSON: {}", e))),
-                }
+                response
+                    .json::<PaginatedFilaResponse>()
+                    .await
+                    .map_err(|e| format!("Erro JSON: {}", e))
             } else {
                 let msg = response.text().await.unwrap_or_default();
-                Err(ApiResponse::error(format!("Erro API ({}): {}", status, msg)))
+                Err(format!("Erro API ({}): {}", status, msg))
             }
         },
-        Err(e) => Err(ApiResponse::error(format!("Falha conexão: {}", e))),
+        Err(e) => Err(format!("Falha conexão: {}", e)),
     }
 }
 
@@ -55,6 +132,10 @@ pub async fn iniciar_analises_tauri(
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/laboratorio/fila-trabalho/iniciar", api_url);
 
+    validar_inicio_analise(&client, &api_url, &payload.equipamentos, &payload.lotes_insumo)
+        .await
+        .map_err(ApiResponse::error)?;
+
     match client.post(&url).json(&payload).send().await {
         Ok(response) => {
             let status = response.status();
@@ -67,4 +148,48 @@ pub async fn iniciar_analises_tauri(
         },
         Err(e) => Err(ApiResponse::error(format!("Erro de conexão: {}", e))),
     }
-}
\ No newline at end of file
+}
+
+/// Regras para iniciar análises (fila de trabalho e sessão de leitura): equipamentos
+/// calibrados e lotes de insumo dentro da validade
+pub async fn validar_inicio_analise(
+    client: &Client,
+    api_url: &str,
+    equipamentos: &[u32],
+    lotes_insumo: &[u32],
+) -> Result<(), String> {
+    if !equipamentos.is_empty() {
+        verificar_equipamentos_liberados(client, api_url, equipamentos).await?;
+    }
+    for id in lotes_insumo {
+        verificar_lote_insumo_2(client, api_url, *id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(analise_id: u32) -> ItemFilaTrabalho {
+        serde_json::from_value(serde_json::json!({
+            "analise_id": analise_id, "amostra_numero": null, "identificacao": null, "complemento": null,
+            "parametro_nome": null, "pop_codigo": null, "pop_numero": null, "pop_revisao": null,
+            "tecnica_nome": null, "data_coleta": null, "data_lab": null, "hora_lab": null,
+            "em_campo": null, "data_inicio": null, "usuario_visto": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_paginar() {
+        let itens: Vec<ItemFilaTrabalho> = (1..=5).map(item).collect();
+
+        let pagina = paginar(itens.clone(), 2, 2);
+        assert_eq!(pagina.items.iter().map(|i| i.analise_id).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!((pagina.total, pagina.total_pages), (5, 3));
+
+        assert!(paginar(itens, 4, 2).items.is_empty());
+        assert_eq!(paginar(Vec::new(), 1, 50).total_pages, 0);
+    }
+}
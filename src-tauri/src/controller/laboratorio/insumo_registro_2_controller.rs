@@ -10,6 +10,7 @@ use crate::model::insumo_registro_2::{
     AtualizacaoAmostraInsumo2Payload
 };
 use crate::config::get_api_url;
+use crate::controller::laboratorio::validade_lote_controller::verificar_lote_insumo_2;
 
 // --- LISTAR (Read) ---
 #[command]
@@ -150,6 +151,11 @@ pub async fn atualizar_amostra_registro_2_tauri(
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/laboratorio/insumos-registros-2/{}/amostra", api_url, id);
 
+    // Lote vencido não pode ter o uso iniciado
+    if payload.tipo == "INICIAL" {
+        verificar_lote_insumo_2(&client, &api_url, id).await.map_err(ApiResponse::error)?;
+    }
+
     match client.patch(&url).json(&payload).send().await {
         Ok(response) => {
             match response.status() {
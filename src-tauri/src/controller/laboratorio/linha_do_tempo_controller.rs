@@ -0,0 +1,418 @@
+use std::path::PathBuf;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::linha_do_tempo::{
+    EventoAuditoriaFromApi, EventoLinhaDoTempo, ExportacaoLinhaDoTempo, LinhaDoTempoAmostra,
+    LinhaDoTempoFiltro, TipoEventoAmostra,
+};
+use crate::controller::laboratorio::amostra_broqueada_controller::HistoricoBloqueio;
+use crate::controller::laboratorio::coleta_checagem_controller::{BuscarChecagemPayload, GrupoChecagem};
+use crate::controller::laboratorio::resultado_controller::AmostraResultadosResponse;
+use crate::exportacao::{aviso_html, campo_csv, destino_exportacao, escapar_html, pagina_relatorio, salvar_csv, salvar_pdf, tabela_html};
+use crate::utils::{buscar_json, parse_data_hora};
+
+const API_RESOURCE: &str = "/laboratorio/linha-do-tempo";
+
+/// Monta a linha do tempo (cadeia de custódia) de uma amostra/análise juntando
+/// auditoria, bloqueios, checagem e datas dos resultados.
+#[command]
+pub async fn linha_do_tempo_amostra(
+    app_handle: AppHandle,
+    filtro: LinhaDoTempoFiltro,
+) -> Result<ApiResponse<LinhaDoTempoAmostra>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+
+    let id_analise = match (filtro.id_analise, filtro.numero_amostra.as_deref()) {
+        (Some(id), _) => id,
+        (None, Some(numero)) if !numero.trim().is_empty() => {
+            resolver_id_analise(&client, &api_url, numero.trim()).await?
+        }
+        _ => return Err(ApiResponse::error("Informe o id da análise ou o número da amostra.".to_string())),
+    };
+
+    let mut eventos: Vec<EventoLinhaDoTempo> = Vec::new();
+    let mut fontes_indisponiveis: Vec<String> = Vec::new();
+    let mut numero: Option<String> = None;
+    let mut identificacao: Option<String> = None;
+
+    // 1. Datas de coleta, entrada e execução das análises
+    let url = format!("{}/amostras/{}/resultados", api_url, id_analise);
+    match buscar_json::<AmostraResultadosResponse>(&client, &url).await {
+        Ok(dados) => {
+            numero = dados.info.numero.clone();
+            identificacao = dados.info.identificacao.clone();
+            eventos.extend(eventos_de_resultados(&dados));
+        }
+        Err(e) => fontes_indisponiveis.push(format!("Resultados: {}", e)),
+    }
+
+    // 2. Auditoria (início, vistos, revisões, publicação, assinatura, impressão)
+    let url = format!("{}{}/{}", api_url, API_RESOURCE, id_analise);
+    match buscar_json::<Vec<EventoAuditoriaFromApi>>(&client, &url).await {
+        Ok(registros) => eventos.extend(registros.into_iter().map(evento_de_auditoria)),
+        Err(e) => fontes_indisponiveis.push(format!("Auditoria: {}", e)),
+    }
+
+    // 3. Histórico de bloqueio/desbloqueio
+    let url = format!("{}/laboratorio/amostras-bloqueadas/{}/historico", api_url, id_analise);
+    match buscar_json::<Vec<HistoricoBloqueio>>(&client, &url).await {
+        Ok(historico) => eventos.extend(historico.into_iter().map(evento_de_bloqueio)),
+        Err(e) => fontes_indisponiveis.push(format!("Bloqueios: {}", e)),
+    }
+
+    // 4. Checagem de recebimento (depende do número da amostra)
+    if let Some(num) = numero.as_deref().and_then(|n| n.trim().parse::<u32>().ok()) {
+        match buscar_checagem(&client, &api_url, num).await {
+            Ok(grupos) => eventos.extend(grupos.iter().filter_map(evento_de_checagem)),
+            Err(e) => fontes_indisponiveis.push(format!("Checagem: {}", e)),
+        }
+    }
+
+    if let Some(tipos) = &filtro.tipos {
+        eventos.retain(|e| tipos.contains(&e.tipo));
+    }
+    ordenar_eventos(&mut eventos);
+
+    let linha = LinhaDoTempoAmostra {
+        id_analise,
+        numero,
+        identificacao,
+        eventos,
+        fontes_indisponiveis,
+    };
+
+    Ok(ApiResponse::success("Linha do tempo carregada.".to_string(), Some(linha)))
+}
+
+/// Exporta a linha do tempo em CSV (separador `;`, compatível com Excel pt-BR)
+#[command]
+pub async fn exportar_linha_do_tempo_csv(
+    linha: LinhaDoTempoAmostra,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoLinhaDoTempo>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, &linha, "csv").map_err(ApiResponse::error)?;
+    salvar_csv(&destino, &gerar_csv(&linha)).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Linha do tempo exportada.".to_string(),
+        Some(ExportacaoLinhaDoTempo {
+            caminho: destino.to_string_lossy().to_string(),
+            total_eventos: linha.eventos.len(),
+        }),
+    ))
+}
+
+/// Exporta a linha do tempo em PDF usando o mesmo pipeline HTML -> PDF da impressão
+#[command]
+pub async fn exportar_linha_do_tempo_pdf(
+    linha: LinhaDoTempoAmostra,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoLinhaDoTempo>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, &linha, "pdf").map_err(ApiResponse::error)?;
+    salvar_pdf(gerar_html(&linha), &destino).await.map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Linha do tempo exportada.".to_string(),
+        Some(ExportacaoLinhaDoTempo {
+            caminho: destino.to_string_lossy().to_string(),
+            total_eventos: linha.eventos.len(),
+        }),
+    ))
+}
+
+// --- Funções auxiliares ---
+
+async fn resolver_id_analise(client: &Client, api_url: &str, numero: &str) -> Result<u32, ApiResponse<()>> {
+    let url = format!("{}{}/numero/{}", api_url, API_RESOURCE, urlencoding::encode(numero));
+
+    #[derive(serde::Deserialize)]
+    struct IdAnaliseResponse {
+        id_analise: u32,
+    }
+
+    buscar_json::<IdAnaliseResponse>(client, &url)
+        .await
+        .map(|r| r.id_analise)
+        .map_err(|e| ApiResponse::error(format!("Amostra {} não encontrada: {}", numero, e)))
+}
+
+async fn buscar_checagem(client: &Client, api_url: &str, numero: u32) -> Result<Vec<GrupoChecagem>, String> {
+    let url = format!("{}/laboratorio/coleta-checagem/buscar", api_url);
+    let payload = BuscarChecagemPayload {
+        numero_ini: Some(numero),
+        numero_fim: Some(numero),
+        id_grupo_edit: None,
+    };
+
+    let response = client
+        .post(&url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    response.json::<Vec<GrupoChecagem>>().await.map_err(|e| format!("Erro JSON: {}", e))
+}
+
+fn evento_simples(tipo: TipoEventoAmostra, descricao: String, data_hora: Option<chrono::NaiveDateTime>) -> EventoLinhaDoTempo {
+    EventoLinhaDoTempo {
+        tipo,
+        descricao,
+        usuario: None,
+        ip: None,
+        computador: None,
+        data_hora,
+        valor_anterior: None,
+        valor_novo: None,
+        id_resultado: None,
+        parametro: None,
+    }
+}
+
+fn eventos_de_resultados(dados: &AmostraResultadosResponse) -> Vec<EventoLinhaDoTempo> {
+    let info = &dados.info;
+    let mut eventos = Vec::new();
+
+    if let Some(data) = info.data_coleta.as_deref() {
+        eventos.push(evento_simples(
+            TipoEventoAmostra::Coleta,
+            "Amostra coletada".to_string(),
+            parse_data_hora(data, info.hora_coleta.as_deref()),
+        ));
+    }
+    if let Some(data) = info.data_entrada_lab.as_deref() {
+        eventos.push(evento_simples(
+            TipoEventoAmostra::EntradaLaboratorio,
+            "Amostra recebida no laboratório".to_string(),
+            parse_data_hora(data, info.hora_entrada_lab.as_deref()),
+        ));
+    }
+
+    for item in &dados.resultados {
+        let parametro = item.nome_parametro.clone().unwrap_or_else(|| "Parâmetro".to_string());
+
+        if let Some(data) = item.data_inicio.as_deref() {
+            let mut evento = evento_simples(
+                TipoEventoAmostra::InicioAnalise,
+                format!("Início da análise de {}", parametro),
+                parse_data_hora(data, item.hora_inicio.as_deref()),
+            );
+            evento.usuario = item.analista.clone();
+            evento.id_resultado = Some(item.id);
+            evento.parametro = item.nome_parametro.clone();
+            eventos.push(evento);
+        }
+        if let Some(data) = item.data_termino.as_deref() {
+            let mut evento = evento_simples(
+                TipoEventoAmostra::TerminoAnalise,
+                format!("Término da análise de {}", parametro),
+                parse_data_hora(data, item.hora_termino.as_deref()),
+            );
+            evento.usuario = item.analista.clone();
+            evento.valor_novo = item.resultado.clone();
+            evento.id_resultado = Some(item.id);
+            evento.parametro = item.nome_parametro.clone();
+            eventos.push(evento);
+        }
+    }
+
+    eventos
+}
+
+fn evento_de_auditoria(registro: EventoAuditoriaFromApi) -> EventoLinhaDoTempo {
+    let tipo = TipoEventoAmostra::from_acao(&registro.acao);
+    EventoLinhaDoTempo {
+        tipo,
+        descricao: registro.descricao.unwrap_or_else(|| tipo.descricao().to_string()),
+        usuario: registro.usuario,
+        ip: registro.ip,
+        computador: registro.computador,
+        data_hora: registro.data_hora.as_deref().and_then(|d| parse_data_hora(d, None)),
+        valor_anterior: registro.valor_anterior,
+        valor_novo: registro.valor_novo,
+        id_resultado: registro.id_resultado,
+        parametro: registro.parametro,
+    }
+}
+
+fn evento_de_bloqueio(historico: HistoricoBloqueio) -> EventoLinhaDoTempo {
+    let tipo = if historico.bloqueado {
+        TipoEventoAmostra::Bloqueio
+    } else {
+        TipoEventoAmostra::Desbloqueio
+    };
+    EventoLinhaDoTempo {
+        tipo,
+        descricao: format!("Amostra {}", if historico.bloqueado { "bloqueada" } else { "desbloqueada" }),
+        usuario: historico.usuario,
+        ip: historico.ip,
+        computador: historico.computador,
+        data_hora: historico.data_hora.as_deref().and_then(|d| parse_data_hora(d, None)),
+        valor_anterior: Some((!historico.bloqueado).to_string()),
+        valor_novo: Some(historico.bloqueado.to_string()),
+        id_resultado: None,
+        parametro: None,
+    }
+}
+
+fn evento_de_checagem(grupo: &GrupoChecagem) -> Option<EventoLinhaDoTempo> {
+    let data = grupo.data_checagem.as_deref()?;
+    let mut evento = evento_simples(
+        TipoEventoAmostra::Checagem,
+        format!("Checagem do grupo {} (amostras {} a {})", grupo.id_grupo, grupo.amostra_min, grupo.amostra_max),
+        parse_data_hora(data, None),
+    );
+    evento.usuario = grupo.usuario_checagem.clone();
+    Some(evento)
+}
+
+/// Ordena cronologicamente; eventos sem data vão para o fim mantendo a ordem de chegada
+fn ordenar_eventos(eventos: &mut [EventoLinhaDoTempo]) {
+    eventos.sort_by(|a, b| match (a.data_hora, b.data_hora) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
+fn resolver_destino(caminho: Option<String>, linha: &LinhaDoTempoAmostra, extensao: &str) -> Result<PathBuf, String> {
+    let identificador = linha
+        .numero
+        .clone()
+        .unwrap_or_else(|| linha.id_analise.to_string());
+    destino_exportacao(caminho, &format!("linha_do_tempo_{}.{}", identificador, extensao))
+}
+
+fn gerar_csv(linha: &LinhaDoTempoAmostra) -> String {
+    let mut csv = String::from("Data/Hora;Evento;Descrição;Parâmetro;Usuário;IP;Computador;Valor anterior;Valor novo\n");
+
+    for e in &linha.eventos {
+        let data = e
+            .data_hora
+            .map(|d| d.format("%d/%m/%Y %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let campos = [
+            data.as_str(),
+            e.tipo.descricao(),
+            e.descricao.as_str(),
+            e.parametro.as_deref().unwrap_or(""),
+            e.usuario.as_deref().unwrap_or(""),
+            e.ip.as_deref().unwrap_or(""),
+            e.computador.as_deref().unwrap_or(""),
+            e.valor_anterior.as_deref().unwrap_or(""),
+            e.valor_novo.as_deref().unwrap_or(""),
+        ];
+        csv.push_str(&campos.iter().map(|c| campo_csv(c)).collect::<Vec<_>>().join(";"));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn gerar_html(linha: &LinhaDoTempoAmostra) -> String {
+    let mut linhas = String::new();
+    for e in &linha.eventos {
+        let data = e
+            .data_hora
+            .map(|d| d.format("%d/%m/%Y %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let alteracao = match (&e.valor_anterior, &e.valor_novo) {
+            (Some(a), Some(n)) => format!("{} → {}", escapar_html(a), escapar_html(n)),
+            (None, Some(n)) => escapar_html(n),
+            _ => String::new(),
+        };
+        linhas.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            data,
+            e.tipo.descricao(),
+            escapar_html(&e.descricao),
+            escapar_html(e.parametro.as_deref().unwrap_or("")),
+            escapar_html(e.usuario.as_deref().unwrap_or("")),
+            escapar_html(&format!(
+                "{} {}",
+                e.ip.as_deref().unwrap_or(""),
+                e.computador.as_deref().unwrap_or("")
+            )),
+            alteracao,
+        ));
+    }
+
+    let introducao = format!(
+        "<p>Análise: {} &middot; Gerado em {}</p>{}",
+        linha.id_analise,
+        chrono::Local::now().format("%d/%m/%Y %H:%M"),
+        aviso_html("Fontes indisponíveis na geração", &linha.fontes_indisponiveis),
+    );
+    let titulo = format!(
+        "Cadeia de custódia - Amostra {} ({})",
+        linha.numero.as_deref().unwrap_or("-"),
+        linha.identificacao.as_deref().unwrap_or(""),
+    );
+    let tabela = tabela_html(
+        &["Data/Hora", "Evento", "Descrição", "Parâmetro", "Usuário", "IP/Computador", "Alteração"],
+        &linhas,
+    );
+    pagina_relatorio(&titulo, &introducao, &tabela)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(dia: u32, hora: u32) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDate::from_ymd_opt(2026, 3, dia).and_then(|d| d.and_hms_opt(hora, 0, 0))
+    }
+
+    #[test]
+    fn test_from_acao() {
+        assert_eq!(TipoEventoAmostra::from_acao(" Iniciar_Analise "), TipoEventoAmostra::InicioAnalise);
+        assert_eq!(TipoEventoAmostra::from_acao("alterar_pop"), TipoEventoAmostra::ResultadoAlterado);
+        assert_eq!(TipoEventoAmostra::from_acao("REMOVER_VISTO"), TipoEventoAmostra::VistoRemovido);
+        assert_eq!(TipoEventoAmostra::from_acao("desbloqueio"), TipoEventoAmostra::Desbloqueio);
+        assert_eq!(TipoEventoAmostra::from_acao("exportar"), TipoEventoAmostra::Outro);
+    }
+
+    #[test]
+    fn test_ordenar_eventos() {
+        let mut eventos = vec![
+            evento_simples(TipoEventoAmostra::Visto, "sem data 1".to_string(), None),
+            evento_simples(TipoEventoAmostra::TerminoAnalise, "termino".to_string(), data(3, 10)),
+            evento_simples(TipoEventoAmostra::Impressao, "sem data 2".to_string(), None),
+            evento_simples(TipoEventoAmostra::Coleta, "coleta".to_string(), data(1, 8)),
+        ];
+        ordenar_eventos(&mut eventos);
+        let ordem: Vec<&str> = eventos.iter().map(|e| e.descricao.as_str()).collect();
+        assert_eq!(ordem, vec!["coleta", "termino", "sem data 1", "sem data 2"]);
+    }
+
+    #[test]
+    fn test_gerar_csv() {
+        let mut evento = evento_simples(TipoEventoAmostra::ResultadoAlterado, "Resultado; corrigido".to_string(), data(2, 14));
+        evento.usuario = Some("Ana".to_string());
+        evento.valor_anterior = Some("1,5".to_string());
+        evento.valor_novo = Some("1,7".to_string());
+        let linha = LinhaDoTempoAmostra {
+            id_analise: 10,
+            numero: Some("2026001".to_string()),
+            identificacao: None,
+            eventos: vec![evento],
+            fontes_indisponiveis: Vec::new(),
+        };
+
+        let csv = gerar_csv(&linha);
+        let linhas: Vec<&str> = csv.lines().collect();
+        assert_eq!(linhas.len(), 2);
+        assert!(linhas[0].starts_with("Data/Hora;Evento;"));
+        assert_eq!(linhas[1], "02/03/2026 14:00:00;Resultado alterado;\"Resultado; corrigido\";;Ana;;;1,5;1,7");
+    }
+}
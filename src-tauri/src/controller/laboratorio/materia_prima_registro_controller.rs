@@ -230,20 +230,28 @@ pub async fn atualizar_obsoleto_materia_prima_registro_tauri(
 ) -> Result<ApiResponse<()>, ApiResponse<()>> {
     let client = Client::new();
     let api_url = get_api_url(&app_handle);
+
+    atualizar_obsoleto_materia_prima(&client, &api_url, id, obsoleto)
+        .await
+        .map(|_| ApiResponse::success("Status 'obsoleto' atualizado.".to_string(), None))
+        .map_err(ApiResponse::error)
+}
+
+/// Também usado pelo monitor de validade ao obsoletar lotes vencidos
+pub async fn atualizar_obsoleto_materia_prima(client: &Client, api_url: &str, id: u32, obsoleto: bool) -> Result<(), String> {
     let url = format!("{}/laboratorio/materia-prima-registros/{}/obsoleto", api_url, id);
-    
     let api_payload = AtualizacaoObsoletoPayload { obsoleto };
 
     match client.patch(&url).json(&api_payload).send().await {
         Ok(response) => {
             if response.status().is_success() {
-                Ok(ApiResponse::success("Status 'obsoleto' atualizado.".to_string(), None))
+                Ok(())
             } else {
                 let status = response.status();
                 let err_body = response.text().await.unwrap_or_default();
-                Err(ApiResponse::error(format!("API retornou erro ({}) {}", status, err_body)))
+                Err(format!("API retornou erro ({}) {}", status, err_body))
             }
         },
-        Err(e) => Err(ApiResponse::error(format!("Erro de conexão com a API: {}", e))),
+        Err(e) => Err(format!("Erro de conexão com a API: {}", e)),
     }
-}
\ No newline at end of file
+}
@@ -19,3 +19,12 @@ pub mod tauri_imprimir_controller;
 pub mod fila_trabalho_controller;
 pub mod mapa_resultado_controller;
 pub mod materia_prima_controller;
+pub mod linha_do_tempo_controller;
+pub mod faixa_numeracao_controller;
+pub mod etiqueta_controller;
+pub mod sessao_leitura_controller;
+pub mod prazo_validade_controller;
+pub mod preparo_insumo_controller;
+pub mod rastreabilidade_controller;
+pub mod validade_lote_controller;
+pub mod calibracao_controller;
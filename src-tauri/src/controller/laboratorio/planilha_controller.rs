@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize}; // <- Adicione aqui também
 use reqwest::Client;
-use tauri::command;
+use tauri::{command, AppHandle};
+use crate::config::get_api_url;
 use crate::model::usuario::get_user_id;
 
 
@@ -9,7 +10,7 @@ pub struct Amostra {
     pub numero: i32,
 }
 
-#[derive(Deserialize, Serialize, Debug)] // <- Aqui está o fix
+#[derive(Deserialize, Serialize, Debug, Clone)] // <- Aqui está o fix
 pub struct NumberRange {
     pub id: u32,
     pub inicial: u32,
@@ -34,10 +35,20 @@ pub struct NumerosEspecificosResponse {
 }
 
 #[command]
-pub async fn consultar_amostras_por_planilha( planilha_id: u32) -> Result<Vec<i32>, String> {
-    let url = format!("http://127.0.0.1:8082/planilha/{}/amostras", planilha_id);
+pub async fn consultar_amostras_por_planilha(app_handle: AppHandle, planilha_id: u32) -> Result<Vec<i32>, String> {
+    let client = Client::new();
+    buscar_amostras_planilha(&client, &get_api_url(&app_handle), planilha_id).await
+}
 
+#[command]
+pub async fn consultar_intervalos_planilhas(app_handle: AppHandle) -> Result<Vec<NumberRange>, String> {
     let client = Client::new();
+    buscar_intervalos_planilhas(&client, &get_api_url(&app_handle)).await
+}
+
+pub async fn buscar_amostras_planilha(client: &Client, api_url: &str, planilha_id: u32) -> Result<Vec<i32>, String> {
+    let url = format!("{}/planilha/{}/amostras", api_url, planilha_id);
+
     let response = client
         .get(&url)
         .send()
@@ -56,13 +67,11 @@ pub async fn consultar_amostras_por_planilha( planilha_id: u32) -> Result<Vec<i3
     Ok(amostras.into_iter().map(|a| a.numero).collect())
 }
 
-#[command]
-pub async fn consultar_intervalos_planilhas() -> Result<Vec<NumberRange>, String> {
-    let url = "http://127.0.0.1:8082/get_final_number";
+pub async fn buscar_intervalos_planilhas(client: &Client, api_url: &str) -> Result<Vec<NumberRange>, String> {
+    let url = format!("{}/get_final_number", api_url);
 
-    let client = Client::new();
     let response = client
-        .post(url)
+        .post(&url)
         .send()
         .await
         .map_err(|e| format!("Erro na requisição: {}", e))?;
@@ -88,13 +97,13 @@ pub struct NovaPlanilhaResponse {
 }
 
 #[command]
-pub async fn gerar_nova_planilha() -> Result<NovaPlanilhaResponse, String> {
-    let url = "http://127.0.0.1:8082/gerar_planilha";
+pub async fn gerar_nova_planilha(app_handle: AppHandle) -> Result<NovaPlanilhaResponse, String> {
+    let url = format!("{}/gerar_planilha", get_api_url(&app_handle));
 
 let user_id = get_user_id().ok_or("Usuário não autenticado")?;
     let client = Client::new();
     let response = client
-        .post(url)
+        .post(&url)
         .json(&serde_json::json!({ "user_id": user_id }))
         .send()
         .await
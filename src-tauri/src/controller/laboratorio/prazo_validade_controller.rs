@@ -0,0 +1,321 @@
+use std::time::Duration;
+use chrono::NaiveDateTime;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::monitor::Monitor;
+use crate::model::fila_trabalho::ItemFilaTrabalho;
+use crate::model::prazo_validade::{AlertaPrazo, RegraPrazoValidade, StatusPrazo};
+use crate::controller::laboratorio::fila_trabalho_controller::buscar_fila_completa;
+use crate::utils::{normalizar_texto, parse_data_hora};
+
+const API_RESOURCE: &str = "/laboratorio/prazos-validade";
+const EVENTO_ALERTA: &str = "alerta_prazo_amostra";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(300);
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn listar_regras_prazo(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<RegraPrazoValidade>>, ApiResponse<()>> {
+    let client = Client::new();
+    let regras = buscar_regras(&client, &get_api_url(&app_handle))
+        .await
+        .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Regras carregadas.".to_string(), Some(regras)))
+}
+
+#[command]
+pub async fn salvar_regra_prazo(
+    app_handle: AppHandle,
+    regra: RegraPrazoValidade,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    if regra.parametro.trim().is_empty() {
+        return Err(ApiResponse::error("Informe o parâmetro.".to_string()));
+    }
+    if regra.horas_maximas <= 0.0 {
+        return Err(ApiResponse::error("O prazo máximo deve ser maior que zero.".to_string()));
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let request = match regra.id {
+        Some(id) => client.put(format!("{}{}/{}", api_url, API_RESOURCE, id)),
+        None => client.post(format!("{}{}", api_url, API_RESOURCE)),
+    };
+
+    match request.json(&regra).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                Ok(ApiResponse::success("Regra salva com sucesso!".to_string(), None))
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                Err(ApiResponse::error(format!("Erro ao salvar ({}): {}", status, msg)))
+            }
+        }
+        Err(e) => Err(ApiResponse::error(format!("Erro de conexão: {}", e))),
+    }
+}
+
+/// Amostras aguardando análise que estão em alerta ou com prazo vencido
+#[command]
+pub async fn verificar_alertas_prazo(
+    app_handle: AppHandle,
+    id_laboratorio: Option<u32>,
+) -> Result<ApiResponse<Vec<AlertaPrazo>>, ApiResponse<()>> {
+    let alertas = coletar_alertas(&app_handle, id_laboratorio)
+        .await
+        .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(format!("{} amostra(s) com prazo crítico.", alertas.len()), Some(alertas)))
+}
+
+// ==================== MONITOR ====================
+
+/// Verifica periodicamente a fila e emite `alerta_prazo_amostra` quando uma análise entra em alerta ou vence
+pub async fn monitorar_prazos(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("prazos", EVENTO_ALERTA);
+
+    loop {
+        monitor.aguardar(INTERVALO_MONITOR).await;
+        match coletar_alertas(&app_handle, None).await {
+            Ok(alertas) => {
+                monitor.notificar(&app_handle, &alertas, |a| (a.analise_id, a.status));
+            }
+            Err(e) => monitor.falha(&e),
+        }
+    }
+}
+
+async fn coletar_alertas(app_handle: &AppHandle, id_laboratorio: Option<u32>) -> Result<Vec<AlertaPrazo>, String> {
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+    let regras = regras_ou_padrao(&client, &api_url).await;
+    let agora = chrono::Local::now().naive_local();
+
+    let mut itens = buscar_fila_completa(&client, &api_url, "aguardando", id_laboratorio).await?;
+    aplicar_prazos(&mut itens, &regras, agora);
+
+    let mut alertas: Vec<AlertaPrazo> = itens
+        .into_iter()
+        .filter_map(|item| match (item.status_prazo, &item.prazo_limite, item.prazo_restante) {
+            (Some(status @ (StatusPrazo::Alerta | StatusPrazo::Vencido)), Some(limite), Some(restante)) => Some(AlertaPrazo {
+                analise_id: item.analise_id,
+                amostra_numero: item.amostra_numero,
+                parametro_nome: item.parametro_nome,
+                status,
+                prazo_limite: limite.clone(),
+                prazo_restante: restante,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    alertas.sort_by_key(|a| (a.status, a.prazo_restante));
+    Ok(alertas)
+}
+
+// ==================== REGRAS ====================
+
+async fn buscar_regras(client: &Client, api_url: &str) -> Result<Vec<RegraPrazoValidade>, String> {
+    let url = format!("{}{}", api_url, API_RESOURCE);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let regras = response
+        .json::<Vec<RegraPrazoValidade>>()
+        .await
+        .map_err(|e| format!("Erro JSON: {}", e))?;
+
+    Ok(if regras.is_empty() { regras_padrao() } else { regras })
+}
+
+/// Usado pela fila de trabalho: sem regras da API, aplica as padrão
+pub async fn regras_ou_padrao(client: &Client, api_url: &str) -> Vec<RegraPrazoValidade> {
+    buscar_regras(client, api_url).await.unwrap_or_else(|e| {
+        println!("Regras de prazo indisponíveis, usando padrão: {}", e);
+        regras_padrao()
+    })
+}
+
+fn regras_padrao() -> Vec<RegraPrazoValidade> {
+    [
+        ("coliformes", 24.0),
+        ("escherichia coli", 24.0),
+        ("bacterias heterotroficas", 24.0),
+        ("dbo", 48.0),
+        ("demanda bioquimica de oxigenio", 48.0),
+        ("nitrito", 48.0),
+        ("nitrato", 48.0),
+        ("turbidez", 48.0),
+        ("ph", 0.25),
+        ("oxigenio dissolvido", 0.25),
+    ]
+    .into_iter()
+    .map(|(parametro, horas_maximas)| RegraPrazoValidade {
+        id: None,
+        parametro: parametro.to_string(),
+        matriz: None,
+        horas_maximas,
+        horas_alerta: None,
+    })
+    .collect()
+}
+
+/// Compara por palavras inteiras para "ph" não casar com "fosfato" etc.
+fn contem_termo(texto: &str, termo: &str) -> bool {
+    let palavras = |s: &str| {
+        normalizar_texto(s)
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let termo = palavras(termo);
+    !termo.is_empty() && format!(" {} ", palavras(texto)).contains(&format!(" {} ", termo))
+}
+
+/// Regras específicas da matriz têm prioridade; depois, o trecho de parâmetro mais longo
+fn regra_para<'a>(item: &ItemFilaTrabalho, regras: &'a [RegraPrazoValidade]) -> Option<&'a RegraPrazoValidade> {
+    let parametro = item.parametro_nome.as_deref()?;
+
+    regras
+        .iter()
+        .filter(|r| contem_termo(parametro, &r.parametro))
+        .filter(|r| match (&r.matriz, &item.matriz) {
+            (None, _) => true,
+            (Some(regra), Some(matriz)) => contem_termo(matriz, regra),
+            (Some(_), None) => false,
+        })
+        .max_by_key(|r| (r.matriz.is_some(), r.parametro.len()))
+}
+
+// ==================== CÁLCULO ====================
+
+/// Preenche `prazo_limite`, `prazo_restante` e `status_prazo`.
+/// O prazo conta da coleta (ou da entrada no laboratório); coleta sem hora conta da 00:00.
+/// Análises já iniciadas são avaliadas pela data de início.
+pub fn aplicar_prazos(itens: &mut [ItemFilaTrabalho], regras: &[RegraPrazoValidade], agora: NaiveDateTime) {
+    for item in itens.iter_mut() {
+        item.prazo_limite = None;
+        item.prazo_restante = None;
+        item.status_prazo = Some(StatusPrazo::SemRegra);
+
+        let referencia = item
+            .data_coleta
+            .as_deref()
+            .and_then(|d| parse_data_hora(d, item.hora_coleta.as_deref()))
+            .or_else(|| item.data_lab.as_deref().and_then(|d| parse_data_hora(d, item.hora_lab.as_deref())));
+
+        let (Some(referencia), Some(regra)) = (referencia, regra_para(item, regras)) else {
+            continue;
+        };
+
+        let limite = referencia + chrono::Duration::minutes((regra.horas_maximas * 60.0).round() as i64);
+        let inicio = item.data_inicio.as_deref().and_then(|d| parse_data_hora(d, None));
+        let restante = (limite - inicio.unwrap_or(agora)).num_minutes();
+        let antecedencia = (regra.horas_alerta.unwrap_or(regra.horas_maximas * 0.25) * 60.0).round() as i64;
+
+        let status = if restante < 0 {
+            StatusPrazo::Vencido
+        } else if inicio.is_none() && restante <= antecedencia {
+            StatusPrazo::Alerta
+        } else {
+            StatusPrazo::NoPrazo
+        };
+
+        item.prazo_limite = Some(limite.format("%Y-%m-%d %H:%M").to_string());
+        item.prazo_restante = Some(restante);
+        item.status_prazo = Some(status);
+    }
+}
+
+/// Vencidos primeiro, depois em alerta, no prazo e sem regra; dentro de cada grupo, menor prazo restante
+pub fn ordenar_por_urgencia(itens: &mut [ItemFilaTrabalho]) {
+    itens.sort_by_key(|i| (i.status_prazo.unwrap_or(StatusPrazo::SemRegra), i.prazo_restante.unwrap_or(i64::MAX)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(analise_id: u32, parametro: &str, data_coleta: &str, hora_coleta: Option<&str>) -> ItemFilaTrabalho {
+        serde_json::from_value(serde_json::json!({
+            "analise_id": analise_id,
+            "amostra_numero": null,
+            "identificacao": null,
+            "complemento": null,
+            "parametro_nome": parametro,
+            "pop_codigo": null,
+            "pop_numero": null,
+            "pop_revisao": null,
+            "tecnica_nome": null,
+            "data_coleta": data_coleta,
+            "data_lab": null,
+            "hora_lab": null,
+            "em_campo": null,
+            "data_inicio": null,
+            "usuario_visto": null,
+            "hora_coleta": hora_coleta,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_aplicar_prazos_e_ordenar() {
+        let agora = parse_data_hora("2024-03-06", Some("08:00")).unwrap();
+        let mut itens = vec![
+            item(1, "Fósforo Total", "2024-03-05", Some("10:00")),
+            item(2, "DBO 5 dias", "2024-03-05", Some("10:00")),
+            item(3, "Coliformes Totais", "2024-03-05", Some("10:00")),
+            item(4, "pH", "2024-03-06", Some("07:47")),
+        ];
+
+        aplicar_prazos(&mut itens, &regras_padrao(), agora);
+
+        let status: Vec<_> = itens.iter().map(|i| (i.status_prazo.unwrap(), i.prazo_restante)).collect();
+        assert_eq!(
+            status,
+            vec![
+                (StatusPrazo::SemRegra, None),
+                (StatusPrazo::NoPrazo, Some(26 * 60)),
+                (StatusPrazo::Alerta, Some(120)),
+                (StatusPrazo::Alerta, Some(2)),
+            ]
+        );
+        assert_eq!(itens[2].prazo_limite.as_deref(), Some("2024-03-06 10:00"));
+
+        ordenar_por_urgencia(&mut itens);
+        assert_eq!(itens.iter().map(|i| i.analise_id).collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_regra_especifica_da_matriz() {
+        let regras = vec![
+            RegraPrazoValidade { id: None, parametro: "coliformes".into(), matriz: None, horas_maximas: 24.0, horas_alerta: None },
+            RegraPrazoValidade { id: None, parametro: "coliformes".into(), matriz: Some("Efluente".into()), horas_maximas: 8.0, horas_alerta: None },
+        ];
+        let mut com_matriz = item(1, "Coliformes Totais", "2024-03-05", Some("10:00"));
+        com_matriz.matriz = Some("Efluente Tratado".into());
+        let sem_matriz = item(2, "Coliformes Totais", "2024-03-05", Some("10:00"));
+
+        assert_eq!(regra_para(&com_matriz, &regras).unwrap().horas_maximas, 8.0);
+        assert_eq!(regra_para(&sem_matriz, &regras).unwrap().horas_maximas, 24.0);
+    }
+}
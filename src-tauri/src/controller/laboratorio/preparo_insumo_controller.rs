@@ -0,0 +1,325 @@
+// src-tauri/src/controller/laboratorio/preparo_insumo_controller.rs
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use reqwest::Client;
+use tauri::{command, AppHandle};
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::insumo_registro::{ReceitaEstoqueItem, RegistroInsumoFrontendPayload, RegistroMateriaPrimaPayload};
+use crate::model::materia_prima_registro::MateriaPrimaRegistroDetalhado;
+use crate::model::preparo_insumo::{ItemPreparo, LotePreparo, PlanejarPreparoPayload, PlanoPreparo};
+use crate::utils::parse_decimal;
+
+const CASAS_DECIMAIS: u32 = 4;
+
+/// Escala a receita do insumo para o volume desejado, corrige pela pureza dos lotes
+/// (FEFO) e devolve o registro de preparo pronto para cadastro
+#[command]
+pub async fn planejar_preparo_insumo_tauri(
+    app_handle: AppHandle,
+    payload: PlanejarPreparoPayload,
+) -> Result<ApiResponse<PlanoPreparo>, ApiResponse<()>> {
+    let volume_receita: Decimal = parse_decimal(&payload.volume_receita).map_err(ApiResponse::error)?;
+    let volume_alvo: Decimal = parse_decimal(&payload.volume_alvo).map_err(ApiResponse::error)?;
+    if volume_receita <= Decimal::ZERO || volume_alvo <= Decimal::ZERO {
+        return Err(ApiResponse::error("Os volumes devem ser maiores que zero.".to_string()));
+    }
+    let fator_correcao = match payload.fator_correcao.as_deref().filter(|f| !f.trim().is_empty()) {
+        Some(f) => parse_decimal(f).map_err(ApiResponse::error)?,
+        None => Decimal::ONE,
+    };
+    if fator_correcao <= Decimal::ZERO {
+        return Err(ApiResponse::error("O fator de correção deve ser maior que zero.".to_string()));
+    }
+    let data_preparo = NaiveDate::parse_from_str(payload.data_preparo.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiResponse::error(format!("Data de preparo inválida: {}", payload.data_preparo)))?;
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+
+    let receita = buscar_receita(&client, &api_url, payload.insumo_id)
+        .await
+        .map_err(ApiResponse::error)?;
+    if receita.is_empty() {
+        return Err(ApiResponse::error("O insumo não possui receita cadastrada.".to_string()));
+    }
+
+    // A listagem de estoque não traz a pureza; consulta cada lote
+    let mut avisos = Vec::new();
+    let mut purezas: HashMap<u32, Decimal> = HashMap::new();
+    for lote in receita.iter().flat_map(|r| &r.estoque_disponivel) {
+        match buscar_lote(&client, &api_url, lote.id).await {
+            Ok(detalhe) => {
+                if let Some(pureza) = detalhe.pureza.filter(|p| *p > Decimal::ZERO) {
+                    purezas.insert(lote.id, pureza);
+                }
+            }
+            Err(e) => avisos.push(format!("Pureza do lote {} indisponível, considerada 100% ({}).", lote.id, e)),
+        }
+    }
+
+    let fator_escala = volume_alvo / volume_receita;
+    let (itens, avisos_plano) = planejar(&receita, &purezas, fator_escala * fator_correcao, data_preparo);
+    avisos.extend(avisos_plano);
+    let suficiente = itens.iter().all(|i| i.faltante.is_zero());
+
+    let validade_lotes = itens
+        .iter()
+        .flat_map(|i| &i.lotes)
+        .filter_map(|l| l.validade.as_deref().and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()))
+        .min();
+    let validade_preparo = payload
+        .validade_dias
+        .and_then(|d| data_preparo.checked_add_days(chrono::Days::new(d as u64)));
+    let validade = match (validade_preparo, validade_lotes) {
+        (Some(p), Some(l)) if l < p => {
+            avisos.push(format!("Validade limitada pelo lote de matéria-prima que vence em {}.", l.format("%d/%m/%Y")));
+            Some(l)
+        }
+        (Some(p), _) => Some(p),
+        (None, l) => l,
+    };
+
+    let registro = RegistroInsumoFrontendPayload {
+        insumo_id: payload.insumo_id,
+        registro: payload.registro,
+        fabricante: None,
+        volume: Some(volume_alvo.normalize().to_string()),
+        data_preparo: Some(data_preparo.format("%Y-%m-%d").to_string()),
+        validade: validade.map(|v| v.format("%Y-%m-%d").to_string()),
+        quantidade: Some(volume_alvo.normalize().to_string()),
+        fator_correcao: Some(fator_correcao.normalize().to_string()),
+        nota_fiscal: None,
+        garantia: None,
+        garantia_tempo: None,
+        fornecedor_id: None,
+        data_compra: None,
+        valor_equipamento: None,
+        modelo: None,
+        numero_serie: None,
+        observacao: None,
+        faixa_min: None,
+        faixa_max: None,
+        desvios: None,
+        fora_de_uso: false,
+        portatil: false,
+        materias_primas: itens
+            .iter()
+            .flat_map(|i| &i.lotes)
+            .map(|l| RegistroMateriaPrimaPayload {
+                materia_prima_registro_id: l.materia_prima_registro_id,
+                quantidade: l.quantidade.to_string(),
+            })
+            .collect(),
+    };
+
+    let mensagem = if suficiente {
+        "Preparo planejado.".to_string()
+    } else {
+        "Estoque insuficiente para o volume solicitado.".to_string()
+    };
+
+    Ok(ApiResponse::success(
+        mensagem,
+        Some(PlanoPreparo {
+            insumo_id: payload.insumo_id,
+            fator_escala: fator_escala.round_dp(CASAS_DECIMAIS),
+            itens,
+            suficiente,
+            avisos,
+            registro,
+        }),
+    ))
+}
+
+async fn buscar_receita(client: &Client, api_url: &str, insumo_id: u32) -> Result<Vec<ReceitaEstoqueItem>, String> {
+    let url = format!("{}/laboratorio/suporte/receita-e-estoque/{}", api_url, insumo_id);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let err_body = response.text().await.unwrap_or_default();
+        return Err(format!("API retornou erro ({}) {}", status, err_body));
+    }
+
+    response
+        .json::<Vec<ReceitaEstoqueItem>>()
+        .await
+        .map_err(|e| format!("Erro no JSON: {}", e))
+}
+
+async fn buscar_lote(client: &Client, api_url: &str, id: u32) -> Result<MateriaPrimaRegistroDetalhado, String> {
+    let url = format!("{}/laboratorio/materia-prima-registros/{}", api_url, id);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Erro de conexão: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("API retornou erro ({})", response.status()));
+    }
+
+    response
+        .json::<MateriaPrimaRegistroDetalhado>()
+        .await
+        .map_err(|e| format!("Erro no JSON: {}", e))
+}
+
+/// Converte os `BigDecimal` da API de insumos para `Decimal`
+fn para_decimal(valor: &bigdecimal::BigDecimal) -> Decimal {
+    Decimal::from_str(&valor.to_string())
+        .or_else(|_| Decimal::from_scientific(&valor.to_string()))
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// Distribui cada matéria-prima entre os lotes por FEFO (vencimento mais próximo primeiro).
+/// `multiplicador` já combina a escala de volume e o fator de correção.
+fn planejar(
+    receita: &[ReceitaEstoqueItem],
+    purezas: &HashMap<u32, Decimal>,
+    multiplicador: Decimal,
+    data_preparo: NaiveDate,
+) -> (Vec<ItemPreparo>, Vec<String>) {
+    let cem = Decimal::ONE_HUNDRED;
+    let mut avisos = Vec::new();
+    let mut itens = Vec::with_capacity(receita.len());
+
+    for item in receita {
+        let mp = &item.mp_requerida;
+        let quantidade_receita = para_decimal(&mp.quantidade);
+        let quantidade_pura = (quantidade_receita * multiplicador).round_dp(CASAS_DECIMAIS);
+
+        let mut lotes: Vec<_> = item
+            .estoque_disponivel
+            .iter()
+            .map(|l| (l, l.validade.as_deref().and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())))
+            .collect();
+        lotes.sort_by_key(|(l, validade)| (validade.is_none(), *validade, l.id));
+
+        let mut pendente = quantidade_pura;
+        let mut usados = Vec::new();
+        for (lote, validade) in lotes {
+            if pendente <= Decimal::ZERO {
+                break;
+            }
+            if validade.is_some_and(|v| v < data_preparo) {
+                avisos.push(format!("Lote {} de {} vencido, ignorado.", lote.lote_fabricante.as_deref().unwrap_or("s/n"), mp.nome));
+                continue;
+            }
+
+            let restante = para_decimal(&lote.quant_restante);
+            if restante <= Decimal::ZERO {
+                continue;
+            }
+            let pureza = purezas.get(&lote.id).copied().unwrap_or(cem);
+            let disponivel_puro = restante * pureza / cem;
+            let puro = pendente.min(disponivel_puro);
+            let quantidade = if puro == disponivel_puro {
+                restante
+            } else {
+                (puro * cem / pureza).round_dp(CASAS_DECIMAIS)
+            };
+
+            pendente -= puro;
+            usados.push(LotePreparo {
+                materia_prima_registro_id: lote.id,
+                lote_fabricante: lote.lote_fabricante.clone(),
+                validade: lote.validade.clone(),
+                pureza,
+                quantidade,
+                quant_restante: restante,
+            });
+        }
+
+        let faltante = pendente.max(Decimal::ZERO).round_dp(CASAS_DECIMAIS);
+        if !faltante.is_zero() {
+            avisos.push(format!(
+                "Faltam {} {} de {}.",
+                faltante.normalize(),
+                mp.unidade.as_deref().unwrap_or(""),
+                mp.nome
+            ));
+        }
+
+        itens.push(ItemPreparo {
+            materia_prima_id: mp.id,
+            nome: mp.nome.clone(),
+            unidade: mp.unidade.clone(),
+            quantidade_receita,
+            quantidade_pura,
+            quantidade_total: usados.iter().map(|l| l.quantidade).sum(),
+            faltante,
+            lotes: usados,
+        });
+    }
+
+    (itens, avisos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::insumo_registro::{MateriaPrimaRegistroDisponivel, MateriaPrimaRequerida};
+
+    fn dec(v: &str) -> Decimal {
+        Decimal::from_str(v).unwrap()
+    }
+
+    fn big(v: &str) -> bigdecimal::BigDecimal {
+        bigdecimal::BigDecimal::from_str(v).unwrap()
+    }
+
+    fn lote(id: u32, validade: &str, restante: &str) -> MateriaPrimaRegistroDisponivel {
+        MateriaPrimaRegistroDisponivel {
+            id,
+            fabricante: None,
+            lote_fabricante: Some(format!("L{}", id)),
+            validade: Some(validade.to_string()),
+            quant_restante: big(restante),
+        }
+    }
+
+    #[test]
+    fn test_planejar_fefo_e_pureza() {
+        let receita = vec![ReceitaEstoqueItem {
+            mp_requerida: MateriaPrimaRequerida {
+                id: 10,
+                nome: "Cloreto de sódio".to_string(),
+                unidade: Some("g".to_string()),
+                quantidade: big("10"),
+            },
+            estoque_disponivel: vec![
+                lote(1, "2026-01-01", "100"),
+                lote(2, "2025-06-01", "5"),
+                lote(3, "2024-01-01", "50"),
+            ],
+        }];
+        let purezas: HashMap<u32, Decimal> = [(2, dec("50"))].into_iter().collect();
+        let data_preparo = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+
+        // 500 mL a partir de uma receita de 1000 mL => 5 g puros
+        let (itens, avisos) = planejar(&receita, &purezas, dec("0.5"), data_preparo);
+        let item = &itens[0];
+
+        assert_eq!(item.quantidade_pura, dec("5"));
+        // Lote 3 vencido; lote 2 (50%) fornece 2,5 g puros com 5 g; lote 1 completa 2,5 g
+        let usados: Vec<(u32, Decimal)> = item.lotes.iter().map(|l| (l.materia_prima_registro_id, l.quantidade)).collect();
+        assert_eq!(usados, vec![(2, dec("5")), (1, dec("2.5"))]);
+        assert_eq!(item.quantidade_total, dec("7.5"));
+        assert!(item.faltante.is_zero());
+        assert_eq!(avisos.len(), 1);
+
+        let (itens, _) = planejar(&receita, &purezas, dec("20"), data_preparo);
+        assert_eq!(itens[0].faltante, dec("97.5"));
+    }
+}
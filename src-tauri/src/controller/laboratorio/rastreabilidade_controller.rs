@@ -0,0 +1,485 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::rastreabilidade::{
+    ArestaRastreio, DirecaoRastreio, ExportacaoRastreio, GrafoRastreio, InsumoRegistroVinculado,
+    MateriaPrimaVinculada, NoRastreio, ResultadoVinculado, TipoNoRastreio,
+};
+use crate::exportacao::{aviso_html, campo_csv, destino_exportacao, escapar_html, pagina_relatorio, salvar_csv, salvar_pdf, tabela_html};
+use crate::utils::buscar_json;
+
+const API_RESOURCE: &str = "/laboratorio/rastreabilidade";
+
+/// Impacto (recall): a partir de um lote de matéria-prima ou registro de insumo, lista os resultados afetados
+#[command]
+pub async fn rastrear_impacto_lote(
+    app_handle: AppHandle,
+    tipo: TipoNoRastreio,
+    id: u32,
+) -> Result<ApiResponse<GrafoRastreio>, ApiResponse<()>> {
+    if tipo == TipoNoRastreio::Resultado {
+        return Err(ApiResponse::error("O impacto parte de um lote de matéria-prima ou de insumo.".to_string()));
+    }
+
+    let grafo = montar_grafo(&app_handle, DirecaoRastreio::Impacto, tipo, id).await;
+    let total = grafo.nos.iter().filter(|n| n.tipo == TipoNoRastreio::Resultado).count();
+    Ok(ApiResponse::success(format!("{} resultado(s) afetado(s).", total), Some(grafo)))
+}
+
+/// Procedência: a partir de um resultado ou registro de insumo, lista os lotes utilizados
+#[command]
+pub async fn rastrear_procedencia(
+    app_handle: AppHandle,
+    tipo: TipoNoRastreio,
+    id: u32,
+) -> Result<ApiResponse<GrafoRastreio>, ApiResponse<()>> {
+    if tipo == TipoNoRastreio::MateriaPrimaRegistro {
+        return Err(ApiResponse::error("A procedência parte de um resultado ou de um registro de insumo.".to_string()));
+    }
+
+    let grafo = montar_grafo(&app_handle, DirecaoRastreio::Procedencia, tipo, id).await;
+    let total = grafo.nos.iter().filter(|n| n.tipo == TipoNoRastreio::MateriaPrimaRegistro).count();
+    Ok(ApiResponse::success(format!("{} lote(s) de matéria-prima na origem.", total), Some(grafo)))
+}
+
+#[command]
+pub async fn exportar_rastreabilidade_csv(
+    grafo: GrafoRastreio,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoRastreio>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, &grafo, "csv").map_err(ApiResponse::error)?;
+    salvar_csv(&destino, &gerar_csv(&grafo)).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Rastreabilidade exportada.".to_string(),
+        Some(ExportacaoRastreio {
+            caminho: destino.to_string_lossy().to_string(),
+            total_nos: grafo.nos.len(),
+        }),
+    ))
+}
+
+#[command]
+pub async fn exportar_rastreabilidade_pdf(
+    grafo: GrafoRastreio,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoRastreio>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, &grafo, "pdf").map_err(ApiResponse::error)?;
+    salvar_pdf(gerar_html(&grafo), &destino).await.map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Rastreabilidade exportada.".to_string(),
+        Some(ExportacaoRastreio {
+            caminho: destino.to_string_lossy().to_string(),
+            total_nos: grafo.nos.len(),
+        }),
+    ))
+}
+
+// --- Montagem do grafo ---
+
+/// Acumula nós e arestas sem duplicar (um lote pode ser alcançado por vários caminhos)
+struct MontadorGrafo {
+    nos: Vec<NoRastreio>,
+    arestas: Vec<ArestaRastreio>,
+    chaves: HashSet<String>,
+}
+
+impl MontadorGrafo {
+    fn new() -> Self {
+        MontadorGrafo { nos: Vec::new(), arestas: Vec::new(), chaves: HashSet::new() }
+    }
+
+    /// Retorna `true` se o nó é novo (e deve ser expandido)
+    fn adicionar_no(&mut self, no: NoRastreio) -> bool {
+        if !self.chaves.insert(no.chave.clone()) {
+            return false;
+        }
+        self.nos.push(no);
+        true
+    }
+
+    fn adicionar_aresta(&mut self, aresta: ArestaRastreio) {
+        if !self.arestas.iter().any(|a| a.origem == aresta.origem && a.destino == aresta.destino) {
+            self.arestas.push(aresta);
+        }
+    }
+}
+
+fn chave(tipo: TipoNoRastreio, id: u32) -> String {
+    format!("{}-{}", tipo.prefixo(), id)
+}
+
+fn no_insumo(v: &InsumoRegistroVinculado, nivel: u32) -> NoRastreio {
+    NoRastreio {
+        chave: chave(TipoNoRastreio::InsumoRegistro, v.insumo_registro_id),
+        tipo: TipoNoRastreio::InsumoRegistro,
+        id: v.insumo_registro_id,
+        rotulo: v.insumo_nome.clone().unwrap_or_else(|| format!("Insumo {}", v.insumo_id)),
+        detalhe: v.registro.as_ref().map(|r| format!("Registro {}", r)),
+        validade: v.validade.clone(),
+        nivel,
+    }
+}
+
+fn no_materia_prima(v: &MateriaPrimaVinculada, nivel: u32) -> NoRastreio {
+    NoRastreio {
+        chave: chave(TipoNoRastreio::MateriaPrimaRegistro, v.materia_prima_registro_id),
+        tipo: TipoNoRastreio::MateriaPrimaRegistro,
+        id: v.materia_prima_registro_id,
+        rotulo: v.nome_materia_prima.clone().unwrap_or_else(|| format!("Matéria-prima {}", v.materia_prima_registro_id)),
+        detalhe: v.lote_fabricante.as_ref().map(|l| format!("Lote {}", l)),
+        validade: v.validade.clone(),
+        nivel,
+    }
+}
+
+fn no_resultado(v: &ResultadoVinculado, nivel: u32) -> NoRastreio {
+    NoRastreio {
+        chave: chave(TipoNoRastreio::Resultado, v.id_resultado),
+        tipo: TipoNoRastreio::Resultado,
+        id: v.id_resultado,
+        rotulo: format!(
+            "Amostra {} - {}",
+            v.numero_amostra.as_deref().unwrap_or("?"),
+            v.parametro.as_deref().unwrap_or("")
+        ),
+        detalhe: Some(format!(
+            "Análise {} · Resultado: {}",
+            v.id_analise,
+            v.resultado.as_deref().unwrap_or("-")
+        )),
+        validade: None,
+        nivel,
+    }
+}
+
+/// Ligações de um nó, conforme a direção do rastreio
+enum Vinculos {
+    Insumos(Vec<InsumoRegistroVinculado>),
+    Resultados(Vec<ResultadoVinculado>),
+    MateriasPrimas(Vec<MateriaPrimaVinculada>),
+}
+
+/// Impacto: matéria-prima -> insumo -> resultado. Procedência: resultado -> insumo -> matéria-prima.
+/// O grafo tem no máximo dois níveis abaixo da raiz; resultados e matérias-primas são folhas.
+async fn buscar_vinculos(
+    client: &Client,
+    base: &str,
+    direcao: DirecaoRastreio,
+    tipo: TipoNoRastreio,
+    id: u32,
+) -> Option<Result<Vinculos, String>> {
+    let resultado = match (direcao, tipo) {
+        (DirecaoRastreio::Impacto, TipoNoRastreio::MateriaPrimaRegistro) => {
+            buscar_json(client, &format!("{}/materia-prima/{}/insumos", base, id)).await.map(Vinculos::Insumos)
+        }
+        (DirecaoRastreio::Impacto, TipoNoRastreio::InsumoRegistro) => {
+            buscar_json(client, &format!("{}/insumo-registro/{}/resultados", base, id)).await.map(Vinculos::Resultados)
+        }
+        (DirecaoRastreio::Procedencia, TipoNoRastreio::Resultado) => {
+            buscar_json(client, &format!("{}/resultado/{}/insumos", base, id)).await.map(Vinculos::Insumos)
+        }
+        (DirecaoRastreio::Procedencia, TipoNoRastreio::InsumoRegistro) => {
+            buscar_json(client, &format!("{}/insumo-registro/{}/materias-primas", base, id)).await.map(Vinculos::MateriasPrimas)
+        }
+        _ => return None,
+    };
+    Some(resultado)
+}
+
+/// Acrescenta os vínculos de `origem` ao grafo; devolve os nós novos a expandir.
+/// As arestas seguem o fluxo de material, da matéria-prima para o resultado.
+fn registrar_vinculos(
+    montador: &mut MontadorGrafo,
+    direcao: DirecaoRastreio,
+    origem: &str,
+    nivel: u32,
+    vinculos: Vinculos,
+) -> Vec<(TipoNoRastreio, u32)> {
+    let mut expandir = Vec::new();
+    let mut ligar = |montador: &mut MontadorGrafo, no: NoRastreio, quantidade: Option<String>| {
+        let (de, para) = match direcao {
+            DirecaoRastreio::Impacto => (origem.to_string(), no.chave.clone()),
+            DirecaoRastreio::Procedencia => (no.chave.clone(), origem.to_string()),
+        };
+        montador.adicionar_aresta(ArestaRastreio { origem: de, destino: para, quantidade });
+        let (tipo, id) = (no.tipo, no.id);
+        if montador.adicionar_no(no) {
+            expandir.push((tipo, id));
+        }
+    };
+
+    match vinculos {
+        Vinculos::Insumos(lista) => {
+            for v in lista {
+                // Na procedência a quantidade consumida fica na ligação insumo -> matéria-prima
+                let quantidade = if direcao == DirecaoRastreio::Impacto { v.quantidade.clone() } else { None };
+                ligar(montador, no_insumo(&v, nivel + 1), quantidade);
+            }
+        }
+        Vinculos::Resultados(lista) => {
+            for v in lista {
+                ligar(montador, no_resultado(&v, nivel + 1), None);
+            }
+        }
+        Vinculos::MateriasPrimas(lista) => {
+            for v in lista {
+                ligar(montador, no_materia_prima(&v, nivel + 1), v.quantidade.clone());
+            }
+        }
+    }
+    expandir
+}
+
+/// Busca em largura a partir da raiz; falhas pontuais vão para `fontes_indisponiveis`
+async fn montar_grafo(app_handle: &AppHandle, direcao: DirecaoRastreio, tipo: TipoNoRastreio, id: u32) -> GrafoRastreio {
+    let client = Client::new();
+    let base = format!("{}{}", get_api_url(app_handle), API_RESOURCE);
+
+    let raiz = chave(tipo, id);
+    let mut montador = MontadorGrafo::new();
+    let mut falhas = Vec::new();
+    montador.adicionar_no(NoRastreio {
+        chave: raiz.clone(),
+        tipo,
+        id,
+        rotulo: format!("{} {}", tipo.descricao(), id),
+        detalhe: None,
+        validade: None,
+        nivel: 0,
+    });
+
+    let mut fila = VecDeque::from([(tipo, id, 0u32)]);
+    while let Some((tipo, id, nivel)) = fila.pop_front() {
+        let origem = chave(tipo, id);
+        match buscar_vinculos(&client, &base, direcao, tipo, id).await {
+            Some(Ok(vinculos)) => {
+                for (tipo, id) in registrar_vinculos(&mut montador, direcao, &origem, nivel, vinculos) {
+                    fila.push_back((tipo, id, nivel + 1));
+                }
+            }
+            Some(Err(e)) => falhas.push(format!("{}: {}", origem, e)),
+            None => {}
+        }
+    }
+
+    GrafoRastreio {
+        direcao,
+        raiz,
+        nos: montador.nos,
+        arestas: montador.arestas,
+        fontes_indisponiveis: falhas,
+    }
+}
+
+// --- Exportação ---
+
+fn resolver_destino(caminho: Option<String>, grafo: &GrafoRastreio, extensao: &str) -> Result<PathBuf, String> {
+    let prefixo = match grafo.direcao {
+        DirecaoRastreio::Impacto => "impacto",
+        DirecaoRastreio::Procedencia => "procedencia",
+    };
+    destino_exportacao(caminho, &format!("rastreabilidade_{}_{}.{}", prefixo, grafo.raiz, extensao))
+}
+
+/// Uma linha por ligação (origem -> destino), no sentido do fluxo de material
+fn gerar_csv(grafo: &GrafoRastreio) -> String {
+    let mut csv = String::from("Origem (tipo);Origem;Origem (detalhe);Destino (tipo);Destino;Destino (detalhe);Quantidade\n");
+    let buscar = |chave: &str| grafo.nos.iter().find(|n| n.chave == chave);
+
+    for a in &grafo.arestas {
+        let (origem, destino) = (buscar(&a.origem), buscar(&a.destino));
+        let campos = [
+            origem.map(|n| n.tipo.descricao()).unwrap_or(""),
+            origem.map(|n| n.rotulo.as_str()).unwrap_or(a.origem.as_str()),
+            origem.and_then(|n| n.detalhe.as_deref()).unwrap_or(""),
+            destino.map(|n| n.tipo.descricao()).unwrap_or(""),
+            destino.map(|n| n.rotulo.as_str()).unwrap_or(a.destino.as_str()),
+            destino.and_then(|n| n.detalhe.as_deref()).unwrap_or(""),
+            a.quantidade.as_deref().unwrap_or(""),
+        ];
+        csv.push_str(&campos.iter().map(|c| campo_csv(c)).collect::<Vec<_>>().join(";"));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn gerar_html(grafo: &GrafoRastreio) -> String {
+    let mut nos: Vec<&NoRastreio> = grafo.nos.iter().collect();
+    nos.sort_by_key(|n| (n.nivel, n.chave.clone()));
+
+    let mut linhas = String::new();
+    for n in nos {
+        linhas.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            n.nivel,
+            n.tipo.descricao(),
+            escapar_html(&n.rotulo),
+            escapar_html(n.detalhe.as_deref().unwrap_or("")),
+            escapar_html(n.validade.as_deref().unwrap_or("")),
+        ));
+    }
+
+    let titulo = match grafo.direcao {
+        DirecaoRastreio::Impacto => "Impacto do lote",
+        DirecaoRastreio::Procedencia => "Procedência",
+    };
+    let introducao = format!(
+        "<p>{} nó(s), {} ligação(ões) &middot; Gerado em {}</p>{}",
+        grafo.nos.len(),
+        grafo.arestas.len(),
+        chrono::Local::now().format("%d/%m/%Y %H:%M"),
+        aviso_html("Fontes indisponíveis na geração", &grafo.fontes_indisponiveis),
+    );
+    pagina_relatorio(
+        &format!("Rastreabilidade - {} ({})", titulo, grafo.raiz),
+        &introducao,
+        &tabela_html(&["Nível", "Tipo", "Item", "Detalhe", "Validade"], &linhas),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_montador_sem_duplicados_e_csv() {
+        let insumo = InsumoRegistroVinculado {
+            insumo_registro_id: 5,
+            insumo_id: 2,
+            insumo_nome: Some("Solução; tampão".to_string()),
+            registro: Some("R-01".to_string()),
+            validade: None,
+            quantidade: Some("2,5".to_string()),
+        };
+
+        let mut montador = MontadorGrafo::new();
+        assert!(montador.adicionar_no(no_insumo(&insumo, 1)));
+        assert!(!montador.adicionar_no(no_insumo(&insumo, 2)));
+        for _ in 0..2 {
+            montador.adicionar_aresta(ArestaRastreio { origem: "MP-1".into(), destino: "IR-5".into(), quantidade: insumo.quantidade.clone() });
+        }
+
+        let grafo = GrafoRastreio {
+            direcao: DirecaoRastreio::Impacto,
+            raiz: "MP-1".into(),
+            nos: montador.nos,
+            arestas: montador.arestas,
+            fontes_indisponiveis: Vec::new(),
+        };
+        let csv = gerar_csv(&grafo);
+        let linhas: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(grafo.nos.len(), 1);
+        assert_eq!(linhas.len(), 2);
+        assert_eq!(linhas[1], ";MP-1;;Registro de insumo;\"Solução; tampão\";Registro R-01;2,5");
+    }
+
+    fn insumo(id: u32, quantidade: Option<&str>) -> InsumoRegistroVinculado {
+        InsumoRegistroVinculado {
+            insumo_registro_id: id,
+            insumo_id: 1,
+            insumo_nome: Some(format!("Insumo {}", id)),
+            registro: None,
+            validade: None,
+            quantidade: quantidade.map(str::to_string),
+        }
+    }
+
+    fn resultado(id: u32) -> ResultadoVinculado {
+        ResultadoVinculado {
+            id_resultado: id,
+            id_analise: id * 10,
+            numero_amostra: Some("2026001".to_string()),
+            parametro: Some("pH".to_string()),
+            resultado: Some("7,1".to_string()),
+            data_termino: None,
+        }
+    }
+
+    #[test]
+    fn test_registrar_vinculos_impacto() {
+        let mut montador = MontadorGrafo::new();
+        montador.adicionar_no(no_materia_prima(
+            &MateriaPrimaVinculada {
+                materia_prima_registro_id: 1,
+                nome_materia_prima: None,
+                lote_fabricante: None,
+                validade: None,
+                quantidade: None,
+            },
+            0,
+        ));
+
+        let expandir = registrar_vinculos(
+            &mut montador,
+            DirecaoRastreio::Impacto,
+            "MP-1",
+            0,
+            Vinculos::Insumos(vec![insumo(5, Some("2,5")), insumo(6, None)]),
+        );
+        assert_eq!(expandir, vec![(TipoNoRastreio::InsumoRegistro, 5), (TipoNoRastreio::InsumoRegistro, 6)]);
+
+        // Os dois insumos usaram a mesma amostra: o resultado entra uma vez, com duas ligações
+        registrar_vinculos(&mut montador, DirecaoRastreio::Impacto, "IR-5", 1, Vinculos::Resultados(vec![resultado(9)]));
+        let expandir = registrar_vinculos(&mut montador, DirecaoRastreio::Impacto, "IR-6", 1, Vinculos::Resultados(vec![resultado(9)]));
+        assert!(expandir.is_empty());
+
+        assert_eq!(montador.nos.iter().map(|n| (n.chave.as_str(), n.nivel)).collect::<Vec<_>>(), vec![("MP-1", 0), ("IR-5", 1), ("IR-6", 1), ("RS-9", 2)]);
+        let ligacoes: Vec<(&str, &str, Option<&str>)> = montador
+            .arestas
+            .iter()
+            .map(|a| (a.origem.as_str(), a.destino.as_str(), a.quantidade.as_deref()))
+            .collect();
+        assert_eq!(
+            ligacoes,
+            vec![("MP-1", "IR-5", Some("2,5")), ("MP-1", "IR-6", None), ("IR-5", "RS-9", None), ("IR-6", "RS-9", None)]
+        );
+    }
+
+    #[test]
+    fn test_registrar_vinculos_procedencia() {
+        let mut montador = MontadorGrafo::new();
+        let expandir = registrar_vinculos(
+            &mut montador,
+            DirecaoRastreio::Procedencia,
+            "RS-9",
+            0,
+            Vinculos::Insumos(vec![insumo(5, Some("2,5"))]),
+        );
+        assert_eq!(expandir, vec![(TipoNoRastreio::InsumoRegistro, 5)]);
+
+        let materia_prima = MateriaPrimaVinculada {
+            materia_prima_registro_id: 1,
+            nome_materia_prima: Some("Ácido <cítrico>".to_string()),
+            lote_fabricante: Some("L-7".to_string()),
+            validade: None,
+            quantidade: Some("10 g".to_string()),
+        };
+        registrar_vinculos(&mut montador, DirecaoRastreio::Procedencia, "IR-5", 1, Vinculos::MateriasPrimas(vec![materia_prima]));
+
+        // Mesmo na procedência a ligação segue o fluxo de material
+        let ligacoes: Vec<(&str, &str, Option<&str>)> = montador
+            .arestas
+            .iter()
+            .map(|a| (a.origem.as_str(), a.destino.as_str(), a.quantidade.as_deref()))
+            .collect();
+        assert_eq!(ligacoes, vec![("IR-5", "RS-9", None), ("MP-1", "IR-5", Some("10 g"))]);
+
+        let grafo = GrafoRastreio {
+            direcao: DirecaoRastreio::Procedencia,
+            raiz: "RS-9".into(),
+            nos: montador.nos,
+            arestas: montador.arestas,
+            fontes_indisponiveis: vec!["IR-6: Falha conexão".to_string()],
+        };
+        let html = gerar_html(&grafo);
+        assert!(html.contains("Ácido &lt;cítrico&gt;"));
+        assert!(html.contains("Fontes indisponíveis na geração: IR-6: Falha conexão"));
+    }
+}
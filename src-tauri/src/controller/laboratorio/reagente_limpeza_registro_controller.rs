@@ -2,6 +2,7 @@ use tauri::{command, AppHandle};
 use reqwest::Client;
 use crate::model::api_response::ApiResponse;
 use crate::config::get_api_url;
+use crate::controller::laboratorio::validade_lote_controller::verificar_lote_reagente;
 use serde::{Deserialize, Serialize};
 
 // --- Structs ---
@@ -117,6 +118,10 @@ pub async fn editar_registro_reagente_tauri(app_handle: AppHandle, id: u32, payl
 pub async fn registrar_uso_reagente_tauri(app_handle: AppHandle, id: u32, payload: UsoPayload) -> Result<ApiResponse<()>, ApiResponse<()>> {
     let client = Client::new();
     let url = format!("{}/laboratorio/reagentes-limpeza/registros/{}/uso", get_api_url(&app_handle), id);   
+    // Lote vencido não pode ter o uso iniciado
+    if payload.tipo_registro == 1 {
+        verificar_lote_reagente(&client, &get_api_url(&app_handle), id).await.map_err(ApiResponse::error)?;
+    }
     let api_payload = UsoApiPayload {
         usuario_id: payload.usuario_id, data: br_to_iso(&payload.data), tipo_registro: payload.tipo_registro,
     };
@@ -0,0 +1,566 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use lazy_static::lazy_static;
+use tauri::{command, AppHandle, Emitter};
+use reqwest::Client;
+use uuid::Uuid;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::usuario::get_user_id;
+use crate::model::etiqueta::CodigoLido;
+use crate::model::sessao_leitura::{
+    AbrirSessaoLeituraPayload, OperacaoLeitura, ResultadoLeitura, ResumoSessaoLeitura, StatusLeitura,
+};
+use crate::controller::laboratorio::etiqueta_controller::decodificar_codigo;
+use crate::controller::laboratorio::coleta_checagem_controller::{
+    BuscarChecagemPayload, GrupoChecagem, SalvarChecagemPayload,
+};
+use crate::controller::laboratorio::amostra_controller::IniciarAmostraPayload;
+use crate::controller::laboratorio::amostra_broqueada_controller::{
+    DesbloquearAmostraPayload, DesbloquearAmostraResponse,
+};
+use crate::controller::laboratorio::fila_trabalho_controller::validar_inicio_analise;
+
+const EVENTO_LEITURA: &str = "leitura_amostra";
+const EVENTO_FINALIZADA: &str = "sessao_leitura_finalizada";
+/// Sessões sem leitura por mais tempo que isso são descartadas (operador saiu sem cancelar)
+const VALIDADE_SESSAO: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Sessão mantida em memória enquanto o operador lê as etiquetas
+#[derive(Debug, Clone)]
+struct SessaoLeitura {
+    id: String,
+    operacao: OperacaoLeitura,
+    grupos: Vec<GrupoChecagem>,
+    /// Número da amostra -> análises vinculadas
+    esperadas: BTreeMap<u32, Vec<u64>>,
+    lidas: BTreeSet<u32>,
+    rejeitadas: usize,
+    duplicadas: usize,
+    equipamentos: Vec<u32>,
+    lotes_insumo: Vec<u32>,
+    /// Análises já iniciadas por uma finalização que falhou em parte; não são reenviadas
+    iniciadas: BTreeSet<u32>,
+    ultima_atividade: Instant,
+}
+
+lazy_static! {
+    static ref SESSOES: Mutex<HashMap<String, SessaoLeitura>> = Mutex::new(HashMap::new());
+}
+
+/// Acesso ao mapa de sessões, já sem as abandonadas
+fn sessoes() -> Result<MutexGuard<'static, HashMap<String, SessaoLeitura>>, ApiResponse<()>> {
+    let mut sessoes = SESSOES
+        .lock()
+        .map_err(|_| ApiResponse::error("Falha ao acessar sessões de leitura.".to_string()))?;
+    descartar_expiradas(&mut sessoes, Instant::now());
+    Ok(sessoes)
+}
+
+// ==================== COMANDOS TAURI ====================
+
+/// Abre uma sessão de leitura carregando os grupos de checagem do intervalo informado
+#[command]
+pub async fn abrir_sessao_leitura(
+    app_handle: AppHandle,
+    payload: AbrirSessaoLeituraPayload,
+) -> Result<ApiResponse<ResumoSessaoLeitura>, ApiResponse<()>> {
+    if payload.numero_ini > payload.numero_fim {
+        return Err(ApiResponse::error("Número inicial maior que o final.".to_string()));
+    }
+
+    let client = Client::new();
+    let url = format!("{}/laboratorio/coleta-checagem/buscar", get_api_url(&app_handle));
+    let busca = BuscarChecagemPayload {
+        numero_ini: Some(payload.numero_ini),
+        numero_fim: Some(payload.numero_fim),
+        id_grupo_edit: None,
+    };
+
+    let grupos = match client.post(&url).json(&busca).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                response
+                    .json::<Vec<GrupoChecagem>>()
+                    .await
+                    .map_err(|e| ApiResponse::error(format!("Erro JSON: {}", e)))?
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                return Err(ApiResponse::error(format!("Erro API ({}): {}", status, msg)));
+            }
+        }
+        Err(e) => return Err(ApiResponse::error(format!("Falha conexão: {}", e))),
+    };
+
+    if grupos.is_empty() {
+        return Err(ApiResponse::error(format!(
+            "Nenhuma coleta encontrada entre {} e {}.",
+            payload.numero_ini, payload.numero_fim
+        )));
+    }
+
+    let mut sessao = nova_sessao(payload.operacao, grupos);
+    sessao.equipamentos = payload.equipamentos;
+    sessao.lotes_insumo = payload.lotes_insumo;
+    let resumo = resumir(&sessao);
+    sessoes()?.insert(sessao.id.clone(), sessao);
+
+    Ok(ApiResponse::success("Sessão de leitura aberta.".to_string(), Some(resumo)))
+}
+
+/// Registra um código lido; o resultado também é emitido no evento `leitura_amostra`
+#[command]
+pub async fn registrar_leitura_codigo(
+    app_handle: AppHandle,
+    id_sessao: String,
+    codigo: String,
+) -> Result<ApiResponse<ResultadoLeitura>, ApiResponse<()>> {
+    let resultado = {
+        let mut sessoes = sessoes()?;
+        let sessao = sessoes
+            .get_mut(&id_sessao)
+            .ok_or_else(|| ApiResponse::error("Sessão de leitura não encontrada.".to_string()))?;
+        sessao.ultima_atividade = Instant::now();
+        registrar_leitura(sessao, &codigo)
+    };
+
+    if let Err(e) = app_handle.emit(EVENTO_LEITURA, &resultado) {
+        println!("Falha ao emitir evento de leitura: {}", e);
+    }
+
+    Ok(ApiResponse::success(resultado.mensagem.clone(), Some(resultado)))
+}
+
+#[command]
+pub async fn resumo_sessao_leitura(
+    id_sessao: String,
+) -> Result<ApiResponse<ResumoSessaoLeitura>, ApiResponse<()>> {
+    let sessoes = sessoes()?;
+    let sessao = sessoes
+        .get(&id_sessao)
+        .ok_or_else(|| ApiResponse::error("Sessão de leitura não encontrada.".to_string()))?;
+
+    Ok(ApiResponse::success("Resumo da sessão.".to_string(), Some(resumir(sessao))))
+}
+
+#[command]
+pub async fn cancelar_sessao_leitura(id_sessao: String) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    sessoes()?.remove(&id_sessao);
+
+    Ok(ApiResponse::success("Sessão de leitura cancelada.".to_string(), None))
+}
+
+/// Confirma o lote lido executando a operação da sessão em uma única chamada.
+/// Com amostras faltantes, retorna erro `FALTANTES:` até que `confirmar_faltantes` seja enviado.
+#[command]
+pub async fn finalizar_sessao_leitura(
+    app_handle: AppHandle,
+    id_sessao: String,
+    confirmar_faltantes: Option<bool>,
+) -> Result<ApiResponse<ResumoSessaoLeitura>, ApiResponse<()>> {
+    // Fora do mapa enquanto a operação roda: uma segunda finalização da mesma sessão não a encontra
+    let mut sessao = sessoes()?
+        .remove(&id_sessao)
+        .ok_or_else(|| ApiResponse::error("Sessão de leitura não encontrada.".to_string()))?;
+
+    match executar_operacao(&app_handle, &mut sessao, confirmar_faltantes.unwrap_or(false)).await {
+        Ok((mensagem, resumo)) => {
+            if let Err(e) = app_handle.emit(EVENTO_FINALIZADA, &resumo) {
+                println!("Falha ao emitir evento de finalização: {}", e);
+            }
+            Ok(ApiResponse::success(mensagem, Some(resumo)))
+        }
+        Err(e) => {
+            // Volta ao mapa para o operador corrigir e finalizar de novo
+            sessao.ultima_atividade = Instant::now();
+            sessoes()?.insert(id_sessao, sessao);
+            Err(ApiResponse::error(e))
+        }
+    }
+}
+
+// ==================== OPERAÇÕES ====================
+
+async fn executar_operacao(
+    app_handle: &AppHandle,
+    sessao: &mut SessaoLeitura,
+    confirmar_faltantes: bool,
+) -> Result<(String, ResumoSessaoLeitura), String> {
+    if sessao.lidas.is_empty() {
+        return Err("Nenhuma amostra lida nesta sessão.".to_string());
+    }
+
+    let resumo = resumir(sessao);
+    if !resumo.faltantes.is_empty() && !confirmar_faltantes {
+        let lista: Vec<String> = resumo.faltantes.iter().map(|n| n.to_string()).collect();
+        return Err(format!(
+            "FALTANTES: {} amostra(s) não lida(s): {}",
+            lista.len(),
+            lista.join(", ")
+        ));
+    }
+
+    let user_id = get_user_id().ok_or("Usuário não autenticado")?;
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+
+    let mensagem = match sessao.operacao {
+        OperacaoLeitura::Receber => confirmar_recebimento(&client, &api_url, sessao, user_id).await,
+        OperacaoLeitura::IniciarAnalise => iniciar_analises(&client, &api_url, sessao, user_id).await,
+        OperacaoLeitura::Bloquear => bloquear(&client, &api_url, sessao, user_id).await,
+    }?;
+    Ok((mensagem, resumo))
+}
+
+fn analises_lidas(sessao: &SessaoLeitura) -> Vec<u32> {
+    sessao
+        .lidas
+        .iter()
+        .filter_map(|n| sessao.esperadas.get(n))
+        .flatten()
+        .filter_map(|id| u32::try_from(*id).ok())
+        .collect()
+}
+
+fn analises_pendentes(sessao: &SessaoLeitura) -> Vec<u32> {
+    analises_lidas(sessao)
+        .into_iter()
+        .filter(|id| !sessao.iniciadas.contains(id))
+        .collect()
+}
+
+/// Registra a chegada ao laboratório dos grupos com todas as amostras lidas. A data de
+/// chegada é do grupo inteiro, então grupos lidos em parte ficam pendentes.
+async fn confirmar_recebimento(
+    client: &Client,
+    api_url: &str,
+    sessao: &SessaoLeitura,
+    user_id: u32,
+) -> Result<String, String> {
+    let agora = chrono::Local::now();
+    let (completos, parciais) = grupos_para_recebimento(sessao);
+    if completos.is_empty() {
+        return Err(format!(
+            "Nenhum grupo com todas as amostras lidas; {} grupo(s) com leitura parcial não foram recebidos.",
+            parciais.len()
+        ));
+    }
+
+    let grupos: Vec<GrupoChecagem> = completos
+        .into_iter()
+        .map(|mut g| {
+            if g.data_lab.is_none() {
+                g.data_lab = Some(agora.format("%Y-%m-%d").to_string());
+            }
+            if g.hora_lab.is_none() {
+                g.hora_lab = Some(agora.format("%H:%M:%S").to_string());
+            }
+            g
+        })
+        .collect();
+
+    let total = grupos.len();
+    let amostras: usize = grupos.iter().map(|g| g.amostras_analises.len()).sum();
+    let payload = SalvarChecagemPayload {
+        grupos,
+        id_usuario_verificacao: user_id,
+    };
+
+    let response = client
+        .post(format!("{}/laboratorio/coleta-checagem/salvar", api_url))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let mut mensagem = format!("Recebimento registrado para {} amostra(s) em {} grupo(s).", amostras, total);
+    if !parciais.is_empty() {
+        let lista: Vec<String> = parciais.iter().map(|id| id.to_string()).collect();
+        mensagem.push_str(&format!(" Grupo(s) com leitura parcial, não recebidos: {}.", lista.join(", ")));
+    }
+    Ok(mensagem)
+}
+
+/// Uma chamada por análise; as que deram certo ficam marcadas na sessão para a nova tentativa
+async fn iniciar_analises(
+    client: &Client,
+    api_url: &str,
+    sessao: &mut SessaoLeitura,
+    user_id: u32,
+) -> Result<String, String> {
+    validar_inicio_analise(client, api_url, &sessao.equipamentos, &sessao.lotes_insumo).await?;
+
+    let agora = chrono::Local::now();
+    let url = format!("{}/amostras-nao-iniciadas/iniciar", api_url);
+    let mut falhas = Vec::new();
+    let ids = analises_pendentes(sessao);
+
+    for id_analise in &ids {
+        let payload = IniciarAmostraPayload {
+            id_analise: *id_analise,
+            data_inicio: agora.format("%Y-%m-%d").to_string(),
+            hora_inicio: agora.format("%H:%M").to_string(),
+            id_usuario: user_id,
+        };
+
+        match client.post(&url).json(&payload).send().await {
+            Ok(response) if response.status().is_success() => {
+                sessao.iniciadas.insert(*id_analise);
+            }
+            Ok(response) => falhas.push(format!("análise {} ({})", id_analise, response.status())),
+            Err(e) => falhas.push(format!("análise {} ({})", id_analise, e)),
+        }
+    }
+
+    if falhas.is_empty() {
+        Ok(format!("{} análise(s) iniciada(s).", ids.len()))
+    } else {
+        Err(format!(
+            "{} de {} análise(s) não iniciada(s); finalize de novo para reenviar só essas: {}",
+            falhas.len(),
+            ids.len(),
+            falhas.join("; ")
+        ))
+    }
+}
+
+async fn bloquear(
+    client: &Client,
+    api_url: &str,
+    sessao: &SessaoLeitura,
+    user_id: u32,
+) -> Result<String, String> {
+    let payload = DesbloquearAmostraPayload {
+        ids_analise: analises_lidas(sessao),
+        id_usuario: user_id,
+    };
+
+    let response = client
+        .post(format!("{}/laboratorio/amostras-bloqueadas/bloquear", api_url))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Falha conexão: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+
+    let resultado = response
+        .json::<DesbloquearAmostraResponse>()
+        .await
+        .map_err(|e| format!("Erro JSON: {}", e))?;
+
+    if resultado.success {
+        Ok(resultado.message)
+    } else {
+        Err(resultado.message)
+    }
+}
+
+// ==================== VALIDAÇÃO ====================
+
+fn nova_sessao(operacao: OperacaoLeitura, grupos: Vec<GrupoChecagem>) -> SessaoLeitura {
+    let mut esperadas: BTreeMap<u32, Vec<u64>> = BTreeMap::new();
+    for amostra in grupos.iter().flat_map(|g| &g.amostras_analises) {
+        esperadas.entry(amostra.numero_amostra).or_default().push(amostra.id_analise);
+    }
+
+    SessaoLeitura {
+        id: Uuid::new_v4().to_string(),
+        operacao,
+        grupos,
+        esperadas,
+        lidas: BTreeSet::new(),
+        rejeitadas: 0,
+        duplicadas: 0,
+        equipamentos: Vec::new(),
+        lotes_insumo: Vec::new(),
+        iniciadas: BTreeSet::new(),
+        ultima_atividade: Instant::now(),
+    }
+}
+
+fn descartar_expiradas(sessoes: &mut HashMap<String, SessaoLeitura>, agora: Instant) {
+    sessoes.retain(|_, s| agora.saturating_duration_since(s.ultima_atividade) < VALIDADE_SESSAO);
+}
+
+/// Grupos com todas as amostras lidas e ids dos grupos lidos só em parte
+fn grupos_para_recebimento(sessao: &SessaoLeitura) -> (Vec<GrupoChecagem>, Vec<u64>) {
+    let mut completos = Vec::new();
+    let mut parciais = Vec::new();
+    for grupo in &sessao.grupos {
+        let lidas = grupo.amostras_analises.iter().filter(|a| sessao.lidas.contains(&a.numero_amostra)).count();
+        if lidas == 0 {
+            continue;
+        }
+        if lidas == grupo.amostras_analises.len() {
+            completos.push(grupo.clone());
+        } else {
+            parciais.push(grupo.id_grupo);
+        }
+    }
+    (completos, parciais)
+}
+
+fn faltantes(sessao: &SessaoLeitura) -> Vec<u32> {
+    sessao.esperadas.keys().copied().filter(|n| !sessao.lidas.contains(n)).collect()
+}
+
+fn resumir(sessao: &SessaoLeitura) -> ResumoSessaoLeitura {
+    ResumoSessaoLeitura {
+        id_sessao: sessao.id.clone(),
+        operacao: sessao.operacao,
+        amostra_min: sessao.grupos.iter().map(|g| g.amostra_min).min().unwrap_or(0),
+        amostra_max: sessao.grupos.iter().map(|g| g.amostra_max).max().unwrap_or(0),
+        lidas: sessao.lidas.iter().copied().collect(),
+        faltantes: faltantes(sessao),
+        total_esperadas: sessao.esperadas.len(),
+        duplicadas: sessao.duplicadas,
+        rejeitadas: sessao.rejeitadas,
+    }
+}
+
+fn registrar_leitura(sessao: &mut SessaoLeitura, codigo: &str) -> ResultadoLeitura {
+    let numero = match decodificar_codigo(codigo) {
+        Some(CodigoLido::Amostra { numero }) => numero.parse::<u32>().ok(),
+        _ => None,
+    };
+
+    let (status, mensagem) = match numero {
+        None => (StatusLeitura::CodigoInvalido, format!("Código '{}' não corresponde a uma amostra.", codigo.trim())),
+        Some(n) if !sessao.grupos.iter().any(|g| n >= g.amostra_min && n <= g.amostra_max) => {
+            (StatusLeitura::ForaDoIntervalo, format!("Amostra {} fora do intervalo da sessão.", n))
+        }
+        Some(n) if !sessao.esperadas.contains_key(&n) => {
+            (StatusLeitura::NaoEncontrada, format!("Amostra {} sem análise cadastrada.", n))
+        }
+        Some(n) if !sessao.lidas.insert(n) => (StatusLeitura::Duplicada, format!("Amostra {} já lida.", n)),
+        Some(n) => (StatusLeitura::Aceita, format!("Amostra {} registrada.", n)),
+    };
+
+    match status {
+        StatusLeitura::Aceita => {}
+        StatusLeitura::Duplicada => sessao.duplicadas += 1,
+        _ => sessao.rejeitadas += 1,
+    }
+
+    ResultadoLeitura {
+        id_sessao: sessao.id.clone(),
+        codigo: codigo.trim().to_string(),
+        numero,
+        status,
+        mensagem,
+        total_lidas: sessao.lidas.len(),
+        total_esperadas: sessao.esperadas.len(),
+        faltantes: faltantes(sessao),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::laboratorio::coleta_checagem_controller::AmostraAnalise;
+
+    fn grupo(amostra_min: u32, amostra_max: u32, numeros: &[u32]) -> GrupoChecagem {
+        GrupoChecagem {
+            id_grupo: amostra_min as u64,
+            amostra_min,
+            amostra_max,
+            data_coleta: None,
+            hora_coleta: None,
+            hora_coleta_ini: None,
+            hora_coleta_ter: None,
+            data_lab: None,
+            hora_lab: None,
+            data_checagem: None,
+            usuario_checagem: None,
+            versao: None,
+            numero_versao: None,
+            form_numero: 0,
+            form_revisao: 0,
+            amostras_analises: numeros
+                .iter()
+                .map(|n| AmostraAnalise {
+                    id_amostra: *n as u64,
+                    numero_amostra: *n,
+                    id_analise: *n as u64 * 10,
+                    hora_coleta_analise: None,
+                    coletada: None,
+                    ncoletada_motivo: None,
+                    checagens: Vec::new(),
+                    parametros: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_registrar_leitura() {
+        let mut sessao = nova_sessao(OperacaoLeitura::Receber, vec![grupo(100, 104, &[100, 101, 103])]);
+
+        let status: Vec<StatusLeitura> = ["AM100", "101\r\n", "101", "102", "200", "RL5"]
+            .iter()
+            .map(|c| registrar_leitura(&mut sessao, c).status)
+            .collect();
+
+        assert_eq!(
+            status,
+            vec![
+                StatusLeitura::Aceita,
+                StatusLeitura::Aceita,
+                StatusLeitura::Duplicada,
+                StatusLeitura::NaoEncontrada,
+                StatusLeitura::ForaDoIntervalo,
+                StatusLeitura::CodigoInvalido,
+            ]
+        );
+
+        let resumo = resumir(&sessao);
+        assert_eq!(resumo.faltantes, vec![103]);
+        assert_eq!((resumo.duplicadas, resumo.rejeitadas), (1, 3));
+        assert_eq!(analises_lidas(&sessao), vec![1000, 1010]);
+        sessao.iniciadas.insert(1000);
+        assert_eq!(analises_pendentes(&sessao), vec![1010]);
+    }
+
+    #[test]
+    fn test_grupos_para_recebimento() {
+        let mut sessao = nova_sessao(
+            OperacaoLeitura::Receber,
+            vec![grupo(100, 101, &[100, 101]), grupo(200, 202, &[200, 201, 202]), grupo(300, 300, &[300])],
+        );
+        for codigo in ["100", "101", "200"] {
+            registrar_leitura(&mut sessao, codigo);
+        }
+
+        let (completos, parciais) = grupos_para_recebimento(&sessao);
+        assert_eq!(completos.iter().map(|g| g.id_grupo).collect::<Vec<_>>(), vec![100]);
+        assert_eq!(parciais, vec![200]);
+    }
+
+    #[test]
+    fn test_descartar_expiradas() {
+        let inicio = Instant::now();
+        let mut ativa = nova_sessao(OperacaoLeitura::Bloquear, vec![grupo(1, 1, &[1])]);
+        ativa.ultima_atividade = inicio + Duration::from_secs(60);
+        let mut abandonada = nova_sessao(OperacaoLeitura::Bloquear, vec![grupo(1, 1, &[1])]);
+        abandonada.ultima_atividade = inicio;
+        let id_ativa = ativa.id.clone();
+
+        let mut sessoes: HashMap<String, SessaoLeitura> =
+            [ativa, abandonada].into_iter().map(|s| (s.id.clone(), s)).collect();
+        descartar_expiradas(&mut sessoes, inicio + VALIDADE_SESSAO);
+        assert_eq!(sessoes.keys().collect::<Vec<_>>(), vec![&id_ativa]);
+    }
+}
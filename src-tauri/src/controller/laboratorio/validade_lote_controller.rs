@@ -0,0 +1,336 @@
+use std::time::Duration;
+use chrono::{Local, NaiveDate};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::model::api_response::ApiResponse;
+use crate::monitor::Monitor;
+use crate::model::historico::NovoHistoricoPayload;
+use crate::model::insumo_registro::{InsumoRegistroDetalhado, InsumoTipoOption};
+use crate::model::materia_prima_registro::PaginatedMateriaPrimaRegistroResponse;
+use crate::model::validade_lote::{
+    ConfiguracaoMonitorValidade, LoteValidade, OrigemLote, RelatorioValidade, SituacaoValidade,
+};
+use crate::controller::admin::historico_controller::registrar_historico;
+use crate::controller::laboratorio::materia_prima_registro_controller::atualizar_obsoleto_materia_prima;
+use crate::controller::laboratorio::reagente_limpeza_registro_controller::{ReagenteItem, ReagenteRegistroDetalhado};
+use crate::model::insumo_registro_2::InsumoRegistro2Detalhado;
+use crate::utils::{buscar_json, parse_data_hora};
+
+const ARQUIVO_CONFIGURACAO: &str = "monitor_validade.json";
+const EVENTO_ALERTA: &str = "alerta_validade_lote";
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn carregar_config_monitor_validade(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<ConfiguracaoMonitorValidade>, ApiResponse<()>> {
+    Ok(ApiResponse::success("Configuração carregada.".to_string(), Some(ler_configuracao(&app_handle))))
+}
+
+#[command]
+pub async fn salvar_config_monitor_validade(
+    app_handle: AppHandle,
+    configuracao: ConfiguracaoMonitorValidade,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    if configuracao.intervalo_minutos == 0 {
+        return Err(ApiResponse::error("O intervalo deve ser de pelo menos 1 minuto.".to_string()));
+    }
+
+    gravar_json(&app_handle, ARQUIVO_CONFIGURACAO, &configuracao).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Configuração salva.".to_string(), None))
+}
+
+/// Verificação manual: lista lotes vencidos e a vencer, sem alterar nada
+#[command]
+pub async fn verificar_validades_tauri(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<RelatorioValidade>, ApiResponse<()>> {
+    let configuracao = ler_configuracao(&app_handle);
+    let relatorio = varrer_lotes(&app_handle, configuracao.dias_aviso).await;
+
+    Ok(ApiResponse::success(
+        format!("{} lote(s) vencido(s) ou a vencer.", relatorio.lotes.len()),
+        Some(relatorio),
+    ))
+}
+
+// ==================== MONITOR ====================
+
+/// Varre periodicamente os lotes, emite `alerta_validade_lote` com as novidades e,
+/// se configurado, marca como obsoletos os lotes de matéria-prima vencidos
+pub async fn monitorar_validades(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("validades", EVENTO_ALERTA);
+
+    loop {
+        let configuracao = ler_configuracao(&app_handle);
+        let user_id = monitor
+            .aguardar(Duration::from_secs(configuracao.intervalo_minutos.max(1) as u64 * 60))
+            .await;
+
+        let mut relatorio = varrer_lotes(&app_handle, configuracao.dias_aviso).await;
+        if configuracao.obsoletar_automaticamente {
+            obsoletar_vencidos(&app_handle, &mut relatorio, user_id).await;
+        }
+
+        // Obsoletado nesta passada sempre é avisado; na seguinte o lote já sai da varredura
+        let novos: Vec<LoteValidade> = monitor
+            .novos(&relatorio.lotes, |l| (l.origem, l.id, l.situacao, l.obsoletado))
+            .into_iter()
+            .cloned()
+            .collect();
+        if !novos.is_empty() {
+            monitor.emitir(&app_handle, RelatorioValidade { lotes: novos, ..relatorio });
+        }
+    }
+}
+
+async fn obsoletar_vencidos(app_handle: &AppHandle, relatorio: &mut RelatorioValidade, user_id: u32) {
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+
+    for lote in relatorio
+        .lotes
+        .iter_mut()
+        .filter(|l| l.origem == OrigemLote::MateriaPrima && l.situacao == SituacaoValidade::Vencido)
+    {
+        match atualizar_obsoleto_materia_prima(&client, &api_url, lote.id, true).await {
+            Ok(_) => {
+                lote.obsoletado = true;
+                let historico = NovoHistoricoPayload {
+                    usuario_id: user_id,
+                    acao: "Obsoleto automático".to_string(),
+                    descricao: format!(
+                        "Lote de matéria-prima {} ({}, lote {}) marcado como obsoleto: validade {} expirada.",
+                        lote.id,
+                        lote.nome,
+                        lote.lote.as_deref().unwrap_or("s/n"),
+                        lote.validade
+                    ),
+                };
+                if let Err(e) = registrar_historico(&client, &api_url, &historico).await {
+                    println!("Falha ao registrar histórico do lote {}: {}", lote.id, e);
+                }
+            }
+            Err(e) => println!("Falha ao marcar lote {} como obsoleto: {}", lote.id, e),
+        }
+    }
+}
+
+// ==================== BLOQUEIO DE USO ====================
+
+/// Impede iniciar o uso de um lote vencido (registro de insumo 2 e reagente de limpeza)
+pub fn verificar_lote_utilizavel(validade: Option<&str>, hoje: NaiveDate) -> Result<(), String> {
+    match validade.and_then(|v| parse_data_hora(v, None)).map(|d| d.date()) {
+        Some(data) if data < hoje => Err(format!(
+            "LOTE_VENCIDO: lote com validade em {} não pode ser utilizado.",
+            data.format("%d/%m/%Y")
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Confere o lote de insumo (registro 2) antes de iniciar o uso. Sem conseguir
+/// consultar a validade, bloqueia: lote vencido não pode passar por falha de rede.
+pub async fn verificar_lote_insumo_2(client: &Client, api_url: &str, id: u32) -> Result<(), String> {
+    let url = format!("{}/laboratorio/insumos-registros-2/{}", api_url, id);
+    let registro: InsumoRegistro2Detalhado = buscar_json(client, &url)
+        .await
+        .map_err(|e| format!("Não foi possível confirmar a validade do lote {}: {}", id, e))?;
+    verificar_lote_utilizavel(registro.validade.as_deref(), Local::now().date_naive())
+}
+
+pub async fn verificar_lote_reagente(client: &Client, api_url: &str, id: u32) -> Result<(), String> {
+    let url = format!("{}/laboratorio/reagentes-limpeza/registros/{}", api_url, id);
+    let registro: ReagenteRegistroDetalhado = buscar_json(client, &url)
+        .await
+        .map_err(|e| format!("Não foi possível confirmar a validade do lote {}: {}", id, e))?;
+    verificar_lote_utilizavel(Some(&registro.validade), Local::now().date_naive())
+}
+
+// ==================== VARREDURA ====================
+
+fn ler_configuracao(app_handle: &AppHandle) -> ConfiguracaoMonitorValidade {
+    ler_json(app_handle, ARQUIVO_CONFIGURACAO)
+}
+
+/// Retorna a situação e os dias restantes, ou `None` se o lote ainda está fora da janela de aviso
+fn classificar(validade: NaiveDate, hoje: NaiveDate, dias_aviso: u32) -> Option<(SituacaoValidade, i64)> {
+    let dias = (validade - hoje).num_days();
+    if dias < 0 {
+        Some((SituacaoValidade::Vencido, dias))
+    } else if dias <= dias_aviso as i64 {
+        Some((SituacaoValidade::AVencer, dias))
+    } else {
+        None
+    }
+}
+
+struct Candidato {
+    origem: OrigemLote,
+    id: u32,
+    nome: String,
+    lote: Option<String>,
+    validade: Option<String>,
+}
+
+fn avaliar(candidatos: Vec<Candidato>, hoje: NaiveDate, dias_aviso: u32) -> Vec<LoteValidade> {
+    let mut lotes: Vec<LoteValidade> = candidatos
+        .into_iter()
+        .filter_map(|c| {
+            let data = c.validade.as_deref().and_then(|v| parse_data_hora(v, None))?.date();
+            let (situacao, dias_restantes) = classificar(data, hoje, dias_aviso)?;
+            Some(LoteValidade {
+                origem: c.origem,
+                id: c.id,
+                nome: c.nome,
+                lote: c.lote,
+                validade: data.format("%Y-%m-%d").to_string(),
+                dias_restantes,
+                situacao,
+                obsoletado: false,
+            })
+        })
+        .collect();
+
+    lotes.sort_by_key(|l| (l.situacao, l.dias_restantes));
+    lotes
+}
+
+async fn varrer_lotes(app_handle: &AppHandle, dias_aviso: u32) -> RelatorioValidade {
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+    let mut candidatos = Vec::new();
+    let mut falhas = Vec::new();
+
+    // Matérias-primas (paginado, sem obsoletos)
+    let mut pagina = 1;
+    loop {
+        let url = format!(
+            "{}/laboratorio/materia-prima-registros?page={}&per_page=200&mostrar_obsoletos=false",
+            api_url, pagina
+        );
+        match buscar_json::<PaginatedMateriaPrimaRegistroResponse>(&client, &url).await {
+            Ok(resposta) => {
+                let recebidos = resposta.items.len() as u32;
+                candidatos.extend(resposta.items.into_iter().filter(|r| !r.obsoleto && !r.finalizado).map(|r| Candidato {
+                    origem: OrigemLote::MateriaPrima,
+                    id: r.id,
+                    nome: r.nome_materia_prima.unwrap_or_else(|| format!("Matéria-prima {}", r.id)),
+                    lote: r.lote_fabricante,
+                    validade: r.validade.map(|v| v.format("%Y-%m-%d").to_string()),
+                }));
+                if recebidos == 0 || (pagina * resposta.per_page.max(1)) as i64 >= resposta.total {
+                    break;
+                }
+                pagina += 1;
+            }
+            Err(e) => {
+                falhas.push(format!("Matérias-primas: {}", e));
+                break;
+            }
+        }
+    }
+
+    // Registros de insumo, por tipo
+    match buscar_json::<Vec<InsumoTipoOption>>(&client, &format!("{}/laboratorio/suporte/insumo-tipos", api_url)).await {
+        Ok(tipos) => {
+            for tipo in tipos {
+                let url = format!("{}/laboratorio/insumos-registros?tipo_id={}&obsoletos=false", api_url, tipo.id);
+                match buscar_json::<Vec<InsumoRegistroDetalhado>>(&client, &url).await {
+                    Ok(registros) => candidatos.extend(
+                        registros.into_iter().filter(|r| !r.obsoleto && !r.fora_de_uso).map(|r| Candidato {
+                            origem: OrigemLote::Insumo,
+                            id: r.id,
+                            nome: r.insumo_nome,
+                            lote: r.registro,
+                            validade: r.validade,
+                        }),
+                    ),
+                    Err(e) => falhas.push(format!("Insumos ({}): {}", tipo.nome, e)),
+                }
+            }
+        }
+        Err(e) => falhas.push(format!("Tipos de insumo: {}", e)),
+    }
+
+    // Reagentes de limpeza ainda não finalizados
+    match buscar_json::<Vec<ReagenteItem>>(&client, &format!("{}/laboratorio/reagentes-limpeza/itens", api_url)).await {
+        Ok(reagentes) => {
+            for reagente in reagentes {
+                let url = format!("{}/laboratorio/reagentes-limpeza/{}/registros", api_url, reagente.id);
+                match buscar_json::<Vec<ReagenteRegistroDetalhado>>(&client, &url).await {
+                    Ok(registros) => candidatos.extend(
+                        registros.into_iter().filter(|r| r.data_final.is_none()).map(|r| Candidato {
+                            origem: OrigemLote::ReagenteLimpeza,
+                            id: r.id,
+                            nome: reagente.nome.clone(),
+                            lote: Some(r.lote),
+                            validade: Some(r.validade),
+                        }),
+                    ),
+                    Err(e) => falhas.push(format!("Reagente {}: {}", reagente.nome, e)),
+                }
+            }
+        }
+        Err(e) => falhas.push(format!("Reagentes de limpeza: {}", e)),
+    }
+
+    let agora = chrono::Local::now();
+    RelatorioValidade {
+        gerado_em: agora.format("%Y-%m-%d %H:%M:%S").to_string(),
+        lotes: avaliar(candidatos, agora.date_naive(), dias_aviso),
+        fontes_indisponiveis: falhas,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avaliar_validades() {
+        let hoje = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let candidato = |id: u32, validade: Option<&str>| Candidato {
+            origem: OrigemLote::ReagenteLimpeza,
+            id,
+            nome: "Detergente".to_string(),
+            lote: None,
+            validade: validade.map(str::to_string),
+        };
+
+        let lotes = avaliar(
+            vec![
+                candidato(1, Some("2025-05-01")),
+                candidato(2, Some("15/03/2025")),
+                candidato(3, Some("2025-03-01")),
+                candidato(4, None),
+                candidato(5, Some("2025-03-10")),
+            ],
+            hoje,
+            30,
+        );
+
+        let resumo: Vec<(u32, SituacaoValidade, i64)> = lotes.iter().map(|l| (l.id, l.situacao, l.dias_restantes)).collect();
+        assert_eq!(
+            resumo,
+            vec![
+                (3, SituacaoValidade::Vencido, -9),
+                (5, SituacaoValidade::AVencer, 0),
+                (2, SituacaoValidade::AVencer, 5),
+            ]
+        );
+        assert_eq!(lotes[2].validade, "2025-03-15");
+    }
+
+    #[test]
+    fn test_verificar_lote_utilizavel() {
+        let hoje = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        assert!(verificar_lote_utilizavel(Some("09/03/2025"), hoje).is_err());
+        assert!(verificar_lote_utilizavel(Some("2025-03-10"), hoje).is_ok());
+        assert!(verificar_lote_utilizavel(None, hoje).is_ok());
+    }
+}
@@ -0,0 +1,612 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+use chrono::{Local, Months, NaiveDate};
+use futures_util::stream::{self, StreamExt};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::monitor::Monitor;
+use crate::model::historico::NovoHistoricoPayload;
+use crate::model::estoque::EstoqueCompletoResponse;
+use crate::model::fornecedor::{AtualizacaoQualificadoPayload, FornecedorDetalhado, FornecedorQualificacao, RespostaPaginada};
+use crate::model::reposicao_estoque::VinculoFornecedorEstoque;
+use crate::model::avaliacao_fornecedor::{
+    AvaliacaoFornecedor, ClassificacaoFornecedor, ComponenteNota, ExportacaoAvaliacaoFornecedores,
+    ParametrosAvaliacaoFornecedor, RelatorioAvaliacaoFornecedores, SituacaoQualificacao,
+};
+use crate::controller::admin::historico_controller::registrar_historico;
+use crate::controller::qualidade::reposicao_estoque_controller::prazos_entrega;
+use crate::exportacao::{aviso_html, destino_exportacao, escapar_html, pagina_relatorio, salvar_pdf, tabela_html};
+use crate::utils::{buscar_json, parse_data_hora};
+
+const EVENTO_REQUALIFICACAO: &str = "requalificacao_fornecedor";
+const INTERVALO_MONITOR: Duration = Duration::from_secs(6 * 60 * 60);
+const CONSULTAS_SIMULTANEAS: usize = 8;
+
+// Pesos dos critérios (somam 100)
+const PESO_QUALIFICACAO: f64 = 40.0;
+const PESO_CERTIFICACAO: f64 = 15.0;
+const PESO_NAO_CONFORMIDADES: f64 = 25.0;
+const PESO_ENTREGAS: f64 = 20.0;
+const PENALIDADE_NC_ABERTA: f64 = 10.0;
+const PENALIDADE_NC_ENCERRADA: f64 = 5.0;
+
+/// Entregas (entradas de estoque) dos itens vinculados a um fornecedor
+#[derive(Debug, Default, Clone)]
+struct DadosEntrega {
+    entregas: usize,
+    prazos: Vec<f64>,
+}
+
+// ==================== COMANDOS TAURI ====================
+
+#[command]
+pub async fn avaliar_fornecedor(
+    app_handle: AppHandle,
+    id: u32,
+    parametros: Option<ParametrosAvaliacaoFornecedor>,
+) -> Result<ApiResponse<AvaliacaoFornecedor>, ApiResponse<()>> {
+    let parametros = parametros.unwrap_or_default();
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let hoje = Local::now().date_naive();
+
+    let fornecedor = buscar_json::<FornecedorDetalhado>(&client, &format!("{}/qualidade/fornecedores/{}", api_url, id))
+        .await
+        .map_err(ApiResponse::error)?;
+    let entregas = coletar_entregas(&client, &api_url, inicio_periodo(hoje, &parametros), Some(id)).await;
+    let avaliacao = avaliar(&fornecedor, entregas.get(&id), &parametros, hoje);
+
+    Ok(ApiResponse::success(format!("Nota {:.1}.", avaliacao.nota), Some(avaliacao)))
+}
+
+/// Avaliação periódica de todos os fornecedores ativos (sem alterar cadastros)
+#[command]
+pub async fn avaliar_fornecedores(
+    app_handle: AppHandle,
+    parametros: Option<ParametrosAvaliacaoFornecedor>,
+) -> Result<ApiResponse<RelatorioAvaliacaoFornecedores>, ApiResponse<()>> {
+    let relatorio = gerar_relatorio(&app_handle, parametros.unwrap_or_default())
+        .await
+        .map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success(
+        format!("{} fornecedor(es) avaliado(s).", relatorio.avaliacoes.len()),
+        Some(relatorio),
+    ))
+}
+
+/// Fornecedores com requalificação vencida, sem qualificação ou dentro da antecedência
+#[command]
+pub async fn listar_agenda_requalificacao(
+    app_handle: AppHandle,
+    parametros: Option<ParametrosAvaliacaoFornecedor>,
+) -> Result<ApiResponse<Vec<AvaliacaoFornecedor>>, ApiResponse<()>> {
+    let relatorio = gerar_relatorio(&app_handle, parametros.unwrap_or_default())
+        .await
+        .map_err(ApiResponse::error)?;
+    let agenda = agenda_requalificacao(relatorio.avaliacoes);
+    Ok(ApiResponse::success(format!("{} requalificação(ões) pendente(s).", agenda.len()), Some(agenda)))
+}
+
+/// Marca como não qualificados os fornecedores qualificados cuja validade expirou
+#[command]
+pub async fn desqualificar_fornecedores_vencidos(
+    app_handle: AppHandle,
+    usuario_id: u32,
+) -> Result<ApiResponse<Vec<AvaliacaoFornecedor>>, ApiResponse<()>> {
+    let relatorio = gerar_relatorio(&app_handle, ParametrosAvaliacaoFornecedor::default())
+        .await
+        .map_err(ApiResponse::error)?;
+    let alterados = desqualificar_vencidos(&app_handle, relatorio.avaliacoes, usuario_id).await;
+    Ok(ApiResponse::success(
+        format!("{} fornecedor(es) desqualificado(s).", alterados.len()),
+        Some(alterados),
+    ))
+}
+
+#[command]
+pub async fn exportar_avaliacao_fornecedores_pdf(
+    relatorio: RelatorioAvaliacaoFornecedores,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoAvaliacaoFornecedores>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho).map_err(ApiResponse::error)?;
+
+    salvar_pdf(gerar_html(&relatorio), &destino).await.map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Avaliação de fornecedores exportada.".to_string(),
+        Some(ExportacaoAvaliacaoFornecedores {
+            caminho: destino.to_string_lossy().to_string(),
+            total_fornecedores: relatorio.avaliacoes.len(),
+        }),
+    ))
+}
+
+// ==================== MONITOR ====================
+
+/// Desqualifica fornecedores vencidos e emite `requalificacao_fornecedor` com as
+/// requalificações que entraram na agenda desde a última verificação
+pub async fn monitorar_fornecedores(app_handle: AppHandle) {
+    let mut monitor = Monitor::new("fornecedores", EVENTO_REQUALIFICACAO);
+
+    loop {
+        let user_id = monitor.aguardar(INTERVALO_MONITOR).await;
+        let relatorio = match gerar_relatorio(&app_handle, ParametrosAvaliacaoFornecedor::default()).await {
+            Ok(relatorio) => relatorio,
+            Err(e) => {
+                monitor.falha(&e);
+                continue;
+            }
+        };
+
+        let desqualificados: HashSet<u32> = desqualificar_vencidos(&app_handle, relatorio.avaliacoes.clone(), user_id)
+            .await
+            .into_iter()
+            .map(|a| a.fornecedor_id)
+            .collect();
+
+        let agenda: Vec<AvaliacaoFornecedor> = agenda_requalificacao(relatorio.avaliacoes)
+            .into_iter()
+            .map(|mut a| {
+                a.desqualificado_automaticamente = desqualificados.contains(&a.fornecedor_id);
+                a
+            })
+            .collect();
+        monitor.notificar(&app_handle, &agenda, |a| (a.fornecedor_id, a.situacao_qualificacao));
+    }
+}
+
+// ==================== AVALIAÇÃO ====================
+
+fn inicio_periodo(hoje: NaiveDate, parametros: &ParametrosAvaliacaoFornecedor) -> NaiveDate {
+    hoje.checked_sub_months(Months::new(parametros.janela_meses)).unwrap_or(hoje)
+}
+
+fn data(valor: Option<&str>) -> Option<NaiveDate> {
+    valor.filter(|v| !v.trim().is_empty()).and_then(|v| parse_data_hora(v, None)).map(|d| d.date())
+}
+
+/// Qualificação de maior validade (empate: a mais recente)
+fn qualificacao_atual(qualificacoes: &[FornecedorQualificacao]) -> Option<(&FornecedorQualificacao, NaiveDate)> {
+    qualificacoes
+        .iter()
+        .filter_map(|q| data(q.VALIDADE.as_deref()).map(|v| (q, v)))
+        .max_by_key(|(q, validade)| (*validade, data(q.DATA_QUALIFICACAO.as_deref()), q.ID))
+}
+
+fn avaliar(
+    fornecedor: &FornecedorDetalhado,
+    entregas: Option<&DadosEntrega>,
+    parametros: &ParametrosAvaliacaoFornecedor,
+    hoje: NaiveDate,
+) -> AvaliacaoFornecedor {
+    let inicio = inicio_periodo(hoje, parametros);
+    let atual = qualificacao_atual(&fornecedor.qualificacoes);
+
+    let situacao = match atual {
+        None => SituacaoQualificacao::SemQualificacao,
+        Some((_, validade)) if validade < hoje => SituacaoQualificacao::Vencida,
+        Some((_, validade)) if (validade - hoje).num_days() <= parametros.antecedencia_requalificacao_dias as i64 => {
+            SituacaoQualificacao::AVencer
+        }
+        Some(_) => SituacaoQualificacao::Vigente,
+    };
+    let vigente = matches!(situacao, SituacaoQualificacao::Vigente | SituacaoQualificacao::AVencer);
+
+    let mut componentes = Vec::new();
+
+    // Qualificação: nota da avaliação (0 a 10) enquanto vigente
+    let nota_avaliacao = atual
+        .and_then(|(q, _)| q.AVALIACAO.as_deref())
+        .and_then(|a| a.trim().replace(',', ".").parse::<f64>().ok())
+        .map(|a| a.clamp(0.0, 10.0));
+    componentes.push(ComponenteNota {
+        criterio: "Qualificação".to_string(),
+        pontos: if vigente { PESO_QUALIFICACAO * nota_avaliacao.unwrap_or(0.0) / 10.0 } else { 0.0 },
+        maximo: PESO_QUALIFICACAO,
+        detalhe: match (atual, nota_avaliacao) {
+            (None, _) => "Sem qualificação registrada".to_string(),
+            (Some((_, validade)), nota) => format!(
+                "Avaliação {} · validade {}",
+                nota.map(|n| format!("{}", n)).unwrap_or_else(|| "-".to_string()),
+                validade.format("%d/%m/%Y")
+            ),
+        },
+    });
+
+    // Certificação informada na qualificação vigente
+    let certificacao = atual
+        .filter(|_| vigente)
+        .and_then(|(q, _)| q.CERTIFICACAO.as_deref())
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    componentes.push(ComponenteNota {
+        criterio: "Certificação".to_string(),
+        pontos: if certificacao.is_some() { PESO_CERTIFICACAO } else { 0.0 },
+        maximo: PESO_CERTIFICACAO,
+        detalhe: certificacao.unwrap_or("Sem certificação vigente").to_string(),
+    });
+
+    // Não conformidades: observações do período (abertas pesam mais)
+    let (mut abertas, mut encerradas) = (0, 0);
+    for obs in &fornecedor.observacoes {
+        let no_periodo = data(obs.DATA_OBSERVACAO.as_deref()).is_none_or(|d| d >= inicio);
+        match obs.ESTADO {
+            Some(true) if no_periodo => encerradas += 1,
+            Some(true) => {}
+            _ if no_periodo => abertas += 1,
+            _ => {}
+        }
+    }
+    let penalidade = abertas as f64 * PENALIDADE_NC_ABERTA + encerradas as f64 * PENALIDADE_NC_ENCERRADA;
+    componentes.push(ComponenteNota {
+        criterio: "Não conformidades".to_string(),
+        pontos: (PESO_NAO_CONFORMIDADES - penalidade).max(0.0),
+        maximo: PESO_NAO_CONFORMIDADES,
+        detalhe: format!("{} aberta(s), {} encerrada(s) no período", abertas, encerradas),
+    });
+
+    // Entregas: prazo médio contra a meta (só entra na nota se houver histórico de prazo)
+    let prazo_medio = entregas
+        .filter(|e| !e.prazos.is_empty())
+        .map(|e| e.prazos.iter().sum::<f64>() / e.prazos.len() as f64);
+    if let Some(media) = prazo_medio {
+        let meta = parametros.prazo_entrega_meta_dias.max(0.1);
+        componentes.push(ComponenteNota {
+            criterio: "Entregas".to_string(),
+            pontos: if media <= meta { PESO_ENTREGAS } else { PESO_ENTREGAS * meta / media },
+            maximo: PESO_ENTREGAS,
+            detalhe: format!("Prazo médio de {:.1} dia(s) (meta {:.0})", media, meta),
+        });
+    }
+
+    let maximo: f64 = componentes.iter().map(|c| c.maximo).sum();
+    let nota = (componentes.iter().map(|c| c.pontos).sum::<f64>() / maximo * 1000.0).round() / 10.0;
+    let classificacao = if situacao == SituacaoQualificacao::Vencida || nota < 60.0 {
+        ClassificacaoFornecedor::Reprovado
+    } else if nota < 80.0 {
+        ClassificacaoFornecedor::AprovadoComRestricao
+    } else {
+        ClassificacaoFornecedor::Aprovado
+    };
+
+    let data_requalificacao = match atual {
+        Some((_, validade)) if vigente => validade - chrono::Duration::days(parametros.antecedencia_requalificacao_dias as i64),
+        _ => hoje,
+    };
+
+    AvaliacaoFornecedor {
+        fornecedor_id: fornecedor.id,
+        nome: fornecedor
+            .fantasia
+            .clone()
+            .filter(|f| !f.trim().is_empty())
+            .or_else(|| fornecedor.nome.clone())
+            .unwrap_or_else(|| format!("Fornecedor {}", fornecedor.id)),
+        qualificado: fornecedor.qualificado,
+        situacao_qualificacao: situacao,
+        validade_qualificacao: atual.map(|(_, v)| v.format("%Y-%m-%d").to_string()),
+        data_requalificacao: Some(data_requalificacao.format("%Y-%m-%d").to_string()),
+        componentes,
+        nota,
+        classificacao,
+        nao_conformidades_abertas: abertas,
+        nao_conformidades_encerradas: encerradas,
+        entregas: entregas.map(|e| e.entregas).unwrap_or(0),
+        prazo_medio_entrega: prazo_medio,
+        desqualificado_automaticamente: false,
+    }
+}
+
+fn agenda_requalificacao(avaliacoes: Vec<AvaliacaoFornecedor>) -> Vec<AvaliacaoFornecedor> {
+    let mut agenda: Vec<AvaliacaoFornecedor> = avaliacoes
+        .into_iter()
+        // "Não se aplica" (-1) não entra na agenda
+        .filter(|a| a.qualificado != Some(-1) && a.situacao_qualificacao != SituacaoQualificacao::Vigente)
+        .collect();
+    agenda.sort_by(|a, b| {
+        a.situacao_qualificacao
+            .cmp(&b.situacao_qualificacao)
+            .then_with(|| a.data_requalificacao.cmp(&b.data_requalificacao))
+    });
+    agenda
+}
+
+// ==================== API ====================
+
+/// Entregas dos itens de estoque vinculados a cada fornecedor (vínculos de `reposicao_estoque_controller`)
+async fn coletar_entregas(
+    client: &Client,
+    api_url: &str,
+    desde: NaiveDate,
+    fornecedor_id: Option<u32>,
+) -> HashMap<u32, DadosEntrega> {
+    let mut dados: HashMap<u32, DadosEntrega> = HashMap::new();
+    let vinculos = match buscar_json::<Vec<VinculoFornecedorEstoque>>(client, &format!("{}/qualidade/estoque/fornecedores", api_url)).await {
+        Ok(vinculos) => vinculos,
+        Err(e) => {
+            println!("Falha ao carregar fornecedores dos itens de estoque: {}", e);
+            return dados;
+        }
+    };
+
+    let consultas = vinculos.into_iter().filter_map(|vinculo| {
+        let fornecedor = vinculo.fornecedor_id.filter(|f| fornecedor_id.is_none_or(|id| id == *f))?;
+        let url = format!("{}/qualidade/estoque/{}", api_url, vinculo.estoque_item_id);
+        Some(async move { (fornecedor, buscar_json::<EstoqueCompletoResponse>(client, &url).await) })
+    });
+    let respostas: Vec<_> = stream::iter(consultas).buffer_unordered(CONSULTAS_SIMULTANEAS).collect().await;
+
+    for (fornecedor, resposta) in respostas {
+        let Ok(completo) = resposta else {
+            continue;
+        };
+        let entrada = dados.entry(fornecedor).or_default();
+        entrada.entregas += completo.historico.iter().filter(|r| r.entrada && r.data >= desde).count();
+        // Só as reposições concluídas dentro do período da avaliação
+        entrada.prazos.extend(
+            prazos_entrega(&completo.detalhes, &completo.historico)
+                .into_iter()
+                .filter(|(data, _)| *data >= desde)
+                .map(|(_, prazo)| prazo),
+        );
+    }
+    dados
+}
+
+async fn gerar_relatorio(
+    app_handle: &AppHandle,
+    parametros: ParametrosAvaliacaoFornecedor,
+) -> Result<RelatorioAvaliacaoFornecedores, String> {
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+    let hoje = Local::now().date_naive();
+    let inicio = inicio_periodo(hoje, &parametros);
+
+    // Lista paginada da API; obsoletos ficam de fora
+    let mut ids = Vec::new();
+    let mut pagina = 1;
+    loop {
+        let url = format!("{}/qualidade/fornecedores?pagina={}&por_pagina=200", api_url, pagina);
+        let resposta = buscar_json::<RespostaPaginada>(&client, &url).await?;
+        let recebidos = resposta.itens.len();
+        ids.extend(resposta.itens.into_iter().filter(|f| f.OBSOLETO != Some(true)).map(|f| f.ID));
+        if recebidos == 0 || (pagina * 200) as i64 >= resposta.total {
+            break;
+        }
+        pagina += 1;
+    }
+
+    let entregas = coletar_entregas(&client, &api_url, inicio, None).await;
+    let respostas: Vec<_> = stream::iter(ids)
+        .map(|id| {
+            let (client, url) = (&client, format!("{}/qualidade/fornecedores/{}", api_url, id));
+            async move { (id, buscar_json::<FornecedorDetalhado>(client, &url).await) }
+        })
+        .buffer_unordered(CONSULTAS_SIMULTANEAS)
+        .collect()
+        .await;
+
+    let mut avaliacoes = Vec::new();
+    let mut indisponiveis = Vec::new();
+    for (id, resposta) in respostas {
+        match resposta {
+            Ok(fornecedor) => avaliacoes.push(avaliar(&fornecedor, entregas.get(&id), &parametros, hoje)),
+            Err(e) => indisponiveis.push(format!("Fornecedor {}: {}", id, e)),
+        }
+    }
+    indisponiveis.sort();
+    avaliacoes.sort_by(|a, b| b.nota.total_cmp(&a.nota).then_with(|| a.nome.cmp(&b.nome)));
+
+    Ok(RelatorioAvaliacaoFornecedores {
+        gerado_em: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        periodo_inicio: inicio.format("%Y-%m-%d").to_string(),
+        periodo_fim: hoje.format("%Y-%m-%d").to_string(),
+        parametros,
+        avaliacoes,
+        fornecedores_indisponiveis: indisponiveis,
+    })
+}
+
+async fn desqualificar_vencidos(
+    app_handle: &AppHandle,
+    avaliacoes: Vec<AvaliacaoFornecedor>,
+    usuario_id: u32,
+) -> Vec<AvaliacaoFornecedor> {
+    let client = Client::new();
+    let api_url = get_api_url(app_handle);
+    let mut alterados = Vec::new();
+
+    for mut avaliacao in avaliacoes
+        .into_iter()
+        .filter(|a| a.qualificado == Some(1) && a.situacao_qualificacao == SituacaoQualificacao::Vencida)
+    {
+        let url = format!("{}/qualidade/fornecedores/{}/qualificado", api_url, avaliacao.fornecedor_id);
+        let motivo = format!(
+            "Qualificação vencida em {}; fornecedor desqualificado automaticamente até a requalificação.",
+            avaliacao.validade_qualificacao.as_deref().unwrap_or("-")
+        );
+        match client.patch(&url).json(&AtualizacaoQualificadoPayload { qualificado: 0 }).send().await {
+            Ok(response) if response.status().is_success() => {
+                let historico = NovoHistoricoPayload {
+                    usuario_id,
+                    acao: "Fornecedor desqualificado".to_string(),
+                    descricao: format!("{} (id {}): {}", avaliacao.nome, avaliacao.fornecedor_id, motivo),
+                };
+                if let Err(e) = registrar_historico(&client, &api_url, &historico).await {
+                    println!("Falha ao registrar histórico do fornecedor {}: {}", avaliacao.fornecedor_id, e);
+                }
+                avaliacao.qualificado = Some(0);
+                avaliacao.desqualificado_automaticamente = true;
+                alterados.push(avaliacao);
+            }
+            Ok(response) => println!(
+                "Falha ao desqualificar fornecedor {}: Erro API ({})",
+                avaliacao.fornecedor_id,
+                response.status()
+            ),
+            Err(e) => println!("Falha ao desqualificar fornecedor {}: {}", avaliacao.fornecedor_id, e),
+        }
+    }
+    alterados
+}
+
+// ==================== RELATÓRIO ====================
+
+fn resolver_destino(caminho: Option<String>) -> Result<PathBuf, String> {
+    destino_exportacao(caminho, &format!("avaliacao_fornecedores_{}.pdf", Local::now().format("%Y%m%d")))
+}
+
+fn formatar_data(valor: Option<&str>) -> String {
+    data(valor).map(|d| d.format("%d/%m/%Y").to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn gerar_html(relatorio: &RelatorioAvaliacaoFornecedores) -> String {
+    let mut linhas = String::new();
+    for a in &relatorio.avaliacoes {
+        let pontos = |criterio: &str| {
+            a.componentes
+                .iter()
+                .find(|c| c.criterio == criterio)
+                .map(|c| format!("{:.1}/{:.0}", c.pontos, c.maximo))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let situacao = match a.situacao_qualificacao {
+            SituacaoQualificacao::Vigente => "Vigente",
+            SituacaoQualificacao::AVencer => "A vencer",
+            SituacaoQualificacao::Vencida => "Vencida",
+            SituacaoQualificacao::SemQualificacao => "Sem qualificação",
+        };
+        let classificacao = match a.classificacao {
+            ClassificacaoFornecedor::Aprovado => "Aprovado",
+            ClassificacaoFornecedor::AprovadoComRestricao => "Aprovado c/ restrição",
+            ClassificacaoFornecedor::Reprovado => "Reprovado",
+        };
+        linhas.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"num\"><b>{:.1}</b></td><td>{}</td></tr>",
+            escapar_html(&a.nome),
+            situacao,
+            formatar_data(a.validade_qualificacao.as_deref()),
+            formatar_data(a.data_requalificacao.as_deref()),
+            pontos("Qualificação"),
+            pontos("Certificação"),
+            pontos("Não conformidades"),
+            pontos("Entregas"),
+            a.nota,
+            classificacao,
+        ));
+    }
+
+    let introducao = format!(
+        "<p>Período de {} a {} &middot; Gerado em {}</p>\n<p>Critérios: qualificação ({:.0}), certificação ({:.0}), não conformidades ({:.0}), entregas ({:.0}). Aprovado a partir de 80; com restrição de 60 a 79; qualificação vencida reprova.</p>{}",
+        formatar_data(Some(&relatorio.periodo_inicio)),
+        formatar_data(Some(&relatorio.periodo_fim)),
+        Local::now().format("%d/%m/%Y %H:%M"),
+        PESO_QUALIFICACAO,
+        PESO_CERTIFICACAO,
+        PESO_NAO_CONFORMIDADES,
+        PESO_ENTREGAS,
+        aviso_html("Não avaliados", &relatorio.fornecedores_indisponiveis),
+    );
+    pagina_relatorio(
+        "Avaliação periódica de fornecedores",
+        &introducao,
+        &tabela_html(
+            &["Fornecedor", "Qualificação", "Validade", "Requalificar em", "Qualif.", "Certif.", "NC", "Entregas", "Nota", "Resultado"],
+            &linhas,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fornecedor::FornecedorObservacao;
+
+    fn fornecedor(validade: &str, avaliacao: &str, certificacao: Option<&str>) -> FornecedorDetalhado {
+        FornecedorDetalhado {
+            id: 3,
+            nome: Some("Química Sul Ltda".to_string()),
+            fantasia: Some("Química Sul".to_string()),
+            documento: None,
+            inscricao_estadual: None,
+            endereco: None,
+            numero: None,
+            bairro: None,
+            cidade: None,
+            uf: None,
+            cep: None,
+            telefone: None,
+            celular: None,
+            email: None,
+            site: None,
+            contato: None,
+            qualificado: Some(1),
+            obsoleto: Some(false),
+            categorias: Vec::new(),
+            observacoes: vec![
+                FornecedorObservacao {
+                    DATA_OBSERVACAO: Some("2025-03-01".to_string()),
+                    OBSERVACAO: Some("Laudo divergente".to_string()),
+                    ESTADO: Some(false),
+                },
+                FornecedorObservacao {
+                    DATA_OBSERVACAO: Some("2025-04-01".to_string()),
+                    OBSERVACAO: Some("Atraso".to_string()),
+                    ESTADO: Some(true),
+                },
+                FornecedorObservacao {
+                    DATA_OBSERVACAO: Some("2023-01-01".to_string()),
+                    OBSERVACAO: Some("Antiga".to_string()),
+                    ESTADO: Some(false),
+                },
+            ],
+            qualificacoes: vec![
+                FornecedorQualificacao {
+                    ID: 1,
+                    DATA_QUALIFICACAO: Some("2023-06-01".to_string()),
+                    RELATORIO: None,
+                    CERTIFICACAO: None,
+                    VALIDADE: Some("2024-06-01".to_string()),
+                    AVALIACAO: Some("4".to_string()),
+                },
+                FornecedorQualificacao {
+                    ID: 2,
+                    DATA_QUALIFICACAO: Some("2024-06-01".to_string()),
+                    RELATORIO: None,
+                    CERTIFICACAO: certificacao.map(str::to_string),
+                    VALIDADE: Some(validade.to_string()),
+                    AVALIACAO: Some(avaliacao.to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_avaliar_fornecedor() {
+        let hoje = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let parametros = ParametrosAvaliacaoFornecedor::default();
+        let entregas = DadosEntrega { entregas: 4, prazos: vec![10.0, 18.0] };
+
+        // 40*8/10 + 15 + (25 - 10 - 5) + 20*7/14 = 32 + 15 + 10 + 10 = 67
+        let a = avaliar(&fornecedor("2026-06-01", "8", Some("ISO 9001")), Some(&entregas), &parametros, hoje);
+        assert_eq!(a.situacao_qualificacao, SituacaoQualificacao::Vigente);
+        assert_eq!((a.nao_conformidades_abertas, a.nao_conformidades_encerradas), (1, 1));
+        assert_eq!(a.nota, 67.0);
+        assert_eq!(a.classificacao, ClassificacaoFornecedor::AprovadoComRestricao);
+        assert_eq!(a.data_requalificacao.as_deref(), Some("2026-04-02"));
+
+        // Sem entregas o critério sai da conta: (32 + 0 + 10) / 80
+        let a = avaliar(&fornecedor("2025-07-01", "8", None), None, &parametros, hoje);
+        assert_eq!(a.situacao_qualificacao, SituacaoQualificacao::AVencer);
+        assert_eq!(a.nota, 52.5);
+        assert_eq!(a.classificacao, ClassificacaoFornecedor::Reprovado);
+
+        let a = avaliar(&fornecedor("2025-05-31", "10", Some("ISO 9001")), None, &parametros, hoje);
+        assert_eq!(a.situacao_qualificacao, SituacaoQualificacao::Vencida);
+        assert_eq!(a.data_requalificacao.as_deref(), Some("2025-06-01"));
+        assert_eq!(a.classificacao, ClassificacaoFornecedor::Reprovado);
+    }
+}
@@ -10,6 +10,7 @@ use crate::model::estoque::{
 };
 use crate::model::dropdown_options::DropdownOption2;
 use crate::config::get_api_url;
+use bigdecimal::ToPrimitive;
 
 #[command]
 pub async fn listar_estoque_items_tauri(
@@ -159,6 +160,14 @@ pub async fn criar_estoque_registro_tauri(
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/qualidade/estoque/{}/registro", api_url, id);
 
+    if payload.quantidade <= 0 {
+        return Err(ApiResponse::error("A quantidade deve ser maior que zero.".to_string()));
+    }
+    if !payload.entrada {
+        let saldo = buscar_saldo_atual(&client, &api_url, id).await.map_err(ApiResponse::error)?;
+        validar_saida(saldo, payload.quantidade).map_err(ApiResponse::error)?;
+    }
+
     match client.post(&url).json(&payload).send().await {
         Ok(response) => {
             if response.status().is_success() {
@@ -227,4 +236,55 @@ pub async fn listar_unidades_compra_tauri(
             Err(ApiResponse::error(format!("Erro de conexão ao buscar unidades: {}", e)))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Saldo atual do item (sem saldo informado pela API, soma o histórico)
+pub async fn buscar_saldo_atual(client: &Client, api_url: &str, id: u32) -> Result<f64, String> {
+    let url = format!("{}/qualidade/estoque/{}", api_url, id);
+    let response = client.get(&url).send().await.map_err(|e| format!("Falha conexão: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+    let completo = response
+        .json::<EstoqueCompletoResponse>()
+        .await
+        .map_err(|e| format!("Erro JSON: {}", e))?;
+
+    Ok(completo
+        .detalhes
+        .saldo_atual
+        .as_ref()
+        .and_then(|s| s.to_f64())
+        .unwrap_or_else(|| {
+            completo
+                .historico
+                .iter()
+                .map(|r| if r.entrada { r.quantidade as f64 } else { -(r.quantidade as f64) })
+                .sum()
+        }))
+}
+
+/// Recusa saídas que deixariam o saldo negativo
+pub fn validar_saida(saldo_atual: f64, quantidade: i32) -> Result<(), String> {
+    if saldo_atual < f64::from(quantidade) {
+        return Err(format!(
+            "SALDO_INSUFICIENTE: saída de {} com saldo atual de {}.",
+            quantidade, saldo_atual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validar_saida() {
+        assert!(validar_saida(10.0, 10).is_ok());
+        assert!(validar_saida(10.0, 11).is_err());
+        assert!(validar_saida(0.0, 1).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 // Em src-tauri/src/controller/qualidade/fornecedor_controller.rs
 
 use tauri::{command, AppHandle};
-use tauri_plugin_opener::OpenerExt;
 use crate::config::get_api_url;
 use crate::model::api_response::ApiResponse;
 use crate::model::fornecedor::{FornecedorDetalhado, SalvarFornecedorPayload, FornecedorListagem, QualificacaoListagem, RespostaPaginada};
-use std::path::Path;
+use crate::model::documento::EscopoDocumento;
+use crate::controller::geral::armazenamento_documento_controller::abrir_pasta_escopo;
+use crate::validacao::{resposta_validacao, ErroCadastro, ErroCampo, Validador};
 
 // --- Comandos CRUD (Sem alterações) ---
 
@@ -38,8 +39,10 @@ pub async fn buscar_fornecedor_detalhado_tauri(
 #[command]
 pub async fn cadastrar_fornecedor_tauri(
     app_handle: AppHandle,
-    payload: SalvarFornecedorPayload
-) -> Result<ApiResponse<FornecedorDetalhado>, ApiResponse<()>> {
+    mut payload: SalvarFornecedorPayload
+) -> Result<ApiResponse<FornecedorDetalhado>, ErroCadastro> {
+    validar_fornecedor(&mut payload).map_err(resposta_validacao)?;
+
     let client = reqwest::Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/qualidade/fornecedores", api_url);
@@ -65,8 +68,10 @@ pub async fn cadastrar_fornecedor_tauri(
 pub async fn editar_fornecedor_tauri(
     app_handle: AppHandle,
     id: u32,
-    payload: SalvarFornecedorPayload
-) -> Result<ApiResponse<FornecedorDetalhado>, ApiResponse<()>> {
+    mut payload: SalvarFornecedorPayload
+) -> Result<ApiResponse<FornecedorDetalhado>, ErroCadastro> {
+    validar_fornecedor(&mut payload).map_err(resposta_validacao)?;
+
     let client = reqwest::Client::new();
     let api_url = get_api_url(&app_handle);
     let url = format!("{}/qualidade/fornecedores/{}", api_url, id);
@@ -111,26 +116,36 @@ pub async fn deletar_fornecedor_tauri(
     }
 }
 
+/// Normaliza documento, IE, CEP, telefones e e-mail antes de enviar à API
+fn validar_fornecedor(payload: &mut SalvarFornecedorPayload) -> Result<(), Vec<ErroCampo>> {
+    let mut v = Validador::new();
+    v.obrigatorio("nome", Some(&payload.nome), "Nome");
+    payload.documento = v.documento("documento", payload.documento.as_deref(), None);
+    payload.uf = v.uf("uf", payload.uf.as_deref());
+    payload.inscricao_estadual = v.inscricao_estadual("inscricao_estadual", payload.inscricao_estadual.as_deref(), payload.uf.as_deref());
+    payload.cep = v.cep("cep", payload.cep.as_deref());
+    payload.telefone = v.telefone("telefone", payload.telefone.as_deref());
+    payload.celular = v.telefone("celular", payload.celular.as_deref());
+    payload.email = v.email("email", payload.email.as_deref());
+    v.concluir()
+}
+
 // --- Comandos Adicionais ---
 
 /// Comando para abrir a pasta do fornecedor no explorador de arquivos.
+/// A raiz vem da configuração do armazenamento de documentos (`{raiz}/Fornecedores/{fantasia}`).
 #[command]
 pub async fn abrir_pasta_fornecedor_tauri(
     app_handle: AppHandle,
     fantasia: String
 ) -> Result<ApiResponse<()>, ApiResponse<()>> {
-    let base_path = Path::new("\\\\192.168.15.2\\HD Gerência\\Bck HD IDE\\Qualidade\\Fornecedores");
-    let full_path = base_path.join(fantasia);
-
-    // CORREÇÃO DEFINITIVA:
-    // O compilador nos mostrou que o método correto é '.open_url()'.
-    // Este método aceita o caminho e um segundo argumento opcional para especificar
-    // um programa. Passamos 'None' para usar o explorador de arquivos padrão do sistema.
-    match app_handle.opener().open_url(full_path.to_string_lossy(), None::<String>) {
-        Ok(_) => Ok(ApiResponse::success(format!("Pasta {} aberta.", full_path.display()), None)),
+    let escopo = EscopoDocumento { modulo: "Fornecedores".to_string(), referencia: fantasia };
+
+    match abrir_pasta_escopo(&app_handle, &escopo) {
+        Ok(pasta) => Ok(ApiResponse::success(format!("Pasta {} aberta.", pasta.display()), None)),
         Err(e) => {
-            eprintln!("Erro ao abrir pasta {}: {}", full_path.display(), e);
-            Err(ApiResponse::error(format!("Não foi possível abrir a pasta: {}", e)))
+            eprintln!("Erro ao abrir pasta do fornecedor {}: {}", escopo.referencia, e);
+            Err(ApiResponse::error(e))
         }
     }
 }
@@ -0,0 +1,289 @@
+use chrono::Local;
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::dados_locais::{alterar_json, ler_json};
+use crate::model::api_response::ApiResponse;
+use crate::model::estoque::EstoqueRegistroPayload;
+use crate::model::inventario_estoque::{
+    AbrirInventarioPayload, AjusteInventario, FinalizarInventarioPayload, ItemContagem,
+    RegistrarContagemPayload, SessaoInventario, StatusInventario,
+};
+use crate::controller::qualidade::estoque_controller::buscar_saldo_atual;
+use crate::controller::qualidade::reposicao_estoque_controller::buscar_itens_ativos;
+
+const ARQUIVO_INVENTARIOS: &str = "inventario_estoque.json";
+
+// ==================== COMANDOS TAURI ====================
+
+/// Abre uma contagem física; só pode haver uma sessão aberta por vez
+#[command]
+pub async fn abrir_inventario_estoque(
+    app_handle: AppHandle,
+    payload: AbrirInventarioPayload,
+) -> Result<ApiResponse<SessaoInventario>, ApiResponse<()>> {
+    if payload.descricao.trim().is_empty() {
+        return Err(ApiResponse::error("Informe uma descrição para o inventário.".to_string()));
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let mut itens = buscar_itens_ativos(&client, &api_url).await.map_err(ApiResponse::error)?;
+    if let Some(ids) = payload.item_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        itens.retain(|i| ids.contains(&i.id));
+    }
+    if itens.is_empty() {
+        return Err(ApiResponse::error("Nenhum item ativo para contar.".to_string()));
+    }
+    itens.sort_by_key(|i| i.nome.to_lowercase());
+
+    let sessao = SessaoInventario {
+        id: uuid::Uuid::new_v4().to_string(),
+        descricao: payload.descricao.trim().to_string(),
+        aberto_em: agora(),
+        aberto_por: payload.usuario_id,
+        status: StatusInventario::Aberto,
+        itens: itens
+            .into_iter()
+            .map(|i| ItemContagem {
+                item_id: i.id,
+                nome: i.nome,
+                unidade: i.unidade,
+                saldo_sistema: 0.0,
+                quantidade_contada: None,
+                diferenca: None,
+                contado_em: None,
+                contado_por: None,
+            })
+            .collect(),
+        finalizado_em: None,
+        finalizado_por: None,
+        motivo_ajuste: None,
+        ajustes: Vec::new(),
+    };
+
+    alterar_sessoes(&app_handle, |sessoes| {
+        if let Some(aberta) = sessoes.iter().find(|s| s.status == StatusInventario::Aberto) {
+            return Err(format!("Já existe um inventário aberto: {}.", aberta.descricao));
+        }
+        sessoes.push(sessao.clone());
+        Ok(())
+    })
+    .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        format!("Inventário aberto com {} item(ns).", sessao.itens.len()),
+        Some(sessao),
+    ))
+}
+
+#[command]
+pub async fn listar_inventarios_estoque(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<Vec<SessaoInventario>>, ApiResponse<()>> {
+    let mut sessoes: Vec<SessaoInventario> = ler_json(&app_handle, ARQUIVO_INVENTARIOS);
+    sessoes.reverse();
+    Ok(ApiResponse::success(format!("{} inventário(s).", sessoes.len()), Some(sessoes)))
+}
+
+/// Registra (ou corrige) a quantidade contada de um item, comparando com o saldo atual do sistema
+#[command]
+pub async fn registrar_contagem_estoque(
+    app_handle: AppHandle,
+    payload: RegistrarContagemPayload,
+) -> Result<ApiResponse<ItemContagem>, ApiResponse<()>> {
+    if payload.quantidade_contada < 0 {
+        return Err(ApiResponse::error("A quantidade contada não pode ser negativa.".to_string()));
+    }
+
+    let client = Client::new();
+    let saldo = buscar_saldo_atual(&client, &get_api_url(&app_handle), payload.item_id)
+        .await
+        .map_err(ApiResponse::error)?;
+
+    let mut contado = None;
+    alterar_sessoes(&app_handle, |sessoes| {
+        let item = sessao_aberta(sessoes, &payload.sessao_id)?
+            .itens
+            .iter_mut()
+            .find(|i| i.item_id == payload.item_id)
+            .ok_or("Item não faz parte deste inventário.")?;
+        item.saldo_sistema = saldo;
+        item.quantidade_contada = Some(payload.quantidade_contada);
+        item.diferenca = Some(payload.quantidade_contada as f64 - saldo);
+        item.contado_em = Some(agora());
+        item.contado_por = Some(payload.usuario_id);
+        contado = Some(item.clone());
+        Ok(())
+    })
+    .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Contagem registrada.".to_string(), contado))
+}
+
+/// Gera os registros de ajuste (entrada/saída) pela diferença entre o contado e o saldo
+/// do sistema na hora da contagem. Se algum ajuste falhar a sessão continua aberta;
+/// finalizar de novo envia só os itens que ainda não foram ajustados.
+#[command]
+pub async fn finalizar_inventario_estoque(
+    app_handle: AppHandle,
+    payload: FinalizarInventarioPayload,
+) -> Result<ApiResponse<SessaoInventario>, ApiResponse<()>> {
+    if payload.motivo.trim().is_empty() {
+        return Err(ApiResponse::error("Informe o motivo do ajuste.".to_string()));
+    }
+
+    let mut sessoes: Vec<SessaoInventario> = ler_json(&app_handle, ARQUIVO_INVENTARIOS);
+    let sessao = sessao_aberta(&mut sessoes, &payload.sessao_id).map_err(ApiResponse::error)?.clone();
+
+    let nao_contados: Vec<&str> = sessao
+        .itens
+        .iter()
+        .filter(|i| i.quantidade_contada.is_none())
+        .map(|i| i.nome.as_str())
+        .collect();
+    if !nao_contados.is_empty() && !payload.ignorar_nao_contados {
+        return Err(ApiResponse::error(format!("NAO_CONTADOS: {}", nao_contados.join(", "))));
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let observacao = format!("Ajuste de inventário ({}): {}", sessao.descricao, payload.motivo.trim());
+    let mut ajustes = Vec::new();
+
+    // Itens já ajustados numa tentativa anterior não entram de novo
+    let pendentes = sessao.itens.iter().filter(|i| {
+        i.quantidade_contada.is_some() && !sessao.ajustes.iter().any(|a| a.registrado && a.item_id == i.item_id)
+    });
+    for item in pendentes {
+        let contado = item.quantidade_contada.unwrap_or_default();
+        // Compara com o saldo da hora da contagem: movimentações feitas depois dela
+        // já estão no saldo atual e não podem ser desfeitas pelo ajuste
+        let Some((entrada, quantidade)) = calcular_ajuste(contado, item.saldo_sistema) else {
+            continue;
+        };
+        let momento = Local::now();
+        let registro = EstoqueRegistroPayload {
+            data: momento.date_naive(),
+            hora: momento.time(),
+            entrada,
+            quantidade,
+            observacao: observacao.clone(),
+        };
+        let url = format!("{}/qualidade/estoque/{}/registro", api_url, item.item_id);
+        let erro = match client.post(&url).json(&registro).send().await {
+            Ok(response) if response.status().is_success() => None,
+            Ok(response) => {
+                let status = response.status();
+                let msg = response.text().await.unwrap_or_default();
+                Some(format!("Erro API ({}): {}", status, msg))
+            }
+            Err(e) => Some(format!("Falha conexão: {}", e)),
+        };
+
+        ajustes.push(AjusteInventario {
+            item_id: item.item_id,
+            nome: item.nome.clone(),
+            entrada,
+            quantidade,
+            observacao: observacao.clone(),
+            registrado: erro.is_none(),
+            erro,
+        });
+    }
+
+    let falhas = ajustes.iter().filter(|a| !a.registrado).count();
+    let mut finalizada = None;
+    alterar_sessoes(&app_handle, |sessoes| {
+        let sessao = sessao_aberta(sessoes, &payload.sessao_id)?;
+        // Mantém o histórico dos ajustes de tentativas anteriores
+        sessao.ajustes.retain(|a| a.registrado);
+        sessao.ajustes.extend(ajustes.iter().cloned());
+        sessao.motivo_ajuste = Some(payload.motivo.trim().to_string());
+        if falhas == 0 {
+            sessao.status = StatusInventario::Finalizado;
+            sessao.finalizado_em = Some(agora());
+            sessao.finalizado_por = Some(payload.usuario_id);
+        }
+        finalizada = Some(sessao.clone());
+        Ok(())
+    })
+    .map_err(ApiResponse::error)?;
+
+    if falhas > 0 {
+        return Err(ApiResponse::error(format!(
+            "{} ajuste(s) não puderam ser registrados. O inventário continua aberto; finalize novamente.",
+            falhas
+        )));
+    }
+
+    let total = ajustes.len();
+    Ok(ApiResponse::success(
+        format!("Inventário finalizado com {} ajuste(s).", total),
+        finalizada,
+    ))
+}
+
+#[command]
+pub async fn cancelar_inventario_estoque(
+    app_handle: AppHandle,
+    sessao_id: String,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    alterar_sessoes(&app_handle, |sessoes| {
+        let sessao = sessao_aberta(sessoes, &sessao_id)?;
+        sessao.status = StatusInventario::Cancelado;
+        sessao.finalizado_em = Some(agora());
+        Ok(())
+    })
+    .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Inventário cancelado.".to_string(), None))
+}
+
+// ==================== AUXILIARES ====================
+
+fn agora() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Entrada/saída que leva o saldo do sistema à quantidade contada; `None` se não há diferença
+fn calcular_ajuste(contado: i32, saldo: f64) -> Option<(bool, i32)> {
+    let diferenca = (contado as f64 - saldo).round() as i32;
+    match diferenca {
+        0 => None,
+        d if d > 0 => Some((true, d)),
+        d => Some((false, -d)),
+    }
+}
+
+fn sessao_aberta<'a>(sessoes: &'a mut [SessaoInventario], id: &str) -> Result<&'a mut SessaoInventario, String> {
+    let sessao = sessoes
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| "Inventário não encontrado.".to_string())?;
+    if sessao.status != StatusInventario::Aberto {
+        return Err("Este inventário já foi encerrado.".to_string());
+    }
+    Ok(sessao)
+}
+
+/// Altera as sessões gravadas sob o lock dos dados locais
+fn alterar_sessoes<F>(app_handle: &AppHandle, alterar: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Vec<SessaoInventario>) -> Result<(), String>,
+{
+    alterar_json(app_handle, ARQUIVO_INVENTARIOS, alterar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calcular_ajuste() {
+        assert_eq!(calcular_ajuste(12, 10.0), Some((true, 2)));
+        assert_eq!(calcular_ajuste(7, 10.0), Some((false, 3)));
+        assert_eq!(calcular_ajuste(10, 10.0), None);
+        assert_eq!(calcular_ajuste(0, 2.4), Some((false, 2)));
+    }
+}
@@ -3,19 +3,12 @@ use serde::{Deserialize, Serialize};
 use tauri::command;
 
 use crate::config::get_api_url;
+use crate::model::template_planilha::{NovaVersaoTemplatePayload, TemplateData, VersaoTemplate};
+use crate::utils::buscar_json;
+use crate::validacao::template::validar_template_base64;
+use crate::model::usuario::get_user_id;
 use tauri::AppHandle;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TemplateData {
-    pub id: Option<u64>,
-    pub caminho_arquivo: String,
-    pub nome_arquivo: String,
-    pub tag: Option<String>,
-    pub tipo: String,
-    pub json_data_base64: Option<String>,
-    pub updated_at: Option<String>, 
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiRequest {
     caminho_arquivo: String,
@@ -52,6 +45,7 @@ pub async fn save_template(app_handle: AppHandle, template_data: TemplateData) -
     if json_data_base64.trim().is_empty() {
         return Err("Dados JSON não podem estar vazios".to_string());
     }
+    validar_conteudo(&json_data_base64)?;
 
     // Preparar dados para envio à API
     let api_request = ApiRequest {
@@ -264,10 +258,14 @@ pub async fn update_template(app_handle: AppHandle, template_data: TemplateData)
     if json_data_base64.trim().is_empty() {
         return Err("Dados JSON não podem estar vazios".to_string());
     }
+    validar_conteudo(&json_data_base64)?;
     
     // Validar se ID existe (obrigatório para update)
     let id_value = template_data.id.ok_or("ID do template é obrigatório para atualização")?;
 
+    // Guarda o conteúdo atual como versão antes de sobrescrever
+    arquivar_versao_atual(&app_handle, id_value, &json_data_base64).await?;
+
     // Preparar dados para envio à API
     let api_request = ApiRequest {  // Certifique-se de usar a struct correta
         caminho_arquivo: template_data.caminho_arquivo,
@@ -325,4 +323,69 @@ pub async fn update_template(app_handle: AppHandle, template_data: TemplateData)
             Err(error_msg)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Rejeita conteúdo fora do schema ou com marcadores desconhecidos
+fn validar_conteudo(json_data_base64: &str) -> Result<(), String> {
+    let validacao = validar_template_base64(json_data_base64);
+    if validacao.valido {
+        return Ok(());
+    }
+    let erros: Vec<String> = validacao
+        .erros
+        .iter()
+        .map(|e| match &e.celula {
+            Some(celula) => format!("{}: {}", celula, e.mensagem),
+            None => e.mensagem.clone(),
+        })
+        .collect();
+    Err(format!("Template inválido: {}", erros.join("; ")))
+}
+
+async fn arquivar_versao_atual(app_handle: &AppHandle, id: u64, novo_conteudo: &str) -> Result<(), String> {
+    let api_url = get_api_url(app_handle);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/templates/{}", api_url, id))
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Erro ao conectar com a API: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Erro ao buscar template atual ({})", response.status()));
+    }
+    let atual = response
+        .json::<TemplateData>()
+        .await
+        .map_err(|e| format!("Erro ao parsear template: {}", e))?;
+    let Some(conteudo_atual) = atual.json_data_base64.filter(|c| !c.trim().is_empty()) else {
+        return Ok(());
+    };
+    if conteudo_atual.trim() == novo_conteudo.trim() {
+        return Ok(());
+    }
+    // Não duplica a última versão se uma atualização anterior falhou depois de arquivar
+    let versoes: Vec<VersaoTemplate> = buscar_json(&client, &format!("{}/templates/{}/versoes", api_url, id)).await?;
+    if versoes.iter().max_by_key(|v| v.versao).is_some_and(|v| v.json_data_base64.trim() == conteudo_atual.trim()) {
+        return Ok(());
+    }
+
+    let payload = NovaVersaoTemplatePayload {
+        nome_arquivo: atual.nome_arquivo,
+        json_data_base64: conteudo_atual,
+        usuario_id: get_user_id(),
+    };
+    let response = client
+        .post(format!("{}/templates/{}/versoes", api_url, id))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Erro ao conectar com a API: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_else(|_| "Erro desconhecido".to_string());
+        return Err(format!("Erro ao arquivar versão do template ({}): {}", status, error_text));
+    }
+    Ok(())
+}
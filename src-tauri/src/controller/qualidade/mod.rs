@@ -5,4 +5,9 @@ pub mod tauri_print_commands_controller;
 pub mod json_parser_controller;
 pub mod fornecedor_controller;
 pub mod pesquisa_controller;    
-pub mod estoque_controller;
\ No newline at end of file
+pub mod estoque_controller;
+pub mod reposicao_estoque_controller;
+pub mod inventario_estoque_controller;
+pub mod avaliacao_fornecedor_controller;
+pub mod pesquisa_envio_controller;
+pub mod template_planilha_controller;
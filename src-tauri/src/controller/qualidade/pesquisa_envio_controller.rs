@@ -0,0 +1,733 @@
+use std::collections::HashMap;
+use chrono::{Local, NaiveDateTime};
+use lettre::{AsyncSmtpTransport, Tokio1Executor};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::dados_locais::{gravar_json, ler_json};
+use crate::email::{criar_transporte, enviar_email, guardar_senha, ler_senha};
+use crate::model::email::ConfiguracaoSmtp;
+use crate::model::api_response::ApiResponse;
+use crate::model::pesquisa::{DestinatarioPesquisa, PesquisaDetalhada, PesquisaItem, ResultadoItem};
+use crate::model::pesquisa_envio::{
+    AnalisePesquisa, ConfiguracaoSmtpPesquisa, ConvitePesquisa, DistribuicaoResposta, EnviarConvitesPayload,
+    EnviarLembretesPayload, EnvioConviteApiPayload, EstatisticaItemPesquisa, FalhaEnvio, NpsItem,
+    RegistroConviteApiPayload, ResultadoEnvioPesquisa,
+};
+use crate::utils::buscar_json;
+
+const ARQUIVO_SMTP: &str = "smtp_pesquisa.json";
+const CREDENCIAL_SMTP: &str = "smtp_pesquisa";
+/// Fração da escala abaixo da qual a média do item é sinalizada na análise
+const META_SATISFACAO: f64 = 0.6;
+/// Abaixo deste percentual de respostas a análise recomenda reforçar os lembretes
+const TAXA_RESPOSTA_MINIMA: f64 = 30.0;
+
+// ==================== CONFIGURAÇÃO SMTP ====================
+
+/// Retorna a configuração sem a senha
+#[command]
+pub async fn carregar_config_smtp(
+    app_handle: AppHandle,
+) -> Result<ApiResponse<ConfiguracaoSmtpPesquisa>, ApiResponse<()>> {
+    let mut configuracao = ler_arquivo_smtp(&app_handle);
+    configuracao.smtp.senha = None;
+    Ok(ApiResponse::success("Configuração carregada.".to_string(), Some(configuracao)))
+}
+
+#[command]
+pub async fn salvar_config_smtp(
+    app_handle: AppHandle,
+    mut configuracao: ConfiguracaoSmtpPesquisa,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    validar_config_smtp(&configuracao).map_err(ApiResponse::error)?;
+    // A senha vai para o cofre do sistema; a de um arquivo antigo é migrada no primeiro salvamento
+    if let Some(senha) = configuracao.smtp.senha.take().or_else(|| ler_arquivo_smtp(&app_handle).smtp.senha) {
+        guardar_senha(CREDENCIAL_SMTP, &senha).map_err(ApiResponse::error)?;
+    }
+
+    gravar_json(&app_handle, ARQUIVO_SMTP, &configuracao).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success("Configuração SMTP salva.".to_string(), None))
+}
+
+/// Envia uma mensagem de teste com a configuração salva
+#[command]
+pub async fn testar_config_smtp(
+    app_handle: AppHandle,
+    email_destino: String,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    let configuracao = ler_config_smtp(&app_handle).map_err(ApiResponse::error)?;
+    validar_config_smtp(&configuracao).map_err(ApiResponse::error)?;
+    let transporte = criar_transporte(&configuracao.smtp).map_err(ApiResponse::error)?;
+
+    enviar_email(
+        &transporte,
+        &configuracao.smtp,
+        &email_destino,
+        &email_destino,
+        "Teste de envio - pesquisa de satisfação",
+        "Mensagem de teste. Se você a recebeu, o envio de convites está configurado.".to_string(),
+    )
+    .await
+    .map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(format!("E-mail de teste enviado para {}.", email_destino), None))
+}
+
+// ==================== CONVITES ====================
+
+/// Gera um token por destinatário e envia o convite com o link de resposta
+#[command]
+pub async fn enviar_convites_pesquisa(
+    app_handle: AppHandle,
+    payload: EnviarConvitesPayload,
+) -> Result<ApiResponse<ResultadoEnvioPesquisa>, ApiResponse<()>> {
+    let configuracao = ler_config_smtp(&app_handle).map_err(ApiResponse::error)?;
+    validar_config_smtp(&configuracao).map_err(ApiResponse::error)?;
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let pesquisa = buscar_pesquisa(&client, &api_url, payload.pesquisa_id).await.map_err(ApiResponse::error)?;
+    if pesquisa.finalizada {
+        return Err(ApiResponse::error("A pesquisa está finalizada.".to_string()));
+    }
+    let destinatarios = buscar_destinatarios(&client, &api_url, payload.pesquisa_id).await.map_err(ApiResponse::error)?;
+    let existentes: HashMap<u32, ConvitePesquisa> = buscar_convites(&client, &api_url, payload.pesquisa_id)
+        .await
+        .map_err(ApiResponse::error)?
+        .into_iter()
+        .map(|c| (c.destinatario_id, c))
+        .collect();
+
+    let selecionados: Vec<&DestinatarioPesquisa> = destinatarios
+        .iter()
+        .filter(|d| match &payload.destinatario_ids {
+            Some(ids) => ids.contains(&d.id),
+            None => !d.enviado && existentes.get(&d.id).is_none_or(|c| c.enviado_em.is_none()),
+        })
+        .collect();
+    if selecionados.is_empty() {
+        return Err(ApiResponse::error("Nenhum destinatário pendente de convite.".to_string()));
+    }
+
+    let transporte = criar_transporte(&configuracao.smtp).map_err(ApiResponse::error)?;
+    let descricao = pesquisa.descricao.clone().unwrap_or_default();
+    let mut resultado = ResultadoEnvioPesquisa { enviados: 0, ignorados: 0, falhas: Vec::new() };
+
+    for destinatario in selecionados {
+        if destinatario.respondido {
+            resultado.ignorados += 1;
+            continue;
+        }
+        let Some(email) = destinatario.email.as_deref().map(str::trim).filter(|e| !e.is_empty()) else {
+            resultado.falhas.push(falha(destinatario, "Destinatário sem e-mail."));
+            continue;
+        };
+
+        let mut convite = existentes.get(&destinatario.id).cloned().unwrap_or_else(|| ConvitePesquisa {
+            pesquisa_id: payload.pesquisa_id,
+            destinatario_id: destinatario.id,
+            nome: destinatario.nome.clone(),
+            email: email.to_string(),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            enviado_em: None,
+            lembretes_enviados: 0,
+            ultimo_lembrete_em: None,
+            respondido: false,
+            ultimo_erro: None,
+        });
+        convite.email = email.to_string();
+        // Reenvio explícito a quem já recebeu conta como lembrete e preserva a data do convite
+        let reenvio = convite.enviado_em.is_some();
+
+        let link = montar_link(&configuracao.url_resposta, &convite.token);
+        let corpo = preencher_mensagem(&payload.mensagem, &convite.nome, &descricao, &link);
+        match enviar_convite(&client, &api_url, &transporte, &configuracao.smtp, &convite, &payload.assunto, corpo, reenvio).await {
+            Ok(()) => resultado.enviados += 1,
+            Err(motivo) => resultado.falhas.push(falha(destinatario, &motivo)),
+        }
+    }
+
+    Ok(ApiResponse::success(
+        format!("{} convite(s) enviado(s), {} falha(s).", resultado.enviados, resultado.falhas.len()),
+        Some(resultado),
+    ))
+}
+
+/// Reenvia o link a quem ainda não respondeu, respeitando intervalo e limite de lembretes
+#[command]
+pub async fn enviar_lembretes_pesquisa(
+    app_handle: AppHandle,
+    payload: EnviarLembretesPayload,
+) -> Result<ApiResponse<ResultadoEnvioPesquisa>, ApiResponse<()>> {
+    let configuracao = ler_config_smtp(&app_handle).map_err(ApiResponse::error)?;
+    validar_config_smtp(&configuracao).map_err(ApiResponse::error)?;
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let pesquisa = buscar_pesquisa(&client, &api_url, payload.pesquisa_id).await.map_err(ApiResponse::error)?;
+    if pesquisa.finalizada {
+        return Err(ApiResponse::error("A pesquisa está finalizada.".to_string()));
+    }
+    let agora = Local::now().naive_local();
+    let mut resultado = ResultadoEnvioPesquisa { enviados: 0, ignorados: 0, falhas: Vec::new() };
+    let mut pendentes = Vec::new();
+    for convite in buscar_convites(&client, &api_url, payload.pesquisa_id).await.map_err(ApiResponse::error)? {
+        if convite.enviado_em.is_none() {
+            continue;
+        }
+        if precisa_lembrete(&convite, agora, payload.intervalo_dias, payload.maximo_lembretes) {
+            pendentes.push(convite);
+        } else {
+            resultado.ignorados += 1;
+        }
+    }
+
+    if !pendentes.is_empty() {
+        let transporte = criar_transporte(&configuracao.smtp).map_err(ApiResponse::error)?;
+        let descricao = pesquisa.descricao.clone().unwrap_or_default();
+        for convite in pendentes {
+            let link = montar_link(&configuracao.url_resposta, &convite.token);
+            let corpo = preencher_mensagem(&payload.mensagem, &convite.nome, &descricao, &link);
+            match enviar_convite(&client, &api_url, &transporte, &configuracao.smtp, &convite, &payload.assunto, corpo, true).await {
+                Ok(()) => resultado.enviados += 1,
+                Err(motivo) => resultado.falhas.push(FalhaEnvio {
+                    destinatario_id: convite.destinatario_id,
+                    nome: convite.nome.clone(),
+                    motivo,
+                }),
+            }
+        }
+    }
+
+    Ok(ApiResponse::success(
+        format!("{} lembrete(s) enviado(s), {} falha(s).", resultado.enviados, resultado.falhas.len()),
+        Some(resultado),
+    ))
+}
+
+/// Convites da pesquisa com as datas de envio e a situação de resposta
+#[command]
+pub async fn listar_convites_pesquisa(
+    app_handle: AppHandle,
+    pesquisa_id: u32,
+) -> Result<ApiResponse<Vec<ConvitePesquisa>>, ApiResponse<()>> {
+    let client = Client::new();
+    let mut convites = buscar_convites(&client, &get_api_url(&app_handle), pesquisa_id)
+        .await
+        .map_err(ApiResponse::error)?;
+    convites.sort_by_key(|c| c.nome.to_lowercase());
+
+    Ok(ApiResponse::success(format!("{} convite(s).", convites.len()), Some(convites)))
+}
+
+// ==================== ANÁLISE ====================
+
+/// Estatísticas por item (distribuição, média, NPS) e rascunho da análise crítica
+#[command]
+pub async fn analisar_pesquisa(
+    app_handle: AppHandle,
+    pesquisa_id: u32,
+) -> Result<ApiResponse<AnalisePesquisa>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let pesquisa = buscar_pesquisa(&client, &api_url, pesquisa_id).await.map_err(ApiResponse::error)?;
+    let destinatarios = buscar_destinatarios(&client, &api_url, pesquisa_id).await.map_err(ApiResponse::error)?;
+    let itens: Vec<PesquisaItem> = buscar_json(
+        &client,
+        &format!("{}/qualidade/pesquisa-modelos/{}/itens", api_url, pesquisa.modelo_id),
+    )
+    .await
+    .map_err(ApiResponse::error)?;
+
+    let mut estatisticas = Vec::new();
+    let mut itens_indisponiveis = Vec::new();
+    for item in itens {
+        let url = format!(
+            "{}/qualidade/pesquisas/{}/resultados/{}",
+            api_url,
+            pesquisa_id,
+            urlencoding::encode(&item.descricao)
+        );
+        match buscar_json::<Vec<ResultadoItem>>(&client, &url).await {
+            Ok(resultados) => estatisticas.push(calcular_estatisticas_item(&item.descricao, &resultados)),
+            Err(e) => itens_indisponiveis.push(format!("{} ({})", item.descricao, e)),
+        }
+    }
+
+    let total_destinatarios = destinatarios.len();
+    let enviados = destinatarios.iter().filter(|d| d.enviado).count();
+    let respondidos = destinatarios.iter().filter(|d| d.respondido).count();
+    let mut analise = AnalisePesquisa {
+        pesquisa_id,
+        descricao: pesquisa.descricao.unwrap_or_default(),
+        total_destinatarios,
+        enviados,
+        respondidos,
+        taxa_resposta: if enviados > 0 { respondidos as f64 / enviados as f64 * 100.0 } else { 0.0 },
+        aprovacao_sugerida: estatisticas.iter().all(|e| !e.abaixo_da_meta),
+        itens: estatisticas,
+        itens_indisponiveis,
+        texto_sugerido: String::new(),
+    };
+    analise.texto_sugerido = redigir_analise(&analise);
+
+    Ok(ApiResponse::success("Análise calculada.".to_string(), Some(analise)))
+}
+
+// ==================== AUXILIARES ====================
+
+fn ler_arquivo_smtp(app_handle: &AppHandle) -> ConfiguracaoSmtpPesquisa {
+    ler_json(app_handle, ARQUIVO_SMTP)
+}
+
+/// Configuração do arquivo com a senha lida do cofre do sistema
+fn ler_config_smtp(app_handle: &AppHandle) -> Result<ConfiguracaoSmtpPesquisa, String> {
+    let mut configuracao = ler_arquivo_smtp(app_handle);
+    if configuracao.smtp.senha.is_none() {
+        configuracao.smtp.senha = ler_senha(CREDENCIAL_SMTP)?;
+    }
+    Ok(configuracao)
+}
+
+fn validar_config_smtp(configuracao: &ConfiguracaoSmtpPesquisa) -> Result<(), String> {
+    crate::email::validar_config_smtp(&configuracao.smtp)?;
+    if !configuracao.url_resposta.starts_with("http://") && !configuracao.url_resposta.starts_with("https://") {
+        return Err("Informe a URL da página de resposta (http/https).".to_string());
+    }
+    Ok(())
+}
+
+/// Registra o token na API antes de mandar o e-mail, para nenhum link chegar sem validade.
+/// Depois informa o resultado do SMTP, e a API só conta o envio quando o e-mail saiu.
+#[allow(clippy::too_many_arguments)]
+async fn enviar_convite(
+    client: &Client,
+    api_url: &str,
+    transporte: &AsyncSmtpTransport<Tokio1Executor>,
+    configuracao: &ConfiguracaoSmtp,
+    convite: &ConvitePesquisa,
+    assunto: &str,
+    corpo: String,
+    lembrete: bool,
+) -> Result<(), String> {
+    let url = format!("{}/qualidade/pesquisas/{}/convites", api_url, convite.pesquisa_id);
+    let registro = RegistroConviteApiPayload {
+        destinatario_id: convite.destinatario_id,
+        email: convite.email.clone(),
+        token: convite.token.clone(),
+    };
+    enviar_para_api(client.post(&url).json(&registro))
+        .await
+        .map_err(|e| format!("Token não registrado, e-mail não enviado: {}", e))?;
+
+    let envio = enviar_email(transporte, configuracao, &convite.nome, &convite.email, assunto, corpo).await;
+    let resultado = EnvioConviteApiPayload {
+        data_envio: agora(),
+        lembrete,
+        erro: envio.as_ref().err().cloned(),
+    };
+    let url = format!("{}/{}/envio", url, convite.destinatario_id);
+    let gravado = enviar_para_api(client.put(&url).json(&resultado)).await;
+
+    envio?;
+    gravado.map_err(|e| format!("E-mail enviado, mas o envio não foi registrado: {}", e))
+}
+
+async fn enviar_para_api(requisicao: reqwest::RequestBuilder) -> Result<(), String> {
+    let response = requisicao.send().await.map_err(|e| format!("Falha conexão: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+    Ok(())
+}
+
+fn falha(destinatario: &DestinatarioPesquisa, motivo: &str) -> FalhaEnvio {
+    FalhaEnvio {
+        destinatario_id: destinatario.id,
+        nome: destinatario.nome.clone(),
+        motivo: motivo.to_string(),
+    }
+}
+
+fn montar_link(url_resposta: &str, token: &str) -> String {
+    let separador = if url_resposta.contains('?') { '&' } else { '?' };
+    format!("{}{}token={}", url_resposta.trim(), separador, token)
+}
+
+fn preencher_mensagem(modelo: &str, nome: &str, pesquisa: &str, link: &str) -> String {
+    let texto = modelo
+        .replace("{nome}", nome)
+        .replace("{pesquisa}", pesquisa)
+        .replace("{link}", link);
+    // Garante o link mesmo quando o modelo não tem o marcador
+    if modelo.contains("{link}") {
+        texto
+    } else {
+        format!("{}\n\n{}", texto.trim_end(), link)
+    }
+}
+
+fn precisa_lembrete(convite: &ConvitePesquisa, agora: NaiveDateTime, intervalo_dias: i64, maximo: u32) -> bool {
+    if convite.respondido || convite.lembretes_enviados >= maximo {
+        return false;
+    }
+    let ultimo_envio = convite
+        .ultimo_lembrete_em
+        .as_deref()
+        .or(convite.enviado_em.as_deref())
+        .and_then(|d| NaiveDateTime::parse_from_str(d, "%Y-%m-%d %H:%M:%S").ok());
+    ultimo_envio.is_some_and(|data| (agora - data).num_days() >= intervalo_dias)
+}
+
+/// Respostas conceituais comuns nos modelos de pesquisa
+fn nota_conceito(resposta: &str) -> Option<f64> {
+    match resposta.trim().to_lowercase().as_str() {
+        "excelente" | "ótimo" | "otimo" => Some(5.0),
+        "bom" => Some(4.0),
+        "regular" => Some(3.0),
+        "ruim" => Some(2.0),
+        "péssimo" | "pessimo" => Some(1.0),
+        _ => None,
+    }
+}
+
+fn nota_numerica(resposta: &str) -> Option<f64> {
+    resposta.trim().replace(',', ".").parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn calcular_estatisticas_item(item: &str, resultados: &[ResultadoItem]) -> EstatisticaItemPesquisa {
+    let validos: Vec<&ResultadoItem> = resultados.iter().filter(|r| r.total > 0).collect();
+    let total: i64 = validos.iter().map(|r| r.total).sum();
+
+    let mut distribuicao: Vec<DistribuicaoResposta> = validos
+        .iter()
+        .map(|r| DistribuicaoResposta {
+            resposta: r.resposta.clone(),
+            total: r.total,
+            percentual: if total > 0 { r.total as f64 / total as f64 * 100.0 } else { 0.0 },
+        })
+        .collect();
+    distribuicao.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.resposta.cmp(&b.resposta)));
+
+    let numericos: Vec<(f64, i64)> = validos.iter().filter_map(|r| nota_numerica(&r.resposta).map(|v| (v, r.total))).collect();
+    let conceituais: Vec<(f64, i64)> = validos.iter().filter_map(|r| nota_conceito(&r.resposta).map(|v| (v, r.total))).collect();
+    let todos_numericos = !numericos.is_empty() && numericos.len() == validos.len();
+    let maior = numericos.iter().map(|(v, _)| *v).fold(f64::MIN, f64::max);
+    let menor = numericos.iter().map(|(v, _)| *v).fold(f64::MAX, f64::min);
+
+    // Escala 0..10 só quando todas as respostas são notas; conceitos valem 1..5
+    let (valores, escala_maxima) = if todos_numericos && menor >= 0.0 && maior > 5.0 && maior <= 10.0 {
+        (numericos.clone(), Some(10.0))
+    } else if todos_numericos && menor >= 0.0 && maior <= 5.0 {
+        (numericos.clone(), Some(5.0))
+    } else if !conceituais.is_empty() {
+        (conceituais, Some(5.0))
+    } else if todos_numericos {
+        (numericos.clone(), Some(maior))
+    } else {
+        (Vec::new(), None)
+    };
+
+    let peso: i64 = valores.iter().map(|(_, t)| *t).sum();
+    let media = (peso > 0).then(|| valores.iter().map(|(v, t)| v * *t as f64).sum::<f64>() / peso as f64);
+
+    let nps = (escala_maxima == Some(10.0) && total > 0).then(|| {
+        let contar = |filtro: &dyn Fn(f64) -> bool| numericos.iter().filter(|(v, _)| filtro(*v)).map(|(_, t)| *t).sum::<i64>();
+        let promotores = contar(&|v| v >= 9.0);
+        let detratores = contar(&|v| v <= 6.0);
+        NpsItem {
+            promotores,
+            neutros: total - promotores - detratores,
+            detratores,
+            nps: (promotores - detratores) as f64 / total as f64 * 100.0,
+        }
+    });
+
+    let abaixo_da_meta = nps.as_ref().is_some_and(|n| n.nps < 0.0)
+        || matches!((media, escala_maxima), (Some(m), Some(e)) if e > 0.0 && m / e < META_SATISFACAO);
+
+    EstatisticaItemPesquisa {
+        item: item.to_string(),
+        total_respostas: total,
+        distribuicao,
+        media,
+        escala_maxima,
+        nps,
+        abaixo_da_meta,
+    }
+}
+
+fn decimal(valor: f64, casas: usize) -> String {
+    format!("{:.*}", casas, valor).replace('.', ",")
+}
+
+fn redigir_analise(analise: &AnalisePesquisa) -> String {
+    let mut texto = format!(
+        "Análise crítica da pesquisa \"{}\".\nParticipação: {} de {} convidados responderam ({}%).",
+        analise.descricao,
+        analise.respondidos,
+        analise.enviados,
+        decimal(analise.taxa_resposta, 1)
+    );
+    if analise.taxa_resposta < TAXA_RESPOSTA_MINIMA {
+        texto.push_str(" A taxa de resposta está baixa; recomenda-se reforçar os lembretes antes de concluir.");
+    }
+
+    if !analise.itens.is_empty() {
+        texto.push_str("\n\nResultados por item:");
+    }
+    for item in &analise.itens {
+        let mut partes = Vec::new();
+        if let (Some(media), Some(escala)) = (item.media, item.escala_maxima) {
+            partes.push(format!("média {} de {}", decimal(media, 2), decimal(escala, 0)));
+        }
+        if let Some(nps) = &item.nps {
+            partes.push(format!(
+                "NPS {} ({} promotores, {} neutros, {} detratores)",
+                decimal(nps.nps, 0),
+                nps.promotores,
+                nps.neutros,
+                nps.detratores
+            ));
+        }
+        if let Some(principal) = item.distribuicao.first() {
+            partes.push(format!("resposta mais frequente \"{}\" ({}%)", principal.resposta, decimal(principal.percentual, 1)));
+        }
+        if partes.is_empty() {
+            partes.push("sem respostas".to_string());
+        }
+        texto.push_str(&format!("\n- {}: {}.", item.item, partes.join("; ")));
+    }
+
+    let atencao: Vec<&str> = analise.itens.iter().filter(|i| i.abaixo_da_meta).map(|i| i.item.as_str()).collect();
+    if atencao.is_empty() {
+        texto.push_str(&format!(
+            "\n\nTodos os itens atingiram a meta (média de pelo menos {}% da escala e NPS não negativo).",
+            decimal(META_SATISFACAO * 100.0, 0)
+        ));
+    } else {
+        texto.push_str(&format!(
+            "\n\nPontos de atenção: {}. Recomenda-se abrir ação de melhoria para estes itens.",
+            atencao.join(", ")
+        ));
+    }
+    if !analise.itens_indisponiveis.is_empty() {
+        texto.push_str(&format!("\nItens sem resultado disponível: {}.", analise.itens_indisponiveis.join(", ")));
+    }
+    texto
+}
+
+fn agora() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+async fn buscar_pesquisa(client: &Client, api_url: &str, pesquisa_id: u32) -> Result<PesquisaDetalhada, String> {
+    buscar_json(client, &format!("{}/qualidade/pesquisas/{}", api_url, pesquisa_id)).await
+}
+
+async fn buscar_destinatarios(client: &Client, api_url: &str, pesquisa_id: u32) -> Result<Vec<DestinatarioPesquisa>, String> {
+    buscar_json(client, &format!("{}/qualidade/pesquisas/{}/destinatarios", api_url, pesquisa_id)).await
+}
+
+async fn buscar_convites(client: &Client, api_url: &str, pesquisa_id: u32) -> Result<Vec<ConvitePesquisa>, String> {
+    buscar_json(client, &format!("{}/qualidade/pesquisas/{}/convites", api_url, pesquisa_id)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+    use crate::model::email::SegurancaSmtp;
+
+    fn resultado(resposta: &str, total: i64) -> ResultadoItem {
+        ResultadoItem { resposta: resposta.to_string(), total }
+    }
+
+    #[test]
+    fn test_estatisticas_nps() {
+        let resultados = vec![resultado("10", 5), resultado("9", 1), resultado("7", 2), resultado("3", 2)];
+        let estatistica = calcular_estatisticas_item("Recomendaria?", &resultados);
+        let nps = estatistica.nps.unwrap();
+        assert_eq!((nps.promotores, nps.neutros, nps.detratores), (6, 2, 2));
+        assert_eq!(nps.nps, 40.0);
+        assert_eq!(estatistica.escala_maxima, Some(10.0));
+        assert_eq!(estatistica.media, Some(7.9));
+        assert_eq!(estatistica.distribuicao[0].resposta, "10");
+        assert!(!estatistica.abaixo_da_meta);
+    }
+
+    #[test]
+    fn test_estatisticas_conceituais() {
+        let resultados = vec![resultado("Ótimo", 1), resultado("Regular", 2), resultado("Ruim", 1), resultado("Bom", 0)];
+        let estatistica = calcular_estatisticas_item("Atendimento", &resultados);
+        assert_eq!(estatistica.total_respostas, 4);
+        assert_eq!(estatistica.distribuicao.len(), 3);
+        assert_eq!(estatistica.media, Some(3.25));
+        assert!(estatistica.nps.is_none());
+        assert!(!estatistica.abaixo_da_meta);
+
+        let ruim = calcular_estatisticas_item("Prazo", &[resultado("Ruim", 3), resultado("Bom", 1)]);
+        assert!(ruim.abaixo_da_meta);
+        let texto = calcular_estatisticas_item("Comentários", &[resultado("Sem sugestões", 2)]);
+        assert!(texto.media.is_none() && !texto.abaixo_da_meta);
+    }
+
+    fn convite(enviado_em: Option<&str>) -> ConvitePesquisa {
+        ConvitePesquisa {
+            pesquisa_id: 1,
+            destinatario_id: 2,
+            nome: "Cliente".to_string(),
+            email: "cliente@exemplo.com".to_string(),
+            token: "abc".to_string(),
+            enviado_em: enviado_em.map(str::to_string),
+            lembretes_enviados: 0,
+            ultimo_lembrete_em: None,
+            respondido: false,
+            ultimo_erro: None,
+        }
+    }
+
+    #[test]
+    fn test_precisa_lembrete() {
+        let agora = NaiveDateTime::parse_from_str("2026-03-10 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut convite = convite(Some("2026-03-01 09:00:00"));
+        assert_eq!(montar_link("https://pesquisa.exemplo.com/responder", "abc"), "https://pesquisa.exemplo.com/responder?token=abc");
+        assert!(precisa_lembrete(&convite, agora, 7, 2));
+
+        convite.ultimo_lembrete_em = Some("2026-03-08 09:00:00".to_string());
+        convite.lembretes_enviados = 1;
+        assert!(!precisa_lembrete(&convite, agora, 7, 2));
+        convite.lembretes_enviados = 2;
+        assert!(!precisa_lembrete(&convite, agora, 1, 2));
+    }
+
+    /// Aceita uma conexão em 127.0.0.1 e a entrega a `atender`. Sem conexão em 5 s a
+    /// thread devolve vazio, para o teste falhar em vez de travar
+    fn servidor_local<F>(atender: F) -> (u16, std::thread::JoinHandle<String>)
+    where
+        F: FnOnce(BufReader<TcpStream>, TcpStream) -> String + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let porta = listener.local_addr().unwrap().port();
+        listener.set_nonblocking(true).unwrap();
+        let handle = std::thread::spawn(move || {
+            let limite = Instant::now() + Duration::from_secs(5);
+            let stream = loop {
+                match listener.accept() {
+                    Ok((stream, _)) => break stream,
+                    Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < limite => {
+                        std::thread::sleep(Duration::from_millis(10))
+                    }
+                    Err(_) => return String::new(),
+                }
+            };
+            stream.set_nonblocking(false).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let escrita = stream.try_clone().unwrap();
+            atender(BufReader::new(stream), escrita)
+        });
+        (porta, handle)
+    }
+
+    #[tokio::test]
+    async fn test_convite_sem_registro_do_token_nao_e_enviado() {
+        // A API recusa o token; o SMTP não pode receber nenhuma conexão
+        let (porta_api, api) = servidor_local(|mut leitura, mut escrita| {
+            let mut requisicao = String::new();
+            let mut linha = String::new();
+            while leitura.read_line(&mut linha).unwrap_or(0) > 0 && linha != "\r\n" {
+                requisicao.push_str(&linha);
+                linha.clear();
+            }
+            escrita
+                .write_all(b"HTTP/1.1 409 Conflict\r\nContent-Length: 15\r\nConnection: close\r\n\r\nToken duplicado")
+                .unwrap();
+            requisicao
+        });
+        let smtp = TcpListener::bind("127.0.0.1:0").unwrap();
+        smtp.set_nonblocking(true).unwrap();
+        let configuracao = ConfiguracaoSmtp {
+            host: "127.0.0.1".to_string(),
+            porta: smtp.local_addr().unwrap().port(),
+            seguranca: SegurancaSmtp::Nenhuma,
+            remetente_email: "qualidade@exemplo.com".to_string(),
+            ..Default::default()
+        };
+        let transporte = criar_transporte(&configuracao).unwrap();
+        let client = Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+        let api_url = format!("http://127.0.0.1:{}", porta_api);
+
+        let envio = enviar_convite(&client, &api_url, &transporte, &configuracao, &convite(None), "Pesquisa", String::new(), false).await;
+        let erro = envio.unwrap_err();
+        assert!(erro.contains("e-mail não enviado") && erro.contains("Token duplicado"), "{}", erro);
+        assert!(api.join().unwrap().starts_with("POST /qualidade/pesquisas/1/convites "));
+        assert_eq!(smtp.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+    }
+
+    /// Servidor SMTP mínimo que aceita uma mensagem e devolve o conteúdo do DATA
+    fn servidor_smtp_local() -> (u16, std::thread::JoinHandle<String>) {
+        servidor_local(|mut leitura, mut escrita| {
+            escrita.write_all(b"220 localhost ESMTP\r\n").unwrap();
+            let mut dados = String::new();
+            let mut em_dados = false;
+            let mut linha = String::new();
+            while leitura.read_line(&mut linha).unwrap_or(0) > 0 {
+                if em_dados {
+                    if linha == ".\r\n" {
+                        em_dados = false;
+                        escrita.write_all(b"250 OK\r\n").unwrap();
+                    } else {
+                        dados.push_str(&linha);
+                    }
+                } else {
+                    let comando = linha.to_uppercase();
+                    if comando.starts_with("DATA") {
+                        em_dados = true;
+                        escrita.write_all(b"354 Continue\r\n").unwrap();
+                    } else if comando.starts_with("QUIT") {
+                        escrita.write_all(b"221 Bye\r\n").unwrap();
+                        break;
+                    } else {
+                        escrita.write_all(b"250 OK\r\n").unwrap();
+                    }
+                }
+                linha.clear();
+            }
+            dados
+        })
+    }
+    #[tokio::test]
+    async fn test_envio_smtp_local() {
+        let (porta, servidor) = servidor_smtp_local();
+        let configuracao = ConfiguracaoSmtpPesquisa {
+            smtp: ConfiguracaoSmtp {
+                host: "127.0.0.1".to_string(),
+                porta,
+                seguranca: SegurancaSmtp::Nenhuma,
+                remetente_email: "qualidade@exemplo.com".to_string(),
+                ..Default::default()
+            },
+            url_resposta: "https://pesquisa.exemplo.com/responder".to_string(),
+        };
+        assert!(validar_config_smtp(&configuracao).is_ok());
+
+        let link = montar_link(&configuracao.url_resposta, "tok123");
+        let corpo = preencher_mensagem("Ola {nome}, responda a {pesquisa}.", "Maria", "Satisfacao 2026", &link);
+        let transporte = criar_transporte(&configuracao.smtp).unwrap();
+        enviar_email(&transporte, &configuracao.smtp, "Maria", "maria@exemplo.com", "Pesquisa", corpo)
+            .await
+            .unwrap();
+        drop(transporte);
+
+        let dados = servidor.join().unwrap();
+        assert!(dados.contains("To: \"Maria\" <maria@exemplo.com>") || dados.contains("To: Maria <maria@exemplo.com>"));
+        assert!(dados.contains("Satisfacao 2026"));
+        assert!(dados.contains("tok123"));
+    }
+}
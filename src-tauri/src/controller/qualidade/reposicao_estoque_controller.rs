@@ -0,0 +1,490 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use bigdecimal::ToPrimitive;
+use chrono::{Local, NaiveDate};
+use rust_xlsxwriter::{Format, Workbook};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::model::api_response::ApiResponse;
+use crate::model::estoque::{EstoqueCompletoResponse, EstoqueItemDetalhado, EstoqueRegistro, PaginatedEstoqueResponse};
+use crate::model::insumo_registro::FornecedorOption;
+use crate::model::reposicao_estoque::{
+    ExportacaoSugestaoCompra, GrupoSugestaoCompra, ParametrosReposicao, PrevisaoReposicaoItem,
+    SugestaoCompra, VinculoFornecedorEstoque,
+};
+use crate::exportacao::{aviso_html, destino_exportacao, escapar_html, pagina_relatorio, salvar_pdf, tabela_html};
+use crate::utils::buscar_json;
+
+const SEM_FORNECEDOR: &str = "Sem fornecedor definido";
+
+/// Calcula consumo médio, prazo de entrega e data projetada do mínimo de cada item
+/// ativo e agrupa a sugestão de compra por fornecedor
+#[command]
+pub async fn prever_reposicao_estoque(
+    app_handle: AppHandle,
+    parametros: Option<ParametrosReposicao>,
+) -> Result<ApiResponse<SugestaoCompra>, ApiResponse<()>> {
+    let parametros = parametros.unwrap_or_default();
+    if parametros.janela_dias == 0 {
+        return Err(ApiResponse::error("A janela de consumo deve ter pelo menos 1 dia.".to_string()));
+    }
+
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let itens = buscar_itens_ativos(&client, &api_url).await.map_err(ApiResponse::error)?;
+
+    // Vínculos e nomes de fornecedor são complementares: sem eles, tudo cai em "Sem fornecedor"
+    let vinculos: HashMap<u32, u32> = buscar_json::<Vec<VinculoFornecedorEstoque>>(&client, &format!("{}/qualidade/estoque/fornecedores", api_url))
+        .await
+        .unwrap_or_else(|e| {
+            println!("Falha ao carregar fornecedores dos itens de estoque: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .filter_map(|v| v.fornecedor_id.map(|f| (v.estoque_item_id, f)))
+        .collect();
+    let fornecedores: HashMap<u32, String> = buscar_json::<Vec<FornecedorOption>>(&client, &format!("{}/laboratorio/suporte/fornecedores", api_url))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|f| (f.id, f.nome))
+        .collect();
+
+    let hoje = Local::now().date_naive();
+    let mut previsoes = Vec::new();
+    let mut indisponiveis = Vec::new();
+    for item in itens {
+        let url = format!("{}/qualidade/estoque/{}", api_url, item.id);
+        match buscar_json::<EstoqueCompletoResponse>(&client, &url).await {
+            Ok(completo) => {
+                let previsao = prever_item(&completo.detalhes, &completo.historico, &parametros, hoje);
+                if previsao.repor || !parametros.apenas_necessarios {
+                    previsoes.push(previsao);
+                }
+            }
+            Err(e) => indisponiveis.push(format!("{}: {}", item.nome, e)),
+        }
+    }
+
+    let grupos = agrupar_por_fornecedor(previsoes, &vinculos, &fornecedores);
+    let total: usize = grupos.iter().map(|g| g.itens.len()).sum();
+
+    Ok(ApiResponse::success(
+        format!("{} item(ns) em {} fornecedor(es).", total, grupos.len()),
+        Some(SugestaoCompra {
+            gerado_em: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            parametros,
+            grupos,
+            itens_indisponiveis: indisponiveis,
+        }),
+    ))
+}
+
+#[command]
+pub async fn definir_fornecedor_estoque_item(
+    app_handle: AppHandle,
+    item_id: u32,
+    fornecedor_id: Option<u32>,
+) -> Result<ApiResponse<()>, ApiResponse<()>> {
+    let client = Client::new();
+    let url = format!("{}/qualidade/estoque/{}/fornecedor", get_api_url(&app_handle), item_id);
+    let payload = VinculoFornecedorEstoque { estoque_item_id: item_id, fornecedor_id };
+
+    match client.put(&url).json(&payload).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                Ok(ApiResponse::success("Fornecedor do item atualizado.".to_string(), None))
+            } else {
+                let msg = response.text().await.unwrap_or_default();
+                Err(ApiResponse::error(format!("Erro API ({}): {}", status, msg)))
+            }
+        }
+        Err(e) => Err(ApiResponse::error(format!("Falha conexão: {}", e))),
+    }
+}
+
+#[command]
+pub async fn exportar_sugestao_compra_xlsx(
+    sugestao: SugestaoCompra,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoSugestaoCompra>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, "xlsx").map_err(ApiResponse::error)?;
+    gerar_xlsx(&sugestao, &destino).map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Sugestão de compra exportada.".to_string(),
+        Some(ExportacaoSugestaoCompra {
+            caminho: destino.to_string_lossy().to_string(),
+            total_itens: sugestao.grupos.iter().map(|g| g.itens.len()).sum(),
+        }),
+    ))
+}
+
+#[command]
+pub async fn exportar_sugestao_compra_pdf(
+    sugestao: SugestaoCompra,
+    caminho: Option<String>,
+) -> Result<ApiResponse<ExportacaoSugestaoCompra>, ApiResponse<()>> {
+    let destino = resolver_destino(caminho, "pdf").map_err(ApiResponse::error)?;
+
+    salvar_pdf(gerar_html(&sugestao), &destino).await.map_err(ApiResponse::error)?;
+
+    Ok(ApiResponse::success(
+        "Sugestão de compra exportada.".to_string(),
+        Some(ExportacaoSugestaoCompra {
+            caminho: destino.to_string_lossy().to_string(),
+            total_itens: sugestao.grupos.iter().map(|g| g.itens.len()).sum(),
+        }),
+    ))
+}
+
+// --- Previsão ---
+
+/// Saldo após cada movimentação, reconstruído de trás para frente a partir do saldo atual
+fn saldos_apos_movimentos(historico: &[&EstoqueRegistro], saldo_atual: f64) -> Vec<f64> {
+    let mut saldos = vec![0.0; historico.len()];
+    let mut saldo = saldo_atual;
+    for (i, registro) in historico.iter().enumerate().rev() {
+        saldos[i] = saldo;
+        let quantidade = registro.quantidade as f64;
+        saldo -= if registro.entrada { quantidade } else { -quantidade };
+    }
+    saldos
+}
+
+fn saldo_atual(item: &EstoqueItemDetalhado, ordenado: &[&EstoqueRegistro]) -> f64 {
+    item.saldo_atual.as_ref().and_then(|s| s.to_f64()).unwrap_or_else(|| {
+        ordenado
+            .iter()
+            .map(|r| if r.entrada { r.quantidade as f64 } else { -(r.quantidade as f64) })
+            .sum()
+    })
+}
+
+/// Prazo de entrega de cada reposição: do dia em que o saldo chegou ao mínimo até a
+/// entrada seguinte, junto com a data dessa entrada
+fn prazos_ordenados(ordenado: &[&EstoqueRegistro], saldo_atual: f64, minimo: f64) -> Vec<(NaiveDate, f64)> {
+    let saldos = saldos_apos_movimentos(ordenado, saldo_atual);
+    let mut prazos = Vec::new();
+    let mut abaixo_desde: Option<NaiveDate> = None;
+    for (registro, saldo) in ordenado.iter().zip(&saldos) {
+        if registro.entrada {
+            if let Some(inicio) = abaixo_desde {
+                prazos.push((registro.data, (registro.data - inicio).num_days().max(0) as f64));
+            }
+            if *saldo > minimo {
+                abaixo_desde = None;
+            }
+        } else if *saldo <= minimo && abaixo_desde.is_none() {
+            abaixo_desde = Some(registro.data);
+        }
+    }
+    prazos
+}
+
+/// Prazos de entrega do histórico completo, para quem precisa recortar por período
+pub fn prazos_entrega(item: &EstoqueItemDetalhado, historico: &[EstoqueRegistro]) -> Vec<(NaiveDate, f64)> {
+    let mut ordenado: Vec<&EstoqueRegistro> = historico.iter().collect();
+    ordenado.sort_by_key(|r| (r.data, r.hora));
+    prazos_ordenados(&ordenado, saldo_atual(item, &ordenado), item.minimo as f64)
+}
+
+pub fn prever_item(
+    item: &EstoqueItemDetalhado,
+    historico: &[EstoqueRegistro],
+    parametros: &ParametrosReposicao,
+    hoje: NaiveDate,
+) -> PrevisaoReposicaoItem {
+    let mut ordenado: Vec<&EstoqueRegistro> = historico.iter().collect();
+    ordenado.sort_by_key(|r| (r.data, r.hora));
+
+    let saldo_atual = saldo_atual(item, &ordenado);
+    let minimo = item.minimo as f64;
+
+    let prazos: Vec<f64> = prazos_ordenados(&ordenado, saldo_atual, minimo).into_iter().map(|(_, p)| p).collect();
+    let lead_time_historico = !prazos.is_empty();
+    let lead_time_dias = if lead_time_historico {
+        prazos.iter().sum::<f64>() / prazos.len() as f64
+    } else {
+        parametros.lead_time_padrao_dias as f64
+    };
+
+    // Consumo médio: saídas da janela divididas pelos dias efetivamente cobertos pelo histórico
+    let inicio_janela = hoje - chrono::Duration::days(parametros.janela_dias as i64);
+    let saidas: f64 = ordenado
+        .iter()
+        .filter(|r| !r.entrada && r.data > inicio_janela && r.data <= hoje)
+        .map(|r| r.quantidade as f64)
+        .sum();
+    let dias_cobertos = ordenado
+        .first()
+        .map(|r| (hoje - r.data.max(inicio_janela)).num_days())
+        .unwrap_or(0)
+        .max(1);
+    let consumo_diario = saidas / dias_cobertos as f64;
+
+    let dias_ate_minimo = if saldo_atual <= minimo {
+        Some(0)
+    } else if consumo_diario > 0.0 {
+        Some(((saldo_atual - minimo) / consumo_diario).floor() as i64)
+    } else {
+        None
+    };
+    let repor = dias_ate_minimo.is_some_and(|d| d as f64 <= lead_time_dias);
+
+    // Chega ao fim do prazo de entrega com o mínimo mais a cobertura desejada
+    let quantidade_sugerida = if repor {
+        let necessario = minimo + consumo_diario * (lead_time_dias + parametros.cobertura_dias as f64) - saldo_atual;
+        (necessario.ceil() as i32).max(1)
+    } else {
+        0
+    };
+
+    PrevisaoReposicaoItem {
+        item_id: item.id,
+        nome: item.nome.clone(),
+        unidade: item.unidade.clone(),
+        saldo_atual,
+        minimo: item.minimo,
+        consumo_diario,
+        lead_time_dias,
+        lead_time_historico,
+        data_minimo: dias_ate_minimo.map(|d| (hoje + chrono::Duration::days(d)).format("%Y-%m-%d").to_string()),
+        dias_ate_minimo,
+        repor,
+        quantidade_sugerida,
+    }
+}
+
+fn agrupar_por_fornecedor(
+    previsoes: Vec<PrevisaoReposicaoItem>,
+    vinculos: &HashMap<u32, u32>,
+    fornecedores: &HashMap<u32, String>,
+) -> Vec<GrupoSugestaoCompra> {
+    let mut por_fornecedor: BTreeMap<Option<u32>, Vec<PrevisaoReposicaoItem>> = BTreeMap::new();
+    for previsao in previsoes {
+        por_fornecedor.entry(vinculos.get(&previsao.item_id).copied()).or_default().push(previsao);
+    }
+
+    let mut grupos: Vec<GrupoSugestaoCompra> = por_fornecedor
+        .into_iter()
+        .map(|(fornecedor_id, mut itens)| {
+            itens.sort_by_key(|i| (i.dias_ate_minimo.unwrap_or(i64::MAX), i.nome.clone()));
+            GrupoSugestaoCompra {
+                fornecedor_id,
+                fornecedor: match fornecedor_id {
+                    Some(id) => fornecedores.get(&id).cloned().unwrap_or_else(|| format!("Fornecedor {}", id)),
+                    None => SEM_FORNECEDOR.to_string(),
+                },
+                itens,
+            }
+        })
+        .collect();
+
+    // Fornecedores em ordem alfabética, itens sem fornecedor por último
+    grupos.sort_by_key(|g| (g.fornecedor_id.is_none(), g.fornecedor.to_lowercase()));
+    grupos
+}
+
+// --- Consulta à API ---
+
+pub async fn buscar_itens_ativos(client: &Client, api_url: &str) -> Result<Vec<EstoqueItemDetalhado>, String> {
+    let mut itens = Vec::new();
+    let mut pagina = 1;
+    loop {
+        let url = format!("{}/qualidade/estoque?page={}&per_page=200", api_url, pagina);
+        let resposta = buscar_json::<PaginatedEstoqueResponse>(client, &url).await?;
+        let recebidos = resposta.items.len();
+        itens.extend(resposta.items.into_iter().filter(|i| i.ativo));
+        if recebidos == 0 || (pagina * resposta.per_page.max(1)) as i64 >= resposta.total {
+            break;
+        }
+        pagina += 1;
+    }
+    Ok(itens)
+}
+
+// --- Exportação ---
+
+fn resolver_destino(caminho: Option<String>, extensao: &str) -> Result<PathBuf, String> {
+    destino_exportacao(
+        caminho,
+        &format!("sugestao_compra_{}.{}", Local::now().format("%Y%m%d_%H%M"), extensao),
+    )
+}
+
+fn formatar_data(data: Option<&str>) -> String {
+    data.and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map(|d| d.format("%d/%m/%Y").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn gerar_xlsx(sugestao: &SugestaoCompra, destino: &Path) -> Result<(), String> {
+    let erro = |e: rust_xlsxwriter::XlsxError| format!("Erro ao gerar XLSX: {}", e);
+
+    let mut workbook = Workbook::new();
+    let negrito = Format::new().set_bold();
+    let decimal = Format::new().set_num_format("0.00");
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Sugestão de compra").map_err(erro)?;
+
+    let cabecalho = [
+        "Fornecedor", "Item", "Unidade", "Saldo atual", "Mínimo", "Consumo/dia",
+        "Prazo entrega (dias)", "Atinge mínimo em", "Qtd. sugerida",
+    ];
+    for (coluna, titulo) in cabecalho.iter().enumerate() {
+        sheet.write_string_with_format(0, coluna as u16, *titulo, &negrito).map_err(erro)?;
+    }
+
+    let mut linha: u32 = 1;
+    for grupo in &sugestao.grupos {
+        for item in &grupo.itens {
+            sheet.write_string(linha, 0, &grupo.fornecedor).map_err(erro)?;
+            sheet.write_string(linha, 1, &item.nome).map_err(erro)?;
+            sheet.write_string(linha, 2, &item.unidade).map_err(erro)?;
+            sheet.write_number(linha, 3, item.saldo_atual).map_err(erro)?;
+            sheet.write_number(linha, 4, item.minimo).map_err(erro)?;
+            sheet.write_number_with_format(linha, 5, item.consumo_diario, &decimal).map_err(erro)?;
+            sheet.write_number_with_format(linha, 6, item.lead_time_dias, &decimal).map_err(erro)?;
+            sheet.write_string(linha, 7, formatar_data(item.data_minimo.as_deref())).map_err(erro)?;
+            sheet.write_number(linha, 8, item.quantidade_sugerida).map_err(erro)?;
+            linha += 1;
+        }
+    }
+
+    sheet.set_freeze_panes(1, 0).map_err(erro)?;
+    if linha > 1 {
+        sheet.autofilter(0, 0, linha - 1, (cabecalho.len() - 1) as u16).map_err(erro)?;
+    }
+    for (coluna, largura) in [28.0, 36.0, 10.0, 12.0, 10.0, 12.0, 18.0, 16.0, 14.0].iter().enumerate() {
+        sheet.set_column_width(coluna as u16, *largura).map_err(erro)?;
+    }
+
+    workbook.save(destino).map_err(erro)
+}
+
+fn gerar_html(sugestao: &SugestaoCompra) -> String {
+    let mut secoes = String::new();
+    for grupo in &sugestao.grupos {
+        let mut linhas = String::new();
+        for item in &grupo.itens {
+            linhas.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:.1}{}</td><td>{}</td><td class=\"num\"><b>{}</b></td></tr>",
+                escapar_html(&item.nome),
+                escapar_html(&item.unidade),
+                item.saldo_atual,
+                item.minimo,
+                item.consumo_diario,
+                item.lead_time_dias,
+                if item.lead_time_historico { "" } else { "*" },
+                formatar_data(item.data_minimo.as_deref()),
+                item.quantidade_sugerida,
+            ));
+        }
+        secoes.push_str(&format!("<h3>{}</h3>", escapar_html(&grupo.fornecedor)));
+        secoes.push_str(&tabela_html(
+            &["Item", "Unidade", "Saldo", "Mínimo", "Consumo/dia", "Prazo entrega (dias)", "Atinge mínimo em", "Qtd. sugerida"],
+            &linhas,
+        ));
+    }
+    secoes.push_str("<p>* Prazo de entrega padrão (sem histórico suficiente)</p>");
+
+    let introducao = format!(
+        "<p>Consumo médio dos últimos {} dias &middot; cobertura de {} dias &middot; Gerado em {}</p>{}",
+        sugestao.parametros.janela_dias,
+        sugestao.parametros.cobertura_dias,
+        Local::now().format("%d/%m/%Y %H:%M"),
+        aviso_html("Itens sem histórico disponível", &sugestao.itens_indisponiveis),
+    );
+    pagina_relatorio("Sugestão de compra", &introducao, &secoes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveTime;
+
+    fn registro(data: &str, entrada: bool, quantidade: i32) -> EstoqueRegistro {
+        EstoqueRegistro {
+            data: NaiveDate::parse_from_str(data, "%Y-%m-%d").unwrap(),
+            hora: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            entrada,
+            quantidade,
+            observacao: None,
+        }
+    }
+
+    #[test]
+    fn test_prever_item() {
+        let item = EstoqueItemDetalhado {
+            id: 1,
+            nome: "Luvas".to_string(),
+            minimo: 10,
+            ativo: true,
+            unidade: "cx".to_string(),
+            saldo_atual: Some(BigDecimal::from(30)),
+        };
+        // 50 -> 10 (mínimo em 01/05) -> entrada de 50 em 05/05 (prazo de 4 dias) -> 60 -> 30
+        let historico = vec![
+            registro("2025-05-05", true, 50),
+            registro("2025-04-01", true, 50),
+            registro("2025-05-01", false, 40),
+            registro("2025-05-20", false, 30),
+        ];
+        let parametros = ParametrosReposicao { janela_dias: 40, ..Default::default() };
+        let hoje = NaiveDate::from_ymd_opt(2025, 5, 31).unwrap();
+
+        let previsao = prever_item(&item, &historico, &parametros, hoje);
+        assert!(previsao.lead_time_historico);
+        assert_eq!(previsao.lead_time_dias, 4.0);
+        // 70 de saída em 40 dias
+        assert_eq!(previsao.consumo_diario, 1.75);
+        assert_eq!(previsao.dias_ate_minimo, Some(11));
+        assert_eq!(previsao.data_minimo.as_deref(), Some("2025-06-11"));
+        assert!(!previsao.repor);
+        // A avaliação de fornecedores recorta pela data da entrada
+        assert_eq!(prazos_entrega(&item, &historico), vec![(NaiveDate::from_ymd_opt(2025, 5, 5).unwrap(), 4.0)]);
+
+        // Sem entradas não há prazo histórico; a saída de 01/05 fica fora da janela de 30 dias
+        let parametros = ParametrosReposicao { janela_dias: 30, lead_time_padrao_dias: 20, ..parametros };
+        let previsao = prever_item(&item, &historico[2..], &parametros, hoje);
+        assert!(!previsao.lead_time_historico);
+        assert_eq!(previsao.consumo_diario, 1.0);
+        assert_eq!(previsao.dias_ate_minimo, Some(20));
+        assert!(previsao.repor);
+        // 10 + 1 * (20 + 30) - 30
+        assert_eq!(previsao.quantidade_sugerida, 30);
+    }
+
+    #[test]
+    fn test_agrupar_por_fornecedor() {
+        let item = EstoqueItemDetalhado {
+            id: 0,
+            nome: String::new(),
+            minimo: 5,
+            ativo: true,
+            unidade: "un".to_string(),
+            saldo_atual: Some(BigDecimal::from(0)),
+        };
+        let hoje = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let previsoes: Vec<PrevisaoReposicaoItem> = [(1, "Papel"), (2, "Álcool"), (3, "Detergente")]
+            .into_iter()
+            .map(|(id, nome)| {
+                let item = EstoqueItemDetalhado { id, nome: nome.to_string(), ..item.clone() };
+                prever_item(&item, &[], &ParametrosReposicao::default(), hoje)
+            })
+            .collect();
+
+        let vinculos = HashMap::from([(1, 20), (3, 10)]);
+        let fornecedores = HashMap::from([(10, "Química Sul".to_string()), (20, "Papelaria Centro".to_string())]);
+        let grupos = agrupar_por_fornecedor(previsoes, &vinculos, &fornecedores);
+
+        let nomes: Vec<&str> = grupos.iter().map(|g| g.fornecedor.as_str()).collect();
+        assert_eq!(nomes, vec!["Papelaria Centro", "Química Sul", SEM_FORNECEDOR]);
+        assert_eq!(grupos[2].itens[0].item_id, 2);
+        assert!(grupos.iter().all(|g| g.itens.iter().all(|i| i.repor && i.quantidade_sugerida == 5)));
+    }
+}
@@ -0,0 +1,550 @@
+use chrono::Local;
+use serde_json::{json, Map, Value};
+use tauri::{command, AppHandle};
+use reqwest::Client;
+use crate::config::get_api_url;
+use crate::controller::laboratorio::resultado_controller::AmostraResultadosResponse;
+use crate::exportacao::escapar_html;
+use crate::model::api_response::ApiResponse;
+use crate::model::template_planilha::{
+    CelulaTemplate, DadosTemplate, DefinicaoPlaceholder, DiferencaTemplate, IntervaloCelulas, PlanilhaTemplate,
+    ResultadoValidacaoTemplate, TemplateData, TipoPlaceholder, VersaoTemplate,
+};
+use crate::utils::{buscar_json, parse_data_hora};
+use crate::validacao::template::{
+    catalogo_placeholders, decodificar_planilha, linha_repetida, referencia_celula, validar_planilha,
+    validar_template_base64, FORMATOS, GRUPO_RESULTADO, MARCADOR, PLACEHOLDERS,
+};
+
+/// Propriedades de `CellStyle` levadas para o CSS da célula
+const ESTILOS_CSS: &[(&str, &str)] = &[
+    ("fontWeight", "font-weight"),
+    ("fontStyle", "font-style"),
+    ("textDecoration", "text-decoration"),
+    ("textAlign", "text-align"),
+    ("backgroundColor", "background-color"),
+    ("color", "color"),
+    ("fontSize", "font-size"),
+    ("fontFamily", "font-family"),
+    ("border", "border"),
+    ("borderTop", "border-top"),
+    ("borderRight", "border-right"),
+    ("borderBottom", "border-bottom"),
+    ("borderLeft", "border-left"),
+    ("borderRadius", "border-radius"),
+    ("padding", "padding"),
+    ("verticalAlign", "vertical-align"),
+    ("textTransform", "text-transform"),
+    ("letterSpacing", "letter-spacing"),
+    ("lineHeight", "line-height"),
+    ("opacity", "opacity"),
+];
+
+// ==================== COMANDOS TAURI ====================
+
+/// JSON Schema (draft 2020-12) do conteúdo de `json_data_base64`
+#[command]
+pub fn obter_schema_template() -> Value {
+    let intervalo = json!({
+        "type": "object",
+        "required": ["startRow", "startCol", "endRow", "endCol"],
+        "properties": {
+            "startRow": { "type": "integer", "minimum": 0 },
+            "startCol": { "type": "integer", "minimum": 0 },
+            "endRow": { "type": "integer", "minimum": 0 },
+            "endCol": { "type": "integer", "minimum": 0 }
+        }
+    });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Template de planilha",
+        "description": "Conteúdo de json_data_base64. Células aceitam marcadores {{grupo.campo}} ou {{grupo.campo|formato}}; linhas com {{resultado.*}} se repetem por resultado.",
+        "type": "object",
+        "required": ["name", "rows", "cols", "data"],
+        "properties": {
+            "name": { "type": "string" },
+            "rows": { "type": "integer", "minimum": 1 },
+            "cols": { "type": "integer", "minimum": 1 },
+            "columnWidths": { "type": "array", "items": { "type": "number", "minimum": 0 } },
+            "rowHeights": { "type": "array", "items": { "type": "number", "minimum": 0 } },
+            "history": { "type": "array" },
+            "globalStyles": { "type": "object" },
+            "data": {
+                "type": "array",
+                "description": "Linhas (tamanho = rows), cada uma com cols células",
+                "items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "value": { "type": "string" },
+                            "id": { "type": "string" },
+                            "style": { "type": "object" },
+                            "media": {
+                                "type": "object",
+                                "properties": {
+                                    "type": { "enum": ["image", "video", "audio", "link", "table"] },
+                                    "url": { "type": "string" },
+                                    "data": { "type": "string" }
+                                }
+                            },
+                            "merged": { "type": "boolean" },
+                            "formula": { "type": ["string", "null"] },
+                            "computed_value": { "type": ["string", "null"] },
+                            "is_formula": { "type": "boolean" },
+                            "masterCell": {
+                                "type": "object",
+                                "properties": { "row": { "type": "integer" }, "col": { "type": "integer" } }
+                            },
+                            "mergeRange": intervalo
+                        }
+                    }
+                }
+            }
+        },
+        "x-placeholders": catalogo_placeholders(),
+        "x-formatos": FORMATOS
+    })
+}
+
+#[command]
+pub fn listar_placeholders_template() -> Vec<DefinicaoPlaceholder> {
+    catalogo_placeholders()
+}
+
+#[command]
+pub fn validar_template_tauri(json_data_base64: String) -> ResultadoValidacaoTemplate {
+    validar_template_base64(&json_data_base64)
+}
+
+#[command]
+pub async fn listar_versoes_template(
+    app_handle: AppHandle,
+    id: u64,
+) -> Result<ApiResponse<Vec<VersaoTemplate>>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let mut versoes = buscar_versoes(&client, &api_url, id).await.map_err(ApiResponse::error)?;
+    versoes.sort_by_key(|v| std::cmp::Reverse(v.versao));
+    Ok(ApiResponse::success(format!("{} versão(ões).", versoes.len()), Some(versoes)))
+}
+
+/// Compara duas versões; `None` em qualquer lado significa o template atual
+#[command]
+pub async fn comparar_versoes_template(
+    app_handle: AppHandle,
+    id: u64,
+    versao_base: Option<u32>,
+    versao_comparada: Option<u32>,
+) -> Result<ApiResponse<Vec<DiferencaTemplate>>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let versoes = buscar_versoes(&client, &api_url, id).await.map_err(ApiResponse::error)?;
+    let atual: TemplateData = buscar_json(&client, &format!("{}/templates/{}", api_url, id))
+        .await
+        .map_err(ApiResponse::error)?;
+
+    let conteudo = |versao: Option<u32>| -> Result<PlanilhaTemplate, String> {
+        let base64 = match versao {
+            Some(v) => versoes
+                .iter()
+                .find(|x| x.versao == v)
+                .map(|x| x.json_data_base64.clone())
+                .ok_or_else(|| format!("Versão {} não encontrada.", v))?,
+            None => atual.json_data_base64.clone().unwrap_or_default(),
+        };
+        decodificar_planilha(&base64)
+    };
+    let base = conteudo(versao_base).map_err(ApiResponse::error)?;
+    let comparada = conteudo(versao_comparada).map_err(ApiResponse::error)?;
+
+    let diferencas = diferencas_template(&base, &comparada);
+    Ok(ApiResponse::success(format!("{} diferença(s).", diferencas.len()), Some(diferencas)))
+}
+
+/// HTML pronto para `generate_pdf_from_html`/`print_html`, a partir de um template salvo ou de um JSON avulso
+#[command]
+pub async fn renderizar_template(
+    app_handle: AppHandle,
+    id: Option<u64>,
+    json_data_base64: Option<String>,
+    dados: DadosTemplate,
+) -> Result<ApiResponse<String>, ApiResponse<()>> {
+    let client = Client::new();
+    let planilha = carregar_planilha(&client, &get_api_url(&app_handle), id, json_data_base64)
+        .await
+        .map_err(ApiResponse::error)?;
+    let html = renderizar_html(&planilha, &dados).map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success("Template renderizado.".to_string(), Some(html)))
+}
+
+/// Renderiza com a amostra e os resultados buscados na API; cliente e legislação vêm do frontend
+#[command]
+pub async fn renderizar_template_amostra(
+    app_handle: AppHandle,
+    id: u64,
+    id_analise: u32,
+    cliente: Option<Map<String, Value>>,
+    legislacao: Option<Map<String, Value>>,
+) -> Result<ApiResponse<String>, ApiResponse<()>> {
+    let client = Client::new();
+    let api_url = get_api_url(&app_handle);
+    let planilha = carregar_planilha(&client, &api_url, Some(id), None).await.map_err(ApiResponse::error)?;
+    let amostra: AmostraResultadosResponse = buscar_json(&client, &format!("{}/amostras/{}/resultados", api_url, id_analise))
+        .await
+        .map_err(ApiResponse::error)?;
+
+    let dados = DadosTemplate {
+        cliente: cliente.unwrap_or_default(),
+        amostra: mapa(&[
+            ("numero", amostra.info.numero.clone()),
+            ("identificacao", amostra.info.identificacao.clone()),
+            ("complemento", amostra.info.complemento.clone()),
+            ("data_coleta", amostra.info.data_coleta.clone()),
+            ("hora_coleta", amostra.info.hora_coleta.clone()),
+            ("data_entrada_lab", amostra.info.data_entrada_lab.clone()),
+            ("data_inicio_analise", amostra.info.data_inicio_analise.clone()),
+        ]),
+        resultados: amostra
+            .resultados
+            .iter()
+            .map(|r| {
+                mapa(&[
+                    ("parametro", r.nome_parametro.clone()),
+                    ("grupo", r.grupo_parametro.clone()),
+                    ("tecnica", r.tecnica_nome.clone()),
+                    ("unidade", r.unidade.clone()),
+                    ("limite", r.limite.clone()),
+                    ("valor", r.resultado.clone()),
+                    ("analista", r.analista.clone()),
+                    ("data_termino", r.data_termino.clone()),
+                ])
+            })
+            .collect(),
+        legislacao: legislacao.unwrap_or_default(),
+    };
+
+    let html = renderizar_html(&planilha, &dados).map_err(ApiResponse::error)?;
+    Ok(ApiResponse::success("Template renderizado.".to_string(), Some(html)))
+}
+
+// ==================== VERSÕES ====================
+
+fn diferencas_template(base: &PlanilhaTemplate, comparada: &PlanilhaTemplate) -> Vec<DiferencaTemplate> {
+    let mut diferencas = Vec::new();
+    let mut registrar = |celula: Option<String>, campo: &str, antes: Option<String>, depois: Option<String>| {
+        if antes != depois {
+            diferencas.push(DiferencaTemplate { celula, campo: campo.to_string(), antes, depois });
+        }
+    };
+
+    registrar(None, "nome", Some(base.name.clone()), Some(comparada.name.clone()));
+    registrar(
+        None,
+        "dimensoes",
+        Some(format!("{}x{}", base.rows, base.cols)),
+        Some(format!("{}x{}", comparada.rows, comparada.cols)),
+    );
+    registrar(None, "larguras", Some(format!("{:?}", base.column_widths)), Some(format!("{:?}", comparada.column_widths)));
+    registrar(None, "alturas", Some(format!("{:?}", base.row_heights)), Some(format!("{:?}", comparada.row_heights)));
+
+    let vazia = CelulaTemplate::default();
+    let linhas = base.data.len().max(comparada.data.len());
+    for r in 0..linhas {
+        let colunas = base.data.get(r).map_or(0, Vec::len).max(comparada.data.get(r).map_or(0, Vec::len));
+        for c in 0..colunas {
+            let a = base.data.get(r).and_then(|l| l.get(c)).unwrap_or(&vazia);
+            let b = comparada.data.get(r).and_then(|l| l.get(c)).unwrap_or(&vazia);
+            let referencia = Some(referencia_celula(r, c));
+            let texto = |v: &str| (!v.is_empty()).then(|| v.to_string());
+            let json = |v: Option<String>| v.filter(|s| s != "null");
+
+            registrar(referencia.clone(), "valor", texto(&a.value), texto(&b.value));
+            registrar(referencia.clone(), "formula", a.formula.clone(), b.formula.clone());
+            registrar(
+                referencia.clone(),
+                "estilo",
+                json(serde_json::to_string(&a.style).ok()),
+                json(serde_json::to_string(&b.style).ok()),
+            );
+            registrar(
+                referencia.clone(),
+                "midia",
+                json(serde_json::to_string(&a.media).ok()),
+                json(serde_json::to_string(&b.media).ok()),
+            );
+            registrar(referencia, "mesclagem", a.merge_range.map(descrever_intervalo), b.merge_range.map(descrever_intervalo));
+        }
+    }
+    diferencas
+}
+
+fn descrever_intervalo(intervalo: IntervaloCelulas) -> String {
+    format!(
+        "{}:{}",
+        referencia_celula(intervalo.start_row, intervalo.start_col),
+        referencia_celula(intervalo.end_row, intervalo.end_col)
+    )
+}
+
+// ==================== RENDERIZAÇÃO ====================
+
+fn renderizar_html(planilha: &PlanilhaTemplate, dados: &DadosTemplate) -> Result<String, String> {
+    let erros = validar_planilha(planilha);
+    if let Some(primeiro) = erros.first() {
+        return Err(format!(
+            "Template inválido{}: {}",
+            primeiro.celula.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default(),
+            primeiro.mensagem
+        ));
+    }
+
+    // Linhas de saída: (linha do template, índice do resultado quando a linha se repete)
+    let mut saida: Vec<(usize, Option<usize>)> = Vec::new();
+    for (r, linha) in planilha.data.iter().enumerate() {
+        if linha_repetida(linha) {
+            saida.extend((0..dados.resultados.len()).map(|i| (r, Some(i))));
+        } else {
+            saida.push((r, None));
+        }
+    }
+
+    let mut html = String::from(
+        "<!DOCTYPE html><html><head><meta charset=\"UTF-8\"><style>\
+         body { font-family: Arial, sans-serif; font-size: 11px; margin: 16px; }\
+         table { border-collapse: collapse; table-layout: fixed; }\
+         td { padding: 2px 4px; overflow: hidden; word-wrap: break-word; }\
+         img { max-width: 100%; max-height: 100%; }\
+         </style></head><body><table>",
+    );
+    if !planilha.column_widths.is_empty() {
+        html.push_str("<colgroup>");
+        for largura in &planilha.column_widths {
+            html.push_str(&format!("<col style=\"width:{}px\">", largura.max(0.0)));
+        }
+        html.push_str("</colgroup>");
+    }
+
+    for &(r, resultado) in &saida {
+        let altura = planilha.row_heights.get(r).map(|h| format!(" style=\"height:{}px\"", h.max(0.0))).unwrap_or_default();
+        html.push_str(&format!("<tr{}>", altura));
+        for (c, celula) in planilha.data[r].iter().enumerate() {
+            let coberta = celula.merged == Some(true) && celula.master_cell.is_some_and(|m| m.row != r || m.col != c);
+            if coberta {
+                continue;
+            }
+
+            let mut atributos = String::new();
+            if let Some(intervalo) = celula.merge_range.filter(|i| i.start_row == r && i.start_col == c) {
+                let colspan = intervalo.end_col - intervalo.start_col + 1;
+                // rowspan conta as linhas de saída, já que linhas de resultado podem ter sido repetidas
+                let rowspan = if resultado.is_some() {
+                    1
+                } else {
+                    saida.iter().filter(|(o, _)| (intervalo.start_row..=intervalo.end_row).contains(o)).count()
+                };
+                if colspan > 1 {
+                    atributos.push_str(&format!(" colspan=\"{}\"", colspan));
+                }
+                if rowspan > 1 {
+                    atributos.push_str(&format!(" rowspan=\"{}\"", rowspan));
+                }
+            }
+            let estilo = estilo_css(celula.style.as_ref());
+            if !estilo.is_empty() {
+                atributos.push_str(&format!(" style=\"{}\"", estilo));
+            }
+
+            let bruto = match (&celula.is_formula, &celula.computed_value) {
+                (Some(true), Some(calculado)) => calculado.as_str(),
+                _ => celula.value.as_str(),
+            };
+            let resultado_atual = resultado.and_then(|i| dados.resultados.get(i));
+            let conteudo = escapar_html(&substituir_marcadores(bruto, dados, resultado_atual)).replace('\n', "<br>");
+            html.push_str(&format!("<td{}>{}{}</td>", atributos, conteudo, midia_html(celula.media.as_ref())));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</table></body></html>");
+    Ok(html)
+}
+
+fn substituir_marcadores(texto: &str, dados: &DadosTemplate, resultado: Option<&Map<String, Value>>) -> String {
+    MARCADOR
+        .replace_all(texto, |captura: &regex::Captures| {
+            let grupo = &captura[1];
+            let campo = &captura[2];
+            let valor = match grupo {
+                "cliente" => dados.cliente.get(campo).cloned(),
+                "amostra" => dados.amostra.get(campo).cloned(),
+                "legislacao" => dados.legislacao.get(campo).cloned(),
+                GRUPO_RESULTADO => resultado.and_then(|r| r.get(campo)).cloned(),
+                "geral" if campo == "data_emissao" => Some(Value::String(Local::now().format("%Y-%m-%d").to_string())),
+                _ => None,
+            };
+            let texto = valor.map(|v| valor_texto(&v)).unwrap_or_default();
+
+            let chave = format!("{}.{}", grupo, campo);
+            let tipo = PLACEHOLDERS.iter().find(|(c, _, _)| *c == chave).map(|(_, t, _)| *t);
+            let casas = captura.get(4).and_then(|m| m.as_str().parse::<usize>().ok());
+            match captura.get(3).map(|m| m.as_str()) {
+                Some("maiusculas") => texto.to_uppercase(),
+                Some("minusculas") => texto.to_lowercase(),
+                Some("data") => formatar_data(&texto),
+                Some("numero") => formatar_numero(&texto, casas),
+                _ => match tipo {
+                    Some(TipoPlaceholder::Data) => formatar_data(&texto),
+                    Some(TipoPlaceholder::Numero) => formatar_numero(&texto, None),
+                    _ => texto,
+                },
+            }
+        })
+        .into_owned()
+}
+
+fn valor_texto(valor: &Value) -> String {
+    match valor {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(true) => "Sim".to_string(),
+        Value::Bool(false) => "Não".to_string(),
+        outro => outro.to_string(),
+    }
+}
+
+fn formatar_data(texto: &str) -> String {
+    parse_data_hora(texto, None)
+        .map(|d| d.format("%d/%m/%Y").to_string())
+        .unwrap_or_else(|| texto.to_string())
+}
+
+/// Vírgula decimal; textos como "< LQ" passam sem alteração
+fn formatar_numero(texto: &str, casas: Option<usize>) -> String {
+    match (texto.trim().replace(',', ".").parse::<f64>(), casas) {
+        (Ok(valor), Some(casas)) => format!("{:.*}", casas, valor).replace('.', ","),
+        (Ok(_), None) => texto.trim().replace('.', ","),
+        (Err(_), _) => texto.to_string(),
+    }
+}
+
+fn estilo_css(estilo: Option<&Map<String, Value>>) -> String {
+    let Some(estilo) = estilo else {
+        return String::new();
+    };
+    let mut css: Vec<String> = ESTILOS_CSS
+        .iter()
+        .filter_map(|(chave, propriedade)| {
+            let valor = valor_texto(estilo.get(*chave)?);
+            // Descarta valores que poderiam sair do atributo style
+            let seguro = !valor.is_empty() && !valor.contains(['"', '<', '>', ';', '{', '}']);
+            seguro.then(|| format!("{}:{}", propriedade, valor))
+        })
+        .collect();
+    for (chave, propriedade) in [("width", "width"), ("height", "height")] {
+        if let Some(valor) = estilo.get(chave).and_then(Value::as_f64) {
+            css.push(format!("{}:{}px", propriedade, valor));
+        }
+    }
+    css.join(";")
+}
+
+fn midia_html(midia: Option<&Value>) -> String {
+    let Some(midia) = midia else {
+        return String::new();
+    };
+    let tipo = midia.get("type").and_then(Value::as_str).unwrap_or_default();
+    let fonte = midia
+        .get("data")
+        .or_else(|| midia.get("url"))
+        .and_then(Value::as_str)
+        .filter(|f| f.starts_with("data:image/") || f.starts_with("http://") || f.starts_with("https://"));
+    let titulo = midia.get("alt").or_else(|| midia.get("title")).and_then(Value::as_str).unwrap_or_default();
+
+    match (tipo, fonte) {
+        ("image", Some(fonte)) => format!("<img src=\"{}\" alt=\"{}\">", escapar_html(fonte), escapar_html(titulo)),
+        ("link", Some(fonte)) if !fonte.starts_with("data:") => {
+            let texto = if titulo.is_empty() { fonte } else { titulo };
+            format!("<a href=\"{}\">{}</a>", escapar_html(fonte), escapar_html(texto))
+        }
+        _ => String::new(),
+    }
+}
+
+// ==================== AUXILIARES ====================
+
+fn mapa(campos: &[(&str, Option<String>)]) -> Map<String, Value> {
+    campos
+        .iter()
+        .map(|(chave, valor)| (chave.to_string(), valor.clone().map(Value::String).unwrap_or(Value::Null)))
+        .collect()
+}
+
+async fn buscar_versoes(client: &Client, api_url: &str, id: u64) -> Result<Vec<VersaoTemplate>, String> {
+    buscar_json(client, &format!("{}/templates/{}/versoes", api_url, id)).await
+}
+
+async fn carregar_planilha(
+    client: &Client,
+    api_url: &str,
+    id: Option<u64>,
+    json_data_base64: Option<String>,
+) -> Result<PlanilhaTemplate, String> {
+    let base64 = match (json_data_base64.filter(|j| !j.trim().is_empty()), id) {
+        (Some(json), _) => json,
+        (None, Some(id)) => {
+            let template: TemplateData = buscar_json(client, &format!("{}/templates/{}", api_url, id)).await?;
+            template.json_data_base64.ok_or("Template sem conteúdo.")?
+        }
+        (None, None) => return Err("Informe o template ou o conteúdo JSON.".to_string()),
+    };
+    decodificar_planilha(&base64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validacao::template::tests::{celula, planilha};
+
+    #[test]
+    fn test_renderizacao_repete_linha_de_resultados() {
+        let mut cabecalho = celula("Amostra {{amostra.numero}} - coleta {{amostra.data_coleta}}");
+        cabecalho.merge_range = Some(IntervaloCelulas { start_row: 0, start_col: 0, end_row: 0, end_col: 1 });
+        let mut coberta = celula("");
+        coberta.merged = Some(true);
+        coberta.master_cell = Some(crate::model::template_planilha::PosicaoCelula { row: 0, col: 0 });
+        let template = planilha(vec![
+            vec![cabecalho, coberta],
+            vec![celula("{{resultado.parametro}}"), celula("{{resultado.valor|numero:2}}")],
+            vec![celula("Ref.: {{legislacao.nome}}"), celula("<fim>")],
+        ]);
+
+        let mut dados = DadosTemplate::default();
+        dados.amostra.insert("numero".to_string(), json!("123/26"));
+        dados.amostra.insert("data_coleta".to_string(), json!("2026-03-05"));
+        dados.legislacao.insert("nome".to_string(), json!("CONAMA 357"));
+        dados.resultados.push(mapa(&[("parametro", Some("pH".to_string())), ("valor", Some("7.1".to_string()))]));
+        dados.resultados.push(mapa(&[("parametro", Some("Cor".to_string())), ("valor", Some("< 5".to_string()))]));
+
+        let html = renderizar_html(&template, &dados).unwrap();
+        assert!(html.contains("<td colspan=\"2\">Amostra 123/26 - coleta 05/03/2026</td>"));
+        assert!(html.contains("<td>pH</td><td>7,10</td>"));
+        assert!(html.contains("<td>Cor</td><td>&lt; 5</td>"));
+        assert!(html.contains("Ref.: CONAMA 357"));
+        assert!(html.contains("&lt;fim&gt;"));
+        assert_eq!(html.matches("<tr").count(), 4);
+    }
+
+    #[test]
+    fn test_diferencas() {
+        let base = planilha(vec![vec![celula("A"), celula("B")]]);
+        let mut nova = base.clone();
+        nova.data[0][1].value = "C".to_string();
+        nova.data[0][0].formula = Some("=1+1".to_string());
+        let diferencas = diferencas_template(&base, &nova);
+        assert_eq!(diferencas.len(), 2);
+        assert_eq!(diferencas[0].celula.as_deref(), Some("A1"));
+        assert_eq!(diferencas[0].campo, "formula");
+        assert_eq!(diferencas[1].antes.as_deref(), Some("B"));
+        assert_eq!(diferencas[1].depois.as_deref(), Some("C"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::config::get_local_data_path;
+
+lazy_static! {
+    /// Serializa a leitura + gravação dos caches entre consultas concorrentes
+    static ref CACHE_LOCK: Mutex<()> = Mutex::new(());
+    /// Serializa as alterações feitas com `alterar_json`
+    static ref ALTERACAO_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Arquivo JSON da pasta de dados da estação; ausente ou inválido volta ao padrão
+pub fn ler_json<T: DeserializeOwned + Default>(app_handle: &AppHandle, arquivo: &str) -> T {
+    get_local_data_path(app_handle, arquivo)
+        .ok()
+        .and_then(|caminho| fs::read_to_string(caminho).ok())
+        .and_then(|conteudo| serde_json::from_str(&conteudo).ok())
+        .unwrap_or_default()
+}
+
+/// Grava formatado, para configurações que o usuário pode conferir no arquivo
+pub fn gravar_json<T: Serialize>(app_handle: &AppHandle, arquivo: &str, valor: &T) -> Result<(), String> {
+    let caminho = get_local_data_path(app_handle, arquivo)?;
+    let conteudo = serde_json::to_string_pretty(valor).map_err(|e| format!("Erro ao serializar {}: {}", arquivo, e))?;
+    fs::write(&caminho, conteudo).map_err(|e| format!("Erro ao salvar {}: {}", arquivo, e))
+}
+
+/// Lê, altera e grava sob o lock. Diferente de `ler_json`, um arquivo existente que não
+/// pode ser lido é erro, para não sobrescrever dados com o padrão
+pub fn alterar_json<T, F>(app_handle: &AppHandle, arquivo: &str, alterar: F) -> Result<(), String>
+where
+    T: Serialize + DeserializeOwned + Default,
+    F: FnOnce(&mut T) -> Result<(), String>,
+{
+    let _guard = ALTERACAO_LOCK.lock().map_err(|_| format!("Falha ao acessar {}.", arquivo))?;
+    let caminho = get_local_data_path(app_handle, arquivo)?;
+    let mut valor = if caminho.exists() {
+        let conteudo = fs::read_to_string(&caminho).map_err(|e| format!("Erro ao ler {}: {}", arquivo, e))?;
+        serde_json::from_str(&conteudo).map_err(|e| format!("Arquivo {} inválido: {}", arquivo, e))?
+    } else {
+        T::default()
+    };
+    alterar(&mut valor)?;
+    gravar_json(app_handle, arquivo, &valor)
+}
+
+/// Junta as entradas novas ao cache gravado, preservando as de outras chamadas
+pub fn mesclar_cache<V>(
+    app_handle: &AppHandle,
+    arquivo: &str,
+    novas: impl IntoIterator<Item = (String, V)>,
+) -> Result<(), String>
+where
+    V: Serialize + DeserializeOwned,
+{
+    let _guard = CACHE_LOCK.lock().map_err(|_| format!("Falha ao acessar {}.", arquivo))?;
+    let mut cache: HashMap<String, V> = ler_json(app_handle, arquivo);
+    cache.extend(novas);
+    escrever_cache(app_handle, arquivo, &cache)
+}
+
+/// Substitui o cache inteiro (ex.: lista baixada de novo)
+pub fn gravar_cache<T: Serialize>(app_handle: &AppHandle, arquivo: &str, valor: &T) -> Result<(), String> {
+    let _guard = CACHE_LOCK.lock().map_err(|_| format!("Falha ao acessar {}.", arquivo))?;
+    escrever_cache(app_handle, arquivo, valor)
+}
+
+fn escrever_cache<T: Serialize>(app_handle: &AppHandle, arquivo: &str, valor: &T) -> Result<(), String> {
+    let caminho = get_local_data_path(app_handle, arquivo)?;
+    let conteudo = serde_json::to_string(valor).map_err(|e| format!("Erro ao serializar {}: {}", arquivo, e))?;
+    fs::write(&caminho, conteudo).map_err(|e| format!("Erro ao salvar {}: {}", arquivo, e))
+}
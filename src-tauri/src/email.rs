@@ -0,0 +1,88 @@
+use std::time::Duration;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::model::email::{ConfiguracaoSmtp, SegurancaSmtp};
+
+const SERVICO_CREDENCIAIS: &str = "sistema_bioma";
+
+fn credencial(nome: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICO_CREDENCIAIS, nome).map_err(|e| format!("Cofre de credenciais indisponível: {}", e))
+}
+
+/// Senha SMTP guardada no cofre do sistema sob `nome`; `None` se nunca foi cadastrada
+pub fn ler_senha(nome: &str) -> Result<Option<String>, String> {
+    match credencial(nome)?.get_password() {
+        Ok(senha) => Ok(Some(senha)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Erro ao ler a senha SMTP do cofre: {}", e)),
+    }
+}
+
+pub fn guardar_senha(nome: &str, senha: &str) -> Result<(), String> {
+    credencial(nome)?
+        .set_password(senha)
+        .map_err(|e| format!("Erro ao guardar a senha SMTP: {}", e))
+}
+
+pub fn validar_config_smtp(configuracao: &ConfiguracaoSmtp) -> Result<(), String> {
+    if configuracao.host.trim().is_empty() {
+        return Err("Servidor SMTP não configurado.".to_string());
+    }
+    if configuracao.porta == 0 {
+        return Err("Porta SMTP inválida.".to_string());
+    }
+    if configuracao.remetente_email.parse::<lettre::Address>().is_err() {
+        return Err(format!("E-mail do remetente inválido: {}", configuracao.remetente_email));
+    }
+    Ok(())
+}
+
+pub fn criar_transporte(configuracao: &ConfiguracaoSmtp) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let host = configuracao.host.trim();
+    let mut builder = match configuracao.seguranca {
+        SegurancaSmtp::Nenhuma => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        SegurancaSmtp::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| format!("Erro ao configurar SMTP: {}", e))?,
+        SegurancaSmtp::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| format!("Erro ao configurar SMTP: {}", e))?,
+    }
+    .port(configuracao.porta)
+    .timeout(Some(Duration::from_secs(30)));
+
+    if let Some(usuario) = configuracao.usuario.as_deref().filter(|u| !u.is_empty()) {
+        let senha = configuracao.senha.clone().unwrap_or_default();
+        builder = builder.credentials(Credentials::new(usuario.to_string(), senha));
+    }
+    Ok(builder.build())
+}
+
+pub async fn enviar_email(
+    transporte: &AsyncSmtpTransport<Tokio1Executor>,
+    configuracao: &ConfiguracaoSmtp,
+    nome: &str,
+    email: &str,
+    assunto: &str,
+    corpo: String,
+) -> Result<(), String> {
+    let remetente = configuracao
+        .remetente_email
+        .parse()
+        .map_err(|e| format!("E-mail do remetente inválido: {}", e))?;
+    let destino = email.parse().map_err(|e| format!("E-mail inválido ({}): {}", email, e))?;
+
+    let mensagem = Message::builder()
+        .from(Mailbox::new(Some(configuracao.remetente_nome.clone()), remetente))
+        .to(Mailbox::new(Some(nome.to_string()), destino))
+        .subject(assunto)
+        .header(ContentType::TEXT_PLAIN)
+        .body(corpo)
+        .map_err(|e| format!("Erro ao montar e-mail: {}", e))?;
+
+    transporte
+        .send(mensagem)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Falha no envio SMTP: {}", e))
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::controller::qualidade::tauri_print_commands_controller::{generate_pdf_from_html, get_default_print_settings};
+
+/// Caminho escolhido pelo usuário ou, sem ele, `nome_arquivo` na pasta Downloads
+pub fn destino_exportacao(caminho: Option<String>, nome_arquivo: &str) -> Result<PathBuf, String> {
+    if let Some(caminho) = caminho.filter(|c| !c.trim().is_empty()) {
+        return Ok(PathBuf::from(caminho));
+    }
+
+    let downloads_dir = dirs::download_dir()
+        .ok_or("Não foi possível encontrar o diretório Downloads")?;
+    Ok(downloads_dir.join(nome_arquivo))
+}
+
+/// Campo de CSV separado por `;`, entre aspas quando necessário
+pub fn campo_csv(valor: &str) -> String {
+    if valor.contains(';') || valor.contains('"') || valor.contains('\n') {
+        format!("\"{}\"", valor.replace('"', "\"\""))
+    } else {
+        valor.to_string()
+    }
+}
+
+/// Grava o CSV com BOM para o Excel reconhecer UTF-8
+pub fn salvar_csv(destino: &Path, conteudo: &str) -> Result<(), String> {
+    fs::write(destino, format!("\u{feff}{}", conteudo)).map_err(|e| format!("Erro ao salvar CSV: {}", e))
+}
+
+pub fn escapar_html(valor: &str) -> String {
+    valor
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const ESTILO_RELATORIO: &str = "\
+body { font-family: Arial, sans-serif; font-size: 11px; }
+table { width: 100%; border-collapse: collapse; margin-bottom: 12px; }
+th, td { border: 1px solid #999; padding: 4px; text-align: left; }
+th { background: #eee; }
+td.num { text-align: right; }
+.aviso { color: #a00; }";
+
+/// Página dos relatórios exportados em PDF. `titulo` é escapado aqui; `introducao` e
+/// `corpo` já vêm em HTML (parágrafos e tabelas montadas com `tabela_html`)
+pub fn pagina_relatorio(titulo: &str, introducao: &str, corpo: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><style>\n{}\n</style></head><body>\n<h2>{}</h2>\n{}\n{}</body></html>",
+        ESTILO_RELATORIO,
+        escapar_html(titulo),
+        introducao,
+        corpo
+    )
+}
+
+/// Tabela com uma linha de cabeçalho; `linhas` são os `<tr>` já montados pelo relatório
+pub fn tabela_html(colunas: &[&str], linhas: &str) -> String {
+    let cabecalho: String = colunas.iter().map(|c| format!("<th>{}</th>", escapar_html(c))).collect();
+    format!("<table><thead><tr>{}</tr></thead><tbody>{}</tbody></table>", cabecalho, linhas)
+}
+
+/// Parágrafo em destaque com a lista; vazio quando não há o que avisar
+pub fn aviso_html(rotulo: &str, itens: &[String]) -> String {
+    if itens.is_empty() {
+        return String::new();
+    }
+    format!("<p class=\"aviso\">{}: {}</p>", escapar_html(rotulo), escapar_html(&itens.join("; ")))
+}
+
+/// Gera o PDF em paisagem pelo mesmo pipeline HTML -> PDF da impressão e copia para `destino`
+pub async fn salvar_pdf(html: String, destino: &Path) -> Result<(), String> {
+    let mut opcoes = get_default_print_settings()?;
+    opcoes.orientation = Some("landscape".to_string());
+
+    let resultado = generate_pdf_from_html(html, opcoes).await?;
+    let temporario = match (resultado.success, resultado.path) {
+        (true, Some(path)) => path,
+        _ => return Err(resultado.error.unwrap_or_else(|| "Falha ao gerar PDF.".to_string())),
+    };
+
+    fs::copy(&temporario, destino).map_err(|e| format!("Erro ao salvar PDF: {}", e))?;
+    let _ = fs::remove_file(&temporario);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_campo_csv_e_escapar_html() {
+        assert_eq!(campo_csv("simples"), "simples");
+        assert_eq!(campo_csv("a;b"), "\"a;b\"");
+        assert_eq!(campo_csv("diz \"oi\""), "\"diz \"\"oi\"\"\"");
+        assert_eq!(escapar_html("<b>\"A&B\"</b>"), "&lt;b&gt;&quot;A&amp;B&quot;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_pagina_relatorio() {
+        let tabela = tabela_html(&["Item", "Qtd."], "<tr><td>A</td><td class=\"num\">1</td></tr>");
+        assert_eq!(tabela, "<table><thead><tr><th>Item</th><th>Qtd.</th></tr></thead><tbody><tr><td>A</td><td class=\"num\">1</td></tr></tbody></table>");
+        assert_eq!(aviso_html("Indisponíveis", &[]), "");
+        assert_eq!(aviso_html("Indisponíveis", &["a<b".to_string(), "c".to_string()]), "<p class=\"aviso\">Indisponíveis: a&lt;b; c</p>");
+
+        let pagina = pagina_relatorio("Lote <1>", "<p>Gerado</p>", &tabela);
+        assert!(pagina.starts_with("<!DOCTYPE html>"));
+        assert!(pagina.contains("<h2>Lote &lt;1&gt;</h2>\n<p>Gerado</p>\n<table>"));
+        assert!(pagina.ends_with("</table></body></html>"));
+    }
+}
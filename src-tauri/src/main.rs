@@ -5,6 +5,11 @@ mod model;
 mod socket_listener;
 mod config;
 mod utils;
+mod validacao;
+mod exportacao;
+mod email;
+mod dados_locais;
+mod monitor;
 
 use controller::inicio_controller::{get_data_inicio, get_data_for_screen};
 use controller::inicio_case::case_x9_controller::{salvar_ticket, update_kanban, update_kanban_card_urgency_and_index};
@@ -150,6 +155,22 @@ use controller::geral::bi_financeiro_controller::{
     listar_rastreabilidade_boletos_tauri,
     abrir_arquivo_rede_bioma_tauri
 };
+use controller::geral::armazenamento_documento_controller::{
+    carregar_config_armazenamento,
+    salvar_config_armazenamento,
+    listar_documentos,
+    enviar_documento,
+    visualizar_documento,
+    baixar_documento,
+};
+use controller::geral::cep_controller::{
+    carregar_config_cep,
+    salvar_config_cep,
+    buscar_endereco_cep,
+    preencher_endereco_cep,
+    normalizar_cidade_ibge,
+    atualizar_municipios_ibge,
+};
 
 use controller::geral::legislacao_parametro_controller::{
     listar_legislacao_parametro_tauri,
@@ -287,6 +308,26 @@ use controller::laboratorio::insumo_registro_controller::{
     listar_insumo_tipos_tauri,
 };
 
+use controller::laboratorio::preparo_insumo_controller::planejar_preparo_insumo_tauri;
+use controller::laboratorio::rastreabilidade_controller::{
+    rastrear_impacto_lote,
+    rastrear_procedencia,
+    exportar_rastreabilidade_csv,
+    exportar_rastreabilidade_pdf,
+};
+use controller::laboratorio::validade_lote_controller::{
+    carregar_config_monitor_validade,
+    salvar_config_monitor_validade,
+    verificar_validades_tauri,
+};
+use controller::laboratorio::calibracao_controller::{
+    listar_planos_calibracao,
+    salvar_plano_calibracao,
+    listar_certificados_calibracao,
+    registrar_certificado_calibracao,
+    listar_agenda_calibracao,
+};
+
 use controller::laboratorio::insumo_registro_2_controller::{
     listar_insumo_registro_2_tauri,
     criar_insumo_registro_2_tauri,
@@ -310,6 +351,31 @@ use controller::laboratorio::mapa_resultado_controller::{
     carregar_mapa_tauri,
     salvar_mapa_tauri,
 };
+use controller::laboratorio::linha_do_tempo_controller::{
+    linha_do_tempo_amostra,
+    exportar_linha_do_tempo_csv,
+    exportar_linha_do_tempo_pdf,
+};
+use controller::laboratorio::etiqueta_controller::{
+    listar_templates_etiqueta,
+    salvar_template_etiqueta,
+    listar_impressoras_etiqueta,
+    salvar_impressoras_etiqueta,
+    gerar_etiquetas,
+    imprimir_etiquetas,
+};
+use controller::laboratorio::prazo_validade_controller::{
+    listar_regras_prazo,
+    salvar_regra_prazo,
+    verificar_alertas_prazo,
+};
+use controller::laboratorio::sessao_leitura_controller::{
+    abrir_sessao_leitura,
+    registrar_leitura_codigo,
+    resumo_sessao_leitura,
+    cancelar_sessao_leitura,
+    finalizar_sessao_leitura,
+};
 use controller::admin::setor_controller::{
     listar_setores_command,
     criar_setor_command,
@@ -330,6 +396,12 @@ use controller::admin::historico_controller::{
 };
 
 use controller::laboratorio::planilha_controller::{ consultar_amostras_por_planilha, consultar_intervalos_planilhas, gerar_nova_planilha};
+use controller::laboratorio::faixa_numeracao_controller::{
+    reconciliar_faixas_planilhas,
+    inutilizar_numero_amostra,
+    listar_numeros_inutilizados,
+    verificar_reuso_numeros,
+};
 
 // Módulo: Qualidade e Utilitários
 use controller::qualidade::xlsx_controller::{import_xlsx_file, import_xlsx_from_bytes, get_xlsx_sheet_names, get_xlsx_sheet_names_from_bytes};
@@ -343,6 +415,15 @@ use controller::qualidade::tauri_print_commands_controller::{
     validate_printer, get_default_print_settings
 };
 use controller::qualidade::json_parser_controller::{save_template, list_templates, delete_template, decode_base64_to_json, update_template, get_template_by_id};
+use controller::qualidade::template_planilha_controller::{
+    obter_schema_template,
+    listar_placeholders_template,
+    validar_template_tauri,
+    listar_versoes_template,
+    comparar_versoes_template,
+    renderizar_template,
+    renderizar_template_amostra,
+};
 
 
 use controller::qualidade::fornecedor_controller::{
@@ -353,6 +434,14 @@ use controller::qualidade::fornecedor_controller::{
     listar_categorias_fornecedor_tauri,
     listar_fornecedores_tauri,
     listar_qualificacoes_tauri,
+    abrir_pasta_fornecedor_tauri,
+};
+use controller::qualidade::avaliacao_fornecedor_controller::{
+    avaliar_fornecedor,
+    avaliar_fornecedores,
+    listar_agenda_requalificacao,
+    desqualificar_fornecedores_vencidos,
+    exportar_avaliacao_fornecedores_pdf,
 };
 
 
@@ -370,6 +459,15 @@ use controller::qualidade::pesquisa_controller::{
     obter_resultados_por_item_tauri,
     salvar_analise_critica_tauri,
 };
+use controller::qualidade::pesquisa_envio_controller::{
+    carregar_config_smtp,
+    salvar_config_smtp,
+    testar_config_smtp,
+    enviar_convites_pesquisa,
+    enviar_lembretes_pesquisa,
+    listar_convites_pesquisa,
+    analisar_pesquisa,
+};
 
 use controller::qualidade::estoque_controller::{
     listar_estoque_items_tauri,
@@ -379,6 +477,19 @@ use controller::qualidade::estoque_controller::{
     criar_estoque_registro_tauri,
     listar_unidades_compra_tauri,
 };
+use controller::qualidade::reposicao_estoque_controller::{
+    prever_reposicao_estoque,
+    definir_fornecedor_estoque_item,
+    exportar_sugestao_compra_xlsx,
+    exportar_sugestao_compra_pdf,
+};
+use controller::qualidade::inventario_estoque_controller::{
+    abrir_inventario_estoque,
+    listar_inventarios_estoque,
+    registrar_contagem_estoque,
+    finalizar_inventario_estoque,
+    cancelar_inventario_estoque,
+};
 
 
 
@@ -415,13 +526,21 @@ use controller::laboratorio::tauri_imprimir_controller::{
  
 use controller::download_controller::{download_file_to_downloads, download_file_bytes};
 use controller::frota::frota_controller::{buscar_agendamentos_hoje};
-use controller::frota::motoristas_controller::{buscar_motoristas, criar_motorista, atualizar_motorista, deletar_motorista};
-use controller::frota::veiculo_controller::{buscar_marcas, deletar_veiculo, atualizar_veiculo, criar_veiculo, buscar_veiculos_e_marcas};
+use controller::frota::motoristas_controller::{buscar_motoristas, criar_motorista, atualizar_motorista, deletar_motorista, verificar_vencimento_cnh};
+use controller::frota::veiculo_controller::{buscar_marcas, deletar_veiculo, atualizar_veiculo, criar_veiculo, buscar_veiculos_e_marcas, validar_placa_veiculo};
 use controller::frota::posto_controller::{buscar_postos, criar_posto, atualizar_posto, deletar_posto};
 use controller::frota::viagem_controller::{criar_frota_viagem, buscar_viagens, deletar_frota_viagem, atualizar_frota_viagem};
 // --- Importações do Sistema ---
 use controller::frota::manutencao_controller::{deletar_tipo_manutencao, buscar_tipos_manutencao, atualizar_frota_manutencao, buscar_manutencoes, criar_frota_manutencao, criar_tipo_manutencao, deletar_frota_manutencao};
 use controller::frota::abastecimento_controller::{deletar_frota_abastecimento, criar_frota_abastecimento, atualizar_frota_abastecimento, buscar_abastecimento, buscar_abastecimento_filtrado};
+use controller::frota::analise_abastecimento_controller::{carregar_parametros_frota, salvar_parametros_frota, analisar_abastecimentos, exportar_analise_abastecimento_xlsx, exportar_analise_abastecimento_pdf};
+use controller::frota::consistencia_odometro_controller::{linha_do_tempo_odometro, listar_inconsistencias_frota, verificar_consistencia_viagem, verificar_consistencia_abastecimento};
+use controller::frota::manutencao_preventiva_controller::{listar_planos_manutencao, salvar_plano_manutencao, excluir_plano_manutencao, calcular_proxima_manutencao, painel_manutencao_preventiva};
+use controller::frota::rota_coleta_controller::{carregar_config_rotas, salvar_config_rotas, geocodificar_endereco, montar_paradas_agendamentos, planejar_rotas_coleta};
+use controller::frota::comprovante_abastecimento_controller::{enviar_comprovante_abastecimento, obter_miniatura_comprovante, obter_comprovante_abastecimento, vincular_comprovante_abastecimento};
+use controller::frota::alertas_frota_controller::{carregar_parametros_alertas_frota, salvar_parametros_alertas_frota};
+use controller::frota::email_frota_controller::{carregar_config_smtp_frota, salvar_config_smtp_frota, testar_config_smtp_frota};
+use controller::frota::custo_veiculo_controller::{listar_lancamentos_veiculo, salvar_lancamento_veiculo, excluir_lancamento_veiculo, registrar_pagamento_lancamento, sugerir_motorista_multa, verificar_vencimentos_veiculos, relatorio_custo_veiculos, exportar_custo_veiculos_xlsx};
 use controller::geral::cadastrar_coleta_controller::{buscar_coleta_referente, atualizar_numero_amostra, buscar_cliente_referente};
 use std::env;
 use crate::config::get_ws_url;
@@ -447,6 +566,28 @@ fn main() {
                 }
             });
 
+            tauri::async_runtime::spawn(
+                controller::laboratorio::prazo_validade_controller::monitorar_prazos(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::laboratorio::validade_lote_controller::monitorar_validades(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::laboratorio::calibracao_controller::monitorar_calibracoes(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::qualidade::avaliacao_fornecedor_controller::monitorar_fornecedores(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::frota::manutencao_preventiva_controller::monitorar_manutencoes(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::frota::motoristas_controller::monitorar_cnh(app_handle.clone()),
+            );
+            tauri::async_runtime::spawn(
+                controller::frota::custo_veiculo_controller::monitorar_vencimentos_veiculos(app_handle.clone()),
+            );
+
             Ok(())
         })
      //   .plugin(tauri_plugin_dialog::init()) // Inicializa o plugin de diálogo
@@ -518,6 +659,18 @@ fn main() {
             registrar_uso_reagente_tauri,
             listar_rastreabilidade_boletos_tauri,
             abrir_arquivo_rede_bioma_tauri,
+            carregar_config_armazenamento,
+            salvar_config_armazenamento,
+            listar_documentos,
+            enviar_documento,
+            visualizar_documento,
+            baixar_documento,
+            carregar_config_cep,
+            salvar_config_cep,
+            buscar_endereco_cep,
+            preencher_endereco_cep,
+            normalizar_cidade_ibge,
+            atualizar_municipios_ibge,
             //get_auditoria_financeira,
            // get_kpis_financeiros,
             //abrir_arquivo_rede_bioma,
@@ -650,6 +803,10 @@ fn main() {
             consultar_amostras_por_planilha,
             consultar_intervalos_planilhas,
             gerar_nova_planilha,
+            reconciliar_faixas_planilhas,
+            inutilizar_numero_amostra,
+            listar_numeros_inutilizados,
+            verificar_reuso_numeros,
             buscar_pg,
             buscar_certificado,
             listar_amostras_por_faixa_tauri,
@@ -682,6 +839,19 @@ fn main() {
             listar_insumos_por_tipo_tauri,
             buscar_receita_e_estoque_mp_tauri,
             listar_insumo_tipos_tauri,
+            planejar_preparo_insumo_tauri,
+            rastrear_impacto_lote,
+            rastrear_procedencia,
+            exportar_rastreabilidade_csv,
+            exportar_rastreabilidade_pdf,
+            carregar_config_monitor_validade,
+            salvar_config_monitor_validade,
+            verificar_validades_tauri,
+            listar_planos_calibracao,
+            salvar_plano_calibracao,
+            listar_certificados_calibracao,
+            registrar_certificado_calibracao,
+            listar_agenda_calibracao,
             listar_insumo_registro_2_tauri,
             criar_insumo_registro_2_tauri,
             editar_insumo_registro_2_tauri,
@@ -715,13 +885,26 @@ fn main() {
             decode_base64_to_json,
             update_template,
             get_template_by_id,
+            obter_schema_template,
+            listar_placeholders_template,
+            validar_template_tauri,
+            listar_versoes_template,
+            comparar_versoes_template,
+            renderizar_template,
+            renderizar_template_amostra,
             buscar_fornecedor_detalhado_tauri,
             cadastrar_fornecedor_tauri,
             editar_fornecedor_tauri,
             deletar_fornecedor_tauri,
             listar_categorias_fornecedor_tauri,
-            listar_fornecedores_tauri, 
+            listar_fornecedores_tauri,
+            abrir_pasta_fornecedor_tauri,
             listar_qualificacoes_tauri,
+            avaliar_fornecedor,
+            avaliar_fornecedores,
+            listar_agenda_requalificacao,
+            desqualificar_fornecedores_vencidos,
+            exportar_avaliacao_fornecedores_pdf,
             listar_modelos_pesquisa_tauri,
             listar_itens_por_modelo_tauri,
             cadastrar_pesquisa_tauri,
@@ -732,12 +915,28 @@ fn main() {
             listar_destinatarios_tauri,
             obter_resultados_por_item_tauri,
             salvar_analise_critica_tauri,
+            carregar_config_smtp,
+            salvar_config_smtp,
+            testar_config_smtp,
+            enviar_convites_pesquisa,
+            enviar_lembretes_pesquisa,
+            listar_convites_pesquisa,
+            analisar_pesquisa,
             listar_estoque_items_tauri,
             criar_estoque_item_tauri,
             editar_estoque_item_tauri,
             buscar_estoque_item_detalhado_tauri,
             criar_estoque_registro_tauri,
             listar_unidades_compra_tauri,
+            prever_reposicao_estoque,
+            definir_fornecedor_estoque_item,
+            exportar_sugestao_compra_xlsx,
+            exportar_sugestao_compra_pdf,
+            abrir_inventario_estoque,
+            listar_inventarios_estoque,
+            registrar_contagem_estoque,
+            finalizar_inventario_estoque,
+            cancelar_inventario_estoque,
 
             // Comandos do Módulo de Administração de Usuários
             listar_usuarios_admin_command,
@@ -783,6 +982,7 @@ fn main() {
             buscar_marcas,
             deletar_veiculo,
             atualizar_veiculo,
+            validar_placa_veiculo,
             criar_veiculo,
             buscar_veiculos_e_marcas,
             deletar_posto,
@@ -798,6 +998,43 @@ fn main() {
             criar_frota_abastecimento,
             buscar_abastecimento,
             buscar_abastecimento_filtrado,
+            carregar_parametros_frota,
+            salvar_parametros_frota,
+            analisar_abastecimentos,
+            exportar_analise_abastecimento_xlsx,
+            exportar_analise_abastecimento_pdf,
+            linha_do_tempo_odometro,
+            listar_inconsistencias_frota,
+            verificar_consistencia_viagem,
+            verificar_consistencia_abastecimento,
+            listar_planos_manutencao,
+            salvar_plano_manutencao,
+            excluir_plano_manutencao,
+            calcular_proxima_manutencao,
+            painel_manutencao_preventiva,
+            verificar_vencimento_cnh,
+            carregar_config_rotas,
+            salvar_config_rotas,
+            geocodificar_endereco,
+            montar_paradas_agendamentos,
+            planejar_rotas_coleta,
+            enviar_comprovante_abastecimento,
+            obter_miniatura_comprovante,
+            obter_comprovante_abastecimento,
+            vincular_comprovante_abastecimento,
+            listar_lancamentos_veiculo,
+            salvar_lancamento_veiculo,
+            excluir_lancamento_veiculo,
+            registrar_pagamento_lancamento,
+            sugerir_motorista_multa,
+            verificar_vencimentos_veiculos,
+            relatorio_custo_veiculos,
+            exportar_custo_veiculos_xlsx,
+            carregar_parametros_alertas_frota,
+            salvar_parametros_alertas_frota,
+            carregar_config_smtp_frota,
+            salvar_config_smtp_frota,
+            testar_config_smtp_frota,
             deletar_tipo_manutencao, 
             buscar_tipos_manutencao, 
             atualizar_frota_manutencao, 
@@ -827,6 +1064,23 @@ fn main() {
             bloquear_amostras,
             desbloquear_amostras,
             listar_amostras_bloqueadas,
+            linha_do_tempo_amostra,
+            exportar_linha_do_tempo_csv,
+            exportar_linha_do_tempo_pdf,
+            listar_templates_etiqueta,
+            salvar_template_etiqueta,
+            listar_impressoras_etiqueta,
+            salvar_impressoras_etiqueta,
+            gerar_etiquetas,
+            imprimir_etiquetas,
+            abrir_sessao_leitura,
+            registrar_leitura_codigo,
+            resumo_sessao_leitura,
+            cancelar_sessao_leitura,
+            finalizar_sessao_leitura,
+            listar_regras_prazo,
+            salvar_regra_prazo,
+            verificar_alertas_prazo,
             buscar_parametro_mapa,
             solicitar_revisao, 
             publicar_resultado,
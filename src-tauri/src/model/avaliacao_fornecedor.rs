@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ParametrosAvaliacaoFornecedor {
+    /// Período considerado para não conformidades e entregas
+    pub janela_meses: u32,
+    /// Antecedência com que a requalificação entra na agenda
+    pub antecedencia_requalificacao_dias: u32,
+    /// Prazo de entrega esperado; acima dele a nota de entrega cai proporcionalmente
+    pub prazo_entrega_meta_dias: f64,
+}
+
+impl Default for ParametrosAvaliacaoFornecedor {
+    fn default() -> Self {
+        ParametrosAvaliacaoFornecedor {
+            janela_meses: 12,
+            antecedencia_requalificacao_dias: 60,
+            prazo_entrega_meta_dias: 7.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoQualificacao {
+    Vencida,
+    SemQualificacao,
+    AVencer,
+    Vigente,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificacaoFornecedor {
+    Aprovado,
+    AprovadoComRestricao,
+    Reprovado,
+}
+
+/// Parcela da nota de um critério
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComponenteNota {
+    pub criterio: String,
+    pub pontos: f64,
+    pub maximo: f64,
+    pub detalhe: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AvaliacaoFornecedor {
+    pub fornecedor_id: u32,
+    pub nome: String,
+    pub qualificado: Option<i8>,
+    pub situacao_qualificacao: SituacaoQualificacao,
+    pub validade_qualificacao: Option<String>, // "YYYY-MM-DD"
+    /// Data a partir da qual a requalificação deve ser feita
+    pub data_requalificacao: Option<String>,
+    /// Critérios sem dados (ex.: sem entregas) ficam de fora e a nota é normalizada
+    pub componentes: Vec<ComponenteNota>,
+    /// 0 a 100
+    pub nota: f64,
+    pub classificacao: ClassificacaoFornecedor,
+    pub nao_conformidades_abertas: usize,
+    pub nao_conformidades_encerradas: usize,
+    pub entregas: usize,
+    pub prazo_medio_entrega: Option<f64>,
+    /// Marcado como não qualificado nesta execução por validade vencida
+    pub desqualificado_automaticamente: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatorioAvaliacaoFornecedores {
+    pub gerado_em: String,
+    pub periodo_inicio: String,
+    pub periodo_fim: String,
+    pub parametros: ParametrosAvaliacaoFornecedor,
+    pub avaliacoes: Vec<AvaliacaoFornecedor>,
+    pub fornecedores_indisponiveis: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoAvaliacaoFornecedores {
+    pub caminho: String,
+    pub total_fornecedores: usize,
+}
@@ -0,0 +1,111 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEventoCalibracao {
+    /// Calibração externa, com certificado de laboratório acreditado
+    Calibracao,
+    /// Verificação intermediária interna
+    Verificacao,
+    Manutencao,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoCalibracao {
+    Vencida,
+    Reprovada,
+    SemRegistro,
+    AVencer,
+    EmDia,
+}
+
+impl SituacaoCalibracao {
+    /// Equipamento pode ser usado em análises
+    pub fn liberado(&self) -> bool {
+        matches!(self, SituacaoCalibracao::EmDia | SituacaoCalibracao::AVencer)
+    }
+}
+
+/// Plano de calibração/verificação de um equipamento (registro de insumo do tipo Equipamento)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanoCalibracao {
+    pub id: Option<u32>,
+    pub insumo_registro_id: u32,
+    pub tipo: TipoEventoCalibracao,
+    pub periodicidade_dias: u32,
+    #[serde(default = "antecedencia_padrao")]
+    pub antecedencia_aviso_dias: u32,
+    #[serde(default = "ativo_padrao")]
+    pub ativo: bool,
+}
+
+fn antecedencia_padrao() -> u32 {
+    15
+}
+
+fn ativo_padrao() -> bool {
+    true
+}
+
+/// Ponto medido no certificado. Valores como string, no padrão dos payloads do frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PontoCalibracao {
+    pub valor_nominal: String,
+    pub valor_medido: String,
+    pub incerteza: Option<String>,
+}
+
+/// Payload recebido do Frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NovoCertificadoPayload {
+    pub insumo_registro_id: u32,
+    pub plano_id: Option<u32>,
+    pub tipo: TipoEventoCalibracao,
+    pub data_execucao: String, // "YYYY-MM-DD" ou "dd/mm/yyyy"
+    pub numero_certificado: Option<String>,
+    pub executor: Option<String>,
+    pub pontos: Vec<PontoCalibracao>,
+    pub observacao: Option<String>,
+    pub usuario_id: u32,
+}
+
+/// Certificado como enviado e devolvido pela API, já avaliado contra a tolerância (`desvios`)
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertificadoCalibracao {
+    pub id: Option<u32>,
+    pub insumo_registro_id: u32,
+    pub plano_id: Option<u32>,
+    pub tipo: TipoEventoCalibracao,
+    pub data_execucao: String, // "YYYY-MM-DD"
+    pub numero_certificado: Option<String>,
+    pub executor: Option<String>,
+    pub pontos: Vec<PontoCalibracao>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub erro_maximo: Option<BigDecimal>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub tolerancia: Option<BigDecimal>,
+    pub aprovado: bool,
+    pub observacao: Option<String>,
+    pub usuario_id: u32,
+}
+
+/// Linha da agenda de calibração
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatusCalibracaoEquipamento {
+    pub insumo_registro_id: u32,
+    pub equipamento: String,
+    pub modelo: Option<String>,
+    pub numero_serie: Option<String>,
+    pub plano_id: Option<u32>,
+    pub tipo: Option<TipoEventoCalibracao>,
+    pub ultima_execucao: Option<String>,
+    pub proxima_execucao: Option<String>,
+    /// Negativo quando vencida
+    pub dias_restantes: Option<i64>,
+    pub situacao: SituacaoCalibracao,
+    pub fora_de_uso: bool,
+}
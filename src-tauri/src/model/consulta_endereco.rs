@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Origem das consultas de endereço (CEP, geocodificação)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvedorConsulta {
+    /// Serviço configurado pela URL; precisa ser ativado na configuração
+    Servico,
+    /// Só a tabela estática da configuração; funciona sem internet
+    #[default]
+    Estatico,
+}
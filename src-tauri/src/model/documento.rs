@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoArmazenamento {
+    /// Estação ainda sem configuração; os comandos de documentos pedem para configurar
+    #[default]
+    NaoConfigurado,
+    /// Pasta no disco da estação
+    PastaLocal,
+    /// Compartilhamento de rede (caminho UNC ou montado)
+    Smb,
+    /// Upload para o backend (`/documentos`)
+    Api,
+}
+
+/// Configuração local da estação (arquivo `armazenamento_documentos.json`)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConfiguracaoArmazenamento {
+    pub tipo: TipoArmazenamento,
+    /// Raiz dos documentos para `PastaLocal`/`Smb`
+    pub caminho_raiz: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoriaDocumento {
+    Certificado,
+    NotaFiscal,
+    Boleto,
+    /// Arquivos soltos na pasta do dono (inclui os já existentes no compartilhamento)
+    Outro,
+}
+
+impl CategoriaDocumento {
+    pub fn pasta(&self) -> Option<&'static str> {
+        match self {
+            CategoriaDocumento::Certificado => Some("Certificados"),
+            CategoriaDocumento::NotaFiscal => Some("Notas Fiscais"),
+            CategoriaDocumento::Boleto => Some("Boletos"),
+            CategoriaDocumento::Outro => None,
+        }
+    }
+}
+
+/// A quem o documento pertence, ex.: `{ modulo: "Fornecedores", referencia: "<fantasia>" }`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EscopoDocumento {
+    pub modulo: String,
+    pub referencia: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersaoDocumento {
+    pub versao: u32,
+    pub tamanho: u64,
+    pub modificado_em: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentoArmazenado {
+    pub nome: String,
+    pub categoria: CategoriaDocumento,
+    /// Versão atual (1 = nunca substituído)
+    pub versao: u32,
+    pub tamanho: u64,
+    pub modificado_em: String,
+    /// Versões anteriores, da mais recente para a mais antiga
+    pub versoes_anteriores: Vec<VersaoDocumento>,
+}
+
+/// Payload do frontend: arquivo escolhido no disco ou conteúdo já em base64
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnviarDocumentoPayload {
+    pub escopo: EscopoDocumento,
+    pub categoria: CategoriaDocumento,
+    pub nome_arquivo: Option<String>,
+    pub caminho_origem: Option<String>,
+    pub conteudo_base64: Option<String>,
+}
+
+/// Corpo enviado e recebido no backend `Api`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentoApiPayload {
+    pub modulo: String,
+    pub referencia: String,
+    pub categoria: CategoriaDocumento,
+    pub nome: String,
+    pub conteudo_base64: String,
+}
+
+/// Conteúdo para pré-visualização no app
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConteudoDocumento {
+    pub nome: String,
+    pub mime: String,
+    pub versao: u32,
+    pub tamanho: u64,
+    pub conteudo_base64: String,
+}
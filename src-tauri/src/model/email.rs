@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SegurancaSmtp {
+    /// Sem criptografia (ex.: servidor SMTP local de testes)
+    Nenhuma,
+    #[default]
+    StartTls,
+    /// TLS implícito, normalmente porta 465
+    Tls,
+}
+
+/// Relay SMTP de um módulo; cada módulo grava o seu em arquivo próprio
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfiguracaoSmtp {
+    pub host: String,
+    pub porta: u16,
+    pub seguranca: SegurancaSmtp,
+    pub usuario: Option<String>,
+    /// Fica no cofre de credenciais do sistema: nunca é gravada no arquivo nem devolvida
+    /// ao frontend; `None` ao salvar mantém a senha atual
+    #[serde(skip_serializing)]
+    pub senha: Option<String>,
+    pub remetente_email: String,
+    pub remetente_nome: String,
+}
+
+impl Default for ConfiguracaoSmtp {
+    fn default() -> Self {
+        ConfiguracaoSmtp {
+            host: String::new(),
+            porta: 587,
+            seguranca: SegurancaSmtp::StartTls,
+            usuario: None,
+            senha: None,
+            remetente_email: String::new(),
+            remetente_nome: String::new(),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEtiqueta {
+    Amostra,
+    Reagente,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoCodigo {
+    Code128,
+    Qr,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinguagemImpressora {
+    Zpl,
+    Epl,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatoEtiqueta {
+    Zpl,
+    Epl,
+    Pdf,
+}
+
+/// Texto impresso na etiqueta; `chave` referencia um campo de `ItemEtiqueta::campos`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CampoEtiqueta {
+    pub chave: String,
+    pub rotulo: Option<String>,
+    pub x_mm: f64,
+    pub y_mm: f64,
+    pub altura_mm: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateEtiqueta {
+    pub id: Option<u32>,
+    pub nome: String,
+    pub tipo: TipoEtiqueta,
+    pub largura_mm: f64,
+    pub altura_mm: f64,
+    pub dpi: u32,
+    pub codigo: TipoCodigo,
+    pub codigo_x_mm: f64,
+    pub codigo_y_mm: f64,
+    pub codigo_altura_mm: f64,
+    pub campos: Vec<CampoEtiqueta>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DadosEtiquetaAmostra {
+    pub numero: String,
+    pub cliente: Option<String>,
+    pub matriz: Option<String>,
+    pub data_coleta: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrigemRegistro {
+    ReagenteLimpeza,
+    Insumo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DadosEtiquetaReagente {
+    pub id_registro: u32,
+    pub origem: OrigemRegistro,
+    pub nome: Option<String>,
+    pub lote: Option<String>,
+    pub validade: Option<String>,
+    pub preparo: Option<String>,
+}
+
+/// Conteúdo já resolvido de uma etiqueta (código de barras + campos de texto)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemEtiqueta {
+    pub codigo: String,
+    pub campos: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum ConexaoImpressora {
+    Rede { host: String, porta: u16 },
+    /// Dispositivo local (`/dev/usb/lp0`) ou compartilhamento Windows (`\\localhost\Zebra`)
+    Usb { caminho: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImpressoraEtiqueta {
+    pub nome: String,
+    pub linguagem: LinguagemImpressora,
+    pub conexao: ConexaoImpressora,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EtiquetaGerada {
+    pub formato: FormatoEtiqueta,
+    /// Comandos ZPL/EPL ou HTML de pré-visualização
+    pub conteudo: String,
+    /// Caminho do PDF gerado (apenas para `FormatoEtiqueta::Pdf`)
+    pub caminho: Option<String>,
+    pub total_etiquetas: usize,
+}
+
+/// Conteúdo identificado a partir de um código lido pelo scanner
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum CodigoLido {
+    Amostra { numero: String },
+    Registro { origem: OrigemRegistro, id_registro: u32 },
+}
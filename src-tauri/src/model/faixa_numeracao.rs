@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Intervalo fechado de números de amostra (`inicio..=fim`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct IntervaloNumeros {
+    pub inicio: u32,
+    pub fim: u32,
+}
+
+/// Resultado da conciliação de uma planilha emitida com as amostras cadastradas
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatorioFaixaPlanilha {
+    pub id_planilha: u32,
+    pub inicial: u32,
+    #[serde(rename = "final")]
+    pub final_: u32,
+    pub total_numeros: u32,
+    pub total_registrados: u32,
+    pub total_inutilizados: u32,
+    /// Números pulados antes do maior número já cadastrado
+    pub lacunas: Vec<IntervaloNumeros>,
+    /// Números ainda livres depois do maior número cadastrado
+    pub nao_utilizados: Vec<IntervaloNumeros>,
+    /// Números cadastrados na planilha mas fora do intervalo emitido
+    pub fora_da_faixa: Vec<u32>,
+    /// Números cadastrados mais de uma vez
+    pub duplicados: Vec<u32>,
+    /// Planilhas cujo intervalo se sobrepõe a este
+    pub sobreposicoes: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NumeroInutilizado {
+    pub numero: u32,
+    pub motivo: String,
+    pub usuario: Option<String>,
+    pub data_hora: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InutilizarNumeroPayload {
+    pub numero: u32,
+    pub motivo: String,
+    pub user_id: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MotivoAvisoNumero {
+    JaRegistrado,
+    Inutilizado,
+    ForaDeFaixa,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AvisoReusoNumero {
+    pub numero: u32,
+    pub motivo: MotivoAvisoNumero,
+    pub id_planilha: Option<u32>,
+    pub mensagem: String,
+}
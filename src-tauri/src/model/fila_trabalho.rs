@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::model::prazo_validade::StatusPrazo;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ItemFilaTrabalho {
     pub analise_id: u32,
     pub amostra_numero: Option<String>,
@@ -17,6 +18,18 @@ pub struct ItemFilaTrabalho {
     pub em_campo: Option<i8>,
     pub data_inicio: Option<String>,
     pub usuario_visto: Option<u32>,
+    #[serde(default)]
+    pub matriz: Option<String>,
+    #[serde(default)]
+    pub hora_coleta: Option<String>,
+    // Calculados localmente a partir das regras de prazo de validade
+    #[serde(default)]
+    pub prazo_limite: Option<String>,
+    /// Minutos até o prazo limite (negativo quando vencido)
+    #[serde(default)]
+    pub prazo_restante: Option<i64>,
+    #[serde(default)]
+    pub status_prazo: Option<StatusPrazo>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,4 +49,11 @@ pub struct IniciarAnalisePayload {
     pub data_inicio: String,
     pub hora_inicio: String,
     pub is_em_campo: bool,
+    /// Registros de insumo (equipamentos) usados; conferidos contra o plano de calibração
+    /// antes de iniciar. Não é repassado à API.
+    #[serde(default, skip_serializing)]
+    pub equipamentos: Vec<u32>,
+    /// Lotes de insumo (registro 2) em uso; lote vencido bloqueia o início. Não é repassado à API.
+    #[serde(default, skip_serializing)]
+    pub lotes_insumo: Vec<u32>,
 }
\ No newline at end of file
@@ -112,6 +112,12 @@ pub struct SalvarFornecedorPayload {
     pub qualificacoes: Option<Vec<NovaQualificacaoPayload>>,
 }
 
+// PATCH só da flag de qualificação (usado na desqualificação automática)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AtualizacaoQualificadoPayload {
+    pub qualificado: i8,
+}
+
 // NOVO: Struct para receber os dados da lista da API.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(non_snake_case)]
@@ -80,4 +80,11 @@ pub struct HistoricoFilterPayload {
     pub acao: Option<String>,
     pub data_inicio: Option<String>,
     pub data_fim: Option<String>,
-}
\ No newline at end of file
+}
+/// Registro de ação feito pelo próprio sistema (ex.: rotinas automáticas)
+#[derive(Debug, Serialize)]
+pub struct NovoHistoricoPayload {
+    pub usuario_id: u32,
+    pub acao: String,
+    pub descricao: String,
+}
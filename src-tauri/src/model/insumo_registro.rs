@@ -58,7 +58,8 @@ pub struct RegistroMateriaPrimaPayload {
 }
 
 /// Payload recebido do Frontend (via comando Tauri)
-#[derive(Debug, Deserialize)]
+/// Também devolvido pronto pelo planejador de preparo (`preparo_insumo_controller`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(non_snake_case)]
 pub struct RegistroInsumoFrontendPayload {
     pub insumo_id: u32,
@@ -175,4 +176,10 @@ pub struct RegistroSuporteFormulario {
 pub struct InsumoTipoOption {
     pub id: u32,
     pub nome: String,
-}
\ No newline at end of file
+}
+
+// Para o PATCH de 'fora_de_uso': só a flag, sem regravar vínculos de matéria-prima
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AtualizacaoForaDeUsoPayload {
+    pub fora_de_uso: bool,
+}
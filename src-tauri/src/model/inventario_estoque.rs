@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusInventario {
+    Aberto,
+    Finalizado,
+    Cancelado,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemContagem {
+    pub item_id: u32,
+    pub nome: String,
+    pub unidade: String,
+    /// Saldo do sistema no momento da última contagem do item
+    pub saldo_sistema: f64,
+    pub quantidade_contada: Option<i32>,
+    /// Contado - sistema; `None` enquanto o item não foi contado
+    pub diferenca: Option<f64>,
+    pub contado_em: Option<String>,
+    pub contado_por: Option<u32>,
+}
+
+/// Movimentação de ajuste gerada na finalização
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AjusteInventario {
+    pub item_id: u32,
+    pub nome: String,
+    pub entrada: bool,
+    pub quantidade: i32,
+    pub observacao: String,
+    pub registrado: bool,
+    pub erro: Option<String>,
+}
+
+/// Sessão de contagem física, guardada em `inventario_estoque.json` na estação
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessaoInventario {
+    pub id: String,
+    pub descricao: String,
+    pub aberto_em: String,
+    pub aberto_por: u32,
+    pub status: StatusInventario,
+    pub itens: Vec<ItemContagem>,
+    pub finalizado_em: Option<String>,
+    pub finalizado_por: Option<u32>,
+    pub motivo_ajuste: Option<String>,
+    pub ajustes: Vec<AjusteInventario>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AbrirInventarioPayload {
+    pub descricao: String,
+    pub usuario_id: u32,
+    /// Itens a contar; `None` conta todos os itens ativos
+    pub item_ids: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegistrarContagemPayload {
+    pub sessao_id: String,
+    pub item_id: u32,
+    pub quantidade_contada: i32,
+    pub usuario_id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinalizarInventarioPayload {
+    pub sessao_id: String,
+    pub usuario_id: u32,
+    /// Motivo gravado na observação dos registros de ajuste
+    pub motivo: String,
+    /// Finaliza mesmo com itens não contados (eles ficam sem ajuste)
+    #[serde(default)]
+    pub ignorar_nao_contados: bool,
+}
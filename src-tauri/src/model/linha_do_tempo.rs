@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+
+/// Tipos de evento que compõem a cadeia de custódia de uma amostra
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoEventoAmostra {
+    Coleta,
+    EntradaLaboratorio,
+    Checagem,
+    Bloqueio,
+    Desbloqueio,
+    InicioAnalise,
+    TerminoAnalise,
+    ResultadoAlterado,
+    Visto,
+    VistoRemovido,
+    RevisaoSolicitada,
+    Publicacao,
+    Assinatura,
+    Impressao,
+    Outro,
+}
+
+impl TipoEventoAmostra {
+    /// Converte a ação registrada na auditoria da API no tipo de evento
+    pub fn from_acao(acao: &str) -> Self {
+        match acao.trim().to_lowercase().as_str() {
+            "inicio" | "iniciar" | "iniciar_analise" => TipoEventoAmostra::InicioAnalise,
+            "termino" | "finalizar" => TipoEventoAmostra::TerminoAnalise,
+            "resultado" | "salvar_resultado" | "alterar_resultado" | "alterar_pop" => TipoEventoAmostra::ResultadoAlterado,
+            "visto" | "vistar" => TipoEventoAmostra::Visto,
+            "remover_visto" => TipoEventoAmostra::VistoRemovido,
+            "revisao" | "solicitar_revisao" => TipoEventoAmostra::RevisaoSolicitada,
+            "publicar" | "publicacao" => TipoEventoAmostra::Publicacao,
+            "assinar" | "assinatura" => TipoEventoAmostra::Assinatura,
+            "imprimir" | "impressao" => TipoEventoAmostra::Impressao,
+            "bloquear" | "bloqueio" => TipoEventoAmostra::Bloqueio,
+            "desbloquear" | "desbloqueio" => TipoEventoAmostra::Desbloqueio,
+            "checagem" => TipoEventoAmostra::Checagem,
+            _ => TipoEventoAmostra::Outro,
+        }
+    }
+
+    pub fn descricao(&self) -> &'static str {
+        match self {
+            TipoEventoAmostra::Coleta => "Coleta",
+            TipoEventoAmostra::EntradaLaboratorio => "Entrada no laboratório",
+            TipoEventoAmostra::Checagem => "Checagem",
+            TipoEventoAmostra::Bloqueio => "Bloqueio",
+            TipoEventoAmostra::Desbloqueio => "Desbloqueio",
+            TipoEventoAmostra::InicioAnalise => "Início de análise",
+            TipoEventoAmostra::TerminoAnalise => "Término de análise",
+            TipoEventoAmostra::ResultadoAlterado => "Resultado alterado",
+            TipoEventoAmostra::Visto => "Visto",
+            TipoEventoAmostra::VistoRemovido => "Visto removido",
+            TipoEventoAmostra::RevisaoSolicitada => "Revisão solicitada",
+            TipoEventoAmostra::Publicacao => "Publicação",
+            TipoEventoAmostra::Assinatura => "Assinatura",
+            TipoEventoAmostra::Impressao => "Impressão",
+            TipoEventoAmostra::Outro => "Outro",
+        }
+    }
+}
+
+/// Registro de auditoria retornado por `/laboratorio/linha-do-tempo/{id_analise}`
+#[derive(Debug, Deserialize)]
+pub struct EventoAuditoriaFromApi {
+    pub acao: String,
+    pub descricao: Option<String>,
+    pub usuario: Option<String>,
+    pub ip: Option<String>,
+    pub computador: Option<String>,
+    pub data_hora: Option<String>,
+    pub valor_anterior: Option<String>,
+    pub valor_novo: Option<String>,
+    pub id_resultado: Option<u32>,
+    pub parametro: Option<String>,
+}
+
+/// Evento já normalizado e enviado para o frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventoLinhaDoTempo {
+    pub tipo: TipoEventoAmostra,
+    pub descricao: String,
+    pub usuario: Option<String>,
+    pub ip: Option<String>,
+    pub computador: Option<String>,
+    pub data_hora: Option<NaiveDateTime>,
+    pub valor_anterior: Option<String>,
+    pub valor_novo: Option<String>,
+    pub id_resultado: Option<u32>,
+    pub parametro: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinhaDoTempoAmostra {
+    pub id_analise: u32,
+    pub numero: Option<String>,
+    pub identificacao: Option<String>,
+    pub eventos: Vec<EventoLinhaDoTempo>,
+    /// Fontes que não puderam ser consultadas (a linha do tempo pode estar incompleta)
+    pub fontes_indisponiveis: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinhaDoTempoFiltro {
+    pub id_analise: Option<u32>,
+    pub numero_amostra: Option<String>,
+    /// Quando informado, mantém apenas eventos destes tipos
+    pub tipos: Option<Vec<TipoEventoAmostra>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoLinhaDoTempo {
+    pub caminho: String,
+    pub total_eventos: usize,
+}
@@ -52,4 +52,21 @@ pub mod insumo_registro_2;
 pub mod reagente_limpeza_registro;
 pub mod fila_trabalho;
 pub mod mapa_resultado;
-pub mod financeiro_bi;
\ No newline at end of file
+pub mod financeiro_bi;
+pub mod linha_do_tempo;
+pub mod faixa_numeracao;
+pub mod etiqueta;
+pub mod sessao_leitura;
+pub mod prazo_validade;
+pub mod preparo_insumo;
+pub mod rastreabilidade;
+pub mod validade_lote;
+pub mod calibracao;
+pub mod reposicao_estoque;
+pub mod inventario_estoque;
+pub mod avaliacao_fornecedor;
+pub mod documento;
+pub mod pesquisa_envio;
+pub mod template_planilha;
+pub mod email;
+pub mod consulta_endereco;
\ No newline at end of file
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use crate::model::email::ConfiguracaoSmtp;
+
+/// Configuração local do envio de convites (arquivo `smtp_pesquisa.json`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfiguracaoSmtpPesquisa {
+    #[serde(flatten)]
+    pub smtp: ConfiguracaoSmtp,
+    /// Página pública de resposta; o token é anexado como `?token=`
+    pub url_resposta: String,
+}
+
+impl Default for ConfiguracaoSmtpPesquisa {
+    fn default() -> Self {
+        ConfiguracaoSmtpPesquisa {
+            smtp: ConfiguracaoSmtp {
+                remetente_nome: "Qualidade".to_string(),
+                ..Default::default()
+            },
+            url_resposta: String::new(),
+        }
+    }
+}
+
+/// Convite de um destinatário, mantido pela API (`/qualidade/pesquisas/{id}/convites`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConvitePesquisa {
+    pub pesquisa_id: u32,
+    pub destinatario_id: u32,
+    pub nome: String,
+    pub email: String,
+    pub token: String,
+    pub enviado_em: Option<String>,
+    pub lembretes_enviados: u32,
+    pub ultimo_lembrete_em: Option<String>,
+    pub respondido: bool,
+    pub ultimo_erro: Option<String>,
+}
+
+/// Registro do token no backend antes do e-mail, para a resposta já ser aceita quando o link chegar
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistroConviteApiPayload {
+    pub destinatario_id: u32,
+    pub email: String,
+    pub token: String,
+}
+
+/// Resultado do envio; o backend atualiza as datas, os lembretes e marca o destinatário como enviado
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnvioConviteApiPayload {
+    pub data_envio: String,
+    pub lembrete: bool,
+    /// Motivo da falha do SMTP; `None` quando o e-mail saiu
+    pub erro: Option<String>,
+}
+
+/// `{nome}`, `{pesquisa}` e `{link}` são substituídos na mensagem de cada destinatário
+#[derive(Debug, Deserialize)]
+pub struct EnviarConvitesPayload {
+    pub pesquisa_id: u32,
+    pub assunto: String,
+    pub mensagem: String,
+    /// Restringe o envio a estes destinatários; `None` envia a todos ainda não convidados
+    pub destinatario_ids: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnviarLembretesPayload {
+    pub pesquisa_id: u32,
+    pub assunto: String,
+    pub mensagem: String,
+    /// Dias desde o último envio (convite ou lembrete) para lembrar de novo
+    pub intervalo_dias: i64,
+    pub maximo_lembretes: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FalhaEnvio {
+    pub destinatario_id: u32,
+    pub nome: String,
+    pub motivo: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoEnvioPesquisa {
+    pub enviados: usize,
+    pub ignorados: usize,
+    pub falhas: Vec<FalhaEnvio>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DistribuicaoResposta {
+    pub resposta: String,
+    pub total: i64,
+    pub percentual: f64,
+}
+
+/// Net Promoter Score de itens com escala 0 a 10
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NpsItem {
+    pub promotores: i64,
+    pub neutros: i64,
+    pub detratores: i64,
+    /// -100 a 100
+    pub nps: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EstatisticaItemPesquisa {
+    pub item: String,
+    pub total_respostas: i64,
+    pub distribuicao: Vec<DistribuicaoResposta>,
+    /// Média das respostas numéricas ou conceituais (Ótimo..Péssimo = 5..1)
+    pub media: Option<f64>,
+    /// Maior nota da escala usada no item (5 ou 10)
+    pub escala_maxima: Option<f64>,
+    pub nps: Option<NpsItem>,
+    /// Média abaixo de 60% da escala ou NPS negativo
+    pub abaixo_da_meta: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnalisePesquisa {
+    pub pesquisa_id: u32,
+    pub descricao: String,
+    pub total_destinatarios: usize,
+    pub enviados: usize,
+    pub respondidos: usize,
+    pub taxa_resposta: f64,
+    pub itens: Vec<EstatisticaItemPesquisa>,
+    pub itens_indisponiveis: Vec<String>,
+    /// Rascunho para `salvar_analise_critica_tauri`; o usuário revisa antes de salvar
+    pub texto_sugerido: String,
+    pub aprovacao_sugerida: bool,
+}
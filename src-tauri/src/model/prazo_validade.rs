@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Tempo máximo entre a coleta e o início da análise para um parâmetro (e opcionalmente uma matriz)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegraPrazoValidade {
+    pub id: Option<u32>,
+    /// Trecho do nome do parâmetro (comparado sem acentos e sem diferenciar maiúsculas)
+    pub parametro: String,
+    /// `None` vale para qualquer matriz
+    pub matriz: Option<String>,
+    pub horas_maximas: f64,
+    /// Antecedência para o alerta; padrão de 25% do prazo
+    pub horas_alerta: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusPrazo {
+    Vencido,
+    Alerta,
+    NoPrazo,
+    SemRegra,
+}
+
+/// Emitido no evento `alerta_prazo_amostra`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertaPrazo {
+    pub analise_id: u32,
+    pub amostra_numero: Option<String>,
+    pub parametro_nome: Option<String>,
+    pub status: StatusPrazo,
+    pub prazo_limite: String,
+    pub prazo_restante: i64,
+}
@@ -0,0 +1,57 @@
+// src-tauri/src/model/preparo_insumo.rs
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use crate::model::insumo_registro::RegistroInsumoFrontendPayload;
+
+/// Payload recebido do Frontend; decimais aceitam "1.234,5" ou "1234.5"
+#[derive(Debug, Deserialize)]
+pub struct PlanejarPreparoPayload {
+    pub insumo_id: u32,
+    /// Volume produzido pela receita cadastrada no insumo
+    pub volume_receita: String,
+    pub volume_alvo: String,
+    /// Multiplica as quantidades da receita (padrão 1)
+    pub fator_correcao: Option<String>,
+    pub data_preparo: String, // "YYYY-MM-DD"
+    pub validade_dias: Option<u32>,
+    pub registro: Option<String>,
+}
+
+/// Quantidade retirada de um lote de matéria-prima
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LotePreparo {
+    pub materia_prima_registro_id: u32,
+    pub lote_fabricante: Option<String>,
+    pub validade: Option<String>,
+    /// Pureza em %, usada para corrigir a quantidade pesada
+    pub pureza: Decimal,
+    pub quantidade: Decimal,
+    pub quant_restante: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemPreparo {
+    pub materia_prima_id: u32,
+    pub nome: String,
+    pub unidade: Option<String>,
+    pub quantidade_receita: Decimal,
+    /// Quantidade escalada e corrigida, considerando 100% de pureza
+    pub quantidade_pura: Decimal,
+    /// Soma do que será retirado dos lotes (já corrigido pela pureza)
+    pub quantidade_total: Decimal,
+    /// Quantidade pura que o estoque não cobre
+    pub faltante: Decimal,
+    pub lotes: Vec<LotePreparo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanoPreparo {
+    pub insumo_id: u32,
+    pub fator_escala: Decimal,
+    pub itens: Vec<ItemPreparo>,
+    pub suficiente: bool,
+    pub avisos: Vec<String>,
+    /// Pronto para `criar_insumo_registro_tauri`
+    pub registro: RegistroInsumoFrontendPayload,
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoNoRastreio {
+    MateriaPrimaRegistro,
+    InsumoRegistro,
+    Resultado,
+}
+
+impl TipoNoRastreio {
+    pub fn prefixo(&self) -> &'static str {
+        match self {
+            TipoNoRastreio::MateriaPrimaRegistro => "MP",
+            TipoNoRastreio::InsumoRegistro => "IR",
+            TipoNoRastreio::Resultado => "RS",
+        }
+    }
+
+    pub fn descricao(&self) -> &'static str {
+        match self {
+            TipoNoRastreio::MateriaPrimaRegistro => "Lote de matéria-prima",
+            TipoNoRastreio::InsumoRegistro => "Registro de insumo",
+            TipoNoRastreio::Resultado => "Resultado",
+        }
+    }
+}
+
+/// `Impacto`: do lote para os resultados (recall). `Procedencia`: do resultado para os lotes.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DirecaoRastreio {
+    Impacto,
+    Procedencia,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoRastreio {
+    /// Chave única no grafo, ex.: `MP-12`
+    pub chave: String,
+    pub tipo: TipoNoRastreio,
+    pub id: u32,
+    pub rotulo: String,
+    pub detalhe: Option<String>,
+    pub validade: Option<String>,
+    /// Distância até a raiz
+    pub nivel: u32,
+}
+
+/// Arestas sempre no sentido do fluxo de material (matéria-prima -> insumo -> resultado)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ArestaRastreio {
+    pub origem: String,
+    pub destino: String,
+    pub quantidade: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrafoRastreio {
+    pub direcao: DirecaoRastreio,
+    pub raiz: String,
+    pub nos: Vec<NoRastreio>,
+    pub arestas: Vec<ArestaRastreio>,
+    pub fontes_indisponiveis: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoRastreio {
+    pub caminho: String,
+    pub total_nos: usize,
+}
+
+// --- Vínculos retornados pela API de rastreabilidade ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InsumoRegistroVinculado {
+    pub insumo_registro_id: u32,
+    pub insumo_id: u32,
+    pub insumo_nome: Option<String>,
+    pub registro: Option<String>,
+    pub validade: Option<String>,
+    pub quantidade: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MateriaPrimaVinculada {
+    pub materia_prima_registro_id: u32,
+    pub nome_materia_prima: Option<String>,
+    pub lote_fabricante: Option<String>,
+    pub validade: Option<String>,
+    pub quantidade: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoVinculado {
+    pub id_resultado: u32,
+    pub id_analise: u32,
+    pub numero_amostra: Option<String>,
+    pub parametro: Option<String>,
+    pub resultado: Option<String>,
+    pub data_termino: Option<String>,
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Parâmetros da previsão de reposição (todos opcionais no frontend)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ParametrosReposicao {
+    /// Período do histórico usado para o consumo médio
+    pub janela_dias: u32,
+    /// Dias de consumo que a compra deve cobrir após a chegada
+    pub cobertura_dias: u32,
+    /// Usado quando o histórico não permite estimar o prazo de entrega
+    pub lead_time_padrao_dias: u32,
+    /// Lista apenas os itens que precisam ser pedidos agora
+    pub apenas_necessarios: bool,
+}
+
+impl Default for ParametrosReposicao {
+    fn default() -> Self {
+        ParametrosReposicao {
+            janela_dias: 90,
+            cobertura_dias: 30,
+            lead_time_padrao_dias: 7,
+            apenas_necessarios: true,
+        }
+    }
+}
+
+/// Vínculo item de estoque -> fornecedor habitual (API `/qualidade/estoque/fornecedores`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VinculoFornecedorEstoque {
+    pub estoque_item_id: u32,
+    pub fornecedor_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrevisaoReposicaoItem {
+    pub item_id: u32,
+    pub nome: String,
+    pub unidade: String,
+    pub saldo_atual: f64,
+    pub minimo: i32,
+    /// Saídas médias por dia na janela
+    pub consumo_diario: f64,
+    pub lead_time_dias: f64,
+    /// `false` quando o prazo de entrega veio do valor padrão
+    pub lead_time_historico: bool,
+    /// Data projetada em que o saldo atinge o mínimo ("YYYY-MM-DD"); `None` sem consumo
+    pub data_minimo: Option<String>,
+    pub dias_ate_minimo: Option<i64>,
+    pub repor: bool,
+    pub quantidade_sugerida: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrupoSugestaoCompra {
+    pub fornecedor_id: Option<u32>,
+    pub fornecedor: String,
+    pub itens: Vec<PrevisaoReposicaoItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SugestaoCompra {
+    pub gerado_em: String,
+    pub parametros: ParametrosReposicao,
+    pub grupos: Vec<GrupoSugestaoCompra>,
+    /// Itens cujo histórico não pôde ser carregado
+    pub itens_indisponiveis: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportacaoSugestaoCompra {
+    pub caminho: String,
+    pub total_itens: usize,
+}
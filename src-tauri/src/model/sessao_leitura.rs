@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperacaoLeitura {
+    Receber,
+    IniciarAnalise,
+    Bloquear,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusLeitura {
+    Aceita,
+    Duplicada,
+    ForaDoIntervalo,
+    /// Dentro do intervalo do grupo, mas sem análise cadastrada
+    NaoEncontrada,
+    CodigoInvalido,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AbrirSessaoLeituraPayload {
+    pub operacao: OperacaoLeitura,
+    pub numero_ini: u32,
+    pub numero_fim: u32,
+    /// Só para `iniciar_analise`: conferidos como na fila de trabalho antes de iniciar
+    #[serde(default)]
+    pub equipamentos: Vec<u32>,
+    #[serde(default)]
+    pub lotes_insumo: Vec<u32>,
+}
+
+/// Emitido no evento `leitura_amostra` a cada código lido
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoLeitura {
+    pub id_sessao: String,
+    pub codigo: String,
+    pub numero: Option<u32>,
+    pub status: StatusLeitura,
+    pub mensagem: String,
+    pub total_lidas: usize,
+    pub total_esperadas: usize,
+    pub faltantes: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResumoSessaoLeitura {
+    pub id_sessao: String,
+    pub operacao: OperacaoLeitura,
+    pub amostra_min: u32,
+    pub amostra_max: u32,
+    pub lidas: Vec<u32>,
+    pub faltantes: Vec<u32>,
+    pub total_esperadas: usize,
+    pub duplicadas: usize,
+    pub rejeitadas: usize,
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Estrutura do JSON guardado em `json_data_base64` pelos templates da planilha
+// (o mesmo `SpreadsheetData` montado em CadastrarPlanilha.tsx). Os campos de
+// estilo/mídia ficam como JSON livre para não perder nada no ida-e-volta.
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanilhaTemplate {
+    pub name: String,
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<Vec<CelulaTemplate>>,
+    #[serde(default)]
+    pub history: Vec<Value>,
+    #[serde(default)]
+    pub column_widths: Vec<f64>,
+    #[serde(default)]
+    pub row_heights: Vec<f64>,
+    #[serde(default)]
+    pub global_styles: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CelulaTemplate {
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub id: String,
+    pub style: Option<Map<String, Value>>,
+    pub media: Option<Value>,
+    pub merged: Option<bool>,
+    pub formula: Option<String>,
+    #[serde(rename = "computed_value")]
+    pub computed_value: Option<String>,
+    #[serde(rename = "is_formula")]
+    pub is_formula: Option<bool>,
+    pub master_cell: Option<PosicaoCelula>,
+    pub merge_range: Option<IntervaloCelulas>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PosicaoCelula {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervaloCelulas {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoPlaceholder {
+    Texto,
+    Numero,
+    /// Formatada como dd/mm/aaaa
+    Data,
+}
+
+/// Marcador `{{grupo.campo}}` aceito nas células; os de `resultado.*` repetem a linha por resultado
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefinicaoPlaceholder {
+    pub chave: String,
+    pub tipo: TipoPlaceholder,
+    pub descricao: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ErroValidacaoTemplate {
+    /// Referência da célula (ex.: "B3"), quando o erro é de uma célula
+    pub celula: Option<String>,
+    pub mensagem: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResultadoValidacaoTemplate {
+    pub valido: bool,
+    pub erros: Vec<ErroValidacaoTemplate>,
+    /// Marcadores encontrados, sem repetição
+    pub placeholders: Vec<String>,
+}
+
+/// Dados do laboratório usados na renderização; as chaves seguem o catálogo de marcadores
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DadosTemplate {
+    pub cliente: Map<String, Value>,
+    pub amostra: Map<String, Value>,
+    pub resultados: Vec<Map<String, Value>>,
+    pub legislacao: Map<String, Value>,
+}
+
+/// Versão anterior de um template, gravada no backend a cada atualização
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersaoTemplate {
+    pub versao: u32,
+    pub nome_arquivo: String,
+    pub json_data_base64: String,
+    pub salvo_em: Option<String>,
+    pub usuario_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NovaVersaoTemplatePayload {
+    pub nome_arquivo: String,
+    pub json_data_base64: String,
+    pub usuario_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiferencaTemplate {
+    pub celula: Option<String>,
+    /// "valor", "formula", "estilo", "midia", "mesclagem", "dimensoes", "larguras", "alturas" ou "nome"
+    pub campo: String,
+    pub antes: Option<String>,
+    pub depois: Option<String>,
+}
+
+/// Registro de template da API (`/templates`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateData {
+    pub id: Option<u64>,
+    pub caminho_arquivo: String,
+    pub nome_arquivo: String,
+    pub tag: Option<String>,
+    pub tipo: String,
+    pub json_data_base64: Option<String>,
+    pub updated_at: Option<String>,
+}
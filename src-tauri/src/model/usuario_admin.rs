@@ -37,6 +37,9 @@ pub struct CriarUsuarioAdminPayload {
     pub privilegio: String,
     pub cargo: Option<String>,
     pub empresa: Option<u32>,
+    /// CPF do usuário (`numero_doc` na API)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numero_doc: Option<String>,
 }
 
 // 🔧 CORRIGIDO: Removido o atributo #[serde(rename_all = "camelCase")]
@@ -48,6 +51,8 @@ pub struct AtualizarUsuarioAdminPayload {
     pub cargo: Option<String>,
     pub ativo: Option<bool>,
     pub empresa: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numero_doc: Option<String>,
 }
 
 // ... (restante do arquivo sem alterações) ...
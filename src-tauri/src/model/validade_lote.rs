@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OrigemLote {
+    MateriaPrima,
+    Insumo,
+    ReagenteLimpeza,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoValidade {
+    Vencido,
+    AVencer,
+}
+
+/// Configuração local da estação (arquivo `monitor_validade.json`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfiguracaoMonitorValidade {
+    /// Antecedência, em dias, para avisar sobre lotes a vencer
+    pub dias_aviso: u32,
+    /// Marca como obsoletos os lotes de matéria-prima vencidos
+    pub obsoletar_automaticamente: bool,
+    pub intervalo_minutos: u32,
+}
+
+impl Default for ConfiguracaoMonitorValidade {
+    fn default() -> Self {
+        ConfiguracaoMonitorValidade {
+            dias_aviso: 30,
+            obsoletar_automaticamente: false,
+            intervalo_minutos: 60,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoteValidade {
+    pub origem: OrigemLote,
+    pub id: u32,
+    pub nome: String,
+    pub lote: Option<String>,
+    pub validade: String, // "YYYY-MM-DD"
+    /// Negativo quando já vencido
+    pub dias_restantes: i64,
+    pub situacao: SituacaoValidade,
+    /// Marcado como obsoleto pelo monitor nesta verificação
+    pub obsoletado: bool,
+}
+
+/// Emitido no evento `alerta_validade_lote`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatorioValidade {
+    pub gerado_em: String,
+    pub lotes: Vec<LoteValidade>,
+    pub fontes_indisponiveis: Vec<String>,
+}
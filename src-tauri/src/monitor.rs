@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::model::usuario::get_user_id;
+
+/// Partes comuns dos monitores em segundo plano: espera entre as verificações,
+/// usuário logado e alertas já notificados, para só avisar de novo quando a situação muda
+pub struct Monitor<K> {
+    nome: &'static str,
+    evento: &'static str,
+    notificados: HashSet<K>,
+}
+
+impl<K: Eq + Hash> Monitor<K> {
+    pub fn new(nome: &'static str, evento: &'static str) -> Self {
+        Monitor { nome, evento, notificados: HashSet::new() }
+    }
+
+    /// Espera `intervalo` e devolve o usuário logado; sem ninguém logado, espera mais um intervalo
+    pub async fn aguardar(&self, intervalo: Duration) -> u32 {
+        loop {
+            tokio::time::sleep(intervalo).await;
+            if let Some(user_id) = get_user_id() {
+                return user_id;
+            }
+        }
+    }
+
+    /// Alertas que ainda não foram notificados nesta situação; os que sumiram podem voltar a ser avisados
+    pub fn novos<'a, T>(&mut self, alertas: &'a [T], chave: impl Fn(&T) -> K) -> Vec<&'a T> {
+        let atuais: HashSet<K> = alertas.iter().map(&chave).collect();
+        let novos = alertas.iter().filter(|a| !self.notificados.contains(&chave(a))).collect();
+        self.notificados = atuais;
+        novos
+    }
+
+    pub fn emitir<P: Serialize + Clone>(&self, app_handle: &AppHandle, payload: P) {
+        if let Err(e) = app_handle.emit(self.evento, payload) {
+            println!("Falha ao emitir alerta de {}: {}", self.nome, e);
+        }
+    }
+
+    /// `novos` + `emitir`; devolve o que foi emitido
+    pub fn notificar<'a, T: Serialize>(&mut self, app_handle: &AppHandle, alertas: &'a [T], chave: impl Fn(&T) -> K) -> Vec<&'a T> {
+        let novos = self.novos(alertas, chave);
+        if !novos.is_empty() {
+            self.emitir(app_handle, &novos);
+        }
+        novos
+    }
+
+    pub fn falha(&self, erro: &str) {
+        println!("Monitor de {}: {}", self.nome, erro);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_novos_so_quando_a_situacao_muda() {
+        let mut monitor: Monitor<(u32, &str)> = Monitor::new("teste", "teste");
+        let chave = |a: &(u32, &'static str)| *a;
+
+        assert_eq!(monitor.novos(&[(1, "a_vencer"), (2, "a_vencer")], chave).len(), 2);
+        assert!(monitor.novos(&[(1, "a_vencer"), (2, "a_vencer")], chave).is_empty());
+        assert_eq!(monitor.novos(&[(1, "vencido"), (2, "a_vencer")], chave), vec![&(1, "vencido")]);
+        // Saiu da lista e voltou: avisa de novo
+        assert!(monitor.novos(&[(1, "vencido")], chave).is_empty());
+        assert_eq!(monitor.novos(&[(1, "vencido"), (2, "a_vencer")], chave), vec![&(2, "a_vencer")]);
+    }
+}
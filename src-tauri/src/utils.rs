@@ -1,3 +1,4 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use regex::Regex;
 
 /// Valida um CPF brasileiro
@@ -70,6 +71,77 @@ pub fn is_valid_cnpj(cnpj: &str) -> bool {
     digits[12] == first_check && digits[13] == second_check
 }
 
+/// Valida o número de registro da CNH (11 dígitos, dois dígitos verificadores)
+pub fn is_valid_cnh(cnh: &str) -> bool {
+    let cnh = clean_document(cnh);
+
+    if cnh.len() != 11 {
+        return false;
+    }
+
+    if cnh.chars().all(|c| c == cnh.chars().next().unwrap()) {
+        return false;
+    }
+
+    let digits: Vec<i64> = cnh.chars().map(|c| i64::from(c.to_digit(10).unwrap())).collect();
+
+    // Primeiro dígito: pesos 9 a 1; quando o resto dá 10 vira 0 e desconta 2 do segundo
+    let sum: i64 = (0..9).map(|i| digits[i] * (9 - i as i64)).sum();
+    let (first_check, discount) = if sum % 11 >= 10 { (0, 2) } else { (sum % 11, 0) };
+
+    // Segundo dígito: pesos 1 a 9
+    let sum: i64 = (0..9).map(|i| digits[i] * (i as i64 + 1)).sum();
+    let second_check = if sum % 11 >= 10 { 0 } else { sum % 11 - discount };
+
+    digits[9] == first_check && digits[10] == second_check
+}
+
+/// Valida o RENAVAM (11 dígitos; os antigos, de 9, são completados com zeros à esquerda)
+pub fn is_valid_renavam(renavam: &str) -> bool {
+    let renavam = clean_document(renavam);
+
+    if renavam.len() != 9 && renavam.len() != 11 {
+        return false;
+    }
+    let renavam = format!("{:0>11}", renavam);
+
+    if renavam.chars().all(|c| c == renavam.chars().next().unwrap()) {
+        return false;
+    }
+
+    let digits: Vec<u32> = renavam.chars().map(|c| c.to_digit(10).unwrap()).collect();
+
+    // Dez primeiros dígitos de trás para frente, pesos 2 a 9 e depois 2 e 3
+    let weights = [2, 3, 4, 5, 6, 7, 8, 9, 2, 3];
+    let sum: u32 = digits[..10].iter().rev().zip(weights).map(|(d, w)| d * w).sum();
+    let check = (sum * 10) % 11;
+    let check = if check == 10 { 0 } else { check };
+
+    digits[10] == check
+}
+
+/// Normaliza a placa para maiúsculas sem separadores, aceitando o padrão antigo (ABC-1234)
+/// e o Mercosul (ABC1D23). Retorna `None` se não for nenhum dos dois.
+pub fn normalizar_placa(placa: &str) -> Option<String> {
+    let placa: String = placa
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+
+    let re = Regex::new(r"^[A-Z]{3}[0-9][A-Z0-9][0-9]{2}$").unwrap();
+    re.is_match(&placa).then_some(placa)
+}
+
+/// Formata a placa normalizada para exibição: ABC-1234 no padrão antigo, ABC1D23 no Mercosul
+pub fn format_placa(placa: &str) -> String {
+    match normalizar_placa(placa) {
+        Some(p) if p.chars().nth(4).is_some_and(|c| c.is_ascii_digit()) => format!("{}-{}", &p[..3], &p[3..]),
+        Some(p) => p,
+        None => placa.to_string(),
+    }
+}
+
 /// Remove caracteres não numéricos de um documento
 pub fn clean_document(document: &str) -> String {
     document.chars().filter(|c| c.is_ascii_digit()).collect()
@@ -152,6 +224,81 @@ pub fn validate_document(document: &str) -> Result<bool, String> {
     }
 }
 
+/// Converte data (e hora opcional) nos formatos usados pela API em `NaiveDateTime`.
+/// Aceita `YYYY-MM-DD`, `DD/MM/YYYY`, `YYYY-MM-DD HH:MM[:SS]`, ISO 8601 e RFC 3339.
+pub fn parse_data_hora(data: &str, hora: Option<&str>) -> Option<NaiveDateTime> {
+    let data = data.trim();
+    if data.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(data) {
+        return Some(dt.naive_local());
+    }
+    for formato in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%d/%m/%Y %H:%M:%S", "%d/%m/%Y %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(data, formato) {
+            return Some(dt);
+        }
+    }
+
+    let dia = NaiveDate::parse_from_str(data, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(data, "%d/%m/%Y"))
+        .ok()?;
+
+    let hora = hora
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .and_then(|h| {
+            NaiveTime::parse_from_str(h, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(h, "%H:%M"))
+                .ok()
+        })
+        .unwrap_or(NaiveTime::MIN);
+
+    Some(dia.and_time(hora))
+}
+
+/// Aceita "1.234,5" (formato BR) e "1234.5"; serve para `Decimal` e `BigDecimal`
+pub fn parse_decimal<T: std::str::FromStr>(valor: &str) -> Result<T, String> {
+    let valor = valor.trim();
+    let normalizado = if valor.contains(',') {
+        valor.replace('.', "").replace(',', ".")
+    } else {
+        valor.to_string()
+    };
+    normalizado.parse().map_err(|_| format!("Valor decimal inválido: {}", valor))
+}
+
+/// Remove acentos e converte para minúsculas, para comparar nomes digitados de formas diferentes
+pub fn normalizar_texto(texto: &str) -> String {
+    texto
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            _ => c,
+        })
+        .collect()
+}
+
+/// GET na API devolvendo o JSON já desserializado; status de erro vira `Err` com o corpo da resposta
+pub async fn buscar_json<T: serde::de::DeserializeOwned>(client: &reqwest::Client, url: &str) -> Result<T, String> {
+    let response = client.get(url).send().await.map_err(|e| format!("Falha conexão: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let msg = response.text().await.unwrap_or_default();
+        return Err(format!("Erro API ({}): {}", status, msg));
+    }
+    response.json::<T>().await.map_err(|e| format!("Erro JSON: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +319,34 @@ mod tests {
         assert!(!is_valid_cnpj("123456789"));
     }
 
+    #[test]
+    fn test_cnh_validation() {
+        assert!(is_valid_cnh("12345678900"));
+        assert!(is_valid_cnh("123.456.789-00"));
+        assert!(!is_valid_cnh("12345678901"));
+        assert!(!is_valid_cnh("11111111111"));
+        assert!(!is_valid_cnh("1234567890"));
+    }
+
+    #[test]
+    fn test_renavam_validation() {
+        assert!(is_valid_renavam("00639884962"));
+        assert!(is_valid_renavam("639884962"));
+        assert!(!is_valid_renavam("00639884961"));
+        assert!(!is_valid_renavam("00000000000"));
+        assert!(!is_valid_renavam("1234567890"));
+    }
+
+    #[test]
+    fn test_placa() {
+        assert_eq!(normalizar_placa("abc-1234"), Some("ABC1234".to_string()));
+        assert_eq!(normalizar_placa(" ABC 1D23 "), Some("ABC1D23".to_string()));
+        assert_eq!(normalizar_placa("AB-12345"), None);
+        assert_eq!(normalizar_placa("ABC12D3"), None);
+        assert_eq!(format_placa("abc1234"), "ABC-1234");
+        assert_eq!(format_placa("abc1d23"), "ABC1D23");
+    }
+
     #[test]
     fn test_phone_formatting() {
         assert_eq!(clean_and_format_phone("11987654321"), "(11) 98765-4321");
@@ -193,4 +368,38 @@ mod tests {
         assert!(!is_valid_email("invalid-email"));
         assert!(!is_valid_email("@example.com"));
     }
+
+    #[test]
+    fn test_parse_data_hora() {
+        let esperado = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap().and_hms_opt(14, 30, 0).unwrap();
+        assert_eq!(parse_data_hora("2024-03-05", Some("14:30")), Some(esperado));
+        assert_eq!(parse_data_hora("05/03/2024", Some("14:30:00")), Some(esperado));
+        assert_eq!(parse_data_hora("2024-03-05T14:30:00", None), Some(esperado));
+        assert_eq!(parse_data_hora("2024-03-05 14:30:00", Some("08:00")), Some(esperado));
+        assert_eq!(
+            parse_data_hora("2024-03-05", None),
+            NaiveDate::from_ymd_opt(2024, 3, 5).unwrap().and_hms_opt(0, 0, 0)
+        );
+        assert_eq!(parse_data_hora("", None), None);
+        assert_eq!(parse_data_hora("ontem", None), None);
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        assert_eq!(parse_decimal::<Decimal>("1.234,5"), Ok(Decimal::from_str("1234.5").unwrap()));
+        assert_eq!(parse_decimal::<Decimal>(" 1234.5 "), Ok(Decimal::from_str("1234.5").unwrap()));
+        assert_eq!(parse_decimal::<bigdecimal::BigDecimal>("0,05"), Ok(bigdecimal::BigDecimal::from_str("0.05").unwrap()));
+        assert!(parse_decimal::<Decimal>("abc").is_err());
+        assert!(parse_decimal::<Decimal>("").is_err());
+    }
+
+    #[test]
+    fn test_normalizar_texto() {
+        assert_eq!(normalizar_texto("  Coliformes Termotolerantes "), "coliformes termotolerantes");
+        assert_eq!(normalizar_texto("Água Subterrânea"), "agua subterranea");
+        assert_eq!(normalizar_texto("DEMANDA BIOQUÍMICA"), "demanda bioquimica");
+    }
 }
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::api_response::ApiResponse;
+use crate::utils::{
+    clean_and_format_phone, clean_document, format_cnpj, format_cpf, is_valid_cnpj, is_valid_cpf,
+    is_valid_email, is_valid_phone, validate_document,
+};
+
+pub mod template;
+
+/// Erro de um campo do formulário; `campo` segue o caminho do payload (ex.: "dados_gerais.documento")
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ErroCampo {
+    pub campo: String,
+    pub mensagem: String,
+}
+
+/// Siglas das unidades federativas
+pub const UFS: [&str; 27] = [
+    "AC", "AL", "AM", "AP", "BA", "CE", "DF", "ES", "GO", "MA", "MG", "MS", "MT", "PA", "PB", "PE",
+    "PI", "PR", "RJ", "RN", "RO", "RR", "RS", "SC", "SE", "SP", "TO",
+];
+
+/// Dígito módulo 11 com pesos aplicados da esquerda para a direita (resto 0 ou 1 vira 0)
+fn digito_modulo_11(digits: &[u32], weights: &[u32]) -> u32 {
+    let sum: u32 = digits.iter().zip(weights).map(|(d, w)| d * w).sum();
+    let rest = sum % 11;
+    if rest < 2 { 0 } else { 11 - rest }
+}
+
+/// Valida a inscrição estadual conforme a UF. "ISENTO" é aceito.
+/// SP, RJ, PR, RS e as UFs de 9 dígitos com módulo 11 simples têm o dígito conferido;
+/// nas demais, apenas a quantidade de dígitos.
+pub fn is_valid_inscricao_estadual(ie: &str, uf: &str) -> bool {
+    if ie.trim().eq_ignore_ascii_case("ISENTO") {
+        return true;
+    }
+    let uf = uf.trim().to_uppercase();
+    let cleaned = clean_document(ie);
+    let digits: Vec<u32> = cleaned.chars().map(|c| c.to_digit(10).unwrap()).collect();
+    if digits.is_empty() || digits.iter().all(|d| *d == digits[0]) {
+        return false;
+    }
+
+    match (uf.as_str(), digits.len()) {
+        // Comércio/indústria: 9º e 12º dígitos verificadores; o resto 10 vira 0
+        ("SP", 12) => {
+            let first = digits[..8].iter().zip([1, 3, 4, 5, 6, 7, 8, 10]).map(|(d, w)| d * w).sum::<u32>() % 11 % 10;
+            let second = digits[..11].iter().zip([3, 2, 10, 9, 8, 7, 6, 5, 4, 3, 2]).map(|(d, w)| d * w).sum::<u32>() % 11 % 10;
+            digits[8] == first && digits[11] == second
+        }
+        ("RJ", 8) => digits[7] == digito_modulo_11(&digits[..7], &[2, 7, 6, 5, 4, 3, 2]),
+        ("PR", 10) => {
+            digits[8] == digito_modulo_11(&digits[..8], &[3, 2, 7, 6, 5, 4, 3, 2])
+                && digits[9] == digito_modulo_11(&digits[..9], &[4, 3, 2, 7, 6, 5, 4, 3, 2])
+        }
+        ("RS", 10) => digits[9] == digito_modulo_11(&digits[..9], &[2, 9, 8, 7, 6, 5, 4, 3, 2]),
+        ("AM" | "CE" | "ES" | "MA" | "MS" | "PA" | "PB" | "PI" | "SC" | "SE", 9) => {
+            digits[8] == digito_modulo_11(&digits[..8], &[9, 8, 7, 6, 5, 4, 3, 2])
+        }
+        ("AL" | "AP" | "GO" | "RR", 9) | ("BA", 8 | 9) | ("PE", 9 | 14) | ("RN", 9 | 10) | ("TO", 9 | 11) => true,
+        ("MT", 11) | ("AC" | "DF" | "MG", 13) | ("RO", 14) => true,
+        _ => false,
+    }
+}
+
+/// Normaliza e valida os campos cadastrais antes do envio à API, acumulando
+/// todos os erros para que a tela destaque cada campo de uma vez.
+/// Campos vazios são opcionais: viram `None` e não geram erro.
+#[derive(Debug, Default)]
+pub struct Validador {
+    erros: Vec<ErroCampo>,
+}
+
+fn preenchido(valor: Option<&str>) -> Option<&str> {
+    valor.map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl Validador {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn erro(&mut self, campo: &str, mensagem: impl Into<String>) {
+        self.erros.push(ErroCampo { campo: campo.to_string(), mensagem: mensagem.into() });
+    }
+
+    pub fn obrigatorio(&mut self, campo: &str, valor: Option<&str>, rotulo: &str) {
+        if preenchido(valor).is_none() {
+            self.erro(campo, format!("{} é obrigatório", rotulo));
+        }
+    }
+
+    /// CPF ou CNPJ, formatado com pontuação. `tipo` ("CPF"/"CNPJ") restringe o aceito.
+    pub fn documento(&mut self, campo: &str, valor: Option<&str>, tipo: Option<&str>) -> Option<String> {
+        let valor = preenchido(valor)?;
+        let digitos = clean_document(valor);
+
+        match tipo.map(|t| t.trim().to_uppercase()).as_deref() {
+            Some("CPF") if !is_valid_cpf(&digitos) => self.erro(campo, "CPF inválido"),
+            Some("CNPJ") if !is_valid_cnpj(&digitos) => self.erro(campo, "CNPJ inválido"),
+            Some("CPF") | Some("CNPJ") => {}
+            _ => match validate_document(&digitos) {
+                Ok(true) => {}
+                Ok(false) => self.erro(campo, if digitos.len() == 11 { "CPF inválido" } else { "CNPJ inválido" }),
+                Err(mensagem) => self.erro(campo, mensagem),
+            },
+        }
+
+        Some(match digitos.len() {
+            11 => format_cpf(&digitos),
+            14 => format_cnpj(&digitos),
+            _ => valor.to_string(),
+        })
+    }
+
+    pub fn telefone(&mut self, campo: &str, valor: Option<&str>) -> Option<String> {
+        let valor = preenchido(valor)?;
+        if !is_valid_phone(valor) {
+            self.erro(campo, "Telefone deve ter DDD e 8 ou 9 dígitos");
+            return Some(valor.to_string());
+        }
+        Some(clean_and_format_phone(valor))
+    }
+
+    pub fn email(&mut self, campo: &str, valor: Option<&str>) -> Option<String> {
+        let valor = preenchido(valor)?;
+        if !is_valid_email(valor) {
+            self.erro(campo, "E-mail inválido");
+        }
+        Some(valor.to_lowercase())
+    }
+
+    /// CEP no formato 00000-000
+    pub fn cep(&mut self, campo: &str, valor: Option<&str>) -> Option<String> {
+        let valor = preenchido(valor)?;
+        let digitos = clean_document(valor);
+        if digitos.len() != 8 || valor.chars().any(|c| !c.is_ascii_digit() && c != '-' && c != '.' && c != ' ') {
+            self.erro(campo, "CEP deve ter 8 dígitos");
+            return Some(valor.to_string());
+        }
+        Some(format!("{}-{}", &digitos[..5], &digitos[5..]))
+    }
+
+    pub fn uf(&mut self, campo: &str, valor: Option<&str>) -> Option<String> {
+        let uf = preenchido(valor)?.to_uppercase();
+        if !UFS.contains(&uf.as_str()) {
+            self.erro(campo, format!("UF inválida: {}", uf));
+        }
+        Some(uf)
+    }
+
+    /// Inscrição estadual só com dígitos (ou "ISENTO"); sem UF válida não há como conferir
+    pub fn inscricao_estadual(&mut self, campo: &str, valor: Option<&str>, uf: Option<&str>) -> Option<String> {
+        let valor = preenchido(valor)?;
+        if valor.eq_ignore_ascii_case("ISENTO") {
+            return Some("ISENTO".to_string());
+        }
+        match preenchido(uf).map(str::to_uppercase) {
+            Some(uf) if UFS.contains(&uf.as_str()) => {
+                if !is_valid_inscricao_estadual(valor, &uf) {
+                    self.erro(campo, format!("Inscrição estadual inválida para {}", uf));
+                }
+            }
+            _ => self.erro(campo, "Informe a UF para validar a inscrição estadual"),
+        }
+        Some(clean_document(valor))
+    }
+
+    pub fn concluir(self) -> Result<(), Vec<ErroCampo>> {
+        if self.erros.is_empty() {
+            Ok(())
+        } else {
+            Err(self.erros)
+        }
+    }
+}
+
+/// Mensagem única para telas que ainda mostram só o texto do erro
+pub fn resumir_erros(erros: &[ErroCampo]) -> String {
+    let detalhes: Vec<String> = erros.iter().map(|e| format!("{}: {}", e.campo, e.mensagem)).collect();
+    format!("Dados inválidos. {}", detalhes.join("; "))
+}
+
+/// Erro de todos os comandos de cadastro. Na validação local `data` traz os campos
+/// recusados; nas falhas de rede ou da API vem só `message`, como no `ApiResponse<()>`
+pub type ErroCadastro = ApiResponse<Vec<ErroCampo>>;
+
+pub fn resposta_validacao(erros: Vec<ErroCampo>) -> ErroCadastro {
+    ApiResponse {
+        success: false,
+        message: resumir_erros(&erros),
+        data: Some(erros),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validador_normaliza_e_acumula_erros() {
+        let mut v = Validador::new();
+        assert_eq!(v.documento("documento", Some("11144477735"), None), Some("111.444.777-35".to_string()));
+        assert_eq!(v.documento("documento", Some(" "), Some("CNPJ")), None);
+        assert_eq!(v.telefone("telefone", Some("11987654321")), Some("(11) 98765-4321".to_string()));
+        assert_eq!(v.cep("cep", Some("01310.100")), Some("01310-100".to_string()));
+        assert_eq!(v.email("email", Some(" Contato@Empresa.com.br ")), Some("contato@empresa.com.br".to_string()));
+        assert_eq!(v.inscricao_estadual("ie", Some("110.042.490.114"), Some("sp")), Some("110042490114".to_string()));
+        assert!(v.concluir().is_ok());
+
+        let mut v = Validador::new();
+        v.documento("documento", Some("11.222.333/0001-81"), Some("CPF"));
+        v.cep("cep", Some("1234"));
+        v.uf("uf", Some("XX"));
+        v.inscricao_estadual("ie", Some("123"), None);
+        let campos: Vec<String> = v.concluir().unwrap_err().into_iter().map(|e| e.campo).collect();
+        assert_eq!(campos, vec!["documento", "cep", "uf", "ie"]);
+    }
+
+    #[test]
+    fn test_inscricao_estadual_validation() {
+        assert!(is_valid_inscricao_estadual("110.042.490.114", "SP"));
+        assert!(!is_valid_inscricao_estadual("110.042.490.115", "SP"));
+        assert!(is_valid_inscricao_estadual("78.045.302", "RJ"));
+        assert!(is_valid_inscricao_estadual("123.45678-50", "pr"));
+        assert!(is_valid_inscricao_estadual("224/3658792", "RS"));
+        assert!(is_valid_inscricao_estadual("251.040.852", "SC"));
+        assert!(!is_valid_inscricao_estadual("251.040.853", "SC"));
+        assert!(is_valid_inscricao_estadual("Isento", "MG"));
+        assert!(!is_valid_inscricao_estadual("251040852", "XX"));
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use base64::{Engine as _, engine::general_purpose};
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::model::template_planilha::{
+    CelulaTemplate, DefinicaoPlaceholder, ErroValidacaoTemplate, PlanilhaTemplate, ResultadoValidacaoTemplate,
+    TipoPlaceholder,
+};
+
+/// Grupo cujos marcadores fazem a linha se repetir uma vez por resultado
+pub const GRUPO_RESULTADO: &str = "resultado";
+
+lazy_static! {
+    /// `{{grupo.campo}}` com formatação opcional: `|maiusculas`, `|minusculas`, `|data`, `|numero:2`
+    pub static ref MARCADOR: Regex = Regex::new(
+        r"\{\{\s*([a-z_]+)\.([a-z_]+)\s*(?:\|\s*([a-z]+)(?::(\d+))?\s*)?\}\}"
+    ).unwrap();
+}
+
+/// Catálogo dos marcadores aceitos: (chave, tipo, descrição)
+pub const PLACEHOLDERS: &[(&str, TipoPlaceholder, &str)] = &[
+    ("cliente.nome", TipoPlaceholder::Texto, "Razão social ou nome do cliente"),
+    ("cliente.documento", TipoPlaceholder::Texto, "CPF/CNPJ"),
+    ("cliente.endereco", TipoPlaceholder::Texto, "Endereço"),
+    ("cliente.cidade", TipoPlaceholder::Texto, "Cidade/UF"),
+    ("cliente.telefone", TipoPlaceholder::Texto, "Telefone"),
+    ("cliente.email", TipoPlaceholder::Texto, "E-mail"),
+    ("amostra.numero", TipoPlaceholder::Texto, "Número da amostra"),
+    ("amostra.identificacao", TipoPlaceholder::Texto, "Identificação"),
+    ("amostra.complemento", TipoPlaceholder::Texto, "Complemento da identificação"),
+    ("amostra.data_coleta", TipoPlaceholder::Data, "Data da coleta"),
+    ("amostra.hora_coleta", TipoPlaceholder::Texto, "Hora da coleta"),
+    ("amostra.data_entrada_lab", TipoPlaceholder::Data, "Entrada no laboratório"),
+    ("amostra.data_inicio_analise", TipoPlaceholder::Data, "Início das análises"),
+    ("resultado.parametro", TipoPlaceholder::Texto, "Parâmetro analisado"),
+    ("resultado.grupo", TipoPlaceholder::Texto, "Grupo do parâmetro"),
+    ("resultado.tecnica", TipoPlaceholder::Texto, "Técnica"),
+    ("resultado.unidade", TipoPlaceholder::Texto, "Unidade"),
+    ("resultado.limite", TipoPlaceholder::Texto, "Limite da legislação"),
+    ("resultado.valor", TipoPlaceholder::Numero, "Resultado"),
+    ("resultado.analista", TipoPlaceholder::Texto, "Analista"),
+    ("resultado.data_termino", TipoPlaceholder::Data, "Término da análise"),
+    ("legislacao.nome", TipoPlaceholder::Texto, "Legislação de referência"),
+    ("legislacao.descricao", TipoPlaceholder::Texto, "Descrição da legislação"),
+    ("geral.data_emissao", TipoPlaceholder::Data, "Data de emissão (hoje)"),
+];
+
+pub const FORMATOS: [&str; 4] = ["maiusculas", "minusculas", "data", "numero"];
+
+pub fn catalogo_placeholders() -> Vec<DefinicaoPlaceholder> {
+    PLACEHOLDERS
+        .iter()
+        .map(|(chave, tipo, descricao)| DefinicaoPlaceholder {
+            chave: chave.to_string(),
+            tipo: *tipo,
+            descricao: descricao.to_string(),
+        })
+        .collect()
+}
+
+pub fn decodificar_planilha(json_data_base64: &str) -> Result<PlanilhaTemplate, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(json_data_base64.trim())
+        .map_err(|e| format!("Falha ao decodificar Base64: {}", e))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("JSON fora do schema do template: {}", e))
+}
+
+/// Usado por `save_template`/`update_template` antes de enviar à API
+pub fn validar_template_base64(json_data_base64: &str) -> ResultadoValidacaoTemplate {
+    match decodificar_planilha(json_data_base64) {
+        Ok(planilha) => {
+            let erros = validar_planilha(&planilha);
+            ResultadoValidacaoTemplate {
+                valido: erros.is_empty(),
+                erros,
+                placeholders: placeholders_usados(&planilha),
+            }
+        }
+        Err(mensagem) => ResultadoValidacaoTemplate {
+            valido: false,
+            erros: vec![ErroValidacaoTemplate { celula: None, mensagem }],
+            placeholders: Vec::new(),
+        },
+    }
+}
+
+pub fn validar_planilha(planilha: &PlanilhaTemplate) -> Vec<ErroValidacaoTemplate> {
+    let mut erros = Vec::new();
+    let mut erro = |celula: Option<String>, mensagem: String| erros.push(ErroValidacaoTemplate { celula, mensagem });
+
+    if planilha.rows == 0 || planilha.cols == 0 {
+        erro(None, "A planilha precisa ter ao menos uma linha e uma coluna.".to_string());
+    }
+    if planilha.data.len() != planilha.rows {
+        erro(None, format!("rows = {}, mas data tem {} linha(s).", planilha.rows, planilha.data.len()));
+    }
+    if !planilha.column_widths.is_empty() && planilha.column_widths.len() != planilha.cols {
+        erro(None, format!("columnWidths tem {} valor(es) para {} coluna(s).", planilha.column_widths.len(), planilha.cols));
+    }
+    if !planilha.row_heights.is_empty() && planilha.row_heights.len() != planilha.rows {
+        erro(None, format!("rowHeights tem {} valor(es) para {} linha(s).", planilha.row_heights.len(), planilha.rows));
+    }
+
+    let dentro = |r: usize, c: usize| r < planilha.rows && c < planilha.cols;
+    for (r, linha) in planilha.data.iter().enumerate() {
+        if linha.len() != planilha.cols {
+            erro(None, format!("Linha {} tem {} célula(s); esperado {}.", r + 1, linha.len(), planilha.cols));
+        }
+        let repetida = linha_repetida(linha);
+        for (c, celula) in linha.iter().enumerate() {
+            let referencia = Some(referencia_celula(r, c));
+            if let Some(intervalo) = celula.merge_range {
+                if intervalo.start_row > intervalo.end_row
+                    || intervalo.start_col > intervalo.end_col
+                    || !dentro(intervalo.end_row, intervalo.end_col)
+                {
+                    erro(referencia.clone(), "Mesclagem fora dos limites da planilha.".to_string());
+                } else if repetida && intervalo.end_row > intervalo.start_row {
+                    erro(referencia.clone(), "Linhas de resultado não podem ter mesclagem vertical.".to_string());
+                }
+            }
+            if let Some(mestre) = celula.master_cell {
+                if !dentro(mestre.row, mestre.col) {
+                    erro(referencia.clone(), "Célula mestre da mesclagem fora da planilha.".to_string());
+                }
+            }
+
+            let texto = &celula.value;
+            if texto.matches("{{").count() != texto.matches("}}").count() {
+                erro(referencia.clone(), "Marcador sem fechamento.".to_string());
+            }
+            for captura in MARCADOR.captures_iter(texto) {
+                let chave = format!("{}.{}", &captura[1], &captura[2]);
+                if !PLACEHOLDERS.iter().any(|(c, _, _)| *c == chave) {
+                    erro(referencia.clone(), format!("Marcador desconhecido: {}", chave));
+                }
+                if let Some(formato) = captura.get(3) {
+                    if !FORMATOS.contains(&formato.as_str()) {
+                        erro(referencia.clone(), format!("Formato desconhecido: {}", formato.as_str()));
+                    }
+                }
+            }
+            if texto.contains("{{") && MARCADOR.find(texto).is_none() {
+                erro(referencia, "Marcador mal formado; use {{grupo.campo}}.".to_string());
+            }
+        }
+    }
+    erros
+}
+
+fn placeholders_usados(planilha: &PlanilhaTemplate) -> Vec<String> {
+    planilha
+        .data
+        .iter()
+        .flatten()
+        .flat_map(|celula| MARCADOR.captures_iter(&celula.value).map(|c| format!("{}.{}", &c[1], &c[2])).collect::<Vec<_>>())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+pub fn linha_repetida(linha: &[CelulaTemplate]) -> bool {
+    linha
+        .iter()
+        .any(|celula| MARCADOR.captures_iter(&celula.value).any(|c| &c[1] == GRUPO_RESULTADO))
+}
+
+/// 0,0 -> "A1"; 2,27 -> "AB3"
+pub fn referencia_celula(linha: usize, coluna: usize) -> String {
+    let mut letras = String::new();
+    let mut n = coluna + 1;
+    while n > 0 {
+        let resto = (n - 1) % 26;
+        letras.insert(0, (b'A' + resto as u8) as char);
+        n = (n - 1) / 26;
+    }
+    format!("{}{}", letras, linha + 1)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Também usados nos testes de `template_planilha_controller`
+    pub(crate) fn celula(valor: &str) -> CelulaTemplate {
+        CelulaTemplate { value: valor.to_string(), ..Default::default() }
+    }
+
+    pub(crate) fn planilha(linhas: Vec<Vec<CelulaTemplate>>) -> PlanilhaTemplate {
+        PlanilhaTemplate {
+            name: "Laudo".to_string(),
+            rows: linhas.len(),
+            cols: linhas[0].len(),
+            data: linhas,
+            history: Vec::new(),
+            column_widths: Vec::new(),
+            row_heights: Vec::new(),
+            global_styles: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_validacao() {
+        let mut template = planilha(vec![
+            vec![celula("Cliente: {{cliente.nome|maiusculas}}"), celula("{{amostra.lote}}")],
+            vec![celula("{{resultado.parametro}}"), celula("{{resultado.valor|moeda}}")],
+        ]);
+        let erros = validar_planilha(&template);
+        assert_eq!(erros.len(), 2);
+        assert_eq!(erros[0].celula.as_deref(), Some("B1"));
+        assert!(erros[1].mensagem.contains("moeda"));
+
+        template.data[0][1] = celula("{{amostra.numero");
+        template.data[1][1] = celula("{{resultado.valor|numero:2}}");
+        template.rows = 3;
+        let erros = validar_planilha(&template);
+        assert!(erros.iter().any(|e| e.mensagem.contains("rows = 3")));
+        assert!(erros.iter().any(|e| e.celula.as_deref() == Some("B1")));
+        assert_eq!(referencia_celula(2, 27), "AB3");
+    }
+}